@@ -4,7 +4,7 @@ use std::{fmt, io};
 
 use sstable::{Dictionary, VoidSSTable};
 
-use crate::column::Column;
+use crate::column::{Column, DocIdsForValueRange};
 use crate::RowId;
 
 /// Dictionary encoded column.
@@ -50,10 +50,41 @@ impl BytesColumn {
         self.term_ord_column.num_docs()
     }
 
+    /// Fills the given `output` buffer with the first value of the row `row_id`, and returns
+    /// it.
+    ///
+    /// Returns `None` if the row has no value. Empty values are returned as an empty slice.
+    pub fn first<'a>(
+        &self,
+        row_id: RowId,
+        output: &'a mut Vec<u8>,
+    ) -> io::Result<Option<&'a [u8]>> {
+        output.clear();
+        let Some(term_ord) = self.term_ord_column.first(row_id) else {
+            return Ok(None);
+        };
+        if !self.ord_to_bytes(term_ord, output)? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Term ordinal {term_ord} is missing from the dictionary"),
+            ));
+        }
+        Ok(Some(output.as_slice()))
+    }
+
     pub fn term_ords(&self, row_id: RowId) -> impl Iterator<Item = u64> + '_ {
         self.term_ord_column.values_for_doc(row_id)
     }
 
+    /// Returns an iterator over the sorted docids having `term` as one of their values, or
+    /// `None` if no document has this term.
+    ///
+    /// The term is looked up in the dictionary, so that only the ordinals have to be scanned.
+    pub fn docids_for_term(&self, term: &[u8]) -> io::Result<Option<DocIdsForValueRange<'_, u64>>> {
+        let term_ord_opt = self.dictionary.term_ord(term)?;
+        Ok(term_ord_opt.map(|term_ord| self.term_ord_column.docids_for_value(term_ord)))
+    }
+
     /// Returns the column of ordinals
     pub fn ords(&self) -> &Column<u64> {
         &self.term_ord_column
@@ -6,7 +6,7 @@ use std::io::Write;
 use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
-use common::BinarySerializable;
+use common::{BinarySerializable, ReadOnlyBitSet};
 pub use dictionary_encoded::{BytesColumn, StrColumn};
 pub use serialize::{
     open_column_bytes, open_column_str, open_column_u128, open_column_u128_as_compact_u64,
@@ -83,6 +83,18 @@ impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Column<T> {
         self.values.max_value()
     }
 
+    /// Returns the minimum and the maximum values of the column, or `None` if the column
+    /// does not hold any value.
+    ///
+    /// These statistics are recorded when the column is serialized, so no value is read.
+    /// Values of deleted documents are taken into account until the segment is merged.
+    pub fn min_max(&self) -> Option<(T, T)> {
+        if self.values.num_vals() == 0 {
+            return None;
+        }
+        Some((self.min_value(), self.max_value()))
+    }
+
     #[inline]
     pub fn first(&self, row_id: RowId) -> Option<T> {
         self.values_for_doc(row_id).next()
@@ -157,6 +169,33 @@ impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Column<T> {
             .select_batch_in_place(selected_docid_range.start, doc_ids);
     }
 
+    /// Returns an iterator over the sorted docids having at least one value equal to `value`.
+    pub fn docids_for_value(&self, value: T) -> DocIdsForValueRange<'_, T> {
+        self.docids_for_value_range(value..=value)
+    }
+
+    /// Returns an iterator over the sorted docids having at least one value in `value_range`.
+    ///
+    /// Each docid is returned once, even if several of its values are in the range. The
+    /// docids are fetched lazily, by blocks of documents.
+    pub fn docids_for_value_range(
+        &self,
+        value_range: RangeInclusive<T>,
+    ) -> DocIdsForValueRange<'_, T> {
+        let next_fetch_start = if value_range.is_empty() {
+            self.num_docs()
+        } else {
+            0
+        };
+        DocIdsForValueRange {
+            column: self,
+            value_range,
+            next_fetch_start,
+            doc_ids: Vec::new(),
+            cursor: 0,
+        }
+    }
+
     /// Fills the output vector with the (possibly multiple values that are associated_with
     /// `row_id`.
     ///
@@ -166,6 +205,79 @@ impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Column<T> {
         output.extend(self.values_for_doc(row_id));
     }
 
+    /// Fills the output vector with all of the values associated with `doc_id`, and returns
+    /// the number of values.
+    ///
+    /// Unlike [`Column::fill_vals`], the values are fetched with a single range read, rather
+    /// than value by value. For a document without any value, the `output` vector is cleared
+    /// and 0 is returned.
+    pub fn fill_vals_for_doc(&self, doc_id: DocId, output: &mut Vec<T>) -> usize {
+        output.clear();
+        let row_ids = self.index.value_row_ids(doc_id);
+        if row_ids.is_empty() {
+            return 0;
+        }
+        let num_vals = row_ids.len();
+        output.resize(num_vals, self.values.get_val(row_ids.start));
+        self.values.get_range(row_ids.start as u64, &mut output[..]);
+        num_vals
+    }
+
+    /// Iterates over all of the `(doc_id, value)` pairs of the column, in increasing `doc_id`
+    /// order. A document with several values yields one pair per value, in the order of its
+    /// values.
+    ///
+    /// Unlike calling [`Column::values_for_doc`] for each document, the column is read
+    /// sequentially, which is much faster for full scans.
+    ///
+    /// If `alive_bitset_opt` is set, the documents it does not contain are skipped.
+    pub fn iter_docs<'a>(
+        &'a self,
+        alive_bitset_opt: Option<&'a ReadOnlyBitSet>,
+    ) -> impl Iterator<Item = (DocId, T)> + 'a {
+        let value_doc_ids: Box<dyn Iterator<Item = DocId> + 'a> = match &self.index {
+            ColumnIndex::Empty { .. } => Box::new(std::iter::empty()),
+            ColumnIndex::Full => Box::new(0..self.values.num_vals()),
+            ColumnIndex::Optional(optional_index) => Box::new(optional_index.iter_rows()),
+            ColumnIndex::Multivalued(multivalued_index) => Box::new(
+                multivalued_index
+                    .iter_doc_value_ranges()
+                    .flat_map(|(doc_id, range)| range.map(move |_| doc_id)),
+            ),
+        };
+        value_doc_ids
+            .zip(self.values.iter())
+            .filter(move |(doc_id, _)| is_alive(alive_bitset_opt, *doc_id))
+    }
+
+    /// Calls `callback` with each document having at least one value, in increasing `doc_id`
+    /// order, and the slice of all of its values.
+    ///
+    /// This is the counterpart of [`Column::iter_docs`] for multivalued columns: the column is
+    /// read sequentially as well. If `alive_bitset_opt` is set, the documents it does not
+    /// contain are skipped.
+    pub fn for_each_doc_values(
+        &self,
+        alive_bitset_opt: Option<&ReadOnlyBitSet>,
+        mut callback: impl FnMut(DocId, &[T]),
+    ) {
+        let ColumnIndex::Multivalued(multivalued_index) = &self.index else {
+            for (doc_id, value) in self.iter_docs(alive_bitset_opt) {
+                callback(doc_id, &[value]);
+            }
+            return;
+        };
+        let mut values = self.values.iter();
+        let mut doc_values = Vec::new();
+        for (doc_id, range) in multivalued_index.iter_doc_value_ranges() {
+            doc_values.clear();
+            doc_values.extend(values.by_ref().take(range.len()));
+            if is_alive(alive_bitset_opt, doc_id) {
+                callback(doc_id, &doc_values);
+            }
+        }
+    }
+
     pub fn first_or_default_col(self, default_value: T) -> Arc<dyn ColumnValues<T>> {
         Arc::new(FirstValueWithDefault {
             column: self,
@@ -174,6 +286,56 @@ impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Column<T> {
     }
 }
 
+fn is_alive(alive_bitset_opt: Option<&ReadOnlyBitSet>, doc_id: DocId) -> bool {
+    match alive_bitset_opt {
+        Some(alive_bitset) => alive_bitset.contains(doc_id),
+        None => true,
+    }
+}
+
+const DOCIDS_FOR_VALUE_RANGE_BLOCK_LEN: u32 = 4_096;
+
+/// Iterator over the docids of a [`Column`] having a value in a given range.
+///
+/// See [`Column::docids_for_value_range`].
+pub struct DocIdsForValueRange<'a, T> {
+    column: &'a Column<T>,
+    value_range: RangeInclusive<T>,
+    next_fetch_start: DocId,
+    doc_ids: Vec<DocId>,
+    cursor: usize,
+}
+
+impl<T: PartialOrd + Copy + Debug + Send + Sync + 'static> Iterator for DocIdsForValueRange<'_, T> {
+    type Item = DocId;
+
+    fn next(&mut self) -> Option<DocId> {
+        loop {
+            if let Some(&doc_id) = self.doc_ids.get(self.cursor) {
+                self.cursor += 1;
+                return Some(doc_id);
+            }
+            let num_docs = self.column.num_docs();
+            if self.next_fetch_start >= num_docs {
+                return None;
+            }
+            // Blocks are aligned on documents, so that a docid never spans two blocks.
+            let fetch_end = self
+                .next_fetch_start
+                .saturating_add(DOCIDS_FOR_VALUE_RANGE_BLOCK_LEN)
+                .min(num_docs);
+            self.doc_ids.clear();
+            self.column.get_docids_for_value_range(
+                self.value_range.clone(),
+                self.next_fetch_start..fetch_end,
+                &mut self.doc_ids,
+            );
+            self.cursor = 0;
+            self.next_fetch_start = fetch_end;
+        }
+    }
+}
+
 impl BinarySerializable for Cardinality {
     fn serialize<W: Write + ?Sized>(&self, writer: &mut W) -> std::io::Result<()> {
         self.to_code().serialize(writer)
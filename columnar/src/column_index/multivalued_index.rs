@@ -189,6 +189,30 @@ impl MultiValueIndex {
         open_multivalued_index(bytes, Version::V2).unwrap()
     }
 
+    /// Iterates over the documents having at least one value, in increasing order, together
+    /// with the range of their values.
+    ///
+    /// The index is read sequentially.
+    pub(crate) fn iter_doc_value_ranges(
+        &self,
+    ) -> Box<dyn Iterator<Item = (DocId, Range<RowId>)> + '_> {
+        let (doc_ids, start_index_column): (Box<dyn Iterator<Item = DocId>>, _) = match self {
+            MultiValueIndex::MultiValueIndexV1(idx) => (Box::new(0..), &idx.start_index_column),
+            MultiValueIndex::MultiValueIndexV2(idx) => (
+                Box::new(idx.optional_index.iter_rows()),
+                &idx.start_index_column,
+            ),
+        };
+        let mut start_indexes = start_index_column.iter();
+        let mut start = start_indexes.next().unwrap_or(0);
+        Box::new(
+            doc_ids
+                .zip(start_indexes)
+                .map(move |(doc_id, end)| (doc_id, std::mem::replace(&mut start, end)..end))
+                .filter(|(_doc_id, range)| !range.is_empty()),
+        )
+    }
+
     pub fn get_start_index_column(&self) -> &Arc<dyn crate::ColumnValues<RowId>> {
         match self {
             MultiValueIndex::MultiValueIndexV1(idx) => &idx.start_index_column,
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::net::Ipv6Addr;
 
-use common::DateTime;
+use common::{BitSet, DateTime, ReadOnlyBitSet};
 use proptest::prelude::*;
 use proptest::sample::subsequence;
 
@@ -151,6 +151,110 @@ fn test_dataframe_writer_numerical() {
     assert_eq!(column_i64.first(4), Some(15i64));
     assert_eq!(column_i64.first(5), None);
     assert_eq!(column_i64.first(6), None); //< we can change the spec for that one.
+    assert_eq!(column_i64.min_max(), Some((12i64, 15i64)));
+    assert_eq!(Column::<i64>::build_empty_column(6).min_max(), None);
+}
+
+#[test]
+fn test_column_fill_vals_for_doc_multivalued_f64() {
+    const NUM_VALS: usize = 10_000;
+    let mut dataframe_writer = ColumnarWriter::default();
+    for i in 0..NUM_VALS {
+        dataframe_writer.record_numerical(1u32, "vals", NumericalValue::F64(i as f64 * 0.5));
+    }
+    dataframe_writer.record_numerical(3u32, "vals", NumericalValue::F64(-1.5));
+    let mut buffer: Vec<u8> = Vec::new();
+    dataframe_writer.serialize(4, &mut buffer).unwrap();
+    let columnar = ColumnarReader::open(buffer).unwrap();
+    let cols: Vec<DynamicColumnHandle> = columnar.read_columns("vals").unwrap();
+    let DynamicColumn::F64(column) = cols[0].open().unwrap() else {
+        panic!();
+    };
+    assert_eq!(column.get_cardinality(), Cardinality::Multivalued);
+
+    let mut vals: Vec<f64> = vec![42.0];
+    // A doc without values clears the buffer.
+    assert_eq!(column.fill_vals_for_doc(0, &mut vals), 0);
+    assert!(vals.is_empty());
+
+    assert_eq!(column.fill_vals_for_doc(1, &mut vals), NUM_VALS);
+    let expected: Vec<f64> = (0..NUM_VALS).map(|i| i as f64 * 0.5).collect();
+    assert_eq!(vals, expected);
+    let mut vals_one_by_one: Vec<f64> = Vec::new();
+    column.fill_vals(1, &mut vals_one_by_one);
+    assert_eq!(vals, vals_one_by_one);
+
+    assert_eq!(column.fill_vals_for_doc(2, &mut vals), 0);
+    assert!(vals.is_empty());
+    assert_eq!(column.fill_vals_for_doc(3, &mut vals), 1);
+    assert_eq!(vals, vec![-1.5]);
+}
+
+#[test]
+fn test_column_iter_docs() {
+    const NUM_DOCS: u32 = 5_000;
+    let mut columnar_writer = ColumnarWriter::default();
+    for doc in 0..NUM_DOCS {
+        columnar_writer.record_numerical(doc, "full", NumericalValue::U64(doc as u64 * 3));
+        if doc % 3 != 0 {
+            columnar_writer.record_numerical(doc, "optional", NumericalValue::I64(-(doc as i64)));
+        }
+        // Doc `d` holds `d % 4` values.
+        for val in 0..doc % 4 {
+            columnar_writer.record_numerical(doc, "multi", NumericalValue::U64((doc + val) as u64));
+        }
+    }
+    let mut buffer: Vec<u8> = Vec::new();
+    columnar_writer.serialize(NUM_DOCS, &mut buffer).unwrap();
+    let columnar = ColumnarReader::open(buffer).unwrap();
+    let open_column = |column_name: &str| -> Column<u64> {
+        let cols: Vec<DynamicColumnHandle> = columnar.read_columns(column_name).unwrap();
+        cols[0].open_u64_lenient().unwrap().unwrap()
+    };
+    let mut alive_bitset = BitSet::with_max_value(NUM_DOCS);
+    for doc in (0..NUM_DOCS).filter(|doc| doc % 7 != 0) {
+        alive_bitset.insert(doc);
+    }
+    let alive_bitset = ReadOnlyBitSet::from(&alive_bitset);
+
+    for (column_name, cardinality) in [
+        ("full", Cardinality::Full),
+        ("optional", Cardinality::Optional),
+        ("multi", Cardinality::Multivalued),
+    ] {
+        let column = open_column(column_name);
+        assert_eq!(column.get_cardinality(), cardinality);
+        for alive_bitset_opt in [None, Some(&alive_bitset)] {
+            let is_alive = |doc: u32| alive_bitset_opt.is_none_or(|bitset| bitset.contains(doc));
+            let expected: Vec<(u32, u64)> = (0..NUM_DOCS)
+                .filter(|&doc| is_alive(doc))
+                .flat_map(|doc| column.values_for_doc(doc).map(move |val| (doc, val)))
+                .collect();
+            assert!(!expected.is_empty());
+            let doc_vals: Vec<(u32, u64)> = column.iter_docs(alive_bitset_opt).collect();
+            assert_eq!(doc_vals, expected);
+
+            let mut expected_doc_vals: Vec<(u32, Vec<u64>)> = Vec::new();
+            for doc in (0..NUM_DOCS).filter(|&doc| is_alive(doc)) {
+                let mut vals = Vec::new();
+                column.fill_vals(doc, &mut vals);
+                if !vals.is_empty() {
+                    expected_doc_vals.push((doc, vals));
+                }
+            }
+            let mut doc_vals: Vec<(u32, Vec<u64>)> = Vec::new();
+            column.for_each_doc_values(alive_bitset_opt, |doc, vals| {
+                doc_vals.push((doc, vals.to_vec()))
+            });
+            assert_eq!(doc_vals, expected_doc_vals);
+        }
+    }
+    assert_eq!(
+        Column::<u64>::build_empty_column(10)
+            .iter_docs(None)
+            .count(),
+        0
+    );
 }
 
 #[test]
@@ -884,3 +988,64 @@ fn test_delete_decrease_cardinality() {
     assert_eq!(cols[0].column_type(), ColumnType::I64);
     assert_eq!(cols[0].open().unwrap().get_cardinality(), Cardinality::Full);
 }
+
+#[test]
+fn test_column_docids_for_value_range_multivalued() {
+    const NUM_DOCS: u32 = 10_000;
+    let mut columnar_writer = ColumnarWriter::default();
+    for doc in 0..NUM_DOCS {
+        // Doc `d` holds the first `d % 3` values of `[d % 100, d % 100 + 1]`.
+        for val in (doc % 100..doc % 100 + 2).take((doc % 3) as usize) {
+            columnar_writer.record_numerical(doc, "vals", NumericalValue::I64(val as i64));
+        }
+    }
+    let mut buffer: Vec<u8> = Vec::new();
+    columnar_writer.serialize(NUM_DOCS, &mut buffer).unwrap();
+    let columnar = ColumnarReader::open(buffer).unwrap();
+    let cols: Vec<DynamicColumnHandle> = columnar.read_columns("vals").unwrap();
+    let DynamicColumn::I64(column) = cols[0].open().unwrap() else {
+        panic!();
+    };
+    assert_eq!(column.get_cardinality(), Cardinality::Multivalued);
+
+    let expected_docids = |range: std::ops::RangeInclusive<i64>| -> Vec<u32> {
+        (0..NUM_DOCS)
+            .filter(|&doc| column.values_for_doc(doc).any(|val| range.contains(&val)))
+            .collect()
+    };
+    let docids: Vec<u32> = column.docids_for_value(7).collect();
+    assert_eq!(&docids[..4], &[7, 107, 206, 307]);
+    assert_eq!(docids, expected_docids(7..=7));
+    // Docs holding both values of the range are returned once.
+    let docids: Vec<u32> = column.docids_for_value_range(10..=11).collect();
+    assert_eq!(docids, expected_docids(10..=11));
+    assert!(docids.windows(2).all(|window| window[0] < window[1]));
+    assert_eq!(column.docids_for_value(1_000).count(), 0);
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty_range = 11..=10;
+    assert_eq!(column.docids_for_value_range(empty_range).count(), 0);
+}
+
+#[test]
+fn test_bytes_column_docids_for_term() {
+    let mut columnar_writer = ColumnarWriter::default();
+    columnar_writer.record_str(1, "my.column", "a");
+    columnar_writer.record_str(1, "my.column", "b");
+    columnar_writer.record_str(3, "my.column", "b");
+    columnar_writer.record_str(4, "my.column", "b");
+    columnar_writer.record_str(4, "my.column", "c");
+    let mut buffer = Vec::new();
+    columnar_writer.serialize(5, &mut buffer).unwrap();
+    let columnar_reader = ColumnarReader::open(buffer).unwrap();
+    let col_handles = columnar_reader.read_columns("my.column").unwrap();
+    let DynamicColumn::Str(str_col) = col_handles[0].open().unwrap() else {
+        panic!();
+    };
+    let docids_for_term = |term: &str| -> Option<Vec<u32>> {
+        Some(str_col.docids_for_term(term.as_bytes()).unwrap()?.collect())
+    };
+    assert_eq!(docids_for_term("a"), Some(vec![1]));
+    assert_eq!(docids_for_term("b"), Some(vec![1, 3, 4]));
+    assert_eq!(docids_for_term("c"), Some(vec![4]));
+    assert_eq!(docids_for_term("d"), None);
+}
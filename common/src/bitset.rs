@@ -239,6 +239,50 @@ impl BitSet {
         self.intersect_update_with_iter(other.iter_tinysets());
     }
 
+    /// Intersect with another `BitSet`.
+    ///
+    /// Elements of `self` greater than the max value of `other` are removed.
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.intersect_update_with_iter(
+            other
+                .tinysets
+                .iter()
+                .cloned()
+                .chain(std::iter::repeat(TinySet::empty())),
+        );
+    }
+
+    /// Adds all of the elements of `other` to the `BitSet`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the max value of `other` is greater than the max value of `self`.
+    pub fn union_with(&mut self, other: &BitSet) {
+        assert!(other.max_value <= self.max_value);
+        self.len = 0;
+        for (left, right) in self.tinysets.iter_mut().zip(
+            other
+                .tinysets
+                .iter()
+                .cloned()
+                .chain(std::iter::repeat(TinySet::empty())),
+        ) {
+            *left = left.union(right);
+            self.len += left.len() as u64;
+        }
+    }
+
+    /// Iterate over the positions of the elements.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.tinysets
+            .iter()
+            .enumerate()
+            .flat_map(|(bucket, tinyset)| {
+                let bucket_base_val = bucket as u32 * 64;
+                tinyset.into_iter().map(move |val| val + bucket_base_val)
+            })
+    }
+
     /// Intersect with tinysets
     fn intersect_update_with_iter(&mut self, other: impl Iterator<Item = TinySet>) {
         self.len = 0;
@@ -447,6 +491,38 @@ mod tests {
         assert_eq!(bitset.len(), 4);
     }
 
+    #[test]
+    fn test_bitset_intersect_and_union_with_bitset() {
+        let mut left = BitSet::with_max_value(200);
+        let mut right = BitSet::with_max_value(200);
+        for el in [1, 3, 64, 150, 199] {
+            left.insert(el);
+        }
+        for el in [3, 4, 150, 198] {
+            right.insert(el);
+        }
+        let mut intersection = left.clone();
+        intersection.intersect_with(&right);
+        assert_eq!(intersection.len(), 2);
+        assert_eq!(intersection.iter().collect::<Vec<u32>>(), vec![3, 150]);
+
+        let mut union = left.clone();
+        union.union_with(&right);
+        assert_eq!(union.len(), 7);
+        assert_eq!(
+            union.iter().collect::<Vec<u32>>(),
+            vec![1, 3, 4, 64, 150, 198, 199]
+        );
+
+        // Elements beyond the max value of the other bitset are not part of the intersection.
+        let mut small = BitSet::with_max_value(10);
+        small.insert(3);
+        let mut intersection = left;
+        intersection.intersect_with(&small);
+        assert_eq!(intersection.iter().collect::<Vec<u32>>(), vec![3]);
+        assert_eq!(intersection.len(), 1);
+    }
+
     #[test]
     fn test_bitset_intersect() {
         let bitset_serialized = {
@@ -223,16 +223,46 @@ fn simple_term_infallible(
     }
 }
 
+/// Splits the fuzzy operator off an unquoted term.
+///
+/// `rust~2` targets the term `rust` with a Levenshtein distance of 2. When the distance is
+/// omitted (`rust~`), it defaults to 1. Quoted phrases are left untouched, as `~` stands for
+/// the slop there.
+fn split_fuzzy_distance(delimiter: Delimiter, phrase: String) -> (String, Option<u32>) {
+    if delimiter != Delimiter::None {
+        return (phrase, None);
+    }
+    let Some((term, distance)) = phrase.rsplit_once('~') else {
+        return (phrase, None);
+    };
+    if term.is_empty() || term.ends_with('\\') {
+        return (phrase, None);
+    }
+    let distance = if distance.is_empty() {
+        1
+    } else if distance.bytes().all(|b| b.is_ascii_digit()) {
+        match distance.parse() {
+            Ok(distance) => distance,
+            Err(_) => return (phrase, None),
+        }
+    } else {
+        return (phrase, None);
+    };
+    (term.to_string(), Some(distance))
+}
+
 fn term_or_phrase(inp: &str) -> IResult<&str, UserInputLeaf> {
     map(
         tuple((simple_term, fallible(slop_or_prefix_val))),
         |((delimiter, phrase), (slop, prefix))| {
+            let (phrase, fuzzy) = split_fuzzy_distance(delimiter, phrase);
             UserInputLiteral {
                 field_name: None,
                 phrase,
                 delimiter,
                 slop,
                 prefix,
+                fuzzy,
             }
             .into()
         },
@@ -245,6 +275,7 @@ fn term_or_phrase_infallible(inp: &str) -> JResult<&str, Option<UserInputLeaf>>
         tuple_infallible((simple_term_infallible(")^"), slop_or_prefix_val)),
         |((delimiter_phrase, (slop, prefix)), errors)| {
             let leaf = if let Some((delimiter, phrase)) = delimiter_phrase {
+                let (phrase, fuzzy) = split_fuzzy_distance(delimiter, phrase);
                 Some(
                     UserInputLiteral {
                         field_name: None,
@@ -252,6 +283,7 @@ fn term_or_phrase_infallible(inp: &str) -> JResult<&str, Option<UserInputLeaf>>
                         delimiter,
                         slop,
                         prefix,
+                        fuzzy,
                     }
                     .into(),
                 )
@@ -263,6 +295,7 @@ fn term_or_phrase_infallible(inp: &str) -> JResult<&str, Option<UserInputLeaf>>
                         delimiter: Delimiter::None,
                         slop,
                         prefix,
+                        fuzzy: None,
                     }
                     .into(),
                 )
@@ -1607,6 +1640,38 @@ mod test {
         test_parse_query_to_ast_helper("\"a b\"~300^2", "(\"a b\"~300)^2");
     }
 
+    #[test]
+    fn test_fuzzy() {
+        test_parse_query_to_ast_helper("rust~", "rust~1");
+        test_parse_query_to_ast_helper("rust~2", "rust~2");
+        test_parse_query_to_ast_helper("rust~0", "rust~0");
+        test_parse_query_to_ast_helper("title:rust~2", "\"title\":rust~2");
+        test_parse_query_to_ast_helper("title:rust~2^3", "(\"title\":rust~2)^3");
+        test_parse_query_to_ast_helper("a~b", "a~b");
+        test_parse_query_to_ast_helper("~rust", "~rust");
+        test_parse_query_to_ast_helper(r"rust\~", r"rust\~");
+        let ast = parse_to_ast("title:rust~2").unwrap().1;
+        let UserInputAst::Leaf(leaf) = ast else {
+            panic!("expected a leaf");
+        };
+        let UserInputLeaf::Literal(literal) = *leaf else {
+            panic!("expected a literal");
+        };
+        assert_eq!(literal.phrase, "rust");
+        assert_eq!(literal.fuzzy, Some(2));
+        assert_eq!(literal.slop, 0);
+        // On a phrase, `~` stands for the slop.
+        let ast = parse_to_ast("\"rust\"~2").unwrap().1;
+        let UserInputAst::Leaf(leaf) = ast else {
+            panic!("expected a leaf");
+        };
+        let UserInputLeaf::Literal(literal) = *leaf else {
+            panic!("expected a literal");
+        };
+        assert_eq!(literal.fuzzy, None);
+        assert_eq!(literal.slop, 2);
+    }
+
     #[test]
     fn test_phrase_prefix() {
         test_parse_query_to_ast_helper("\"a b\"*", "\"a b\"*");
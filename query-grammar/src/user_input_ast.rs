@@ -121,6 +121,8 @@ pub struct UserInputLiteral {
     pub delimiter: Delimiter,
     pub slop: u32,
     pub prefix: bool,
+    /// Levenshtein distance requested through the fuzzy operator (e.g. `rust~2`)
+    pub fuzzy: Option<u32>,
 }
 
 impl fmt::Debug for UserInputLiteral {
@@ -143,7 +145,9 @@ impl fmt::Debug for UserInputLiteral {
                 write!(formatter, "{}", self.phrase)?;
             }
         }
-        if self.slop > 0 {
+        if let Some(distance) = self.fuzzy {
+            write!(formatter, "~{distance}")?;
+        } else if self.slop > 0 {
             write!(formatter, "~{}", self.slop)?;
         } else if self.prefix {
             write!(formatter, "*")?;
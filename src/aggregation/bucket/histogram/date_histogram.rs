@@ -1,20 +1,24 @@
 use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
 
+use super::histogram::deserialize_option_date_or_num;
 use super::{HistogramAggregation, HistogramBounds};
 use crate::aggregation::*;
 
 /// DateHistogramAggregation is similar to `HistogramAggregation`, but it can only be used with date
 /// type.
 ///
-/// Currently only **fixed time** intervals are supported. Calendar-aware time intervals are not
-/// supported.
+/// Buckets are either **fixed time** intervals (`fixed_interval`), or calendar-aware intervals
+/// (`calendar_interval`) of one month, one quarter or one year.
 ///
 /// Like the histogram, values are rounded down into the closest bucket.
 ///
 /// For this calculation all fastfield values are converted to f64.
 ///
 /// # Limitations/Compatibility
-/// Only fixed time intervals are supported.
+/// Calendar-aware intervals only support a single unit, e.g. `1M`, but not `2M`. Time zones
+/// are only supported as fixed offsets to UTC, e.g. `+01:00`, daylight saving time is not taken
+/// into account.
 ///
 /// # JSON Format
 /// ```json
@@ -35,9 +39,6 @@ pub struct DateHistogramAggregationReq {
     #[doc(hidden)]
     /// Only for validation
     interval: Option<String>,
-    #[doc(hidden)]
-    /// Only for validation
-    calendar_interval: Option<String>,
     /// The field to aggregate on.
     pub field: String,
     /// The format to format dates. Unsupported currently.
@@ -63,8 +64,28 @@ pub struct DateHistogramAggregationReq {
     /// Fractional time values are not supported, but you can address this by shifting to another
     /// time unit (e.g., `1.5h` could instead be specified as `90m`).
     ///
-    /// `Option` for validation, the parameter is not optional
+    /// Either `fixed_interval` or `calendar_interval` has to be set.
     pub fixed_interval: Option<String>,
+    /// The calendar-aware interval to chunk your data range.
+    ///
+    /// Unlike fixed intervals, calendar-aware intervals account for the varying number of days
+    /// of months and years, e.g. a monthly bucket of February spans 29 days in a leap year. The
+    /// key of a bucket is the timestamp of the start of the month, quarter or year.
+    ///
+    /// The accepted values are:
+    /// * `1M` or `month`
+    /// * `1q` or `quarter`
+    /// * `1y` or `year`
+    ///
+    /// Either `fixed_interval` or `calendar_interval` has to be set. `offset` can not be
+    /// combined with `calendar_interval`.
+    pub calendar_interval: Option<String>,
+    /// The time zone in which buckets are computed, as a fixed offset to UTC, e.g. `+01:00`,
+    /// `-0800` or `Z`. Defaults to UTC.
+    ///
+    /// Values are shifted by the offset before bucketing, so that e.g. daily or monthly buckets
+    /// start at midnight local time. Bucket keys are still returned as UTC timestamps.
+    pub time_zone: Option<String>,
     /// Intervals implicitly defines an absolute grid of buckets `[interval * k, interval * (k +
     /// 1))`.
     ///
@@ -114,25 +135,84 @@ pub struct DateHistogramAggregationReq {
     /// Whether to return the buckets as a hash map
     #[serde(default)]
     pub keyed: bool,
+    /// The missing parameter defines how documents that are missing a value should be treated.
+    /// By default they are not counted in any bucket, but it is also possible to treat them as
+    /// if they had the given date, provided as a timestamp in milliseconds or as a Rfc3339
+    /// formatted date.
+    ///
+    /// ## Example
+    /// ```json
+    /// {
+    ///     "sales_over_time": {
+    ///        "date_histogram": {
+    ///            "field": "dates",
+    ///            "fixed_interval": "1d",
+    ///            "missing": "2015-01-01T00:00:00Z"
+    ///        }
+    ///    }
+    /// }
+    /// ```
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_date_or_num",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub missing: Option<f64>,
 }
 
 impl DateHistogramAggregationReq {
     pub(crate) fn to_histogram_req(&self) -> crate::Result<HistogramAggregation> {
         self.validate()?;
+        let utc_offset_secs = self
+            .time_zone
+            .as_ref()
+            .map(|time_zone| parse_time_zone_into_seconds(time_zone))
+            .transpose()?
+            .unwrap_or(0);
+        if let Some(calendar_interval) = self.calendar_interval.as_ref() {
+            let unit = parse_calendar_unit(calendar_interval)?;
+            return Ok(HistogramAggregation {
+                field: self.field.to_string(),
+                // The interval is ignored by calendar-aware buckets, the nominal length of the
+                // unit keeps the request valid.
+                interval: unit.nominal_length_in_milliseconds() as f64,
+                offset: None,
+                min_doc_count: self.min_doc_count,
+                hard_bounds: self.hard_bounds,
+                extended_bounds: self.extended_bounds,
+                keyed: self.keyed,
+                missing: self.missing,
+                is_normalized_to_ns: false,
+                calendar_interval: Some(CalendarInterval {
+                    unit,
+                    utc_offset_secs,
+                }),
+            });
+        }
+        let offset = self
+            .offset
+            .as_ref()
+            .map(|offset| parse_offset_into_milliseconds(offset))
+            .transpose()?;
+        // Buckets start at midnight local time, i.e. the grid is shifted back by the offset of
+        // the time zone.
+        let offset = match (offset, utc_offset_secs) {
+            (offset, 0) => offset,
+            (offset, utc_offset_secs) => {
+                Some(offset.unwrap_or(0) - i64::from(utc_offset_secs) * 1000)
+            }
+        };
         Ok(HistogramAggregation {
             field: self.field.to_string(),
             interval: parse_into_milliseconds(self.fixed_interval.as_ref().unwrap())? as f64,
-            offset: self
-                .offset
-                .as_ref()
-                .map(|offset| parse_offset_into_milliseconds(offset))
-                .transpose()?
-                .map(|el| el as f64),
+            offset: offset.map(|el| el as f64),
             min_doc_count: self.min_doc_count,
             hard_bounds: self.hard_bounds,
             extended_bounds: self.extended_bounds,
             keyed: self.keyed,
+            missing: self.missing,
             is_normalized_to_ns: false,
+            calendar_interval: None,
         })
     }
 
@@ -143,30 +223,114 @@ impl DateHistogramAggregationReq {
                  `fixed_interval` is supported"
             )));
         }
-        if let Some(interval) = self.calendar_interval.as_ref() {
-            return Err(crate::TantivyError::InvalidArgument(format!(
-                "`calendar_interval` parameter {interval:?} in date histogram is unsupported, \
-                 only `fixed_interval` is supported"
-            )));
-        }
         if self.format.is_some() {
             return Err(crate::TantivyError::InvalidArgument(
                 "format parameter on date_histogram is unsupported".to_string(),
             ));
         }
 
-        if self.fixed_interval.is_none() {
-            return Err(crate::TantivyError::InvalidArgument(
-                "fixed_interval in date histogram is missing".to_string(),
-            ));
+        match (&self.fixed_interval, &self.calendar_interval) {
+            (None, None) => {
+                return Err(crate::TantivyError::InvalidArgument(
+                    "fixed_interval or calendar_interval in date histogram is missing".to_string(),
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(crate::TantivyError::InvalidArgument(
+                    "fixed_interval and calendar_interval in date histogram can not be set at the \
+                     same time"
+                        .to_string(),
+                ));
+            }
+            (Some(fixed_interval), None) => {
+                parse_into_milliseconds(fixed_interval)?;
+            }
+            (None, Some(calendar_interval)) => {
+                if self.offset.is_some() {
+                    return Err(crate::TantivyError::InvalidArgument(
+                        "offset parameter on date_histogram is unsupported with calendar_interval"
+                            .to_string(),
+                    ));
+                }
+                parse_calendar_unit(calendar_interval)?;
+            }
         }
 
-        parse_into_milliseconds(self.fixed_interval.as_ref().unwrap())?;
+        if let Some(time_zone) = self.time_zone.as_ref() {
+            parse_time_zone_into_seconds(time_zone)?;
+        }
 
         Ok(())
     }
 }
 
+/// The unit of a calendar-aware interval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarUnit {
+    /// One calendar month.
+    Month,
+    /// Three calendar months, starting in January, April, July or October.
+    Quarter,
+    /// One calendar year.
+    Year,
+}
+
+impl CalendarUnit {
+    fn num_months(self) -> i64 {
+        match self {
+            CalendarUnit::Month => 1,
+            CalendarUnit::Quarter => 3,
+            CalendarUnit::Year => 12,
+        }
+    }
+
+    fn nominal_length_in_milliseconds(self) -> i64 {
+        self.num_months() * 30 * 24 * 60 * 60 * 1000
+    }
+}
+
+/// A calendar-aware interval, evaluated in a time zone with a fixed offset to UTC.
+///
+/// Values are expected to be timestamps in nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarInterval {
+    /// The unit of the interval.
+    pub unit: CalendarUnit,
+    /// The offset of the time zone to UTC, in seconds.
+    pub utc_offset_secs: i32,
+}
+
+impl CalendarInterval {
+    /// Returns the number of calendar units between the Unix epoch and the bucket containing
+    /// `timestamp_nanos`, in local time.
+    pub(crate) fn get_bucket_pos(&self, timestamp_nanos: f64) -> i64 {
+        let local_timestamp_nanos = (timestamp_nanos as i64)
+            .saturating_add(i64::from(self.utc_offset_secs) * 1_000_000_000);
+        let local_date =
+            OffsetDateTime::from_unix_timestamp_nanos(i128::from(local_timestamp_nanos))
+                .expect("i64 nanosecond timestamps are always in the supported date range");
+        let num_months = (i64::from(local_date.year()) - 1970) * 12
+            + i64::from(u8::from(local_date.month()))
+            - 1;
+        num_months.div_euclid(self.unit.num_months())
+    }
+
+    /// Returns the UTC timestamp in nanoseconds of the start of the bucket `bucket_pos`.
+    pub(crate) fn get_bucket_key_from_pos(&self, bucket_pos: i64) -> f64 {
+        let num_months = bucket_pos * self.unit.num_months();
+        let year = 1970 + num_months.div_euclid(12);
+        let month = Month::try_from(num_months.rem_euclid(12) as u8 + 1)
+            .expect("month is always in the range [1, 12]");
+        let local_start = Date::from_calendar_date(year as i32, month, 1)
+            .expect("bucket positions are computed from i64 nanosecond timestamps")
+            .midnight()
+            .assume_utc();
+        (local_start.unix_timestamp_nanos() - i128::from(self.utc_offset_secs) * 1_000_000_000)
+            as f64
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 /// Errors when parsing the fixed interval for `DateHistogramAggregationReq`.
 pub enum DateHistogramParseError {
@@ -185,6 +349,54 @@ pub enum DateHistogramParseError {
     /// Value out of bounds
     #[error("passed value is out of bounds: {0:?}")]
     OutOfBounds(String),
+    /// Calendar interval not recognized
+    #[error("Calendar interval not recognized in passed String {0:?}")]
+    CalendarIntervalNotRecognized(String),
+    /// Time zone invalid
+    #[error("passed time zone is invalid {0:?}")]
+    InvalidTimeZone(String),
+}
+
+fn parse_calendar_unit(input: &str) -> Result<CalendarUnit, AggregationError> {
+    match input {
+        "1M" | "month" => Ok(CalendarUnit::Month),
+        "1q" | "quarter" => Ok(CalendarUnit::Quarter),
+        "1y" | "year" => Ok(CalendarUnit::Year),
+        _ => Err(DateHistogramParseError::CalendarIntervalNotRecognized(input.to_string()).into()),
+    }
+}
+
+/// Parses a fixed offset to UTC, e.g. `+01:00`, `-0830` or `Z`, into seconds.
+fn parse_time_zone_into_seconds(input: &str) -> Result<i32, AggregationError> {
+    let invalid_time_zone = || DateHistogramParseError::InvalidTimeZone(input.to_string());
+    if matches!(input, "Z" | "UTC") {
+        return Ok(0);
+    }
+    let (sign, hours_and_minutes) = if let Some(rest) = input.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = input.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        return Err(invalid_time_zone().into());
+    };
+    let (hours, minutes) = match hours_and_minutes.as_bytes() {
+        [_, _, b':', _, _] | [_, _, _, _] => (
+            &hours_and_minutes[..2],
+            &hours_and_minutes[hours_and_minutes.len() - 2..],
+        ),
+        _ => return Err(invalid_time_zone().into()),
+    };
+    let parse_digits = |digits: &str| -> Result<i32, AggregationError> {
+        if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid_time_zone().into());
+        }
+        digits.parse().map_err(|_| invalid_time_zone().into())
+    };
+    let (hours, minutes) = (parse_digits(hours)?, parse_digits(minutes)?);
+    if hours > 23 || minutes > 59 {
+        return Err(invalid_time_zone().into());
+    }
+    Ok(sign * (hours * 3600 + minutes * 60))
 }
 
 fn parse_offset_into_milliseconds(input: &str) -> Result<i64, AggregationError> {
@@ -672,4 +884,242 @@ pub(crate) mod tests {
             r#"An invalid argument was passed: '`interval` parameter "30d" in date histogram is unsupported, only `fixed_interval` is supported'"#
         );
     }
+
+    #[test]
+    fn test_parse_time_zone_into_seconds() {
+        assert_eq!(parse_time_zone_into_seconds("Z").unwrap(), 0);
+        assert_eq!(parse_time_zone_into_seconds("+00:00").unwrap(), 0);
+        assert_eq!(parse_time_zone_into_seconds("+01:00").unwrap(), 3600);
+        assert_eq!(parse_time_zone_into_seconds("-08:30").unwrap(), -30600);
+        assert_eq!(parse_time_zone_into_seconds("+0530").unwrap(), 19800);
+        for invalid_time_zone in ["", "01:00", "+1:00", "+24:00", "+01:60", "+１:00", "CET"] {
+            assert_eq!(
+                parse_time_zone_into_seconds(invalid_time_zone).unwrap_err(),
+                DateHistogramParseError::InvalidTimeZone(invalid_time_zone.to_string()).into()
+            );
+        }
+    }
+
+    fn exec_date_histogram(index: &Index, date_histogram: serde_json::Value) -> serde_json::Value {
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "sales_over_time": { "date_histogram": date_histogram }
+        }))
+        .unwrap();
+        exec_request(agg_req, index).unwrap()
+    }
+
+    fn bucket_keys_and_counts(res: &serde_json::Value) -> Vec<(String, u64)> {
+        res["sales_over_time"]["buckets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bucket| {
+                (
+                    bucket["key_as_string"].as_str().unwrap().to_string(),
+                    bucket["doc_count"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn histogram_test_date_calendar_interval_force_merge_segments() {
+        histogram_test_date_calendar_interval_merge_segments(true)
+    }
+
+    #[test]
+    fn histogram_test_date_calendar_interval() {
+        histogram_test_date_calendar_interval_merge_segments(false)
+    }
+
+    fn histogram_test_date_calendar_interval_merge_segments(merge_segments: bool) {
+        let docs = vec![
+            vec![
+                r#"{ "date": "2019-12-31T23:00:00Z", "text": "aaa" }"#,
+                r#"{ "date": "2020-02-29T12:00:00Z", "text": "aaa" }"#,
+            ],
+            vec![
+                r#"{ "date": "2020-02-29T23:30:00Z", "text": "bbb" }"#,
+                r#"{ "date": "2020-03-01T00:00:00Z", "text": "bbb" }"#,
+            ],
+            vec![r#"{ "date": "2020-05-15T00:00:00Z", "text": "ccc" }"#],
+        ];
+        let index = get_test_index_from_docs(merge_segments, &docs).unwrap();
+
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "calendar_interval": "1M" }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![
+                ("2019-12-01T00:00:00Z".to_string(), 1),
+                ("2020-01-01T00:00:00Z".to_string(), 0),
+                ("2020-02-01T00:00:00Z".to_string(), 2),
+                ("2020-03-01T00:00:00Z".to_string(), 1),
+                ("2020-04-01T00:00:00Z".to_string(), 0),
+                ("2020-05-01T00:00:00Z".to_string(), 1),
+            ]
+        );
+        // February 2020 spans 29 days.
+        let buckets = &res["sales_over_time"]["buckets"];
+        assert_eq!(
+            buckets[3]["key"].as_f64().unwrap() - buckets[2]["key"].as_f64().unwrap(),
+            29.0 * 24.0 * 3600.0 * 1000.0
+        );
+
+        // In UTC+1, the first doc falls into January and the third one into March.
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "calendar_interval": "month", "time_zone": "+01:00" }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![
+                ("2019-12-31T23:00:00Z".to_string(), 1),
+                ("2020-01-31T23:00:00Z".to_string(), 1),
+                ("2020-02-29T23:00:00Z".to_string(), 2),
+                ("2020-03-31T23:00:00Z".to_string(), 0),
+                ("2020-04-30T23:00:00Z".to_string(), 1),
+            ]
+        );
+
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "calendar_interval": "1q", "min_doc_count": 1 }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![
+                ("2019-10-01T00:00:00Z".to_string(), 1),
+                ("2020-01-01T00:00:00Z".to_string(), 3),
+                ("2020-04-01T00:00:00Z".to_string(), 1),
+            ]
+        );
+
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "calendar_interval": "1y", "time_zone": "+01:00" }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![("2019-12-31T23:00:00Z".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn histogram_test_date_calendar_interval_sub_agg() {
+        let docs = vec![
+            vec![r#"{ "date": "2020-01-31T23:00:00Z", "text": "aaa" }"#],
+            vec![
+                r#"{ "date": "2020-01-01T00:00:00Z", "text": "aaa" }"#,
+                r#"{ "date": "2020-02-01T00:00:00Z", "text": "bbb" }"#,
+            ],
+        ];
+        let index = get_test_index_from_docs(false, &docs).unwrap();
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "sales_over_time": {
+                "date_histogram": { "field": "date", "calendar_interval": "1M" },
+                "aggs": { "texts": { "terms": { "field": "text" } } }
+            }
+        }))
+        .unwrap();
+        let res = exec_request(agg_req, &index).unwrap();
+        let buckets = &res["sales_over_time"]["buckets"];
+        assert_eq!(buckets[0]["key_as_string"], "2020-01-01T00:00:00Z");
+        assert_eq!(buckets[0]["doc_count"], 2);
+        assert_eq!(buckets[0]["texts"]["buckets"][0]["key"], "aaa");
+        assert_eq!(buckets[0]["texts"]["buckets"][0]["doc_count"], 2);
+        assert_eq!(buckets[1]["key_as_string"], "2020-02-01T00:00:00Z");
+        assert_eq!(buckets[1]["texts"]["buckets"][0]["key"], "bbb");
+        assert_eq!(buckets[2], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn histogram_test_date_missing() {
+        let docs = vec![
+            vec![
+                r#"{ "date": "2015-01-01T12:00:00Z", "text": "aaa" }"#,
+                r#"{ "text": "bbb" }"#,
+            ],
+            // A segment in which the field does not exist.
+            vec![r#"{ "text": "ccc" }"#],
+        ];
+        let index = get_test_index_from_docs(false, &docs).unwrap();
+        let res = exec_date_histogram(&index, json!({ "field": "date", "fixed_interval": "1d" }));
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![("2015-01-01T00:00:00Z".to_string(), 1)]
+        );
+
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "fixed_interval": "1d", "missing": "2015-01-03T08:00:00Z" }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![
+                ("2015-01-01T00:00:00Z".to_string(), 1),
+                ("2015-01-02T00:00:00Z".to_string(), 0),
+                ("2015-01-03T00:00:00Z".to_string(), 2),
+            ]
+        );
+
+        // The missing date can be given as a timestamp in milliseconds.
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "calendar_interval": "1M", "missing": 1420070400000i64 }),
+        );
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![("2015-01-01T00:00:00Z".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn histogram_test_date_fixed_interval_time_zone() {
+        let docs = vec![vec![
+            r#"{ "date": "2015-01-01T12:00:00Z", "text": "aaa" }"#,
+            r#"{ "date": "2015-01-02T03:00:00Z", "text": "bbb" }"#,
+        ]];
+        let index = get_test_index_from_docs(false, &docs).unwrap();
+        let res = exec_date_histogram(
+            &index,
+            json!({ "field": "date", "fixed_interval": "1d", "time_zone": "-05:00" }),
+        );
+        // Both docs are on January 1st in UTC-5.
+        assert_eq!(
+            bucket_keys_and_counts(&res),
+            vec![("2015-01-01T05:00:00Z".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn histogram_test_date_calendar_interval_invalid_req() {
+        let index = get_test_index_from_docs(false, &[]).unwrap();
+        let exec_err = |date_histogram: serde_json::Value| {
+            let agg_req: Aggregations = serde_json::from_value(json!({
+                "sales_over_time": { "date_histogram": date_histogram }
+            }))
+            .unwrap();
+            exec_request(agg_req, &index).unwrap_err().to_string()
+        };
+        assert!(
+            exec_err(json!({ "field": "date", "calendar_interval": "2M" }))
+                .contains(r#"CalendarIntervalNotRecognized("2M")"#)
+        );
+        assert!(exec_err(
+            json!({ "field": "date", "calendar_interval": "1M", "fixed_interval": "1d" })
+        )
+        .contains("can not be set at the same time"));
+        assert!(
+            exec_err(json!({ "field": "date", "calendar_interval": "1M", "offset": "1d" }))
+                .contains("unsupported with calendar_interval")
+        );
+        assert!(exec_err(json!({ "field": "date" })).contains("is missing"));
+        assert!(exec_err(
+            json!({ "field": "date", "calendar_interval": "1M", "time_zone": "Europe/Paris" })
+        )
+        .contains("InvalidTimeZone"));
+    }
 }
@@ -4,6 +4,7 @@ use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use tantivy_bitpacker::minmax;
 
+use super::CalendarInterval;
 use crate::aggregation::agg_limits::MemoryConsumption;
 use crate::aggregation::agg_req::Aggregations;
 use crate::aggregation::agg_req_with_accessor::{
@@ -121,9 +122,26 @@ pub struct HistogramAggregation {
     /// Whether to return the buckets as a hash map
     #[serde(default)]
     pub keyed: bool,
+    /// The missing parameter defines how documents that are missing a value should be treated.
+    /// By default they are not counted in any bucket, but it is also possible to treat them as
+    /// if they had the given value. Example in JSON format:
+    /// { "field": "price", "interval": 10, "missing": 0 }
+    ///
+    /// For date time fields, the value is provided in milliseconds.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub missing: Option<f64>,
     /// Whether the values are normalized to ns for date time values. Defaults to false.
     #[serde(default)]
     pub is_normalized_to_ns: bool,
+    /// Calendar-aware interval of a date histogram. When set, buckets span calendar units
+    /// instead of `interval`, and `offset` is ignored.
+    #[doc(hidden)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_interval: Option<CalendarInterval>,
 }
 
 impl HistogramAggregation {
@@ -132,6 +150,7 @@ impl HistogramAggregation {
             // values are provided in ms, but the fastfield is in nano seconds
             self.interval *= 1_000_000.0;
             self.offset = self.offset.map(|off| off * 1_000_000.0);
+            self.missing = self.missing.map(|missing| missing * 1_000_000.0);
             self.hard_bounds = self.hard_bounds.map(|bounds| HistogramBounds {
                 min: bounds.min * 1_000_000.0,
                 max: bounds.max * 1_000_000.0,
@@ -212,6 +231,17 @@ where D: serde::Deserializer<'de> {
     }
 }
 
+/// Deserializes an optional timestamp in milliseconds, or Rfc3339 formatted date.
+pub(super) fn deserialize_option_date_or_num<'de, D>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error>
+where D: serde::Deserializer<'de> {
+    #[derive(Deserialize)]
+    struct DateOrNum(#[serde(deserialize_with = "deserialize_date_or_num")] f64);
+    let date_or_num_opt: Option<DateOrNum> = Deserialize::deserialize(deserializer)?;
+    Ok(date_or_num_opt.map(|date_or_num| date_or_num.0))
+}
+
 impl Display for HistogramBounds {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("[{},{}]", self.min, self.max))
@@ -260,7 +290,10 @@ pub struct SegmentHistogramCollector {
     column_type: ColumnType,
     interval: f64,
     offset: f64,
+    calendar_interval: Option<CalendarInterval>,
     bounds: HistogramBounds,
+    /// The fast field representation of the value of the documents without a value.
+    missing: Option<u64>,
     accessor_idx: usize,
 }
 
@@ -301,11 +334,21 @@ impl SegmentAggregationCollector for SegmentHistogramCollector {
         let bounds = self.bounds;
         let interval = self.interval;
         let offset = self.offset;
-        let get_bucket_pos = |val| (get_bucket_pos_f64(val, interval, offset) as i64);
+        let calendar_interval = self.calendar_interval;
+        let get_bucket_pos = |val| match calendar_interval {
+            Some(calendar_interval) => calendar_interval.get_bucket_pos(val),
+            None => get_bucket_pos_f64(val, interval, offset) as i64,
+        };
 
-        bucket_agg_accessor
-            .column_block_accessor
-            .fetch_block(docs, &bucket_agg_accessor.accessor);
+        if let Some(missing) = self.missing {
+            bucket_agg_accessor
+                .column_block_accessor
+                .fetch_block_with_missing(docs, &bucket_agg_accessor.accessor, missing);
+        } else {
+            bucket_agg_accessor
+                .column_block_accessor
+                .fetch_block(docs, &bucket_agg_accessor.accessor);
+        }
 
         for (doc, val) in bucket_agg_accessor
             .column_block_accessor
@@ -317,7 +360,12 @@ impl SegmentAggregationCollector for SegmentHistogramCollector {
 
             if bounds.contains(val) {
                 let bucket = self.buckets.entry(bucket_pos).or_insert_with(|| {
-                    let key = get_bucket_key_from_pos(bucket_pos as f64, interval, offset);
+                    let key = match calendar_interval {
+                        Some(calendar_interval) => {
+                            calendar_interval.get_bucket_key_from_pos(bucket_pos)
+                        }
+                        None => get_bucket_key_from_pos(bucket_pos as f64, interval, offset),
+                    };
                     SegmentHistogramBucketEntry { key, doc_count: 0 }
                 });
                 bucket.doc_count += 1;
@@ -410,7 +458,11 @@ impl SegmentHistogramCollector {
             column_type: field_type,
             interval: req.interval,
             offset: req.offset.unwrap_or(0.0),
+            calendar_interval: req.calendar_interval,
             bounds,
+            missing: req
+                .missing
+                .and_then(|missing| f64_to_fastfield_u64(missing, &field_type)),
             sub_aggregations: Default::default(),
             sub_aggregation_blueprint,
             accessor_idx,
@@ -568,6 +620,11 @@ pub(crate) fn generate_bucket_pos_with_opt_minmax(
 ) -> (f64, i64, i64) {
     let (min, max) = get_req_min_max(req, min_max);
 
+    if let Some(calendar_interval) = req.calendar_interval {
+        let first_bucket_num = calendar_interval.get_bucket_pos(min);
+        let last_bucket_num = calendar_interval.get_bucket_pos(max);
+        return (0.0, first_bucket_num, last_bucket_num);
+    }
     let offset = req.offset.unwrap_or(0.0);
     let first_bucket_num = get_bucket_pos_f64(min, req.interval, offset) as i64;
     let last_bucket_num = get_bucket_pos_f64(max, req.interval, offset) as i64;
@@ -585,7 +642,10 @@ pub(crate) fn generate_buckets_with_opt_minmax(
         generate_bucket_pos_with_opt_minmax(req, min_max);
     let mut buckets = Vec::with_capacity((first_bucket_num..=last_bucket_num).count());
     for bucket_pos in first_bucket_num..=last_bucket_num {
-        let bucket_key = bucket_pos as f64 * req.interval + offset;
+        let bucket_key = match req.calendar_interval {
+            Some(calendar_interval) => calendar_interval.get_bucket_key_from_pos(bucket_pos),
+            None => bucket_pos as f64 * req.interval + offset,
+        };
         buckets.push(bucket_key);
     }
 
@@ -1387,4 +1447,72 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn histogram_missing_test() -> crate::Result<()> {
+        use crate::indexer::NoMergePolicy;
+        use crate::schema::{Schema, FAST};
+        use crate::{Index, IndexWriter};
+
+        let mut schema_builder = Schema::builder();
+        let score = schema_builder.add_f64_field("score", FAST);
+        let count = schema_builder.add_u64_field("count", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer: IndexWriter = index.writer_for_tests()?;
+            index_writer.set_merge_policy(Box::new(NoMergePolicy));
+            // A zero is a value, not a missing value.
+            index_writer.add_document(doc!(score => 0.0f64, count => 1u64))?;
+            index_writer.add_document(doc!(score => 12.0f64, count => 1u64))?;
+            index_writer.add_document(doc!(count => 1u64))?;
+            index_writer.commit()?;
+            // A segment in which the field does not exist.
+            index_writer.add_document(doc!(count => 1u64))?;
+            index_writer.add_document(doc!(count => 1u64))?;
+            index_writer.commit()?;
+        }
+
+        let histogram = |missing: Option<f64>| -> crate::Result<Value> {
+            let mut histogram = json!({ "field": "score", "interval": 10.0 });
+            if let Some(missing) = missing {
+                histogram["missing"] = json!(missing);
+            }
+            let agg_req: Aggregations = serde_json::from_value(json!({
+                "histogram": {
+                    "histogram": histogram,
+                    "aggs": { "count": { "sum": { "field": "count" } } }
+                }
+            }))
+            .unwrap();
+            exec_request(agg_req, &index)
+        };
+
+        // By default, the documents without a value are not counted.
+        let res = histogram(None)?;
+        assert_eq!(
+            res["histogram"]["buckets"],
+            json!([
+                { "key": 0.0, "doc_count": 1, "count": { "value": 1.0 } },
+                { "key": 10.0, "doc_count": 1, "count": { "value": 1.0 } },
+            ])
+        );
+
+        // They can be treated as if they had a value, which falls into a bucket of its own.
+        let res = histogram(Some(25.0))?;
+        assert_eq!(
+            res["histogram"]["buckets"],
+            json!([
+                { "key": 0.0, "doc_count": 1, "count": { "value": 1.0 } },
+                { "key": 10.0, "doc_count": 1, "count": { "value": 1.0 } },
+                { "key": 20.0, "doc_count": 3, "count": { "value": 3.0 } },
+            ])
+        );
+
+        // Or they can be merged with the existing values.
+        let res = histogram(Some(5.0))?;
+        assert_eq!(res["histogram"]["buckets"][0]["key"], 0.0);
+        assert_eq!(res["histogram"]["buckets"][0]["doc_count"], 4);
+        assert_eq!(res["histogram"]["buckets"][1]["doc_count"], 1);
+        Ok(())
+    }
 }
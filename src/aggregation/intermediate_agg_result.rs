@@ -238,8 +238,8 @@ pub(crate) fn empty_from_req(req: &Aggregation) -> IntermediateAggregationResult
         TopHits(ref req) => IntermediateAggregationResult::Metric(
             IntermediateMetricResult::TopHits(TopHitsTopNComputer::new(req)),
         ),
-        Cardinality(_) => IntermediateAggregationResult::Metric(
-            IntermediateMetricResult::Cardinality(CardinalityCollector::default()),
+        Cardinality(ref req) => IntermediateAggregationResult::Metric(
+            IntermediateMetricResult::Cardinality(CardinalityCollector::new(0, req.precision())),
         ),
     }
 }
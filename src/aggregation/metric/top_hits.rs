@@ -913,4 +913,73 @@ mod tests {
     fn test_aggregation_top_hits_multi_segment() -> crate::Result<()> {
         test_aggregation_top_hits(false)
     }
+
+    fn test_aggregation_top_hits_per_terms_bucket(merge_segments: bool) -> crate::Result<()> {
+        // The newest documents of each category are spread over the segments.
+        let docs = vec![
+            vec![
+                r#"{ "text": "books", "text2": "b1", "date": "2015-01-02T00:00:00Z" }"#,
+                r#"{ "text": "games", "text2": "g1", "date": "2019-01-02T00:00:00Z" }"#,
+                r#"{ "text": "books", "text2": "b2", "date": "2018-01-02T00:00:00Z" }"#,
+            ],
+            vec![
+                r#"{ "text": "books", "text2": "b3", "date": "2017-01-02T00:00:00Z" }"#,
+                r#"{ "text": "games", "text2": "g2", "date": "2016-01-02T00:00:00Z" }"#,
+                r#"{ "text": "games", "text2": "g3", "date": "2020-01-02T00:00:00Z" }"#,
+                r#"{ "text": "books", "text2": "b4", "date": "2014-01-02T00:00:00Z" }"#,
+            ],
+        ];
+        let index = get_test_index_from_docs(merge_segments, &docs)?;
+
+        let agg_req: Aggregations = serde_json::from_value(json!({
+            "categories": {
+                "terms": { "field": "text" },
+                "aggs": {
+                    "newest": {
+                        "top_hits": {
+                            "size": 2,
+                            "sort": [ { "date": "desc" } ],
+                            "docvalue_fields": [ "text2" ],
+                        }
+                    }
+                }
+            }
+        }))?;
+        let collector = AggregationCollector::from_aggs(agg_req, Default::default());
+        let searcher = index.reader()?.searcher();
+        let agg_res = serde_json::to_value(searcher.search(&AllQuery, &collector)?)?;
+
+        let newest = |bucket: &Value| -> Vec<String> {
+            bucket["newest"]["hits"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|hit| {
+                    hit["docvalue_fields"]["text2"][0]
+                        .as_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect()
+        };
+        let buckets = agg_res["categories"]["buckets"].as_array().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0]["key"], "books");
+        assert_eq!(buckets[0]["doc_count"], 4);
+        assert_eq!(newest(&buckets[0]), vec!["b2", "b3"]);
+        assert_eq!(buckets[1]["key"], "games");
+        assert_eq!(buckets[1]["doc_count"], 3);
+        assert_eq!(newest(&buckets[1]), vec!["g3", "g1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregation_top_hits_per_terms_bucket_single_segment() -> crate::Result<()> {
+        test_aggregation_top_hits_per_terms_bucket(true)
+    }
+
+    #[test]
+    fn test_aggregation_top_hits_per_terms_bucket_multi_segment() -> crate::Result<()> {
+        test_aggregation_top_hits_per_terms_bucket(false)
+    }
 }
@@ -17,8 +17,7 @@ use super::metric::{
 };
 use crate::aggregation::bucket::TermMissingAgg;
 use crate::aggregation::metric::{
-    CardinalityAggregationReq, SegmentCardinalityCollector, SegmentExtendedStatsCollector,
-    TopHitsSegmentCollector,
+    SegmentCardinalityCollector, SegmentExtendedStatsCollector, TopHitsSegmentCollector,
 };
 
 pub(crate) trait SegmentAggregationCollector: CollectorClone + Debug {
@@ -172,8 +171,12 @@ pub(crate) fn build_single_agg_segment_collector(
             accessor_idx,
             req.segment_ordinal,
         ))),
-        Cardinality(CardinalityAggregationReq { missing, .. }) => Ok(Box::new(
-            SegmentCardinalityCollector::from_req(req.field_type, accessor_idx, missing),
+        Cardinality(cardinality_req) => Ok(Box::new(
+            SegmentCardinalityCollector::from_req_and_validate(
+                cardinality_req,
+                req.field_type,
+                accessor_idx,
+            )?,
         )),
     }
 }
@@ -1,7 +1,9 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+use common::BitSet;
 
 use super::{Collector, SegmentCollector};
-use crate::{DocAddress, DocId, Score};
+use crate::{DocAddress, DocId, Score, SegmentOrdinal};
 
 /// Collectors that returns the set of DocAddress that matches the query.
 ///
@@ -58,3 +60,180 @@ impl SegmentCollector for DocSetChildCollector {
         (self.segment_local_id, self.docs)
     }
 }
+
+/// Collector that returns, for each segment, the set of the DocIds matching the query as a
+/// [`BitSet`].
+///
+/// Unlike [`DocSetCollector`], the fruit remains compact on large result sets, and the results
+/// of several queries can be cheaply combined, segment by segment, with
+/// [`BitSet::intersect_with`] and [`BitSet::union_with`].
+///
+/// The fruit contains a bitset for every segment of the searcher, even if no document of the
+/// segment matches the query. The bitsets of a segment all have the same max value, its
+/// `max_doc`.
+///
+/// ```rust
+/// use tantivy::collector::BitSetCollector;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+/// index_writer.add_document(doc!(title => "A Dairy Cow"))?;
+/// index_writer.add_document(doc!(title => "The Diary of a Young Girl"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query_parser = QueryParser::for_index(&index, vec![title]);
+/// let mut diary_docs = searcher.search(&query_parser.parse_query("diary")?, &BitSetCollector)?;
+/// let the_docs = searcher.search(&query_parser.parse_query("the")?, &BitSetCollector)?;
+/// for (segment_ord, docs) in diary_docs.iter_mut() {
+///     docs.intersect_with(&the_docs[segment_ord]);
+/// }
+/// assert_eq!(diary_docs[&0].len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct BitSetCollector;
+
+impl Collector for BitSetCollector {
+    type Fruit = HashMap<SegmentOrdinal, BitSet>;
+    type Child = BitSetChildCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        segment: &crate::SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        Ok(BitSetChildCollector {
+            segment_local_id,
+            docs: BitSet::with_max_value(segment.max_doc()),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<(SegmentOrdinal, BitSet)>,
+    ) -> crate::Result<Self::Fruit> {
+        Ok(segment_fruits.into_iter().collect())
+    }
+}
+
+pub struct BitSetChildCollector {
+    segment_local_id: SegmentOrdinal,
+    docs: BitSet,
+}
+
+impl SegmentCollector for BitSetChildCollector {
+    type Fruit = (SegmentOrdinal, BitSet);
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.docs.insert(doc);
+    }
+
+    fn collect_block(&mut self, docs: &[DocId]) {
+        for &doc in docs {
+            self.docs.insert(doc);
+        }
+    }
+
+    fn harvest(self) -> (SegmentOrdinal, BitSet) {
+        (self.segment_local_id, self.docs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use common::BitSet;
+
+    use super::{BitSetCollector, DocSetCollector};
+    use crate::query::{BooleanQuery, Occur, Query, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{DocAddress, Index, IndexWriter, Searcher, SegmentOrdinal, Term};
+
+    fn docs_from_bitsets(bitsets: &HashMap<SegmentOrdinal, BitSet>) -> HashSet<DocAddress> {
+        bitsets
+            .iter()
+            .flat_map(|(&segment_ord, docs)| {
+                docs.iter()
+                    .map(move |doc| DocAddress::new(segment_ord, doc))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bitset_collector_set_operations() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for doc_id in 0..300u64 {
+            let mut doc = doc!();
+            if doc_id % 2 == 0 {
+                doc.add_text(tag, "even");
+            }
+            if doc_id % 3 == 0 {
+                doc.add_text(tag, "three");
+            }
+            index_writer.add_document(doc)?;
+            if doc_id % 100 == 99 {
+                index_writer.commit()?;
+            }
+        }
+        // Deleted documents are not part of the bitsets.
+        index_writer.delete_term(Term::from_field_text(tag, "three"));
+        index_writer.add_document(doc!(tag => "three"))?;
+        index_writer.commit()?;
+        let searcher: Searcher = index.reader()?.searcher();
+        assert!(searcher.segment_readers().len() > 1);
+
+        let term_query = |text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(tag, text),
+                IndexRecordOption::Basic,
+            ))
+        };
+        let even_docs = searcher.search(&term_query("even"), &BitSetCollector)?;
+        let three_docs = searcher.search(&term_query("three"), &BitSetCollector)?;
+        assert_eq!(even_docs.len(), searcher.segment_readers().len());
+        assert_eq!(docs_from_bitsets(&three_docs).len(), 1);
+
+        let mut intersection = even_docs.clone();
+        let mut union = even_docs.clone();
+        for (segment_ord, docs) in intersection.iter_mut() {
+            docs.intersect_with(&three_docs[segment_ord]);
+        }
+        for (segment_ord, docs) in union.iter_mut() {
+            docs.union_with(&three_docs[segment_ord]);
+        }
+        let must_query = BooleanQuery::new(vec![
+            (Occur::Must, term_query("even")),
+            (Occur::Must, term_query("three")),
+        ]);
+        let should_query = BooleanQuery::new(vec![
+            (Occur::Should, term_query("even")),
+            (Occur::Should, term_query("three")),
+        ]);
+        assert_eq!(
+            docs_from_bitsets(&intersection),
+            searcher.search(&must_query, &DocSetCollector)?
+        );
+        assert_eq!(
+            docs_from_bitsets(&union),
+            searcher.search(&should_query, &DocSetCollector)?
+        );
+        assert_eq!(docs_from_bitsets(&union).len(), 101);
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::{btree_map, BTreeMap, BTreeSet, BinaryHeap};
 use std::io;
 use std::ops::Bound;
 
-use crate::collector::{Collector, SegmentCollector};
+use crate::collector::{Collector, SegmentCollector, TopNComputer};
 use crate::fastfield::FacetReader;
 use crate::schema::Facet;
 use crate::{DocId, Score, SegmentOrdinal, SegmentReader};
@@ -68,6 +68,9 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 /// Once collection is finished, you can harvest its results in the form
 /// of a [`FacetCounts`] object, and extract your facet counts from it.
 ///
+/// If a facet has a large number of children, you can keep only the first
+/// ones by adding it with [`FacetCollector::add_facet_with_limit`] instead.
+///
 /// This implementation assumes you are working with a number of facets that
 /// is many hundreds of times smaller than your number of documents.
 ///
@@ -183,6 +186,17 @@ fn facet_depth(facet_bytes: &[u8]) -> usize {
 pub struct FacetCollector {
     field_name: String,
     facets: BTreeSet<Facet>,
+    facet_limits: BTreeMap<Facet, (usize, FacetOrder)>,
+}
+
+/// Defines which children of a facet are kept by
+/// [`FacetCollector::add_facet_with_limit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FacetOrder {
+    /// Keeps the children with the highest counts. Ties are broken by facet.
+    ByCount,
+    /// Keeps the first children in the lexicographic order of the facets.
+    ByFacet,
 }
 
 pub struct FacetSegmentCollector {
@@ -205,6 +219,7 @@ impl FacetCollector {
         FacetCollector {
             field_name: field_name.to_string(),
             facets: BTreeSet::default(),
+            facet_limits: BTreeMap::default(),
         }
     }
 
@@ -232,6 +247,18 @@ impl FacetCollector {
         }
         self.facets.insert(facet);
     }
+
+    /// Adds a facet that we want to record counts, like [`FacetCollector::add_facet`],
+    /// only keeping `limit` of its children, following `order`.
+    ///
+    /// The children are selected once the counts of all segments have been merged, so that
+    /// the counts of the kept children are exact.
+    pub fn add_facet_with_limit<T>(&mut self, facet_from: T, limit: usize, order: FacetOrder)
+    where Facet: From<T> {
+        let facet = Facet::from(facet_from);
+        self.add_facet::<Facet>(facet.clone());
+        self.facet_limits.insert(facet, (limit, order));
+    }
 }
 
 fn compress_mapping(mapping: &[(u64, usize)]) -> (Vec<usize>, Vec<(u64, usize)>) {
@@ -292,10 +319,58 @@ impl Collector for FacetCollector {
                 *(facet_counts.entry(facet).or_insert(0)) += count;
             }
         }
+        for (facet, &(limit, order)) in &self.facet_limits {
+            retain_top_children(&mut facet_counts, facet, limit, order);
+        }
         Ok(FacetCounts { facet_counts })
     }
 }
 
+/// Returns the first facet following all of the children of `facet`, or `None` for the root.
+fn facet_after_children(facet: &Facet) -> Option<Facet> {
+    if facet.is_root() {
+        return None;
+    }
+    let mut facet_after_bytes: String = facet.encoded_str().to_owned();
+    facet_after_bytes.push('\u{1}');
+    Some(Facet::from_encoded_string(facet_after_bytes))
+}
+
+/// Removes the children of `facet` from the merged counts, except the first `limit` ones
+/// following `order`.
+///
+/// The children are moved out of the map and pushed into a bounded [`TopNComputer`], so that
+/// only the kept ones are inserted back.
+fn retain_top_children(
+    facet_counts: &mut BTreeMap<Facet, u64>,
+    facet: &Facet,
+    limit: usize,
+    order: FacetOrder,
+) {
+    let mut children = facet_counts.split_off(facet);
+    if let Some(facet_after) = facet_after_children(facet) {
+        let mut facets_after = children.split_off(&facet_after);
+        facet_counts.append(&mut facets_after);
+    }
+    if let Some(count) = children.remove(facet) {
+        facet_counts.insert(facet.clone(), count);
+    }
+    // Ties on the feature are broken by ascending facet, so a constant feature keeps the
+    // first children in the lexicographic order.
+    let mut top_children: TopNComputer<u64, (Facet, u64)> = TopNComputer::new(limit);
+    for (child, count) in children {
+        let feature = match order {
+            FacetOrder::ByCount => count,
+            FacetOrder::ByFacet => 0,
+        };
+        top_children.push(feature, (child, count));
+    }
+    for top_child in top_children.into_vec() {
+        let (child, count) = top_child.doc;
+        facet_counts.insert(child, count);
+    }
+}
+
 fn is_child_facet(parent_facet: &[u8], possible_child_facet: &[u8]) -> bool {
     if !possible_child_facet.starts_with(parent_facet) {
         return false;
@@ -434,13 +509,9 @@ impl FacetCounts {
     where Facet: From<T> {
         let facet = Facet::from(facet_from);
         let lower_bound = Bound::Excluded(facet.clone());
-        let upper_bound = if facet.is_root() {
-            Bound::Unbounded
-        } else {
-            let mut facet_after_bytes: String = facet.encoded_str().to_owned();
-            facet_after_bytes.push('\u{1}');
-            let facet_after = Facet::from_encoded_string(facet_after_bytes);
-            Bound::Excluded(facet_after)
+        let upper_bound = match facet_after_children(&facet) {
+            Some(facet_after) => Bound::Excluded(facet_after),
+            None => Bound::Unbounded,
         };
         let underlying: btree_map::Range<'_, _, _> =
             self.facet_counts.range((lower_bound, upper_bound));
@@ -491,7 +562,7 @@ mod tests {
     use rand::prelude::SliceRandom;
     use rand::{thread_rng, Rng};
 
-    use super::{FacetCollector, FacetCounts};
+    use super::{FacetCollector, FacetCounts, FacetOrder};
     use crate::collector::facet_collector::compress_mapping;
     use crate::collector::Count;
     use crate::index::Index;
@@ -810,6 +881,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_facet_collector_with_limit() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let facet_field = schema_builder.add_facet_field("facet", FacetOptions::default());
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+
+        let mut index_writer = index.writer_for_tests()?;
+        // Child `/facet/cXX` appears in `XX + 1` documents, split over two segments.
+        for child_id in 0..40 {
+            for doc_id in 0..=child_id {
+                index_writer.add_document(doc!(
+                    facet_field => Facet::from(&format!("/facet/c{child_id:02}")),
+                    facet_field => Facet::from(&format!("/other/o{}", doc_id % 3)),
+                ))?;
+            }
+            if child_id == 20 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet_with_limit("/facet", 5, FacetOrder::ByCount);
+        facet_collector.add_facet("/other");
+        let counts: FacetCounts = searcher.search(&AllQuery, &facet_collector)?;
+        let facets: Vec<(String, u64)> = counts
+            .get("/facet")
+            .map(|(facet, count)| (facet.to_string(), count))
+            .collect();
+        assert_eq!(
+            facets,
+            vec![
+                ("/facet/c35".to_string(), 36),
+                ("/facet/c36".to_string(), 37),
+                ("/facet/c37".to_string(), 38),
+                ("/facet/c38".to_string(), 39),
+                ("/facet/c39".to_string(), 40),
+            ]
+        );
+        // Facets added without a limit are not truncated.
+        assert_eq!(counts.get("/other").count(), 3);
+
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet_with_limit("/facet", 3, FacetOrder::ByFacet);
+        let counts: FacetCounts = searcher.search(&AllQuery, &facet_collector)?;
+        let facets: Vec<(String, u64)> = counts
+            .get("/facet")
+            .map(|(facet, count)| (facet.to_string(), count))
+            .collect();
+        assert_eq!(
+            facets,
+            vec![
+                ("/facet/c00".to_string(), 1),
+                ("/facet/c01".to_string(), 2),
+                ("/facet/c02".to_string(), 3),
+            ]
+        );
+
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet_with_limit("/facet", 0, FacetOrder::ByCount);
+        let counts: FacetCounts = searcher.search(&AllQuery, &facet_collector)?;
+        assert_eq!(counts.get("/facet").count(), 0);
+        Ok(())
+    }
+
     #[test]
     fn is_child_facet() {
         assert!(super::is_child_facet(&b"foo"[..], &b"foo\0bar"[..]));
@@ -0,0 +1,242 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::{Collector, SegmentCollector, TopNComputer};
+use crate::schema::document::DocumentDeserialize;
+use crate::store::{StoreReader, DOCSTORE_CACHE_CAPACITY};
+use crate::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader, TantivyDocument};
+
+/// The `FilteredTopDocs` collector returns the top `limit` documents by score that pass a
+/// filter evaluated on the stored document.
+///
+/// This is useful when the filter cannot be expressed as a query or as a predicate over a fast
+/// field, e.g. a complex predicate over a JSON blob.
+///
+/// Each segment keeps its `limit + num_extra_candidates` best scoring documents. Once the segment
+/// has been collected, these candidates are fetched from the doc store in decreasing score order,
+/// and the filter is applied until `limit` of them pass.
+///
+/// # Performance
+///
+/// Every candidate that is examined is fetched and deserialized from the doc store, which
+/// is orders of magnitude slower than collecting a document: this requires decompressing the
+/// block containing it, unless the block is in the cache.
+/// The collector is designed for filters that reject a small fraction of the candidates.
+///
+/// If more than `num_extra_candidates` of the candidates of a segment are rejected, the segment
+/// returns fewer than `limit` documents, and the result may be under-filled even though
+/// other matching documents would pass the filter. Raise `num_extra_candidates` with
+/// [`FilteredTopDocs::with_num_extra_candidates`] to mitigate this.
+///
+/// ```rust
+/// use tantivy::collector::FilteredTopDocs;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, Value, STORED, TEXT};
+/// use tantivy::{doc, Index, TantivyDocument};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT | STORED);
+/// let year = schema_builder.add_u64_field("year", STORED);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(title => "The Diary of Muadib", year => 1965u64))?;
+/// index_writer.add_document(doc!(title => "The Diary of a Young Girl", year => 1947u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+/// let collector = FilteredTopDocs::with_limit(10, move |doc: &TantivyDocument| {
+///     doc.get_first(year).and_then(|value| value.as_u64()) < Some(1950)
+/// });
+/// let top_docs = searcher.search(&query, &collector)?;
+/// assert_eq!(top_docs.len(), 1);
+/// let doc: TantivyDocument = searcher.doc(top_docs[0].1)?;
+/// assert_eq!(
+///     doc.get_first(title).and_then(|value| value.as_str()),
+///     Some("The Diary of a Young Girl")
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct FilteredTopDocs<F, D = TantivyDocument> {
+    limit: usize,
+    num_extra_candidates: usize,
+    filter: Arc<F>,
+    _document: PhantomData<fn(&D)>,
+}
+
+impl<F, D> FilteredTopDocs<F, D>
+where
+    F: Fn(&D) -> bool + Send + Sync + 'static,
+    D: DocumentDeserialize + 'static,
+{
+    /// Creates a `FilteredTopDocs` collector returning at most `limit` documents passing the
+    /// `filter`.
+    ///
+    /// By default, `limit` extra candidates are fetched for each segment.
+    ///
+    /// # Panics
+    /// The method panics if limit is 0
+    pub fn with_limit(limit: usize, filter: F) -> FilteredTopDocs<F, D> {
+        assert!(limit >= 1, "Limit must be strictly greater than 0.");
+        FilteredTopDocs {
+            limit,
+            num_extra_candidates: limit,
+            filter: Arc::new(filter),
+            _document: PhantomData,
+        }
+    }
+
+    /// Sets the number of candidates, in addition to `limit`, kept for each segment in order to
+    /// replace the documents rejected by the filter.
+    #[must_use]
+    pub fn with_num_extra_candidates(mut self, num_extra_candidates: usize) -> Self {
+        self.num_extra_candidates = num_extra_candidates;
+        self
+    }
+}
+
+impl<F, D> Collector for FilteredTopDocs<F, D>
+where
+    F: Fn(&D) -> bool + Send + Sync + 'static,
+    D: DocumentDeserialize + 'static,
+{
+    type Fruit = Vec<(Score, DocAddress)>;
+
+    type Child = FilteredTopDocsSegmentCollector<F, D>;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<FilteredTopDocsSegmentCollector<F, D>> {
+        Ok(FilteredTopDocsSegmentCollector {
+            segment_ord: segment_local_id,
+            limit: self.limit,
+            candidates: TopNComputer::new(self.limit + self.num_extra_candidates),
+            store_reader: segment_reader.get_store_reader(DOCSTORE_CACHE_CAPACITY)?,
+            filter: self.filter.clone(),
+            _document: PhantomData,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<crate::Result<Vec<(Score, DocAddress)>>>,
+    ) -> crate::Result<Vec<(Score, DocAddress)>> {
+        let mut top_n: TopNComputer<Score, DocAddress> = TopNComputer::new(self.limit);
+        for segment_fruit in segment_fruits {
+            for (score, doc_address) in segment_fruit? {
+                top_n.push(score, doc_address);
+            }
+        }
+        Ok(top_n
+            .into_sorted_vec()
+            .into_iter()
+            .map(|comparable_doc| (comparable_doc.feature, comparable_doc.doc))
+            .collect())
+    }
+}
+
+/// Segment collector of the [`FilteredTopDocs`] collector.
+pub struct FilteredTopDocsSegmentCollector<F, D> {
+    segment_ord: SegmentOrdinal,
+    limit: usize,
+    candidates: TopNComputer<Score, DocId>,
+    store_reader: StoreReader,
+    filter: Arc<F>,
+    _document: PhantomData<fn(&D)>,
+}
+
+impl<F, D> SegmentCollector for FilteredTopDocsSegmentCollector<F, D>
+where
+    F: Fn(&D) -> bool + Send + Sync + 'static,
+    D: DocumentDeserialize + 'static,
+{
+    type Fruit = crate::Result<Vec<(Score, DocAddress)>>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        self.candidates.push(score, doc);
+    }
+
+    fn harvest(self) -> crate::Result<Vec<(Score, DocAddress)>> {
+        let mut top_docs = Vec::with_capacity(self.limit);
+        for candidate in self.candidates.into_sorted_vec() {
+            if top_docs.len() == self.limit {
+                break;
+            }
+            let doc: D = self.store_reader.get(candidate.doc)?;
+            if (self.filter)(&doc) {
+                top_docs.push((
+                    candidate.feature,
+                    DocAddress::new(self.segment_ord, candidate.doc),
+                ));
+            }
+        }
+        Ok(top_docs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilteredTopDocs;
+    use crate::collector::TopDocs;
+    use crate::query::TermQuery;
+    use crate::schema::{IndexRecordOption, Schema, Value, STORED, TEXT};
+    use crate::{DocAddress, Index, IndexWriter, Score, TantivyDocument, Term};
+
+    #[test]
+    fn test_filtered_top_docs() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let allowed = schema_builder.add_bool_field("allowed", STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // The more occurrences of `hello`, the higher the score.
+        for (num_hellos, is_allowed) in [
+            (1, true),
+            (2, true),
+            (3, true),
+            (4, false),
+            (5, false),
+            (6, true),
+            (7, false),
+        ] {
+            let text_value =
+                vec!["hello"; num_hellos].join(" ") + &" filler".repeat(10 - num_hellos);
+            index_writer.add_document(doc!(text => text_value, allowed => is_allowed))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(text, "hello"),
+            IndexRecordOption::WithFreqs,
+        );
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(7))?;
+        let top_doc_ids: Vec<u32> = top_docs
+            .iter()
+            .map(|(_, doc_address)| doc_address.doc_id)
+            .collect();
+        assert_eq!(top_doc_ids, vec![6, 5, 4, 3, 2, 1, 0]);
+
+        let is_allowed = move |doc: &TantivyDocument| {
+            doc.get_first(allowed).and_then(|value| value.as_bool()) == Some(true)
+        };
+        // The rejected high-scoring documents are replaced by the next best ones.
+        let filtered_top_docs =
+            searcher.search(&query, &FilteredTopDocs::with_limit(3, is_allowed))?;
+        let expected: Vec<(Score, DocAddress)> = [1, 4, 5].iter().map(|&i| top_docs[i]).collect();
+        assert_eq!(filtered_top_docs, expected);
+
+        // Without extra candidates, the rejected documents are not replaced.
+        let collector = FilteredTopDocs::with_limit(3, is_allowed).with_num_extra_candidates(0);
+        let filtered_top_docs = searcher.search(&query, &collector)?;
+        assert_eq!(filtered_top_docs, vec![top_docs[1]]);
+        Ok(())
+    }
+}
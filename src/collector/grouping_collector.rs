@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use columnar::{Cardinality, Column};
+
+use super::top_collector::ComparableDoc;
+use super::{Collector, SegmentCollector, TopNComputer};
+use crate::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+/// The `GroupingCollector` collapses the matching documents on the value of a fast field, and
+/// returns the best scoring documents of the best groups. This is typically used to display one
+/// result per domain, or per category.
+///
+/// The documents are grouped on the value of a single-valued `u64` fast field. Documents
+/// without a value for the field are ignored.
+///
+/// The groups are sorted by decreasing score of their best document, and the documents of a
+/// group by decreasing score. As for [`TopDocs`](crate::collector::TopDocs), ties are broken
+/// by increasing `DocAddress`.
+///
+/// The memory usage is proportional to the number of distinct groups of the matching
+/// documents, as a group that is not among the best groups of a segment may still be among the
+/// best groups of the index.
+///
+/// ```rust
+/// use tantivy::collector::GroupingCollector;
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let domain = schema_builder.add_u64_field("domain", FAST);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+///
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(title => "diary", domain => 1u64))?;
+/// index_writer.add_document(doc!(title => "diary diary", domain => 1u64))?;
+/// index_writer.add_document(doc!(title => "diary of a cow", domain => 2u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+/// let groups = searcher.search(&query, &GroupingCollector::new("domain", 10))?;
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].key, 1);
+/// // Only the best document of each group is returned by default.
+/// assert_eq!(groups[0].hits.len(), 1);
+/// assert_eq!(groups[1].key, 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GroupingCollector {
+    field: String,
+    num_groups: usize,
+    hits_per_group: usize,
+}
+
+impl GroupingCollector {
+    /// Creates a `GroupingCollector` grouping the documents on the values of the fast field
+    /// `field`, and returning at most `num_groups` groups.
+    ///
+    /// # Panics
+    /// The method panics if `num_groups` is 0.
+    pub fn new(field: impl ToString, num_groups: usize) -> GroupingCollector {
+        assert!(
+            num_groups >= 1,
+            "The number of groups must be strictly greater than 0."
+        );
+        GroupingCollector {
+            field: field.to_string(),
+            num_groups,
+            hits_per_group: 1,
+        }
+    }
+
+    /// Sets the maximum number of documents returned for each group. Defaults to 1.
+    ///
+    /// # Panics
+    /// The method panics if `hits_per_group` is 0.
+    pub fn with_hits_per_group(mut self, hits_per_group: usize) -> GroupingCollector {
+        assert!(
+            hits_per_group >= 1,
+            "The number of hits per group must be strictly greater than 0."
+        );
+        self.hits_per_group = hits_per_group;
+        self
+    }
+}
+
+/// A group of documents sharing the same fast field value, as returned by the
+/// [`GroupingCollector`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    /// The fast field value shared by the documents of the group.
+    pub key: u64,
+    /// The best documents of the group, sorted by decreasing score.
+    pub hits: Vec<(Score, DocAddress)>,
+}
+
+impl Group {
+    fn best_hit(&self) -> ComparableDoc<Score, DocAddress, true> {
+        let (score, doc_address) = self.hits[0];
+        ComparableDoc {
+            feature: score,
+            doc: doc_address,
+        }
+    }
+}
+
+impl Collector for GroupingCollector {
+    type Fruit = Vec<Group>;
+
+    type Child = GroupingSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<GroupingSegmentCollector> {
+        let column = segment_reader.fast_fields().u64(&self.field)?;
+        if column.get_cardinality() == Cardinality::Multivalued {
+            return Err(TantivyError::InvalidArgument(format!(
+                "GroupingCollector requires a single-valued fast field: {}",
+                self.field
+            )));
+        }
+        Ok(GroupingSegmentCollector {
+            segment_ord: segment_local_id,
+            column,
+            hits_per_group: self.hits_per_group,
+            groups: HashMap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Vec<Group>>) -> crate::Result<Vec<Group>> {
+        // A group may span several segments.
+        let mut top_n_per_group: HashMap<u64, TopNComputer<Score, DocAddress>> = HashMap::new();
+        for group in segment_fruits.into_iter().flatten() {
+            let top_n = top_n_per_group
+                .entry(group.key)
+                .or_insert_with(|| TopNComputer::new(self.hits_per_group));
+            for (score, doc_address) in group.hits {
+                top_n.push(score, doc_address);
+            }
+        }
+        let mut groups: Vec<Group> = top_n_per_group
+            .into_iter()
+            .map(|(key, top_n)| Group {
+                key,
+                hits: top_n
+                    .into_sorted_vec()
+                    .into_iter()
+                    .map(|comparable_doc| (comparable_doc.feature, comparable_doc.doc))
+                    .collect(),
+            })
+            .collect();
+        groups.sort_unstable_by_key(Group::best_hit);
+        groups.truncate(self.num_groups);
+        Ok(groups)
+    }
+}
+
+/// Segment collector of the [`GroupingCollector`].
+pub struct GroupingSegmentCollector {
+    segment_ord: SegmentOrdinal,
+    column: Column<u64>,
+    hits_per_group: usize,
+    groups: HashMap<u64, TopNComputer<Score, DocId>>,
+}
+
+impl SegmentCollector for GroupingSegmentCollector {
+    type Fruit = Vec<Group>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let Some(key) = self.column.first(doc) else {
+            return;
+        };
+        let hits_per_group = self.hits_per_group;
+        self.groups
+            .entry(key)
+            .or_insert_with(|| TopNComputer::new(hits_per_group))
+            .push(score, doc);
+    }
+
+    fn harvest(self) -> Vec<Group> {
+        let segment_ord = self.segment_ord;
+        self.groups
+            .into_iter()
+            .map(|(key, top_n)| Group {
+                key,
+                hits: top_n
+                    .into_vec()
+                    .into_iter()
+                    .map(|comparable_doc| {
+                        let doc_address = DocAddress::new(segment_ord, comparable_doc.doc);
+                        (comparable_doc.feature, doc_address)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GroupingCollector;
+    use crate::collector::TopDocs;
+    use crate::query::{AllQuery, QueryParser};
+    use crate::schema::{Schema, FAST, TEXT};
+    use crate::{DocAddress, Index, IndexWriter, Score};
+
+    #[test]
+    fn test_grouping_collector_across_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let category = schema_builder.add_u64_field("category", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // The number of occurrences of `rust` drives the score.
+        let docs_per_segment: [&[(&str, u64)]; 2] = [
+            &[
+                ("rust", 1),
+                ("rust rust rust", 2),
+                ("rust rust", 1),
+                ("go", 3),
+            ],
+            &[("rust rust rust rust", 1), ("rust", 3), ("rust rust", 2)],
+        ];
+        for docs in docs_per_segment {
+            for &(text, category_id) in docs {
+                index_writer.add_document(doc!(title => text, category => category_id))?;
+            }
+            index_writer.commit()?;
+        }
+        // A document without a category is ignored.
+        index_writer.add_document(doc!(title => "rust rust rust rust rust"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        let query = QueryParser::for_index(&index, vec![title]).parse_query("rust")?;
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10))?;
+        let score_of = |doc_address: DocAddress| -> Score {
+            top_docs
+                .iter()
+                .find(|(_, top_doc_address)| *top_doc_address == doc_address)
+                .unwrap()
+                .0
+        };
+
+        let groups = searcher.search(&query, &GroupingCollector::new("category", 10))?;
+        let keys: Vec<u64> = groups.iter().map(|group| group.key).collect();
+        assert_eq!(keys, vec![1, 2, 3]);
+        for group in &groups {
+            assert_eq!(group.hits.len(), 1);
+        }
+        assert_eq!(groups[0].hits[0].1, DocAddress::new(1, 0));
+        assert_eq!(groups[1].hits[0].1, DocAddress::new(0, 1));
+        assert_eq!(groups[2].hits[0].1, DocAddress::new(1, 1));
+        let best_scores: Vec<Score> = groups.iter().map(|group| group.hits[0].0).collect();
+        assert!(best_scores.windows(2).all(|window| window[0] >= window[1]));
+        assert_eq!(best_scores[0], score_of(DocAddress::new(1, 0)));
+
+        // The number of groups is limited, and groups can hold several hits.
+        let collector = GroupingCollector::new("category", 2).with_hits_per_group(2);
+        let groups = searcher.search(&query, &collector)?;
+        assert_eq!(groups.len(), 2);
+        let hits: Vec<DocAddress> = groups[0].hits.iter().map(|hit| hit.1).collect();
+        assert_eq!(hits, vec![DocAddress::new(1, 0), DocAddress::new(0, 2)]);
+        let hits: Vec<DocAddress> = groups[1].hits.iter().map(|hit| hit.1).collect();
+        assert_eq!(hits, vec![DocAddress::new(0, 1), DocAddress::new(1, 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_grouping_collector_multivalued_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_u64_field("category", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(category => 1u64, category => 2u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert!(matches!(
+            searcher.search(&AllQuery, &GroupingCollector::new("category", 10)),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+}
@@ -18,7 +18,10 @@ use crate::{DocId, Score};
 /// An histogram is then defined as a `Vec<u64>` of length `num_buckets`, that contains a count of
 /// documents for each value bucket.
 ///
-/// See also [`HistogramCollector::new()`].
+/// See also [`HistogramCollector::new()`] and [`HistogramCollector::for_range()`].
+///
+/// Values outside of the buckets are ignored. Use
+/// [`HistogramCollector::with_out_of_range_counts()`] to count them as well.
 ///
 /// # Warning
 ///
@@ -60,10 +63,67 @@ impl HistogramCollector {
             divider: DividerU64::divide_by(bucket_width),
         }
     }
+
+    /// Builds a new HistogramCollector covering `[min_value, max_value)` with buckets of width
+    /// `bucket_width`.
+    ///
+    /// If the width of the range is not a multiple of `bucket_width`, the last bucket extends
+    /// past `max_value`.
+    ///
+    /// # Disclaimer
+    /// This function panics if the field given is of type f64, or if `max_value` is lower than
+    /// `min_value`.
+    pub fn for_range<TFastValue: FastValue>(
+        field: String,
+        min_value: TFastValue,
+        max_value: TFastValue,
+        bucket_width: u64,
+    ) -> HistogramCollector {
+        let range_width = max_value
+            .to_u64()
+            .checked_sub(min_value.to_u64())
+            .expect("max_value should not be lower than min_value");
+        let num_buckets = range_width.div_ceil(bucket_width) as usize;
+        HistogramCollector::new(field, min_value, bucket_width, num_buckets)
+    }
+
+    /// Returns a collector computing the same histogram, along with the number of values
+    /// falling below or above its buckets.
+    pub fn with_out_of_range_counts(self) -> HistogramCountsCollector {
+        HistogramCountsCollector(self)
+    }
+}
+
+/// Histogram computed by a [`HistogramCountsCollector`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistogramCounts {
+    /// Number of values in each bucket.
+    pub counts: Vec<u64>,
+    /// Number of values lower than the lower bound of the first bucket.
+    pub underflow: u64,
+    /// Number of values greater than or equal to the upper bound of the last bucket.
+    pub overflow: u64,
+}
+
+impl HistogramCounts {
+    fn merge(&mut self, other: &HistogramCounts) {
+        add_arrays_into(&mut self.counts, &other.counts);
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+    }
 }
 
+/// `HistogramCountsCollector` computes a histogram like the [`HistogramCollector`] it was built
+/// from, and also counts the values outside of the buckets of the histogram.
+///
+/// See [`HistogramCollector::with_out_of_range_counts()`].
+#[derive(Clone)]
+pub struct HistogramCountsCollector(HistogramCollector);
+
 struct HistogramComputer {
     counts: Vec<u64>,
+    underflow: u64,
+    overflow: u64,
     min_value: u64,
     divider: DividerU64,
 }
@@ -72,17 +132,24 @@ impl HistogramComputer {
     #[inline]
     pub(crate) fn add_value(&mut self, value: u64) {
         if value < self.min_value {
+            self.underflow += 1;
             return;
         }
         let delta = value - self.min_value;
         let bucket_id: usize = self.divider.divide(delta) as usize;
         if bucket_id < self.counts.len() {
             self.counts[bucket_id] += 1;
+        } else {
+            self.overflow += 1;
         }
     }
 
-    fn harvest(self) -> Vec<u64> {
-        self.counts
+    fn harvest(self) -> HistogramCounts {
+        HistogramCounts {
+            counts: self.counts,
+            underflow: self.underflow,
+            overflow: self.overflow,
+        }
     }
 }
 pub struct SegmentHistogramCollector {
@@ -91,7 +158,7 @@ pub struct SegmentHistogramCollector {
 }
 
 impl SegmentCollector for SegmentHistogramCollector {
-    type Fruit = Vec<u64>;
+    type Fruit = HistogramCounts;
 
     fn collect(&mut self, doc: DocId, _score: Score) {
         let value = self.column_u64.get_val(doc);
@@ -120,6 +187,8 @@ impl Collector for HistogramCollector {
         Ok(SegmentHistogramCollector {
             histogram_computer: HistogramComputer {
                 counts: vec![0; self.num_buckets],
+                underflow: 0,
+                overflow: 0,
                 min_value: self.min_value,
                 divider: self.divider,
             },
@@ -131,8 +200,44 @@ impl Collector for HistogramCollector {
         false
     }
 
-    fn merge_fruits(&self, child_histograms: Vec<Vec<u64>>) -> crate::Result<Vec<u64>> {
-        Ok(add_vecs(child_histograms, self.num_buckets))
+    fn merge_fruits(&self, child_histograms: Vec<HistogramCounts>) -> crate::Result<Vec<u64>> {
+        let child_counts = child_histograms
+            .into_iter()
+            .map(|histogram| histogram.counts)
+            .collect();
+        Ok(add_vecs(child_counts, self.num_buckets))
+    }
+}
+
+impl Collector for HistogramCountsCollector {
+    type Fruit = HistogramCounts;
+    type Child = SegmentHistogramCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: crate::SegmentOrdinal,
+        segment: &crate::SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        self.0.for_segment(segment_local_id, segment)
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        child_histograms: Vec<HistogramCounts>,
+    ) -> crate::Result<HistogramCounts> {
+        let mut histogram = HistogramCounts {
+            counts: vec![0; self.0.num_buckets],
+            underflow: 0,
+            overflow: 0,
+        };
+        for child_histogram in &child_histograms {
+            histogram.merge(child_histogram);
+        }
+        Ok(histogram)
     }
 }
 
@@ -157,7 +262,7 @@ mod tests {
     use fastdivide::DividerU64;
     use query::AllQuery;
 
-    use super::{add_vecs, HistogramCollector, HistogramComputer};
+    use super::{add_vecs, HistogramCollector, HistogramComputer, HistogramCounts};
     use crate::schema::{Schema, FAST};
     use crate::time::{Date, Month};
     use crate::{query, DateTime, Index};
@@ -183,34 +288,44 @@ mod tests {
         // [9..11)
         let mut histogram_computer = HistogramComputer {
             counts: vec![0; 5],
+            underflow: 0,
+            overflow: 0,
             min_value: 1,
             divider: DividerU64::divide_by(2),
         };
         histogram_computer.add_value(1);
         histogram_computer.add_value(7);
-        assert_eq!(histogram_computer.harvest(), vec![1, 0, 0, 1, 0]);
+        assert_eq!(histogram_computer.harvest().counts, vec![1, 0, 0, 1, 0]);
     }
 
     #[test]
     fn test_histogram_too_low_is_ignored() {
         let mut histogram_computer = HistogramComputer {
             counts: vec![0; 5],
+            underflow: 0,
+            overflow: 0,
             min_value: 2,
             divider: DividerU64::divide_by(2),
         };
         histogram_computer.add_value(0);
-        assert_eq!(histogram_computer.harvest(), vec![0, 0, 0, 0, 0]);
+        let histogram = histogram_computer.harvest();
+        assert_eq!(histogram.counts, vec![0, 0, 0, 0, 0]);
+        assert_eq!(histogram.underflow, 1);
     }
 
     #[test]
     fn test_histogram_too_high_is_ignored() {
         let mut histogram_computer = HistogramComputer {
             counts: vec![0u64; 5],
+            underflow: 0,
+            overflow: 0,
             min_value: 0,
             divider: DividerU64::divide_by(2),
         };
         histogram_computer.add_value(10);
-        assert_eq!(histogram_computer.harvest(), vec![0, 0, 0, 0, 0]);
+        let histogram = histogram_computer.harvest();
+        assert_eq!(histogram.counts, vec![0, 0, 0, 0, 0]);
+        assert_eq!(histogram.overflow, 1);
     }
     #[test]
     fn test_no_segments() -> crate::Result<()> {
@@ -302,4 +417,47 @@ mod tests {
         assert_eq!(week_histogram, vec![0, 0, 1, 1, 0, 0, 1, 0, 0, 0]);
         Ok(())
     }
+
+    #[test]
+    fn test_histogram_out_of_range_merge() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let val_field = schema_builder.add_u64_field("val_field", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut writer = index.writer_for_tests()?;
+        for vals in [&[3u64, 10, 25][..], &[9, 30, 51], &[5, 49, 50, 12]] {
+            for &val in vals {
+                writer.add_document(doc!(val_field=>val))?;
+            }
+            writer.commit()?;
+        }
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        // [10..20), [20..30), [30..40), [40..50)
+        let histogram_collector =
+            HistogramCollector::for_range("val_field".to_string(), 10u64, 50u64, 10);
+        assert_eq!(
+            searcher.search(&AllQuery, &histogram_collector)?,
+            vec![2, 1, 1, 1]
+        );
+        let histogram =
+            searcher.search(&AllQuery, &histogram_collector.with_out_of_range_counts())?;
+        assert_eq!(
+            histogram,
+            HistogramCounts {
+                counts: vec![2, 1, 1, 1],
+                underflow: 3,
+                overflow: 2,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_histogram_for_range_partial_last_bucket() {
+        let histogram_collector =
+            HistogramCollector::for_range("val_field".to_string(), -5i64, 20i64, 10);
+        assert_eq!(histogram_collector.num_buckets, 3);
+    }
 }
@@ -0,0 +1,232 @@
+use std::marker::PhantomData;
+
+use columnar::{Column, DynamicColumn, HasAssociatedColumnType};
+
+use super::{Collector, SegmentCollector};
+use crate::{DocId, Score, SegmentOrdinal, SegmentReader};
+
+/// The minimum and the maximum values computed by a [`MinMaxCollector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinMax<T> {
+    /// The smallest value.
+    pub min: T,
+    /// The largest value.
+    pub max: T,
+}
+
+impl<T: PartialOrd + Copy> MinMax<T> {
+    fn new(val: T) -> MinMax<T> {
+        MinMax { min: val, max: val }
+    }
+
+    fn merge(&mut self, other: &MinMax<T>) {
+        if other.min < self.min {
+            self.min = other.min;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+        }
+    }
+}
+
+fn merge_min_max<T: PartialOrd + Copy>(min_max_opt: &mut Option<MinMax<T>>, other: MinMax<T>) {
+    match min_max_opt {
+        Some(min_max) => min_max.merge(&other),
+        None => *min_max_opt = Some(other),
+    }
+}
+
+/// The `MinMaxCollector` computes the minimum and the maximum values of a numeric fast field
+/// over the matching documents, e.g. to display the price range of the results.
+///
+/// All of the values of a multi-valued field are taken into account. The fruit is `None` if
+/// none of the matching documents has a value for the field, or if the field does not exist.
+///
+/// This is a lightweight alternative to the
+/// [`StatsAggregation`](crate::aggregation::metric::StatsAggregation).
+///
+/// ```rust
+/// use tantivy::collector::{MinMax, MinMaxCollector};
+/// use tantivy::query::QueryParser;
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let price = schema_builder.add_u64_field("price", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(title => "red shoes", price => 40u64))?;
+/// index_writer.add_document(doc!(title => "blue shoes", price => 25u64))?;
+/// index_writer.add_document(doc!(title => "red hat", price => 10u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("shoes")?;
+/// let min_max = searcher.search(&query, &MinMaxCollector::<u64>::new("price"))?;
+/// assert_eq!(min_max, Some(MinMax { min: 25, max: 40 }));
+/// # Ok(())
+/// # }
+/// ```
+pub struct MinMaxCollector<T> {
+    field: String,
+    _value: PhantomData<T>,
+}
+
+impl<T> MinMaxCollector<T> {
+    /// Creates a `MinMaxCollector` tracking the values of the fast field `field`.
+    pub fn new(field: impl ToString) -> MinMaxCollector<T> {
+        MinMaxCollector {
+            field: field.to_string(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<T> Collector for MinMaxCollector<T>
+where
+    T: HasAssociatedColumnType,
+    DynamicColumn: Into<Option<Column<T>>>,
+{
+    type Fruit = Option<MinMax<T>>;
+
+    type Child = MinMaxSegmentCollector<T>;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<MinMaxSegmentCollector<T>> {
+        let column_opt = segment_reader.fast_fields().column_opt(&self.field)?;
+        Ok(MinMaxSegmentCollector {
+            column_opt,
+            min_max_opt: None,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<Option<MinMax<T>>>,
+    ) -> crate::Result<Option<MinMax<T>>> {
+        let mut min_max_opt = None;
+        for segment_min_max in segment_fruits.into_iter().flatten() {
+            merge_min_max(&mut min_max_opt, segment_min_max);
+        }
+        Ok(min_max_opt)
+    }
+}
+
+/// Segment collector of the [`MinMaxCollector`].
+pub struct MinMaxSegmentCollector<T> {
+    column_opt: Option<Column<T>>,
+    min_max_opt: Option<MinMax<T>>,
+}
+
+impl<T: HasAssociatedColumnType> SegmentCollector for MinMaxSegmentCollector<T> {
+    type Fruit = Option<MinMax<T>>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        if let Some(column) = self.column_opt.as_ref() {
+            for val in column.values_for_doc(doc) {
+                merge_min_max(&mut self.min_max_opt, MinMax::new(val));
+            }
+        }
+    }
+
+    fn harvest(self) -> Option<MinMax<T>> {
+        self.min_max_opt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MinMax, MinMaxCollector};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, FAST, STRING};
+    use crate::{DateTime, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_min_max_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING);
+        let price = schema_builder.add_f64_field("price", FAST);
+        let sizes = schema_builder.add_i64_field("sizes", FAST);
+        let date = schema_builder.add_date_field("date", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(
+            category => "shoes",
+            price => 42.5f64,
+            sizes => 38i64,
+            sizes => 45i64,
+            date => DateTime::from_timestamp_secs(1_000),
+        ))?;
+        index_writer.add_document(doc!(category => "hat", price => 9.99f64, sizes => 1i64))?;
+        index_writer.commit()?;
+        // A segment without any shoes.
+        index_writer.add_document(doc!(category => "hat", price => 15.0f64, sizes => 2i64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(category => "shoes", sizes => -3i64))?;
+        index_writer.add_document(doc!(
+            category => "shoes",
+            price => 80.0f64,
+            sizes => 40i64,
+            date => DateTime::from_timestamp_secs(10),
+        ))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+
+        let shoes = TermQuery::new(
+            Term::from_field_text(category, "shoes"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(
+            searcher.search(&shoes, &MinMaxCollector::<f64>::new("price"))?,
+            Some(MinMax {
+                min: 42.5,
+                max: 80.0
+            })
+        );
+        // All of the values of the multi-valued field are considered.
+        assert_eq!(
+            searcher.search(&shoes, &MinMaxCollector::<i64>::new("sizes"))?,
+            Some(MinMax { min: -3, max: 45 })
+        );
+        assert_eq!(
+            searcher.search(&AllQuery, &MinMaxCollector::<DateTime>::new("date"))?,
+            Some(MinMax {
+                min: DateTime::from_timestamp_secs(10),
+                max: DateTime::from_timestamp_secs(1_000)
+            })
+        );
+
+        // No match, or no value.
+        let socks = TermQuery::new(
+            Term::from_field_text(category, "socks"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(
+            searcher.search(&socks, &MinMaxCollector::<f64>::new("price"))?,
+            None
+        );
+        let hats = TermQuery::new(
+            Term::from_field_text(category, "hat"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(
+            searcher.search(&hats, &MinMaxCollector::<DateTime>::new("date"))?,
+            None
+        );
+        assert_eq!(
+            searcher.search(&AllQuery, &MinMaxCollector::<u64>::new("unknown"))?,
+            None
+        );
+        Ok(())
+    }
+}
@@ -89,7 +89,7 @@ mod count_collector;
 pub use self::count_collector::Count;
 
 mod histogram_collector;
-pub use histogram_collector::HistogramCollector;
+pub use histogram_collector::{HistogramCollector, HistogramCounts, HistogramCountsCollector};
 
 mod multi_collector;
 pub use self::multi_collector::{FruitHandle, MultiCollector, MultiFruit};
@@ -106,15 +106,37 @@ pub use self::custom_score_top_collector::{CustomScorer, CustomSegmentScorer};
 mod tweak_score_top_collector;
 pub use self::tweak_score_top_collector::{ScoreSegmentTweaker, ScoreTweaker};
 mod facet_collector;
-pub use self::facet_collector::{FacetCollector, FacetCounts};
+pub use self::facet_collector::{FacetCollector, FacetCounts, FacetOrder};
 use crate::query::Weight;
 
 mod docset_collector;
-pub use self::docset_collector::DocSetCollector;
+pub use self::docset_collector::{BitSetCollector, DocSetCollector};
+
+mod grouping_collector;
+pub use self::grouping_collector::{Group, GroupingCollector, GroupingSegmentCollector};
+
+mod reservoir_sampling_collector;
+pub use self::reservoir_sampling_collector::{
+    ReservoirSamplingCollector, ReservoirSamplingSegmentCollector, SegmentSample,
+};
 
 mod filter_collector_wrapper;
 pub use self::filter_collector_wrapper::{BytesFilterCollector, FilterCollector};
 
+mod filtered_top_docs;
+pub use self::filtered_top_docs::{FilteredTopDocs, FilteredTopDocsSegmentCollector};
+
+mod term_count_collector;
+pub use self::term_count_collector::{
+    SegmentTermCounts, TermCountCollector, TermCountSegmentCollector,
+};
+
+mod take_while_collector;
+pub use self::take_while_collector::{TakeWhileCollector, TakeWhileSegmentCollector};
+
+mod min_max_collector;
+pub use self::min_max_collector::{MinMax, MinMaxCollector, MinMaxSegmentCollector};
+
 /// `Fruit` is the type for the result of our collection.
 /// e.g. `usize` for the `Count` collector.
 pub trait Fruit: Send + downcast_rs::Downcast {}
@@ -0,0 +1,301 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use super::{Collector, SegmentCollector};
+use crate::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader};
+
+/// The `ReservoirSamplingCollector` returns a uniformly random sample of at most `sample_size`
+/// of the matching documents, without materializing all of the matches.
+///
+/// Each segment maintains a reservoir of `sample_size` documents, and the reservoirs are merged
+/// according to the number of matches of each segment: every subset of `sample_size` matching
+/// documents has the same probability to be returned. If there are fewer matches than
+/// `sample_size`, all of them are returned.
+///
+/// The sample is returned sorted by `DocAddress`.
+///
+/// The random number generator is seeded randomly, unless a seed is given with
+/// [`ReservoirSamplingCollector::with_seed`], in which case the sample is reproducible for a
+/// given searcher.
+///
+/// ```rust
+/// use tantivy::collector::ReservoirSamplingCollector;
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// for i in 0..100 {
+///     index_writer.add_document(doc!(title => format!("doc {i}")))?;
+/// }
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let collector = ReservoirSamplingCollector::new(10).with_seed(42);
+/// let sample = searcher.search(&AllQuery, &collector)?;
+/// assert_eq!(sample.len(), 10);
+/// assert_eq!(sample, searcher.search(&AllQuery, &collector)?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReservoirSamplingCollector {
+    sample_size: usize,
+    seed: u64,
+}
+
+impl ReservoirSamplingCollector {
+    /// Creates a `ReservoirSamplingCollector` returning at most `sample_size` documents.
+    ///
+    /// # Panics
+    /// The method panics if `sample_size` is 0.
+    pub fn new(sample_size: usize) -> ReservoirSamplingCollector {
+        assert!(
+            sample_size >= 1,
+            "The sample size must be strictly greater than 0."
+        );
+        ReservoirSamplingCollector {
+            sample_size,
+            seed: RandomState::new().build_hasher().finish(),
+        }
+    }
+
+    /// Seeds the random number generator, making the sample reproducible.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> ReservoirSamplingCollector {
+        self.seed = seed;
+        self
+    }
+}
+
+/// The sample of the matching documents of a segment, as returned by the
+/// [`ReservoirSamplingSegmentCollector`].
+pub struct SegmentSample {
+    num_matches: u64,
+    docs: Vec<DocAddress>,
+}
+
+impl Collector for ReservoirSamplingCollector {
+    type Fruit = Vec<DocAddress>;
+
+    type Child = ReservoirSamplingSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        _segment_reader: &SegmentReader,
+    ) -> crate::Result<ReservoirSamplingSegmentCollector> {
+        Ok(ReservoirSamplingSegmentCollector {
+            segment_ord: segment_local_id,
+            sample_size: self.sample_size,
+            num_matches: 0,
+            reservoir: Vec::with_capacity(self.sample_size),
+            rng: SplitMix64::new(self.seed ^ (u64::from(segment_local_id) + 1)),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_samples: Vec<SegmentSample>) -> crate::Result<Vec<DocAddress>> {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut segment_samples: Vec<SegmentSample> = segment_samples
+            .into_iter()
+            .filter(|segment_sample| segment_sample.num_matches > 0)
+            .collect();
+        let mut num_remaining_matches: u64 = segment_samples
+            .iter()
+            .map(|segment_sample| segment_sample.num_matches)
+            .sum();
+        let mut sample = Vec::with_capacity(self.sample_size.min(num_remaining_matches as usize));
+        // Each draw picks one of the remaining matches uniformly: the segment it belongs to is
+        // picked proportionally to its number of remaining matches, and the document is picked
+        // uniformly among the documents of the reservoir of the segment. A segment is never
+        // picked more than `min(num_matches, sample_size)` times, i.e. the size of its
+        // reservoir.
+        while sample.len() < self.sample_size && num_remaining_matches > 0 {
+            let mut target = rng.next_below(num_remaining_matches);
+            let segment_sample = segment_samples
+                .iter_mut()
+                .find(|segment_sample| {
+                    if target < segment_sample.num_matches {
+                        true
+                    } else {
+                        target -= segment_sample.num_matches;
+                        false
+                    }
+                })
+                .expect("The remaining matches belong to a segment");
+            let doc_ord = rng.next_below(segment_sample.docs.len() as u64) as usize;
+            sample.push(segment_sample.docs.swap_remove(doc_ord));
+            segment_sample.num_matches -= 1;
+            num_remaining_matches -= 1;
+        }
+        sample.sort_unstable();
+        Ok(sample)
+    }
+}
+
+/// Segment collector of the [`ReservoirSamplingCollector`].
+pub struct ReservoirSamplingSegmentCollector {
+    segment_ord: SegmentOrdinal,
+    sample_size: usize,
+    num_matches: u64,
+    reservoir: Vec<DocId>,
+    rng: SplitMix64,
+}
+
+impl SegmentCollector for ReservoirSamplingSegmentCollector {
+    type Fruit = SegmentSample;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.num_matches += 1;
+        if self.reservoir.len() < self.sample_size {
+            self.reservoir.push(doc);
+            return;
+        }
+        // The document replaces a document of the reservoir with a probability of
+        // `sample_size / num_matches`.
+        let ord = self.rng.next_below(self.num_matches);
+        if ord < self.sample_size as u64 {
+            self.reservoir[ord as usize] = doc;
+        }
+    }
+
+    fn harvest(self) -> SegmentSample {
+        let segment_ord = self.segment_ord;
+        SegmentSample {
+            num_matches: self.num_matches,
+            docs: self
+                .reservoir
+                .into_iter()
+                .map(|doc| DocAddress::new(segment_ord, doc))
+                .collect(),
+        }
+    }
+}
+
+/// A small, seedable pseudo random number generator.
+///
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `[0, upper_bound)`, using Lemire's multiply-shift reduction. The bias
+    /// is negligible for the bounds used here.
+    fn next_below(&mut self, upper_bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(upper_bound)) >> 64) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReservoirSamplingCollector;
+    use crate::collector::{Count, DocSetCollector};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, STRING};
+    use crate::{DocAddress, Index, IndexWriter, Term};
+
+    #[test]
+    fn test_reservoir_sampling_collector_small_result_set() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..20 {
+            let tag_value = if i % 5 == 0 { "rare" } else { "common" };
+            index_writer.add_document(doc!(tag => tag_value))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(Term::from_field_text(tag, "rare"), IndexRecordOption::Basic);
+        // All of the matches are returned when there are fewer of them than the sample size.
+        let sample = searcher.search(&query, &ReservoirSamplingCollector::new(10))?;
+        let mut matches: Vec<DocAddress> = searcher
+            .search(&query, &DocSetCollector)?
+            .into_iter()
+            .collect();
+        matches.sort();
+        assert_eq!(sample, matches);
+
+        let collector = ReservoirSamplingCollector::new(3).with_seed(7);
+        let sample = searcher.search(&AllQuery, &collector)?;
+        assert_eq!(sample.len(), 3);
+        assert!(sample.windows(2).all(|window| window[0] < window[1]));
+        // The sample is reproducible.
+        assert_eq!(searcher.search(&AllQuery, &collector)?, sample);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reservoir_sampling_collector_is_uniform() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // Segments with very different numbers of matches.
+        for num_docs in [10, 60, 130] {
+            for _ in 0..num_docs {
+                index_writer.add_document(doc!(tag => "tag"))?;
+            }
+            index_writer.commit()?;
+        }
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        assert_eq!(searcher.search(&AllQuery, &Count)?, 200);
+
+        let sample_size = 10;
+        let num_samples = 4_000;
+        let mut num_selections: Vec<Vec<u32>> = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment_reader| vec![0u32; segment_reader.max_doc() as usize])
+            .collect();
+        for seed in 0..num_samples {
+            let collector = ReservoirSamplingCollector::new(sample_size).with_seed(seed);
+            let sample = searcher.search(&AllQuery, &collector)?;
+            assert_eq!(sample.len(), sample_size);
+            for doc_address in sample {
+                num_selections[doc_address.segment_ord as usize][doc_address.doc_id as usize] += 1;
+            }
+        }
+
+        // Each document is expected to be selected `sample_size * num_samples / 200` = 200
+        // times. A chi-squared test with 199 degrees of freedom rejects uniformity at the 0.1%
+        // level above 267.
+        let expected = (sample_size as u64 * num_samples / 200) as f64;
+        let chi_squared: f64 = num_selections
+            .iter()
+            .flatten()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi_squared < 267.0, "chi_squared={chi_squared}");
+        // The segments are represented according to their number of matches.
+        for segment_selections in &num_selections {
+            let num_docs = segment_selections.len() as u64;
+            let segment_count: u32 = segment_selections.iter().sum();
+            let expected_count = (sample_size as u64 * num_samples * num_docs / 200) as f64;
+            assert!((segment_count as f64 - expected_count).abs() < 0.05 * expected_count);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use crate::collector::{Collector, SegmentCollector};
+use crate::query::Weight;
+use crate::{DocId, DocSet, Score, SegmentReader, COLLECT_BLOCK_BUFFER_LEN, TERMINATED};
+
+/// The `TakeWhileCollector` passes documents on to the next collector, as long as a predicate
+/// returns `true`.
+///
+/// As soon as the predicate returns `false` for a document, the collection of the current
+/// segment stops: the document is not collected, and the remaining documents of the segment
+/// are not even visited. The fruit of the wrapped collector is still valid, and reflects the
+/// documents collected until then.
+///
+/// The predicate applies to each segment separately: it is cloned for each segment, so that
+/// the state of a stateful predicate, e.g. a number of documents, starts over in every segment.
+/// Segments are not visited in any specific order. Documents within a segment are visited in
+/// increasing `DocId` order.
+///
+/// If the wrapped collector does not require scoring, the score passed to the predicate is
+/// always `0.0`, and the documents accepted by the predicate are passed to the wrapped
+/// collector in blocks, with [`SegmentCollector::collect_block`]. The
+/// [`Collector::collect_segment`] of the wrapped collector is not used, as it cannot be
+/// stopped early.
+///
+/// ```rust
+/// use tantivy::collector::{Count, TakeWhileCollector};
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let schema = schema_builder.build();
+/// let index = Index::create_in_ram(schema);
+///
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(title => "The Name of the Wind"))?;
+/// index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+/// index_writer.add_document(doc!(title => "A Dairy Cow"))?;
+/// index_writer.add_document(doc!(title => "The Diary of a Young Girl"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+///
+/// // Stops after having collected two documents of each segment. The index has a single
+/// // segment here.
+/// let mut num_docs = 0;
+/// let take_two = TakeWhileCollector::new(Count, move |_doc, _score| {
+///     num_docs += 1;
+///     num_docs <= 2
+/// });
+/// assert_eq!(searcher.search(&AllQuery, &take_two)?, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TakeWhileCollector<TCollector, TPredicate> {
+    collector: TCollector,
+    predicate: TPredicate,
+}
+
+impl<TCollector, TPredicate> TakeWhileCollector<TCollector, TPredicate>
+where
+    TCollector: Collector,
+    TPredicate: FnMut(DocId, Score) -> bool + Send + Sync + Clone,
+{
+    /// Create a new `TakeWhileCollector`.
+    pub fn new(collector: TCollector, predicate: TPredicate) -> Self {
+        TakeWhileCollector {
+            collector,
+            predicate,
+        }
+    }
+}
+
+impl<TCollector, TPredicate> Collector for TakeWhileCollector<TCollector, TPredicate>
+where
+    TCollector: Collector,
+    TPredicate: 'static + FnMut(DocId, Score) -> bool + Send + Sync + Clone,
+{
+    type Fruit = TCollector::Fruit;
+
+    type Child = TakeWhileSegmentCollector<TCollector::Child, TPredicate>;
+
+    fn for_segment(
+        &self,
+        segment_local_id: u32,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        let segment_collector = self
+            .collector
+            .for_segment(segment_local_id, segment_reader)?;
+        Ok(TakeWhileSegmentCollector {
+            segment_collector,
+            predicate: self.predicate.clone(),
+            terminated: false,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.collector.requires_scoring()
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<TCollector::Child as SegmentCollector>::Fruit>,
+    ) -> crate::Result<TCollector::Fruit> {
+        self.collector.merge_fruits(segment_fruits)
+    }
+
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: u32,
+        reader: &SegmentReader,
+    ) -> crate::Result<<Self::Child as SegmentCollector>::Fruit> {
+        let mut segment_collector = self.for_segment(segment_ord, reader)?;
+        let alive_bitset_opt = reader.alive_bitset();
+        let mut scorer = weight.scorer(reader, 1.0)?;
+        if self.requires_scoring() {
+            let mut doc = scorer.doc();
+            while doc != TERMINATED {
+                if alive_bitset_opt.map_or(true, |alive_bitset| alive_bitset.is_alive(doc)) {
+                    segment_collector.collect(doc, scorer.score());
+                    if segment_collector.terminated {
+                        break;
+                    }
+                }
+                doc = scorer.advance();
+            }
+        } else {
+            let mut docs = [0; COLLECT_BLOCK_BUFFER_LEN];
+            while !segment_collector.terminated {
+                let num_docs = scorer.fill_buffer(&mut docs);
+                if num_docs == 0 {
+                    break;
+                }
+                // The accepted documents are moved to the beginning of the block.
+                let mut num_accepted = 0;
+                for doc_idx in 0..num_docs {
+                    let doc = docs[doc_idx];
+                    if !alive_bitset_opt.map_or(true, |alive_bitset| alive_bitset.is_alive(doc)) {
+                        continue;
+                    }
+                    if !(segment_collector.predicate)(doc, 0.0) {
+                        segment_collector.terminated = true;
+                        break;
+                    }
+                    docs[num_accepted] = doc;
+                    num_accepted += 1;
+                }
+                segment_collector
+                    .segment_collector
+                    .collect_block(&docs[..num_accepted]);
+            }
+        }
+        Ok(segment_collector.harvest())
+    }
+}
+
+/// Segment Collector associated with `TakeWhileCollector`.
+pub struct TakeWhileSegmentCollector<TSegmentCollector, TPredicate> {
+    segment_collector: TSegmentCollector,
+    predicate: TPredicate,
+    terminated: bool,
+}
+
+impl<TSegmentCollector, TPredicate> SegmentCollector
+    for TakeWhileSegmentCollector<TSegmentCollector, TPredicate>
+where
+    TSegmentCollector: SegmentCollector,
+    TPredicate: 'static + FnMut(DocId, Score) -> bool,
+{
+    type Fruit = TSegmentCollector::Fruit;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        if self.terminated {
+            return;
+        }
+        if !(self.predicate)(doc, score) {
+            self.terminated = true;
+            return;
+        }
+        self.segment_collector.collect(doc, score);
+    }
+
+    fn harvest(self) -> TSegmentCollector::Fruit {
+        self.segment_collector.harvest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::TakeWhileCollector;
+    use crate::collector::{Count, DocSetCollector, TopDocs};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, FAST, INDEXED, TEXT};
+    use crate::{DocAddress, Index, IndexWriter, Term};
+
+    fn create_index() -> crate::Result<Index> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let num = schema_builder.add_u64_field("num", FAST | INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..10u64 {
+            index_writer.add_document(doc!(text => "hello", num => i))?;
+        }
+        index_writer.commit()?;
+        Ok(index)
+    }
+
+    #[test]
+    fn test_take_while_collector_stops_early() -> crate::Result<()> {
+        let index = create_index()?;
+        let searcher = index.reader()?.searcher();
+        let num_calls = Arc::new(AtomicUsize::new(0));
+        let num_calls_clone = num_calls.clone();
+        let collector = TakeWhileCollector::new(DocSetCollector, move |doc, _score| {
+            num_calls_clone.fetch_add(1, Ordering::SeqCst);
+            doc < 3
+        });
+        let docs = searcher.search(&AllQuery, &collector)?;
+        assert_eq!(
+            docs,
+            (0..3).map(|doc_id| DocAddress::new(0, doc_id)).collect()
+        );
+        // The predicate is not called anymore once it returned false.
+        assert_eq!(num_calls.load(Ordering::SeqCst), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_while_collector_applies_per_segment() -> crate::Result<()> {
+        let index = create_index()?;
+        let text = index.schema().get_field("text").unwrap();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for _ in 0..10 {
+            index_writer.add_document(doc!(text => "hello"))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let mut num_docs = 0;
+        let take_three = TakeWhileCollector::new(Count, move |_doc, _score| {
+            num_docs += 1;
+            num_docs <= 3
+        });
+        // The number of documents starts over in each segment.
+        assert_eq!(searcher.search(&AllQuery, &take_three)?, 6);
+        let mut num_docs = 0;
+        let take_three = TakeWhileCollector::new(TopDocs::with_limit(10), move |_doc, _score| {
+            num_docs += 1;
+            num_docs <= 3
+        });
+        let query = TermQuery::new(
+            Term::from_field_text(text, "hello"),
+            IndexRecordOption::WithFreqs,
+        );
+        let top_docs = searcher.search(&query, &take_three)?;
+        assert_eq!(top_docs.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_while_collector_with_scoring() -> crate::Result<()> {
+        let index = create_index()?;
+        let text = index.schema().get_field("text").unwrap();
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(text, "hello"),
+            IndexRecordOption::WithFreqs,
+        );
+        let mut num_docs = 0;
+        let collector = TakeWhileCollector::new(TopDocs::with_limit(10), move |_doc, score| {
+            assert!(score > 0.0);
+            num_docs += 1;
+            num_docs <= 5
+        });
+        let top_docs = searcher.search(&query, &collector)?;
+        assert_eq!(top_docs.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_while_collector_with_deletes() -> crate::Result<()> {
+        let index = create_index()?;
+        let num = index.schema().get_field("num").unwrap();
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.delete_term(Term::from_field_u64(num, 0));
+        index_writer.delete_term(Term::from_field_u64(num, 1));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let mut num_docs = 0;
+        let collector = TakeWhileCollector::new(Count, move |doc, _score| {
+            // Deleted documents are not passed to the predicate.
+            assert!(doc >= 2);
+            num_docs += 1;
+            num_docs <= 3
+        });
+        assert_eq!(searcher.search(&AllQuery, &collector)?, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::io;
+
+use columnar::StrColumn;
+
+use super::{Collector, SegmentCollector};
+use crate::{DocId, Score, SegmentOrdinal, SegmentReader};
+
+/// The `TermCountCollector` counts the matching documents for each of the terms of a string
+/// fast field, e.g. to count the results by category.
+///
+/// For a single, flat field, it is a lightweight alternative to the
+/// [`FacetCollector`](crate::collector::FacetCollector): each segment counts the term ordinals
+/// of the matching documents, and the ordinals are only resolved to their terms when the
+/// segment counts are merged.
+///
+/// A document contributes to the count of each of its values if the field is multi-valued, and
+/// is counted once for a value it contains several times.
+/// Documents without a value for the field, and segments in which the field does not exist,
+/// do not contribute to any count. Terms with no matching documents are not returned.
+///
+/// ```rust
+/// use tantivy::collector::TermCountCollector;
+/// use tantivy::query::AllQuery;
+/// use tantivy::schema::{Schema, FAST, STRING};
+/// use tantivy::{doc, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let category = schema_builder.add_text_field("category", STRING | FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(category => "book"))?;
+/// index_writer.add_document(doc!(category => "book", category => "music"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let counts = searcher.search(&AllQuery, &TermCountCollector::new("category"))?;
+/// assert_eq!(counts.len(), 2);
+/// assert_eq!(counts["book"], 2);
+/// assert_eq!(counts["music"], 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TermCountCollector {
+    field: String,
+}
+
+impl TermCountCollector {
+    /// Creates a `TermCountCollector` counting the terms of the string fast field `field`.
+    pub fn new(field: impl ToString) -> TermCountCollector {
+        TermCountCollector {
+            field: field.to_string(),
+        }
+    }
+}
+
+/// The term ordinal counts of a segment, as returned by the [`TermCountSegmentCollector`].
+pub struct SegmentTermCounts {
+    column_opt: Option<StrColumn>,
+    counts: Vec<u64>,
+}
+
+impl SegmentTermCounts {
+    fn add_to(&self, term_counts: &mut HashMap<String, u64>) -> crate::Result<()> {
+        let Some(column) = self.column_opt.as_ref() else {
+            return Ok(());
+        };
+        let counts = &self.counts;
+        let non_zero_ords = (0..counts.len() as u64).filter(|&ord| counts[ord as usize] > 0);
+        let mut non_zero_counts = counts.iter().copied().filter(|&count| count > 0);
+        column
+            .dictionary()
+            .sorted_ords_to_term_cb(non_zero_ords, |term_bytes| {
+                let term = std::str::from_utf8(term_bytes)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                let count = non_zero_counts.next().unwrap_or(0);
+                *term_counts.entry(term.to_string()).or_default() += count;
+                Ok(())
+            })?;
+        Ok(())
+    }
+}
+
+impl Collector for TermCountCollector {
+    type Fruit = HashMap<String, u64>;
+
+    type Child = TermCountSegmentCollector;
+
+    fn for_segment(
+        &self,
+        _segment_local_id: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<TermCountSegmentCollector> {
+        let column_opt = segment_reader.fast_fields().str(&self.field)?;
+        let num_terms = column_opt
+            .as_ref()
+            .map(|column| column.num_terms())
+            .unwrap_or(0);
+        Ok(TermCountSegmentCollector {
+            column_opt,
+            counts: vec![0; num_terms],
+            term_ords_buffer: Vec::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_term_counts: Vec<SegmentTermCounts>,
+    ) -> crate::Result<HashMap<String, u64>> {
+        let mut term_counts = HashMap::new();
+        for segment_term_count in &segment_term_counts {
+            segment_term_count.add_to(&mut term_counts)?;
+        }
+        Ok(term_counts)
+    }
+}
+
+/// Segment collector of the [`TermCountCollector`].
+pub struct TermCountSegmentCollector {
+    column_opt: Option<StrColumn>,
+    counts: Vec<u64>,
+    term_ords_buffer: Vec<u64>,
+}
+
+impl SegmentCollector for TermCountSegmentCollector {
+    type Fruit = SegmentTermCounts;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        if let Some(column) = self.column_opt.as_ref() {
+            self.term_ords_buffer.clear();
+            self.term_ords_buffer.extend(column.term_ords(doc));
+            // A document repeating a value is only counted once for it.
+            self.term_ords_buffer.sort_unstable();
+            self.term_ords_buffer.dedup();
+            for &term_ord in &self.term_ords_buffer {
+                self.counts[term_ord as usize] += 1;
+            }
+        }
+    }
+
+    fn harvest(self) -> SegmentTermCounts {
+        SegmentTermCounts {
+            column_opt: self.column_opt,
+            counts: self.counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::TermCountCollector;
+    use crate::collector::{Count, FacetCollector};
+    use crate::query::{AllQuery, TermQuery};
+    use crate::schema::{Facet, FacetOptions, IndexRecordOption, Schema, FAST, STRING};
+    use crate::{Index, IndexWriter, Term};
+
+    #[test]
+    fn test_term_count_collector_multivalued() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let category = schema_builder.add_text_field("category", STRING | FAST);
+        let facet = schema_builder.add_facet_field("facet", FacetOptions::default());
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let docs: [&[&str]; 6] = [
+            &["book"],
+            &["book", "music"],
+            &[],
+            &["music", "film", "book"],
+            &["film"],
+            &["book", "book"],
+        ];
+        for (i, categories) in docs.iter().enumerate() {
+            let mut doc = crate::TantivyDocument::default();
+            for &category_value in categories.iter() {
+                doc.add_text(category, category_value);
+                doc.add_facet(facet, Facet::from_path([category_value]));
+            }
+            index_writer.add_document(doc)?;
+            // Spread the documents over several segments.
+            if i % 2 == 1 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 3);
+
+        let counts = searcher.search(&AllQuery, &TermCountCollector::new("category"))?;
+        let expected: HashMap<String, u64> = [("book", 4), ("music", 2), ("film", 2)]
+            .into_iter()
+            .map(|(term, count)| (term.to_string(), count))
+            .collect();
+        assert_eq!(counts, expected);
+
+        // The counts match the ones of the facet collector.
+        let mut facet_collector = FacetCollector::for_field("facet");
+        facet_collector.add_facet("/");
+        let facet_counts = searcher.search(&AllQuery, &facet_collector)?;
+        for (facet, count) in facet_counts.get("/") {
+            assert_eq!(counts[&facet.to_path()[0].to_string()], count);
+        }
+
+        // Only the matching documents are counted.
+        let query = TermQuery::new(
+            Term::from_field_text(category, "music"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+        let counts = searcher.search(&query, &TermCountCollector::new("category"))?;
+        let expected: HashMap<String, u64> = [("book", 2), ("music", 2), ("film", 1)]
+            .into_iter()
+            .map(|(term, count)| (term.to_string(), count))
+            .collect();
+        assert_eq!(counts, expected);
+
+        // An unknown field has no terms.
+        let counts = searcher.search(&AllQuery, &TermCountCollector::new("unknown"))?;
+        assert!(counts.is_empty());
+        Ok(())
+    }
+}
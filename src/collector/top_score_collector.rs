@@ -40,23 +40,7 @@ where
         segment_local_id: crate::SegmentOrdinal,
         segment: &SegmentReader,
     ) -> crate::Result<Self::Child> {
-        let schema = segment.schema();
-        let field = schema.get_field(&self.field)?;
-        let field_entry = schema.get_field_entry(field);
-        if !field_entry.is_fast() {
-            return Err(TantivyError::SchemaError(format!(
-                "Field {:?} is not a fast field.",
-                field_entry.name()
-            )));
-        }
-        let schema_type = TFastValue::to_type();
-        let requested_type = field_entry.field_type().value_type();
-        if schema_type != requested_type {
-            return Err(TantivyError::SchemaError(format!(
-                "Field {:?} is of type {schema_type:?}!={requested_type:?}",
-                field_entry.name()
-            )));
-        }
+        check_fast_field_type::<TFastValue>(segment, &self.field)?;
         self.collector.for_segment(segment_local_id, segment)
     }
 
@@ -83,6 +67,129 @@ where
     }
 }
 
+/// Checks that `field` is a fast field of type `TFastValue` in the schema of the segment.
+fn check_fast_field_type<TFastValue: FastValue>(
+    segment: &SegmentReader,
+    field: &str,
+) -> crate::Result<()> {
+    let schema = segment.schema();
+    let field = schema.get_field(field)?;
+    let field_entry = schema.get_field_entry(field);
+    if !field_entry.is_fast() {
+        return Err(TantivyError::SchemaError(format!(
+            "Field {:?} is not a fast field.",
+            field_entry.name()
+        )));
+    }
+    let schema_type = TFastValue::to_type();
+    let requested_type = field_entry.field_type().value_type();
+    if schema_type != requested_type {
+        return Err(TantivyError::SchemaError(format!(
+            "Field {:?} is of type {schema_type:?}!={requested_type:?}",
+            field_entry.name()
+        )));
+    }
+    Ok(())
+}
+
+/// Drops the fast field value used to break ties from the fruit of a
+/// `TopDocs::order_by_score_then_fast_field` collector.
+struct ScoreThenFastFieldCollector<
+    TCollector: Collector<Fruit = Vec<((Score, u64), DocAddress)>>,
+    TFastValue: FastValue,
+> {
+    collector: TCollector,
+    field: String,
+    fast_value: PhantomData<TFastValue>,
+}
+
+impl<TCollector, TFastValue> Collector for ScoreThenFastFieldCollector<TCollector, TFastValue>
+where
+    TCollector: Collector<Fruit = Vec<((Score, u64), DocAddress)>>,
+    TFastValue: FastValue,
+{
+    type Fruit = Vec<(Score, DocAddress)>;
+
+    type Child = TCollector::Child;
+
+    fn for_segment(
+        &self,
+        segment_local_id: crate::SegmentOrdinal,
+        segment: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        check_fast_field_type::<TFastValue>(segment, &self.field)?;
+        self.collector.for_segment(segment_local_id, segment)
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.collector.requires_scoring()
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> crate::Result<Self::Fruit> {
+        let raw_result = self.collector.merge_fruits(segment_fruits)?;
+        Ok(raw_result
+            .into_iter()
+            .map(|((score, _), doc_address)| (score, doc_address))
+            .collect())
+    }
+}
+
+/// Converts the u64 representation of the fast field back to `TFastValue` in the fruit of a
+/// `TopDocs::order_by_fast_field_with_score` collector.
+struct FastFieldWithScoreCollector<
+    TCollector: Collector<Fruit = Vec<((u64, Score), DocAddress)>>,
+    TFastValue: FastValue,
+> {
+    collector: TCollector,
+    field: String,
+    fast_value: PhantomData<TFastValue>,
+    order: Order,
+}
+
+impl<TCollector, TFastValue> Collector for FastFieldWithScoreCollector<TCollector, TFastValue>
+where
+    TCollector: Collector<Fruit = Vec<((u64, Score), DocAddress)>>,
+    TFastValue: FastValue,
+{
+    type Fruit = Vec<(TFastValue, Score, DocAddress)>;
+
+    type Child = TCollector::Child;
+
+    fn for_segment(
+        &self,
+        segment_local_id: crate::SegmentOrdinal,
+        segment: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        check_fast_field_type::<TFastValue>(segment, &self.field)?;
+        self.collector.for_segment(segment_local_id, segment)
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.collector.requires_scoring()
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> crate::Result<Self::Fruit> {
+        let raw_result = self.collector.merge_fruits(segment_fruits)?;
+        let transformed_result = raw_result
+            .into_iter()
+            .map(|((value, score), doc_address)| {
+                if self.order.is_desc() {
+                    (TFastValue::from_u64(value), score, doc_address)
+                } else {
+                    (TFastValue::from_u64(u64::MAX - value), score, doc_address)
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(transformed_result)
+    }
+}
+
 /// The `TopDocs` collector keeps track of the top `K` documents
 /// sorted by their score.
 ///
@@ -127,14 +234,17 @@ where
 /// # Ok(())
 /// # }
 /// ```
-pub struct TopDocs(TopCollector<Score>);
+pub struct TopDocs {
+    collector: TopCollector<Score>,
+    wand: bool,
+}
 
 impl fmt::Debug for TopDocs {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
             "TopDocs(limit={}, offset={})",
-            self.0.limit, self.0.offset
+            self.collector.limit, self.collector.offset
         )
     }
 }
@@ -185,13 +295,51 @@ impl CustomScorer<u64> for ScorerByField {
     }
 }
 
+/// Pairs the score of a document with the u64 representation of a fast field.
+struct FastFieldSegmentTweaker(ScorerByFastFieldReader);
+
+impl ScoreSegmentTweaker<(Score, u64)> for FastFieldSegmentTweaker {
+    fn score(&mut self, doc: DocId, score: Score) -> (Score, u64) {
+        (score, self.0.score(doc))
+    }
+}
+
+impl ScoreSegmentTweaker<(u64, Score)> for FastFieldSegmentTweaker {
+    fn score(&mut self, doc: DocId, score: Score) -> (u64, Score) {
+        (self.0.score(doc), score)
+    }
+}
+
+impl ScoreTweaker<(Score, u64)> for ScorerByField {
+    type Child = FastFieldSegmentTweaker;
+
+    fn segment_tweaker(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child> {
+        Ok(FastFieldSegmentTweaker(
+            self.segment_scorer(segment_reader)?,
+        ))
+    }
+}
+
+impl ScoreTweaker<(u64, Score)> for ScorerByField {
+    type Child = FastFieldSegmentTweaker;
+
+    fn segment_tweaker(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child> {
+        Ok(FastFieldSegmentTweaker(
+            self.segment_scorer(segment_reader)?,
+        ))
+    }
+}
+
 impl TopDocs {
     /// Creates a top score collector, with a number of documents equal to "limit".
     ///
     /// # Panics
     /// The method panics if limit is 0
     pub fn with_limit(limit: usize) -> TopDocs {
-        TopDocs(TopCollector::with_limit(limit))
+        TopDocs {
+            collector: TopCollector::with_limit(limit),
+            wand: true,
+        }
     }
 
     /// Skip the first "offset" documents when collecting.
@@ -236,7 +384,34 @@ impl TopDocs {
     /// ```
     #[must_use]
     pub fn and_offset(self, offset: usize) -> TopDocs {
-        TopDocs(self.0.and_offset(offset))
+        TopDocs {
+            collector: self.collector.and_offset(offset),
+            wand: self.wand,
+        }
+    }
+
+    /// Enables the pruning of the documents that cannot enter the top-K. This is the
+    /// default.
+    ///
+    /// Queries whose scorers support it, like unions of term queries, then rely on
+    /// block-max WAND to skip the blocks of postings whose maximum possible score cannot
+    /// exceed the score of the current K-th document. The returned documents are the exact
+    /// top-K.
+    #[must_use]
+    pub fn enable_wand(mut self) -> TopDocs {
+        self.wand = true;
+        self
+    }
+
+    /// Disables the pruning of the documents that cannot enter the top-K: all of the
+    /// matching documents are scored.
+    ///
+    /// The returned documents are the same as with [`TopDocs::enable_wand`], this is only
+    /// useful to compare both traversals.
+    #[must_use]
+    pub fn disable_wand(mut self) -> TopDocs {
+        self.wand = false;
+        self
     }
 
     /// Set top-K to rank documents by a given fast field.
@@ -320,7 +495,7 @@ impl TopDocs {
                 field: field.to_string(),
                 order,
             },
-            self.0.into_tscore(),
+            self.collector.into_tscore(),
         )
     }
 
@@ -410,6 +585,133 @@ impl TopDocs {
         }
     }
 
+    /// Set top-K to rank documents by a given fast field, and return the score of
+    /// each document along with the value of the fast field.
+    ///
+    /// This collects the same documents as
+    /// [.order_by_fast_field(...)](TopDocs::order_by_fast_field), in a single pass, without
+    /// having to compute the relevance score of the top documents in a second step. Documents
+    /// with the same fast field value are ordered by decreasing score.
+    ///
+    /// If the field is not a fast field, or its field type does not match the generic type, an
+    /// error will be returned at the moment of collection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tantivy::schema::{Schema, FAST, TEXT};
+    /// # use tantivy::{doc, Index, DocAddress, Order, Score};
+    /// # use tantivy::query::QueryParser;
+    /// use tantivy::collector::TopDocs;
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let rating = schema_builder.add_u64_field("rating", FAST);
+    /// let schema = schema_builder.build();
+    /// let index = Index::create_in_ram(schema);
+    /// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Diary of Muadib", rating => 97u64))?;
+    /// index_writer.add_document(doc!(title => "A Dairy Cow", rating => 63u64))?;
+    /// index_writer.add_document(doc!(title => "The Diary of a Young Girl", rating => 80u64))?;
+    /// index_writer.commit()?;
+    ///
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+    /// let top_books_by_rating = TopDocs::with_limit(10)
+    ///     .order_by_fast_field_with_score::<u64>("rating", Order::Desc);
+    /// let top_docs: Vec<(u64, Score, DocAddress)> =
+    ///     index.reader()?.searcher().search(&query, &top_books_by_rating)?;
+    /// assert_eq!(top_docs.len(), 2);
+    /// assert_eq!((top_docs[0].0, top_docs[0].2), (97, DocAddress::new(0, 0)));
+    /// assert_eq!((top_docs[1].0, top_docs[1].2), (80, DocAddress::new(0, 2)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn order_by_fast_field_with_score<TFastValue>(
+        self,
+        fast_field: impl ToString,
+        order: Order,
+    ) -> impl Collector<Fruit = Vec<(TFastValue, Score, DocAddress)>>
+    where
+        TFastValue: FastValue,
+    {
+        let tweaked_collector = TweakedScoreTopCollector::new(
+            ScorerByField {
+                field: fast_field.to_string(),
+                order: order.clone(),
+            },
+            self.collector.into_tscore(),
+        );
+        FastFieldWithScoreCollector {
+            collector: tweaked_collector,
+            field: fast_field.to_string(),
+            fast_value: PhantomData::<TFastValue>,
+            order,
+        }
+    }
+
+    /// Set top-K to rank documents by score, breaking ties using a given fast field.
+    ///
+    /// Documents with the same score are sorted by the value of `fast_field`, following
+    /// `order`. Documents without any value for the field come last. This makes the
+    /// ordering of tied documents meaningful, e.g. "equal relevance, newest first", and
+    /// independent of the way documents are split into segments.
+    ///
+    /// If the field is not a fast field, or its field type does not match the generic type, an
+    /// error will be returned at the moment of collection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tantivy::schema::{Schema, FAST, TEXT};
+    /// # use tantivy::{doc, Index, DocAddress, Order, Score};
+    /// # use tantivy::query::QueryParser;
+    /// use tantivy::collector::TopDocs;
+    ///
+    /// # fn main() -> tantivy::Result<()> {
+    /// let mut schema_builder = Schema::builder();
+    /// let title = schema_builder.add_text_field("title", TEXT);
+    /// let year = schema_builder.add_u64_field("year", FAST);
+    /// let schema = schema_builder.build();
+    /// let index = Index::create_in_ram(schema);
+    /// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+    /// index_writer.add_document(doc!(title => "The Diary", year => 1947u64))?;
+    /// index_writer.add_document(doc!(title => "The Diary", year => 2012u64))?;
+    /// index_writer.add_document(doc!(title => "The Diary", year => 1982u64))?;
+    /// index_writer.commit()?;
+    ///
+    /// let query = QueryParser::for_index(&index, vec![title]).parse_query("diary")?;
+    /// let newest_first = TopDocs::with_limit(3)
+    ///     .order_by_score_then_fast_field::<u64>("year", Order::Desc);
+    /// let top_docs: Vec<(Score, DocAddress)> =
+    ///     index.reader()?.searcher().search(&query, &newest_first)?;
+    /// let doc_ids: Vec<u32> = top_docs.iter().map(|(_, doc_address)| doc_address.doc_id).collect();
+    /// assert_eq!(doc_ids, vec![1, 2, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn order_by_score_then_fast_field<TFastValue>(
+        self,
+        fast_field: impl ToString,
+        order: Order,
+    ) -> impl Collector<Fruit = Vec<(Score, DocAddress)>>
+    where
+        TFastValue: FastValue,
+    {
+        let tweaked_collector = TweakedScoreTopCollector::new(
+            ScorerByField {
+                field: fast_field.to_string(),
+                order,
+            },
+            self.collector.into_tscore(),
+        );
+        ScoreThenFastFieldCollector {
+            collector: tweaked_collector,
+            field: fast_field.to_string(),
+            fast_value: PhantomData::<TFastValue>,
+        }
+    }
+
     /// Ranks the documents using a custom score.
     ///
     /// This method offers a convenient way to tweak or replace
@@ -515,7 +817,7 @@ impl TopDocs {
         TScoreSegmentTweaker: ScoreSegmentTweaker<TScore> + 'static,
         TScoreTweaker: ScoreTweaker<TScore, Child = TScoreSegmentTweaker> + Send + Sync,
     {
-        TweakedScoreTopCollector::new(score_tweaker, self.0.into_tscore())
+        TweakedScoreTopCollector::new(score_tweaker, self.collector.into_tscore())
     }
 
     /// Ranks the documents using a custom score.
@@ -628,7 +930,7 @@ impl TopDocs {
         TCustomSegmentScorer: CustomSegmentScorer<TScore> + 'static,
         TCustomScorer: CustomScorer<TScore, Child = TCustomSegmentScorer> + Send + Sync,
     {
-        CustomScoreTopCollector::new(custom_score, self.0.into_tscore())
+        CustomScoreTopCollector::new(custom_score, self.collector.into_tscore())
     }
 }
 
@@ -642,7 +944,7 @@ impl Collector for TopDocs {
         segment_local_id: SegmentOrdinal,
         reader: &SegmentReader,
     ) -> crate::Result<Self::Child> {
-        let collector = self.0.for_segment(segment_local_id, reader);
+        let collector = self.collector.for_segment(segment_local_id, reader);
         Ok(TopScoreSegmentCollector(collector))
     }
 
@@ -654,7 +956,7 @@ impl Collector for TopDocs {
         &self,
         child_fruits: Vec<Vec<(Score, DocAddress)>>,
     ) -> crate::Result<Self::Fruit> {
-        self.0.merge_fruits(child_fruits)
+        self.collector.merge_fruits(child_fruits)
     }
 
     fn collect_segment(
@@ -663,10 +965,18 @@ impl Collector for TopDocs {
         segment_ord: u32,
         reader: &SegmentReader,
     ) -> crate::Result<<Self::Child as SegmentCollector>::Fruit> {
-        let heap_len = self.0.limit + self.0.offset;
+        let heap_len = self.collector.limit + self.collector.offset;
         let mut top_n: TopNComputer<_, _> = TopNComputer::new(heap_len);
 
-        if let Some(alive_bitset) = reader.alive_bitset() {
+        if !self.wand {
+            let alive_bitset_opt = reader.alive_bitset();
+            weight.for_each(reader, &mut |doc, score| {
+                if alive_bitset_opt.is_some_and(|alive_bitset| alive_bitset.is_deleted(doc)) {
+                    return;
+                }
+                top_n.push(score, doc);
+            })?;
+        } else if let Some(alive_bitset) = reader.alive_bitset() {
             let mut threshold = Score::MIN;
             top_n.threshold = Some(threshold);
             weight.for_each_pruning(Score::MIN, reader, &mut |doc, score| {
@@ -895,6 +1205,58 @@ mod tests {
             crate::assert_nearly_equals!(result.0, expected.0);
         }
     }
+
+    #[test]
+    fn test_top_docs_with_and_without_wand() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        // Enough documents to get several blocks of postings per term, with varied
+        // term frequencies and document lengths.
+        let words = ["a", "b", "c", "d", "e", "f"];
+        let mut seed = 42u64;
+        for doc_id in 0..3_000u64 {
+            let mut text = String::new();
+            for _ in 0..(1 + doc_id % 7) {
+                seed = seed
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                let word_id = ((seed >> 33) % 64).trailing_zeros() as usize;
+                text.push_str(words[word_id.min(words.len() - 1)]);
+                text.push(' ');
+            }
+            index_writer.add_document(doc!(text_field => text))?;
+            if doc_id == 2_000 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.delete_term(crate::Term::from_field_text(text_field, "f"));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        for query_str in [
+            "a",
+            "b d",
+            "a b c d e",
+            "+a c e",
+            "+b +c d",
+            "a -b c",
+            "\"a b\" c",
+        ] {
+            let query = query_parser.parse_query(query_str)?;
+            for (limit, offset) in [(1, 0), (10, 0), (10, 20), (200, 0)] {
+                let top_docs = TopDocs::with_limit(limit).and_offset(offset);
+                let with_wand = searcher.search(&query, &top_docs.enable_wand())?;
+                let top_docs = TopDocs::with_limit(limit).and_offset(offset);
+                let without_wand = searcher.search(&query, &top_docs.disable_wand())?;
+                assert_eq!(with_wand.len(), without_wand.len());
+                assert_results_equals(&with_wand, &without_wand);
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_topn_computer_serde() {
         let computer: TopNComputer<u32, u32> = TopNComputer::new(1);
@@ -1291,6 +1653,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_tweak_score_linear_combination_across_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let popularity = schema_builder.add_u64_field("popularity", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "diary diary diary", popularity => 0u64))?;
+        index_writer.add_document(doc!(text => "diary of a cow", popularity => 0u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "young diary", popularity => 50u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let query = QueryParser::for_index(&index, vec![text]).parse_query("diary")?;
+
+        let by_relevance = searcher.search(&query, &TopDocs::with_limit(3))?;
+        assert_eq!(by_relevance[0].1, DocAddress::new(0, 0));
+        let relevance_score = |doc_address: DocAddress| {
+            by_relevance
+                .iter()
+                .find(|(_, address)| *address == doc_address)
+                .unwrap()
+                .0
+        };
+
+        let collector = TopDocs::with_limit(3).tweak_score(|segment_reader: &SegmentReader| {
+            let popularity_reader = segment_reader
+                .fast_fields()
+                .u64("popularity")
+                .unwrap()
+                .first_or_default_col(0);
+            move |doc: DocId, score: Score| score + 0.1 * popularity_reader.get_val(doc) as Score
+        });
+        let rescored = searcher.search(&query, &collector)?;
+        let rescored_docs: Vec<DocAddress> = rescored.iter().map(|(_, doc)| *doc).collect();
+        assert_eq!(
+            rescored_docs,
+            vec![
+                DocAddress::new(1, 0),
+                DocAddress::new(0, 0),
+                DocAddress::new(0, 1)
+            ]
+        );
+        assert_nearly_equals!(rescored[0].0, relevance_score(DocAddress::new(1, 0)) + 5.0);
+        Ok(())
+    }
+
     #[test]
     fn test_custom_score_top_collector_with_offset() {
         let index = make_index().unwrap();
@@ -1373,4 +1783,128 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_order_by_score_then_fast_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field(TITLE, TEXT);
+        let size = schema_builder.add_u64_field(SIZE, FAST);
+        let schema = schema_builder.build();
+        let (index, query) = index("beer", title, schema, |index_writer| {
+            index_writer
+                .add_document(doc!(title => "pint of beer", size => 12u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "pint of beer", size => 64u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "beer", size => 1u64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "pint of beer"))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "pint of beer", size => 16u64))
+                .unwrap();
+        });
+        let searcher = index.reader()?.searcher();
+        let scores: Vec<(Score, DocAddress)> = searcher.search(&query, &TopDocs::with_limit(5))?;
+        let score_of = |doc_address: DocAddress| {
+            scores
+                .iter()
+                .find(|(_, scored_doc_address)| *scored_doc_address == doc_address)
+                .map(|(score, _)| *score)
+                .unwrap()
+        };
+        let assert_top_docs = |order: Order, expected: &[DocId]| -> crate::Result<()> {
+            let top_collector =
+                TopDocs::with_limit(5).order_by_score_then_fast_field::<u64>(SIZE, order);
+            let top_docs = searcher.search(&query, &top_collector)?;
+            let doc_ids: Vec<DocId> = top_docs
+                .iter()
+                .map(|(_, doc_address)| doc_address.doc_id)
+                .collect();
+            assert_eq!(doc_ids, expected);
+            for (score, doc_address) in top_docs {
+                assert_nearly_equals!(score, score_of(doc_address));
+            }
+            Ok(())
+        };
+        assert_top_docs(Order::Desc, &[2, 1, 4, 0, 3])?;
+        assert_top_docs(Order::Asc, &[2, 0, 4, 1, 3])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by_score_then_fast_field_wrong_type() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let size = schema_builder.add_u64_field(SIZE, FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(size=>1u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let segment = searcher.segment_reader(0);
+        let top_collector =
+            TopDocs::with_limit(4).order_by_score_then_fast_field::<i64>(SIZE, Order::Desc);
+        let err = top_collector.for_segment(0, segment).err().unwrap();
+        assert!(matches!(err, crate::TantivyError::SchemaError(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_by_fast_field_with_score() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field(TITLE, TEXT);
+        let size = schema_builder.add_i64_field(SIZE, FAST);
+        let schema = schema_builder.build();
+        let (index, query) = index("beer", title, schema, |index_writer| {
+            index_writer
+                .add_document(doc!(title => "bottle of beer", size => 12i64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "beer beer beer", size => -3i64))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "pint of lager"))
+                .unwrap();
+            index_writer
+                .add_document(doc!(title => "growler of beer", size => 64i64))
+                .unwrap();
+        });
+        let searcher = index.reader()?.searcher();
+        let scores: Vec<(Score, DocAddress)> = searcher.search(&query, &TopDocs::with_limit(4))?;
+        assert_eq!(scores.len(), 3);
+
+        let top_collector =
+            TopDocs::with_limit(4).order_by_fast_field_with_score::<i64>(SIZE, Order::Desc);
+        let top_docs: Vec<(i64, Score, DocAddress)> = searcher.search(&query, &top_collector)?;
+        let values_and_docs: Vec<(i64, DocAddress)> = top_docs
+            .iter()
+            .map(|(value, _, doc_address)| (*value, *doc_address))
+            .collect();
+        assert_eq!(
+            values_and_docs,
+            vec![
+                (64, DocAddress::new(0, 3)),
+                (12, DocAddress::new(0, 0)),
+                (-3, DocAddress::new(0, 1)),
+            ]
+        );
+        for (_, score, doc_address) in &top_docs {
+            let (expected_score, _) = scores
+                .iter()
+                .find(|(_, scored_doc_address)| scored_doc_address == doc_address)
+                .unwrap();
+            assert_nearly_equals!(*score, *expected_score);
+        }
+
+        let top_collector =
+            TopDocs::with_limit(2).order_by_fast_field_with_score::<i64>(SIZE, Order::Asc);
+        let top_docs: Vec<(i64, Score, DocAddress)> = searcher.search(&query, &top_collector)?;
+        let values: Vec<i64> = top_docs.iter().map(|(value, _, _)| *value).collect();
+        assert_eq!(values, vec![-3, 12]);
+        Ok(())
+    }
 }
@@ -1,16 +1,22 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Instant;
 use std::{fmt, io};
 
-use crate::collector::Collector;
-use crate::core::Executor;
+use crate::collector::{Collector, SegmentCollector};
+use crate::core::term_vector::compute_term_vector;
+use crate::core::{Executor, TermVector};
 use crate::index::{SegmentId, SegmentReader};
-use crate::query::{Bm25StatisticsProvider, EnableScoring, Query};
+use crate::postings::Postings;
+use crate::query::{Bm25StatisticsProvider, EnableScoring, Query, Weight};
 use crate::schema::document::DocumentDeserialize;
-use crate::schema::{Schema, Term};
+use crate::schema::{Field, IndexRecordOption, Schema, Term};
 use crate::space_usage::SearcherSpaceUsage;
 use crate::store::{CacheStats, StoreReader};
-use crate::{DocAddress, Index, Opstamp, TrackedObject};
+use crate::{DocAddress, DocId, DocSet, Index, Opstamp, TantivyError, TrackedObject, TERMINATED};
+
+/// Number of documents visited between two checks of the deadline of a search.
+const DEADLINE_CHECK_INTERVAL: u32 = 1_024;
 
 /// Identifies the searcher generation accessed by a [`Searcher`].
 ///
@@ -81,6 +87,11 @@ impl Searcher {
         self.inner.generation.as_ref()
     }
 
+    /// Returns the opstamp of the commit this `Searcher` was opened on.
+    pub fn opstamp(&self) -> Opstamp {
+        self.inner.opstamp
+    }
+
     /// Fetches a document from tantivy's store given a [`DocAddress`].
     ///
     /// The searcher uses the segment ordinal to route the
@@ -90,6 +101,32 @@ impl Searcher {
         store_reader.get(doc_address.doc_id)
     }
 
+    /// Fetches several documents from tantivy's store, given their `DocAddress`es.
+    ///
+    /// Documents are returned in the order of `doc_addresses`. They are grouped by segment
+    /// and read in doc id order, so that each block of the doc store is decompressed at most
+    /// once. See [`StoreReader::get_many`].
+    pub fn docs<D: DocumentDeserialize>(
+        &self,
+        doc_addresses: &[DocAddress],
+    ) -> crate::Result<Vec<D>> {
+        let mut doc_ids_per_segment: Vec<Vec<DocId>> =
+            vec![Vec::new(); self.inner.store_readers.len()];
+        for doc_address in doc_addresses {
+            doc_ids_per_segment[doc_address.segment_ord as usize].push(doc_address.doc_id);
+        }
+        let mut docs_per_segment: Vec<std::vec::IntoIter<D>> =
+            Vec::with_capacity(doc_ids_per_segment.len());
+        for (store_reader, doc_ids) in self.inner.store_readers.iter().zip(&doc_ids_per_segment) {
+            docs_per_segment.push(store_reader.get_many(doc_ids)?.into_iter());
+        }
+        // Within a segment, documents come in the order of `doc_addresses`.
+        Ok(doc_addresses
+            .iter()
+            .flat_map(|doc_address| docs_per_segment[doc_address.segment_ord as usize].next())
+            .collect())
+    }
+
     /// The cache stats for the underlying store reader.
     ///
     /// Aggregates the sum for each segment store reader.
@@ -130,6 +167,8 @@ impl Searcher {
 
     /// Return the overall number of documents containing
     /// the given term.
+    ///
+    /// Deleted documents are counted until their segment gets merged.
     pub fn doc_freq(&self, term: &Term) -> crate::Result<u64> {
         let mut total_doc_freq = 0;
         for segment_reader in &self.inner.segment_readers {
@@ -140,6 +179,58 @@ impl Searcher {
         Ok(total_doc_freq)
     }
 
+    /// Return the overall number of occurrences of the given term,
+    /// summed over all of the documents containing it.
+    ///
+    /// Unlike [`Searcher::doc_freq`], deleted documents are not taken into account, which
+    /// requires reading the postings of the term. If the field does not record term
+    /// frequencies, each document containing the term counts as a single occurrence.
+    pub fn total_term_freq(&self, term: &Term) -> crate::Result<u64> {
+        let mut total_term_freq = 0u64;
+        for segment_reader in &self.inner.segment_readers {
+            let inverted_index = segment_reader.inverted_index(term.field())?;
+            let Some(mut postings) =
+                inverted_index.read_postings(term, IndexRecordOption::WithFreqs)?
+            else {
+                continue;
+            };
+            let alive_bitset = segment_reader.alive_bitset();
+            let mut doc = postings.doc();
+            while doc != TERMINATED {
+                if alive_bitset.map_or(true, |alive_bitset| alive_bitset.is_alive(doc)) {
+                    total_term_freq += u64::from(postings.term_freq());
+                }
+                doc = postings.advance();
+            }
+        }
+        Ok(total_term_freq)
+    }
+
+    /// Returns the term vector of the document for the given field: its distinct terms,
+    /// together with their frequencies and positions in the document.
+    ///
+    /// Tantivy does not store term vectors, so they are reconstructed from the postings:
+    /// this visits all of the terms of the field in the segment of the document, and is
+    /// meant for debugging or for highlighting a handful of documents, not for every hit of
+    /// a search.
+    ///
+    /// The field must be a text field indexed with positions, i.e. with
+    /// [`IndexRecordOption::WithFreqsAndPositions`]. Otherwise, a
+    /// [`TantivyError::SchemaError`] is returned.
+    pub fn term_vector(&self, doc_address: DocAddress, field: Field) -> crate::Result<TermVector> {
+        let segment_reader = self
+            .inner
+            .segment_readers
+            .get(doc_address.segment_ord as usize)
+            .ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Segment ordinal {} is out of bounds",
+                    doc_address.segment_ord
+                ))
+            })?;
+        compute_term_vector(segment_reader, doc_address.doc_id, field)
+    }
+
     /// Return the overall number of documents containing
     /// the given term in an asynchronous manner.
     #[cfg(feature = "quickwit")]
@@ -217,6 +308,15 @@ impl Searcher {
     /// Also, keep in my multithreading a single query on several
     /// threads will not improve your throughput. It can actually
     /// hurt it. It will however, decrease the average response time.
+    ///
+    /// A caller-provided rayon thread pool can be used via
+    /// `Executor::from(Arc<rayon::ThreadPool>)`, and [`Executor::single_thread()`] runs the
+    /// search in the calling thread.
+    ///
+    /// The segments are collected in an arbitrary order, but their fruits are always passed
+    /// to [`Collector::merge_fruits`] in the order of the segment ordinals, so that the result
+    /// does not depend on the executor. Collectors with side effects in their segment
+    /// collectors should not rely on the collection order.
     pub fn search_with_executor<C: Collector>(
         &self,
         query: &dyn Query,
@@ -235,6 +335,50 @@ impl Searcher {
         collector.merge_fruits(fruits)
     }
 
+    /// Same as [`search(...)`](Searcher::search), but gives up with a
+    /// [`TantivyError::TimedOut`] error if the search does not complete before `deadline`.
+    ///
+    /// The deadline is checked once the weight of the query is built, before building the
+    /// scorer of each segment, and then every 1024 documents visited by the query. Building the
+    /// weight, which may for instance expand a fuzzy or a regex query over the term
+    /// dictionaries, and building the scorer of one segment cannot be interrupted: the search
+    /// can therefore time out well after the deadline. Documents are collected one at a time,
+    /// so that the search may be slower than with [`Searcher::search`] for collectors relying
+    /// on [`Weight::for_each_pruning`](crate::query::Weight::for_each_pruning), as
+    /// [`TopDocs`](crate::collector::TopDocs) does.
+    ///
+    /// No partial result is returned: on timeout, the fruits of the segments collected before
+    /// the deadline are discarded.
+    pub fn search_with_deadline<C: Collector>(
+        &self,
+        query: &dyn Query,
+        collector: &C,
+        deadline: Instant,
+    ) -> crate::Result<C::Fruit> {
+        let enabled_scoring = if collector.requires_scoring() {
+            EnableScoring::enabled_from_searcher(self)
+        } else {
+            EnableScoring::disabled_from_searcher(self)
+        };
+        let weight = query.weight(enabled_scoring)?;
+        check_deadline(deadline)?;
+        let segment_readers = self.segment_readers();
+        let executor = self.inner.index.search_executor();
+        let fruits = executor.map(
+            |(segment_ord, segment_reader)| {
+                collect_segment_with_deadline(
+                    collector,
+                    weight.as_ref(),
+                    segment_ord as u32,
+                    segment_reader,
+                    deadline,
+                )
+            },
+            segment_readers.iter().enumerate(),
+        )?;
+        collector.merge_fruits(fruits)
+    }
+
     /// Summarize total space usage of this searcher.
     pub fn space_usage(&self) -> io::Result<SearcherSpaceUsage> {
         let mut space_usage = SearcherSpaceUsage::new();
@@ -245,6 +389,47 @@ impl Searcher {
     }
 }
 
+fn check_deadline(deadline: Instant) -> crate::Result<()> {
+    if Instant::now() >= deadline {
+        return Err(TantivyError::TimedOut);
+    }
+    Ok(())
+}
+
+fn collect_segment_with_deadline<C: Collector>(
+    collector: &C,
+    weight: &dyn Weight,
+    segment_ord: u32,
+    reader: &SegmentReader,
+    deadline: Instant,
+) -> crate::Result<<C::Child as SegmentCollector>::Fruit> {
+    check_deadline(deadline)?;
+    let mut segment_collector = collector.for_segment(segment_ord, reader)?;
+    check_deadline(deadline)?;
+    let mut scorer = weight.scorer(reader, 1.0)?;
+    let requires_scoring = collector.requires_scoring();
+    let alive_bitset_opt = reader.alive_bitset();
+    let mut num_docs_before_check = DEADLINE_CHECK_INTERVAL;
+    let mut doc = scorer.doc();
+    while doc != TERMINATED {
+        if alive_bitset_opt.map_or(true, |alive_bitset| alive_bitset.is_alive(doc)) {
+            let score = if requires_scoring {
+                scorer.score()
+            } else {
+                0.0
+            };
+            segment_collector.collect(doc, score);
+        }
+        num_docs_before_check -= 1;
+        if num_docs_before_check == 0 {
+            check_deadline(deadline)?;
+            num_docs_before_check = DEADLINE_CHECK_INTERVAL;
+        }
+        doc = scorer.advance();
+    }
+    Ok(segment_collector.harvest())
+}
+
 impl From<Arc<SearcherInner>> for Searcher {
     fn from(inner: Arc<SearcherInner>) -> Self {
         Searcher { inner }
@@ -261,6 +446,7 @@ pub(crate) struct SearcherInner {
     segment_readers: Vec<SegmentReader>,
     store_readers: Vec<StoreReader>,
     generation: TrackedObject<SearcherGeneration>,
+    opstamp: Opstamp,
 }
 
 impl SearcherInner {
@@ -270,6 +456,7 @@ impl SearcherInner {
         index: Index,
         segment_readers: Vec<SegmentReader>,
         generation: TrackedObject<SearcherGeneration>,
+        opstamp: Opstamp,
         doc_store_cache_num_blocks: usize,
     ) -> io::Result<SearcherInner> {
         assert_eq!(
@@ -291,6 +478,7 @@ impl SearcherInner {
             segment_readers,
             store_readers,
             generation,
+            opstamp,
         })
     }
 }
@@ -0,0 +1,109 @@
+use crate::index::SegmentReader;
+use crate::postings::Postings;
+use crate::schema::{Field, FieldType, IndexRecordOption, Term};
+use crate::{DocId, DocSet, TantivyError};
+
+/// A term of a document, together with its occurrences in the document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TermVectorEntry {
+    /// The term.
+    pub term: Term,
+    /// The number of times the term appears in the field of the document.
+    pub term_freq: u32,
+    /// The positions of the term in the field of the document, in increasing order.
+    pub positions: Vec<u32>,
+}
+
+/// The terms of a field of a document, as returned by
+/// [`Searcher::term_vector`](crate::Searcher::term_vector).
+///
+/// The terms are sorted in the order of the term dictionary.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TermVector {
+    entries: Vec<TermVectorEntry>,
+}
+
+impl TermVector {
+    /// Returns the number of distinct terms of the document.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the document has no terms for the field.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entry associated with the given term, if the document contains it.
+    pub fn get(&self, term: &Term) -> Option<&TermVectorEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.term.cmp(term))
+            .ok()
+            .map(|ord| &self.entries[ord])
+    }
+
+    /// Iterates over the entries of the term vector, in the order of the term dictionary.
+    pub fn iter(&self) -> impl Iterator<Item = &TermVectorEntry> {
+        self.entries.iter()
+    }
+}
+
+impl IntoIterator for TermVector {
+    type Item = TermVectorEntry;
+    type IntoIter = std::vec::IntoIter<TermVectorEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Reconstructs the term vector of `doc` for `field` by seeking `doc` in the postings of all of
+/// the terms of the field in the segment.
+pub(crate) fn compute_term_vector(
+    segment_reader: &SegmentReader,
+    doc: DocId,
+    field: Field,
+) -> crate::Result<TermVector> {
+    let field_entry = segment_reader.schema().get_field_entry(field);
+    let field_type = field_entry.field_type();
+    let has_positions = match field_type {
+        FieldType::Str(text_options) => text_options
+            .get_indexing_options()
+            .map(|text_indexing| text_indexing.index_option().has_positions())
+            .unwrap_or(false),
+        _ => false,
+    };
+    if !has_positions {
+        let field_name = field_entry.name();
+        return Err(TantivyError::SchemaError(format!(
+            "Requested the term vector of field {field_name:?}, which is not a text field with \
+             positions indexed"
+        )));
+    }
+    if doc >= segment_reader.max_doc() {
+        return Err(TantivyError::InvalidArgument(format!(
+            "Document {doc} does not exist in a segment of {} documents",
+            segment_reader.max_doc()
+        )));
+    }
+    let inverted_index = segment_reader.inverted_index(field)?;
+    let mut term_stream = inverted_index.terms().stream()?;
+    let mut entries = Vec::new();
+    while term_stream.advance() {
+        let mut postings = inverted_index.read_postings_from_terminfo(
+            term_stream.value(),
+            IndexRecordOption::WithFreqsAndPositions,
+        )?;
+        if postings.seek(doc) != doc {
+            continue;
+        }
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        entries.push(TermVectorEntry {
+            term: Term::from_bytes(field, field_type, term_stream.key())?,
+            term_freq: postings.term_freq(),
+            positions,
+        });
+    }
+    Ok(TermVector { entries })
+}
@@ -3,12 +3,13 @@ use crate::directory::{RamDirectory, WatchCallback};
 use crate::index::SegmentId;
 use crate::indexer::{LogMergePolicy, NoMergePolicy};
 use crate::postings::Postings;
-use crate::query::TermQuery;
-use crate::schema::{Field, IndexRecordOption, Schema, INDEXED, STRING, TEXT};
+use crate::query::{QueryParser, QueryParserError, TermQuery};
+use crate::schema::document::Document;
+use crate::schema::{Field, IndexRecordOption, Schema, Type, Value, INDEXED, STORED, STRING, TEXT};
 use crate::tokenizer::TokenizerManager;
 use crate::{
-    Directory, DocSet, Index, IndexBuilder, IndexReader, IndexSettings, IndexWriter, ReloadPolicy,
-    TantivyDocument, Term,
+    Directory, DocAddress, DocSet, Index, IndexBuilder, IndexReader, IndexSettings, IndexWriter,
+    ReloadPolicy, TantivyDocument, TantivyError, Term,
 };
 
 #[test]
@@ -276,6 +277,43 @@ fn garbage_collect_works_as_intended() -> crate::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_index_reader_reload_if_newer() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let field = schema_builder.add_u64_field("num_likes", INDEXED);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut writer: IndexWriter = index.writer_for_tests()?;
+    writer.add_document(doc!(field => 1u64))?;
+    let first_opstamp = writer.commit()?;
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::Manual)
+        .try_into()?;
+    assert_eq!(reader.opstamp(), first_opstamp);
+    assert_eq!(reader.searcher().opstamp(), first_opstamp);
+    assert_eq!(reader.latest_opstamp()?, first_opstamp);
+    assert_eq!(reader.reload_if_newer()?, None);
+
+    writer.add_document(doc!(field => 2u64))?;
+    let second_opstamp = writer.commit()?;
+    assert!(second_opstamp > first_opstamp);
+    // Checking for freshness does not reload the searcher.
+    assert_eq!(reader.latest_opstamp()?, second_opstamp);
+    assert_eq!(reader.opstamp(), first_opstamp);
+    assert_eq!(reader.searcher().num_docs(), 1);
+
+    assert_eq!(
+        reader.reload_if_newer()?,
+        Some((first_opstamp, second_opstamp))
+    );
+    assert_eq!(reader.opstamp(), second_opstamp);
+    assert_eq!(reader.searcher().num_docs(), 2);
+    let searcher = reader.searcher();
+    assert_eq!(reader.reload_if_newer()?, None);
+    assert_eq!(reader.searcher().generation(), searcher.generation());
+    Ok(())
+}
+
 #[test]
 fn test_single_segment_index_writer() -> crate::Result<()> {
     let mut schema_builder = Schema::builder();
@@ -300,6 +338,49 @@ fn test_single_segment_index_writer() -> crate::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_segment_searcher() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", STRING);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(text_field=>"a"))?;
+    index_writer.add_document(doc!(text_field=>"b"))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(text_field=>"a"))?;
+    index_writer.add_document(doc!(text_field=>"c"))?;
+    index_writer.add_document(doc!(text_field=>"c"))?;
+    index_writer.commit()?;
+    index_writer.delete_term(Term::from_field_text(text_field, "c"));
+    index_writer.commit()?;
+
+    let segment_ids = index.searchable_segment_ids()?;
+    assert_eq!(segment_ids.len(), 2);
+    let term_query = TermQuery::new(
+        Term::from_field_text(text_field, "a"),
+        IndexRecordOption::Basic,
+    );
+    assert_eq!(index.reader()?.searcher().search(&term_query, &Count)?, 2);
+    let mut num_docs_per_segment = Vec::new();
+    for segment_id in segment_ids {
+        let searcher = index.segment_searcher(segment_id)?;
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.segment_reader(0).segment_id(), segment_id);
+        assert_eq!(searcher.search(&term_query, &Count)?, 1);
+        num_docs_per_segment.push((searcher.segment_reader(0).max_doc(), searcher.num_docs()));
+    }
+    num_docs_per_segment.sort();
+    // The deletes of the second segment are honored.
+    assert_eq!(num_docs_per_segment, vec![(2, 2), (3, 1)]);
+
+    assert!(matches!(
+        index.segment_searcher(SegmentId::generate_random()),
+        Err(crate::TantivyError::InvalidArgument(_))
+    ));
+    Ok(())
+}
+
 #[test]
 fn test_merging_segment_update_docfreq() {
     let mut schema_builder = Schema::builder();
@@ -466,3 +547,650 @@ fn test_non_text_json_term_freq_bitpacked() {
         assert_eq!(postings.term_freq(), 1u32);
     }
 }
+
+#[test]
+fn test_searcher_docs() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", STRING | STORED);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    for i in 0..5 {
+        index_writer.add_document(doc!(text_field=>format!("a{i}")))?;
+    }
+    index_writer.commit()?;
+    for i in 0..3 {
+        index_writer.add_document(doc!(text_field=>format!("b{i}")))?;
+    }
+    index_writer.commit()?;
+
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.segment_readers().len(), 2);
+    let doc_addresses = [
+        DocAddress::new(1, 2),
+        DocAddress::new(0, 4),
+        DocAddress::new(1, 0),
+        DocAddress::new(0, 0),
+        DocAddress::new(1, 2),
+    ];
+    let docs: Vec<TantivyDocument> = searcher.docs(&doc_addresses)?;
+    assert_eq!(docs.len(), doc_addresses.len());
+    for (doc, doc_address) in docs.iter().zip(doc_addresses) {
+        let expected_doc: TantivyDocument = searcher.doc(doc_address)?;
+        assert_eq!(
+            doc.to_json(searcher.schema()),
+            expected_doc.to_json(searcher.schema())
+        );
+    }
+    assert!(searcher.docs::<TantivyDocument>(&[]).unwrap().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_searcher_term_statistics() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let id_field = schema_builder.add_text_field("id", STRING);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(text_field=>"a a b", id_field=>"1"))?;
+    index_writer.add_document(doc!(text_field=>"a c", id_field=>"2"))?;
+    index_writer.commit()?;
+    index_writer.add_document(doc!(text_field=>"a a a", id_field=>"3"))?;
+    index_writer.add_document(doc!(text_field=>"b", id_field=>"4"))?;
+    index_writer.commit()?;
+
+    let term = |text: &str| Term::from_field_text(text_field, text);
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.num_docs(), 4);
+    assert_eq!(searcher.doc_freq(&term("a"))?, 3);
+    assert_eq!(searcher.total_term_freq(&term("a"))?, 6);
+    assert_eq!(searcher.doc_freq(&term("b"))?, 2);
+    assert_eq!(searcher.total_term_freq(&term("b"))?, 2);
+    assert_eq!(searcher.doc_freq(&term("z"))?, 0);
+    assert_eq!(searcher.total_term_freq(&term("z"))?, 0);
+    // Without term frequencies, each document counts as one occurrence.
+    assert_eq!(
+        searcher.total_term_freq(&Term::from_field_text(id_field, "3"))?,
+        1
+    );
+
+    index_writer.delete_term(Term::from_field_text(id_field, "3"));
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.num_docs(), 3);
+    // The document frequency still counts the deleted document, the term frequency does not.
+    assert_eq!(searcher.doc_freq(&term("a"))?, 3);
+    assert_eq!(searcher.total_term_freq(&term("a"))?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_stored_only_fields() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let title = schema_builder.add_text_field("title", TEXT);
+    let payload = schema_builder.add_stored_only_field("payload", Type::Str);
+    let thumbnail = schema_builder.add_stored_only_field("thumbnail", Type::Bytes);
+    let schema = schema_builder.build();
+    assert!(schema.get_field_entry(payload).is_stored_only());
+    assert!(schema.get_field_entry(thumbnail).is_stored_only());
+    assert!(!schema.get_field_entry(title).is_stored_only());
+    let index = Index::create_in_ram(schema);
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(
+        title => "hello",
+        payload => r#"{"hello": "world"}"#,
+        thumbnail => vec![1u8, 2u8, 3u8],
+    ))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let segment_reader = searcher.segment_reader(0);
+
+    // Nothing is built for the stored-only fields...
+    for field in [payload, thumbnail] {
+        assert_eq!(segment_reader.inverted_index(field)?.terms().num_terms(), 0);
+        assert!(segment_reader
+            .fieldnorms_readers()
+            .get_field(field)?
+            .is_none());
+    }
+    assert_eq!(segment_reader.inverted_index(title)?.terms().num_terms(), 1);
+    assert!(segment_reader.fast_fields().str("payload").is_err());
+    assert!(segment_reader.fast_fields().bytes("thumbnail").is_err());
+
+    // ... but their values are retrievable from the doc store.
+    let doc: TantivyDocument = searcher.doc(DocAddress::new(0, 0))?;
+    assert_eq!(
+        doc.get_first(payload).and_then(|value| value.as_str()),
+        Some(r#"{"hello": "world"}"#)
+    );
+    assert_eq!(
+        doc.get_first(thumbnail).and_then(|value| value.as_bytes()),
+        Some(&[1u8, 2u8, 3u8][..])
+    );
+
+    // Querying a stored-only field is an error.
+    let term_query = TermQuery::new(
+        Term::from_field_text(payload, "world"),
+        IndexRecordOption::Basic,
+    );
+    assert!(matches!(
+        searcher.search(&term_query, &Count),
+        Err(crate::TantivyError::SchemaError(_))
+    ));
+    let query_parser = QueryParser::for_index(&index, vec![title]);
+    assert!(matches!(
+        query_parser.parse_query("payload:world"),
+        Err(QueryParserError::FieldNotIndexed(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_search_with_executor() -> crate::Result<()> {
+    use crate::collector::TopDocs;
+    use crate::query::EnableScoring;
+    use crate::Executor;
+
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    for segment in 0..8 {
+        for i in 0..50 {
+            let text = if (segment + i) % 3 == 0 {
+                "hello hello world"
+            } else if i % 2 == 0 {
+                "hello"
+            } else {
+                "world"
+            };
+            index_writer.add_document(doc!(text_field => text))?;
+        }
+        index_writer.commit()?;
+    }
+    let searcher = index.reader()?.searcher();
+    assert_eq!(searcher.segment_readers().len(), 8);
+
+    let query = QueryParser::for_index(&index, vec![text_field]).parse_query("hello")?;
+    let collector = (Count, TopDocs::with_limit(20));
+    let sequential_result = searcher.search_with_executor(
+        query.as_ref(),
+        &collector,
+        &Executor::single_thread(),
+        EnableScoring::enabled_from_searcher(&searcher),
+    )?;
+    assert_eq!(
+        sequential_result,
+        searcher.search(query.as_ref(), &collector)?
+    );
+    let executor = Executor::multi_thread(4, "search-test-")?;
+    for _ in 0..10 {
+        let concurrent_result = searcher.search_with_executor(
+            query.as_ref(),
+            &collector,
+            &executor,
+            EnableScoring::enabled_from_searcher(&searcher),
+        )?;
+        assert_eq!(concurrent_result, sequential_result);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_export_import_segment() -> crate::Result<()> {
+    let mut schema_builder = Schema::builder();
+    let id_field = schema_builder.add_u64_field("id", INDEXED | STORED);
+    let text_field = schema_builder.add_text_field("text", TEXT | STORED);
+    let schema = schema_builder.build();
+    let source_index = Index::create_in_ram(schema.clone());
+    let mut index_writer: IndexWriter = source_index.writer_for_tests()?;
+    for id in 0..10u64 {
+        let text = if id % 2 == 0 { "even" } else { "odd" };
+        index_writer.add_document(doc!(id_field => id, text_field => text))?;
+    }
+    index_writer.commit()?;
+    index_writer.delete_term(Term::from_field_u64(id_field, 4));
+    index_writer.commit()?;
+    index_writer.wait_merging_threads()?;
+    let segment_ids = source_index.searchable_segment_ids()?;
+    assert_eq!(segment_ids.len(), 1);
+
+    let mut archive = Vec::new();
+    source_index.export_segment(segment_ids[0], &mut archive)?;
+
+    let target_index = Index::create_in_ram(schema.clone());
+    assert_eq!(target_index.import_segment(&archive[..])?, segment_ids[0]);
+    assert_eq!(target_index.searchable_segment_ids()?, segment_ids);
+    // The same segment cannot be imported twice.
+    assert!(matches!(
+        target_index.import_segment(&archive[..]),
+        Err(crate::TantivyError::InvalidArgument(_))
+    ));
+
+    let query_parser = QueryParser::for_index(&target_index, vec![text_field]);
+    let searcher = target_index.reader()?.searcher();
+    assert_eq!(searcher.num_docs(), 9);
+    assert_eq!(
+        searcher.search(&query_parser.parse_query("even")?, &Count)?,
+        4
+    );
+    assert_eq!(
+        searcher.search(&query_parser.parse_query("odd")?, &Count)?,
+        5
+    );
+    assert_eq!(
+        searcher.search(&query_parser.parse_query("id:4")?, &Count)?,
+        0
+    );
+    let doc: TantivyDocument = searcher.doc(DocAddress::new(0, 3))?;
+    assert_eq!(
+        doc.get_first(id_field).and_then(|value| value.as_u64()),
+        Some(3)
+    );
+
+    // The imported segment behaves like any other segment of the index.
+    let mut index_writer: IndexWriter = target_index.writer_for_tests()?;
+    index_writer.delete_term(Term::from_field_u64(id_field, 5));
+    index_writer.add_document(doc!(id_field => 10u64, text_field => "even"))?;
+    index_writer.commit()?;
+    // No segment can be imported while an index writer works on the index.
+    assert!(target_index.import_segment(&archive[..]).is_err());
+    let segment_ids = target_index.searchable_segment_ids()?;
+    index_writer.merge(&segment_ids).wait()?;
+    index_writer.wait_merging_threads()?;
+    let searcher = target_index.reader()?.searcher();
+    assert_eq!(searcher.segment_readers().len(), 1);
+    assert_eq!(searcher.num_docs(), 9);
+    assert_eq!(
+        searcher.search(&query_parser.parse_query("even")?, &Count)?,
+        5
+    );
+    assert_eq!(
+        searcher.search(&query_parser.parse_query("odd")?, &Count)?,
+        4
+    );
+
+    // The schema of the archive must match the schema of the index.
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_text_field("text", TEXT);
+    let other_index = Index::create_in_ram(schema_builder.build());
+    assert!(matches!(
+        other_index.import_segment(&archive[..]),
+        Err(crate::TantivyError::SchemaError(_))
+    ));
+    assert!(other_index.import_segment(&archive[..20]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_search_with_deadline() -> crate::Result<()> {
+    use std::time::{Duration, Instant};
+
+    use crate::collector::{Collector, SegmentCollector, TopDocs};
+    use crate::query::AllQuery;
+    use crate::{DocId, Score, SegmentOrdinal, SegmentReader, TantivyError};
+
+    // A collector spending some time on each segment and on each document.
+    struct SlowCount {
+        segment_delay: Duration,
+    }
+
+    struct SlowSegmentCount(usize);
+
+    impl Collector for SlowCount {
+        type Fruit = usize;
+        type Child = SlowSegmentCount;
+
+        fn for_segment(
+            &self,
+            _segment_local_id: SegmentOrdinal,
+            _segment_reader: &SegmentReader,
+        ) -> crate::Result<SlowSegmentCount> {
+            std::thread::sleep(self.segment_delay);
+            Ok(SlowSegmentCount(0))
+        }
+
+        fn requires_scoring(&self) -> bool {
+            false
+        }
+
+        fn merge_fruits(&self, segment_counts: Vec<usize>) -> crate::Result<usize> {
+            Ok(segment_counts.into_iter().sum())
+        }
+    }
+
+    impl SegmentCollector for SlowSegmentCount {
+        type Fruit = usize;
+
+        fn collect(&mut self, _doc: DocId, _score: Score) {
+            std::thread::sleep(Duration::from_micros(100));
+            self.0 += 1;
+        }
+
+        fn harvest(self) -> usize {
+            self.0
+        }
+    }
+
+    let mut schema_builder = Schema::builder();
+    let text_field = schema_builder.add_text_field("text", TEXT);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    for i in 0..10_000 {
+        let text = if i % 2 == 0 { "hello" } else { "world" };
+        index_writer.add_document(doc!(text_field => text))?;
+    }
+    index_writer.delete_term(Term::from_field_text(text_field, "world"));
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+
+    // Collecting all of the documents takes at least 0.5 second.
+    let slow_count = SlowCount {
+        segment_delay: Duration::ZERO,
+    };
+    let start = Instant::now();
+    let result =
+        searcher.search_with_deadline(&AllQuery, &slow_count, start + Duration::from_millis(20));
+    assert!(matches!(result, Err(TantivyError::TimedOut)));
+    assert!(matches!(
+        searcher.search_with_deadline(&AllQuery, &slow_count, start),
+        Err(TantivyError::TimedOut)
+    ));
+    // The deadline is checked before building the scorer of a segment, even if the query
+    // matches no document.
+    let no_match_query = TermQuery::new(
+        Term::from_field_text(text_field, "absent"),
+        IndexRecordOption::Basic,
+    );
+    let slow_segment_count = SlowCount {
+        segment_delay: Duration::from_millis(50),
+    };
+    let result = searcher.search_with_deadline(
+        &no_match_query,
+        &slow_segment_count,
+        Instant::now() + Duration::from_millis(20),
+    );
+    assert!(matches!(result, Err(TantivyError::TimedOut)));
+
+    // The result is the same as without a deadline if the search completes in time.
+    let deadline = Instant::now() + Duration::from_secs(600);
+    assert_eq!(
+        searcher.search_with_deadline(&AllQuery, &crate::collector::Count, deadline)?,
+        5_000
+    );
+    let query = QueryParser::for_index(&index, vec![text_field]).parse_query("hello")?;
+    let collector = TopDocs::with_limit(10);
+    assert_eq!(
+        searcher.search_with_deadline(query.as_ref(), &collector, deadline)?,
+        searcher.search(query.as_ref(), &collector)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_add_fields() -> crate::Result<()> {
+    use crate::collector::{TermCountCollector, TopDocs};
+    use crate::query::AllQuery;
+    use crate::schema::{FieldEntry, NumericOptions, FAST};
+    use crate::TantivyError;
+
+    let mut schema_builder = Schema::builder();
+    let title = schema_builder.add_text_field("title", TEXT | STORED);
+    let directory = RamDirectory::create();
+    let mut index = Index::create(
+        directory.clone(),
+        schema_builder.build(),
+        IndexSettings::default(),
+    )?;
+    let stale_index = index.clone();
+    {
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(title => "old hello"))?;
+        index_writer.add_document(doc!(title => "old world"))?;
+        index_writer.commit()?;
+        // No field can be added while an index writer is working on the index.
+        assert!(index
+            .add_fields(vec![FieldEntry::new_u64("year".to_string(), FAST.into())])
+            .is_err());
+        index_writer.wait_merging_threads()?;
+    }
+    assert_eq!(index.schema_version(), 0);
+
+    let new_fields = index.add_fields(vec![
+        FieldEntry::new_text("body".to_string(), TEXT | STORED | FAST),
+        FieldEntry::new_u64("year".to_string(), NumericOptions::from(INDEXED) | FAST),
+    ])?;
+    let (body, year) = (new_fields[0], new_fields[1]);
+    assert_eq!(index.schema_version(), 1);
+    assert_eq!(index.schema().get_field("title")?, title);
+    assert_eq!(index.schema().get_field("body")?, body);
+    assert!(matches!(
+        index.add_fields(vec![FieldEntry::new_u64("title".to_string(), FAST.into())]),
+        Err(TantivyError::SchemaError(_))
+    ));
+    // The documents of the existing segments have no value for a new field, so it cannot be
+    // required.
+    assert!(matches!(
+        index.add_fields(vec![FieldEntry::new_u64(
+            "rating".to_string(),
+            NumericOptions::from(FAST).set_required()
+        )]),
+        Err(TantivyError::SchemaError(_))
+    ));
+    assert_eq!(index.schema_version(), 1);
+    // The index opened before the change cannot write to the index anymore.
+    assert!(matches!(
+        stale_index.writer_for_tests::<TantivyDocument>(),
+        Err(TantivyError::SchemaError(_))
+    ));
+
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.set_merge_policy(Box::new(NoMergePolicy));
+    index_writer.add_document(doc!(title => "new hello", body => "hello body", year => 2024u64))?;
+    index_writer.add_document(doc!(title => "new world", year => 2025u64))?;
+    index_writer.commit()?;
+
+    let check_index = |index: &Index| -> crate::Result<()> {
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 4);
+        let query_parser = QueryParser::for_index(index, vec![title, body]);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        // The existing field matches documents of all of the segments.
+        assert_eq!(count("title:hello")?, 2);
+        // The new fields only match documents of the new segments.
+        assert_eq!(count("body:hello")?, 1);
+        assert_eq!(count("hello")?, 2);
+        assert_eq!(count("year:2024")?, 1);
+        assert_eq!(count("year:[2000 TO 3000]")?, 2);
+        let body_counts = searcher.search(&AllQuery, &TermCountCollector::new("body"))?;
+        assert_eq!(body_counts.len(), 1);
+        assert_eq!(body_counts["hello body"], 1);
+        // The documents of the old segments have no value for the new fields.
+        let query = query_parser.parse_query("title:old")?;
+        for (_score, doc_address) in searcher.search(&query, &TopDocs::with_limit(10))? {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            assert!(doc.get_first(body).is_none());
+            assert!(doc.get_first(title).is_some());
+        }
+        Ok(())
+    };
+    check_index(&index)?;
+
+    // The new schema is persisted with its version.
+    let reopened_index = Index::open(directory)?;
+    assert_eq!(reopened_index.schema(), index.schema());
+    assert_eq!(reopened_index.schema_version(), 1);
+    check_index(&reopened_index)?;
+
+    // Old and new segments can be merged.
+    let segment_ids = index.searchable_segment_ids()?;
+    assert_eq!(segment_ids.len(), 2);
+    index_writer.merge(&segment_ids).wait()?;
+    index_writer.wait_merging_threads()?;
+    assert_eq!(index.searchable_segment_ids()?.len(), 1);
+    check_index(&index)?;
+    Ok(())
+}
+
+#[test]
+fn test_search_tokenizer() -> crate::Result<()> {
+    use std::collections::HashMap;
+
+    use crate::schema::{TextFieldIndexing, TextOptions};
+    use crate::tokenizer::{LowerCaser, SimpleTokenizer, SynonymFilter, TextAnalyzer};
+
+    let mut schema_builder = Schema::builder();
+    let title = schema_builder.add_text_field(
+        "title",
+        TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_search_tokenizer("synonyms"),
+        ),
+    );
+    let body = schema_builder.add_text_field("body", TEXT);
+    let schema = schema_builder.build();
+    // The search tokenizer is part of the schema.
+    let schema_json = serde_json::to_string(&schema)?;
+    assert!(schema_json.contains(r#""search_tokenizer":"synonyms""#));
+    assert_eq!(serde_json::from_str::<Schema>(&schema_json)?, schema);
+
+    // Both tokenizers have to be registered.
+    let err = Index::builder()
+        .schema(schema.clone())
+        .create_in_ram()
+        .err()
+        .unwrap();
+    assert!(matches!(err, crate::TantivyError::SchemaError(_)));
+
+    let tokenizer_manager = TokenizerManager::default();
+    tokenizer_manager.register(
+        "synonyms",
+        TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(SynonymFilter::new(HashMap::from([(
+                "tv".to_string(),
+                vec!["television".to_string()],
+            )])))
+            .build(),
+    );
+    let directory = RamDirectory::create();
+    let index = Index::builder()
+        .schema(schema.clone())
+        .tokenizers(tokenizer_manager.clone())
+        .open_or_create(directory.clone())?;
+    // They are validated again when the index is opened by the builder...
+    let err = Index::builder()
+        .schema(schema.clone())
+        .open_or_create(directory.clone())
+        .err()
+        .unwrap();
+    assert!(matches!(err, crate::TantivyError::SchemaError(_)));
+    Index::builder()
+        .schema(schema)
+        .tokenizers(tokenizer_manager.clone())
+        .open_or_create(directory.clone())?;
+    // ... but not by `Index::open`, as they can be registered afterwards.
+    let reopened_index = Index::open(directory)?;
+    reopened_index
+        .tokenizers()
+        .register("synonyms", tokenizer_manager.get("synonyms").unwrap());
+    QueryParser::for_index(&reopened_index, vec![title]).parse_query("title:tv")?;
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(title => "A television set", body => "A television set"))?;
+    index_writer.add_document(doc!(title => "TV remote", body => "TV remote"))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+
+    // The synonyms were not expanded at indexing time...
+    assert_eq!(searcher.doc_freq(&Term::from_field_text(title, "tv"))?, 1);
+    assert_eq!(
+        searcher.doc_freq(&Term::from_field_text(title, "television"))?,
+        1
+    );
+    // ... but they are at query time.
+    let query_parser = QueryParser::for_index(&index, vec![title, body]);
+    let count = |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+    assert_eq!(count("title:tv")?, 2);
+    assert_eq!(count("title:TV")?, 2);
+    assert_eq!(count("title:television")?, 1);
+    // The field without a search tokenizer uses its indexing tokenizer.
+    assert_eq!(count("body:tv")?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_term_vector() -> crate::Result<()> {
+    use std::collections::BTreeMap;
+
+    let mut schema_builder = Schema::builder();
+    let title = schema_builder.add_text_field("title", TEXT);
+    let id = schema_builder.add_text_field("id", STRING);
+    let index = Index::create_in_ram(schema_builder.build());
+    let mut index_writer: IndexWriter = index.writer_for_tests()?;
+    index_writer.add_document(doc!(title => "a first segment", id => "a"))?;
+    index_writer.commit()?;
+    let text = "The quick fox jumps over the lazy FOX";
+    index_writer.add_document(doc!(title => "another document", id => "b"))?;
+    index_writer.add_document(doc!(title => text, id => "c"))?;
+    index_writer.add_document(doc!(id => "d"))?;
+    index_writer.commit()?;
+    let searcher = index.reader()?.searcher();
+    let segment_ord = searcher
+        .segment_readers()
+        .iter()
+        .position(|segment_reader| segment_reader.max_doc() == 3)
+        .unwrap() as u32;
+
+    // The expected term vector, computed from the tokens of the text.
+    let mut expected: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    let mut tokenizer = index.tokenizers().get("default").unwrap();
+    let mut token_stream = tokenizer.token_stream(text);
+    while let Some(token) = token_stream.next() {
+        expected
+            .entry(token.text.clone())
+            .or_default()
+            .push(token.position as u32);
+    }
+    assert_eq!(expected["fox"], vec![2, 7]);
+
+    let term_vector = searcher.term_vector(DocAddress::new(segment_ord, 1), title)?;
+    assert_eq!(term_vector.len(), expected.len());
+    for (entry, (token, positions)) in term_vector.iter().zip(&expected) {
+        assert_eq!(entry.term, Term::from_field_text(title, token));
+        assert_eq!(entry.term_freq as usize, positions.len());
+        assert_eq!(&entry.positions, positions);
+    }
+    let the = term_vector
+        .get(&Term::from_field_text(title, "the"))
+        .unwrap();
+    assert_eq!(the.positions, vec![0, 5]);
+    assert!(term_vector
+        .get(&Term::from_field_text(title, "document"))
+        .is_none());
+
+    // A document without a value for the field has an empty term vector.
+    assert!(searcher
+        .term_vector(DocAddress::new(segment_ord, 2), title)?
+        .is_empty());
+    // The field must index positions, and the document must exist.
+    assert!(matches!(
+        searcher.term_vector(DocAddress::new(segment_ord, 1), id),
+        Err(TantivyError::SchemaError(_))
+    ));
+    assert!(matches!(
+        searcher.term_vector(DocAddress::new(segment_ord, 3), title),
+        Err(TantivyError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        searcher.term_vector(DocAddress::new(2, 0), title),
+        Err(TantivyError::InvalidArgument(_))
+    ));
+    Ok(())
+}
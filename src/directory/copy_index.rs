@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::core::{MANAGED_FILEPATH, META_FILEPATH};
+use crate::directory::{Directory, TerminatingWrite, META_LOCK};
+use crate::error::{DataCorruption, TantivyError};
+use crate::index::{IndexMeta, SegmentMetaInventory};
+use crate::Index;
+
+/// Size of the chunks in which the files are copied, so that large files are never loaded
+/// in memory at once.
+const COPY_CHUNK_NUM_BYTES: usize = 1 << 20;
+
+/// Copies the index stored in `src` to `dst`, e.g. to take a [`RamDirectory`] snapshot of an
+/// index stored in a [`MmapDirectory`], or to persist an index built in memory.
+///
+/// Only the files of the last commit are copied, i.e. the `meta.json` file and the files of
+/// the segments it references: the files left behind by uncommitted or discarded changes are
+/// skipped. The copy is a consistent index that can be opened with [`Index::open`].
+///
+/// The segment files are streamed in chunks. `meta.json` is written last, with
+/// [`Directory::atomic_write`], so that the copy only becomes visible as an index once all of
+/// its files have been copied.
+///
+/// `src` and `dst` are the directories in which the indexes are stored, not the
+/// [`ManagedDirectory`](crate::directory::ManagedDirectory) returned by
+/// [`Index::directory`], which hides the footers of the files.
+///
+/// Returns [`TantivyError::IndexAlreadyExists`] if `dst` already contains an index.
+///
+/// [`RamDirectory`]: crate::directory::RamDirectory
+/// [`MmapDirectory`]: crate::directory::MmapDirectory
+pub fn copy_index(src: &dyn Directory, dst: &dyn Directory) -> crate::Result<()> {
+    if Index::exists(dst)? {
+        return Err(TantivyError::IndexAlreadyExists);
+    }
+    // Prevents the files of the commit from getting garbage collected while we copy them.
+    let _meta_lock = src.acquire_lock(&META_LOCK)?;
+    let meta_data = src.atomic_read(&META_FILEPATH)?;
+    let meta_string = String::from_utf8(meta_data).map_err(|_| {
+        DataCorruption::new(
+            META_FILEPATH.to_path_buf(),
+            "Meta file does not contain valid utf8 file.".to_string(),
+        )
+    })?;
+    let index_meta = IndexMeta::deserialize(&meta_string, &SegmentMetaInventory::default())?;
+
+    let mut managed_paths: HashSet<PathBuf> = HashSet::new();
+    for segment_meta in &index_meta.segments {
+        for path in segment_meta.list_files() {
+            // Not all of the segments have all of the components.
+            if !src.exists(&path)? {
+                continue;
+            }
+            let file_slice = src.open_read(&path)?;
+            let mut write = dst.open_write(&path)?;
+            let num_bytes = file_slice.num_bytes().get_bytes() as usize;
+            let mut start = 0;
+            while start < num_bytes {
+                let end = (start + COPY_CHUNK_NUM_BYTES).min(num_bytes);
+                write.write_all(file_slice.read_bytes_slice(start..end)?.as_slice())?;
+                start = end;
+            }
+            write.terminate()?;
+            managed_paths.insert(path);
+        }
+    }
+    // The copied files are managed by tantivy, so that they get garbage collected once they are
+    // not used anymore.
+    managed_paths.insert(META_FILEPATH.to_path_buf());
+    let mut managed_json = serde_json::to_vec(&managed_paths)?;
+    writeln!(&mut managed_json)?;
+    dst.atomic_write(&MANAGED_FILEPATH, &managed_json)?;
+    dst.atomic_write(&META_FILEPATH, meta_string.as_bytes())?;
+    dst.sync_directory()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::copy_index;
+    use crate::collector::Count;
+    use crate::directory::{Directory, RamDirectory};
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, TEXT};
+    use crate::{Index, IndexSettings, IndexWriter, TantivyError, Term};
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn test_copy_index() -> crate::Result<()> {
+        use crate::directory::MmapDirectory;
+
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let mmap_directory = MmapDirectory::create_from_tempdir()?;
+        let index = Index::create(mmap_directory.clone(), schema, IndexSettings::default())?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "hello happy tax payer"))?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "hello world"))?;
+        index_writer.delete_term(Term::from_field_text(text, "tax"));
+        index_writer.commit()?;
+        // Neither uncommitted documents nor orphaned files are copied.
+        index_writer.add_document(doc!(text => "hello uncommitted"))?;
+        mmap_directory.atomic_write(Path::new("orphan.idx"), b"orphan")?;
+
+        let ram_directory = RamDirectory::create();
+        copy_index(&mmap_directory, &ram_directory)?;
+        assert!(!ram_directory.exists(Path::new("orphan.idx"))?);
+
+        let copy = Index::open(ram_directory.clone())?;
+        assert_eq!(copy.schema(), index.schema());
+        assert_eq!(copy.searchable_segment_ids()?.len(), 2);
+        let searcher = copy.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&copy, vec![text]);
+        let count =
+            |query: &str| searcher.search(&query_parser.parse_query(query).unwrap(), &Count);
+        assert_eq!(count("hello")?, 2);
+        assert_eq!(count("tax")?, 0);
+        assert_eq!(count("world")?, 1);
+        assert_eq!(count("uncommitted")?, 0);
+
+        // The copy can be written to.
+        let mut copy_writer: IndexWriter = copy.writer_for_tests()?;
+        copy_writer.add_document(doc!(text => "hello copy"))?;
+        copy_writer.commit()?;
+        assert_eq!(copy.reader()?.searcher().num_docs(), 3);
+
+        // An existing index is never overwritten.
+        assert!(matches!(
+            copy_index(&mmap_directory, &ram_directory),
+            Err(TantivyError::IndexAlreadyExists)
+        ));
+        Ok(())
+    }
+}
@@ -208,6 +208,17 @@ pub trait Directory: DirectoryClone + fmt::Debug + Send + Sync + 'static {
         }
     }
 
+    /// Hints that the files at `paths` are about to be read, so that their content can be
+    /// loaded before the first queries arrive, e.g. the term dictionary and the fast fields of a
+    /// hot segment.
+    ///
+    /// This is only a hint. The default implementation does nothing, which is the right
+    /// behavior for directories keeping their data in memory.
+    fn prefetch(&self, paths: &[PathBuf]) -> Result<(), OpenReadError> {
+        let _ = paths;
+        Ok(())
+    }
+
     /// Registers a callback that will be called whenever a change on the `meta.json`
     /// using the [`Directory::atomic_write()`] API is detected.
     ///
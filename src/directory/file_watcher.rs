@@ -2,7 +2,7 @@ use std::io::BufRead;
 use std::path::Path;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{fs, io, thread};
 
 use crc32fast::Hasher;
@@ -11,19 +11,33 @@ use crate::directory::{WatchCallback, WatchCallbackList, WatchHandle};
 
 const POLLING_INTERVAL: Duration = Duration::from_millis(if cfg!(test) { 1 } else { 500 });
 
+/// Maximum delay of the callbacks after a modification, as a number of debounce windows.
+const MAX_DEBOUNCE_WINDOWS: u32 = 4;
+
 // Watches a file and executes registered callbacks when the file is modified.
+//
+// Modifications happening within `debounce` of each other are coalesced: callbacks are
+// executed once the file has not been modified for `debounce`. So that modifications arriving
+// steadily more often than `debounce` are still broadcast, callbacks are also executed once
+// `MAX_DEBOUNCE_WINDOWS * debounce` has elapsed since the first modification not broadcast yet.
 pub struct FileWatcher {
     path: Arc<Path>,
     callbacks: Arc<WatchCallbackList>,
     state: Arc<AtomicUsize>, // 0: new, 1: runnable, 2: terminated
+    debounce: Duration,
 }
 
 impl FileWatcher {
     pub fn new(path: &Path) -> FileWatcher {
+        FileWatcher::with_debounce(path, Duration::ZERO)
+    }
+
+    pub fn with_debounce(path: &Path, debounce: Duration) -> FileWatcher {
         FileWatcher {
             path: Arc::from(path),
             callbacks: Default::default(),
             state: Default::default(),
+            debounce,
         }
     }
 
@@ -39,11 +53,15 @@ impl FileWatcher {
         let path = self.path.clone();
         let callbacks = self.callbacks.clone();
         let state = self.state.clone();
+        let debounce = self.debounce;
+        let max_delay = debounce * MAX_DEBOUNCE_WINDOWS;
 
         thread::Builder::new()
             .name("thread-tantivy-meta-file-watcher".to_string())
             .spawn(move || {
                 let mut current_checksum_opt = None;
+                // Times of the first and of the last modifications that were not broadcast yet.
+                let mut pending_changes_opt: Option<(Instant, Instant)> = None;
 
                 while state.load(Ordering::SeqCst) == 1 {
                     if let Ok(checksum) = FileWatcher::compute_checksum(&path) {
@@ -53,6 +71,16 @@ impl FileWatcher {
                         if metafile_has_changed {
                             info!("Meta file {:?} was modified", path);
                             current_checksum_opt = Some(checksum);
+                            let now = Instant::now();
+                            let first_change =
+                                pending_changes_opt.map_or(now, |(first_change, _)| first_change);
+                            pending_changes_opt = Some((first_change, now));
+                        }
+                    }
+                    if let Some((first_change, last_change)) = pending_changes_opt {
+                        if last_change.elapsed() >= debounce || first_change.elapsed() >= max_delay
+                        {
+                            pending_changes_opt = None;
                             // We actually ignore callbacks failing here.
                             // We just wait for the end of their execution.
                             let _ = callbacks.broadcast().wait();
@@ -182,4 +210,68 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_file_watcher_debounce() -> crate::Result<()> {
+        let tmp_dir = tempfile::TempDir::new()?;
+        let tmp_file = tmp_dir.path().join("watched.txt");
+
+        let counter: Arc<AtomicUsize> = Default::default();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let debounce = Duration::from_millis(200);
+
+        let watcher = FileWatcher::with_debounce(&tmp_file, debounce);
+        let counter_clone = counter.clone();
+        let _handle = watcher.watch(WatchCallback::new(move || {
+            let val = counter_clone.fetch_add(1, Ordering::SeqCst);
+            tx.send(val + 1).unwrap();
+        }));
+
+        // A burst of modifications, each of them within the debounce window of the previous one.
+        let start = Instant::now();
+        for content in [b"foo", b"bar", b"baz", b"qux"] {
+            atomic_write(&tmp_file, content)?;
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert!(start.elapsed() >= debounce);
+        assert!(rx.recv_timeout(debounce * 2).is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        atomic_write(&tmp_file, b"foo")?;
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_watcher_debounce_steady_modifications() -> crate::Result<()> {
+        let tmp_dir = tempfile::TempDir::new()?;
+        let tmp_file = tmp_dir.path().join("watched.txt");
+
+        let counter: Arc<AtomicUsize> = Default::default();
+        let debounce = Duration::from_millis(100);
+
+        let watcher = FileWatcher::with_debounce(&tmp_file, debounce);
+        let counter_clone = counter.clone();
+        let _handle = watcher.watch(WatchCallback::new(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        // The file is modified more often than the debounce window, for more than twice the
+        // maximum delay of the callbacks.
+        let start = Instant::now();
+        let mut num_modifications = 0u32;
+        while start.elapsed() < debounce * MAX_DEBOUNCE_WINDOWS * 3 {
+            num_modifications += 1;
+            atomic_write(&tmp_file, num_modifications.to_string().as_bytes())?;
+            thread::sleep(Duration::from_millis(10));
+        }
+        // The callbacks are executed even though the file never stopped changing.
+        let num_callbacks = counter.load(Ordering::SeqCst);
+        assert!(num_callbacks >= 2, "{num_callbacks} callbacks");
+        assert!(num_callbacks < num_modifications as usize / 4);
+
+        Ok(())
+    }
 }
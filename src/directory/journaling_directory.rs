@@ -0,0 +1,450 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use common::{AntiCallToken, TerminatingWrite};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use super::FileHandle;
+use crate::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use crate::directory::{
+    Directory, DirectoryLock, FileSlice, Lock, WatchCallback, WatchHandle, WritePtr,
+};
+use crate::error::TantivyError;
+
+/// Path of the journal of a [`JournalingDirectory`], relative to the directory.
+pub(crate) static JOURNAL_FILEPATH: Lazy<&'static Path> = Lazy::new(|| Path::new(".journal.json"));
+
+/// Held by the [`JournalingDirectory`] owning the journal, for its whole lifetime.
+pub(crate) static JOURNAL_LOCK: Lazy<Lock> = Lazy::new(|| Lock {
+    filepath: PathBuf::from(".tantivy-journal.lock"),
+    is_blocking: false,
+});
+
+/// A [`Directory`] recording the file operations in progress in a journal, so that an
+/// interruption, e.g. a crash of the process, never leaves partially written files behind.
+///
+/// Before a file is opened for write, or deleted, the operation is recorded in a journal
+/// kept in the wrapped directory. The operation is removed from the journal once it is
+/// complete, i.e. once the write has been terminated, or the file has been deleted.
+///
+/// # Recovery
+///
+/// [`JournalingDirectory::open`] recovers the operations left in the journal by a previous
+/// process before returning the directory:
+/// - incomplete writes are rolled back: the partially written files are deleted. As files are never
+///   overwritten, a complete version of the file never existed.
+/// - incomplete deletes are replayed: the files are deleted if they still exist.
+///
+/// A file is only referenced by the index once its write has been terminated, so that
+/// rolling back the incomplete writes never removes a file that a commit relies on. After the
+/// recovery, the directory only contains complete files.
+///
+/// # Ownership of the journal
+///
+/// The operations of the journal may still be in progress in another process, or in another
+/// `JournalingDirectory` of the same process. The journal is therefore owned by the
+/// `JournalingDirectory` holding the journal lock, `.tantivy-journal.lock`, which is acquired
+/// by [`JournalingDirectory::open`] and released once the directory and all of its clones are
+/// dropped. Only the owner recovers and updates the journal.
+///
+/// If the lock is held already, the directory is opened without recovering the journal, and is
+/// read-only: [`Directory::open_write`] and [`Directory::delete`] fail. Such a directory can
+/// be used to search the index, as [atomic writes](Directory::atomic_write) are still allowed.
+///
+/// Atomic writes are atomic already, and are not journaled. Each journaled operation costs
+/// two atomic writes of the journal.
+///
+/// ```rust
+/// use tantivy::directory::{JournalingDirectory, RamDirectory};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, IndexWriter};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let directory = JournalingDirectory::open(RamDirectory::create())?;
+/// let index = Index::create(directory, schema_builder.build(), Default::default())?;
+/// let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+/// index_writer.commit()?;
+/// assert_eq!(index.reader()?.searcher().num_docs(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct JournalingDirectory<D> {
+    inner: D,
+    // `None` if the journal is owned by another directory.
+    journal: Option<Arc<Journal>>,
+}
+
+/// The operations in progress.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JournalEntries {
+    pending_writes: BTreeSet<PathBuf>,
+    pending_deletes: BTreeSet<PathBuf>,
+}
+
+impl JournalEntries {
+    fn is_empty(&self) -> bool {
+        self.pending_writes.is_empty() && self.pending_deletes.is_empty()
+    }
+}
+
+struct Journal {
+    directory: Box<dyn Directory>,
+    entries: Mutex<JournalEntries>,
+    _lock: DirectoryLock,
+}
+
+impl fmt::Debug for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Journal")
+            .field("directory", &self.directory)
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl Journal {
+    /// Applies `update` to the entries of the journal, and persists them.
+    fn update(&self, update: impl FnOnce(&mut JournalEntries)) -> io::Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        update(&mut entries);
+        let json = serde_json::to_vec(&*entries)?;
+        self.directory.atomic_write(&JOURNAL_FILEPATH, &json)
+    }
+}
+
+impl<D: Directory + Clone> JournalingDirectory<D> {
+    /// Wraps `inner`, after recovering the operations left incomplete in its journal.
+    ///
+    /// See the [recovery semantics](JournalingDirectory#recovery). If the journal is owned by
+    /// another directory, the returned directory is
+    /// [read-only](JournalingDirectory#ownership-of-the-journal).
+    pub fn open(inner: D) -> crate::Result<JournalingDirectory<D>> {
+        let lock = match inner.acquire_lock(&JOURNAL_LOCK) {
+            Ok(lock) => lock,
+            Err(LockError::LockBusy) => {
+                info!("The journal is owned by another directory, opening it read-only");
+                return Ok(JournalingDirectory {
+                    inner,
+                    journal: None,
+                });
+            }
+            Err(lock_error) => return Err(lock_error.into()),
+        };
+        let entries: JournalEntries = match inner.atomic_read(&JOURNAL_FILEPATH) {
+            Ok(json) => serde_json::from_slice(&json)?,
+            Err(OpenReadError::FileDoesNotExist(_)) => JournalEntries::default(),
+            Err(open_read_error) => return Err(open_read_error.into()),
+        };
+        if !entries.is_empty() {
+            info!("Recovering the journaled operations {entries:?}");
+        }
+        for path in entries
+            .pending_writes
+            .iter()
+            .chain(entries.pending_deletes.iter())
+        {
+            match inner.delete(path) {
+                Ok(()) | Err(DeleteError::FileDoesNotExist(_)) => {}
+                Err(DeleteError::IoError { io_error, .. }) => {
+                    return Err(TantivyError::IoError(io_error))
+                }
+            }
+        }
+        let journal = Journal {
+            directory: inner.box_clone(),
+            entries: Mutex::default(),
+            _lock: lock,
+        };
+        journal.update(|_| {})?;
+        Ok(JournalingDirectory {
+            inner,
+            journal: Some(Arc::new(journal)),
+        })
+    }
+
+    /// Returns the wrapped directory.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    /// Returns true if this directory owns the journal, i.e. if it is not read-only.
+    pub fn owns_journal(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    fn owned_journal(&self) -> io::Result<&Arc<Journal>> {
+        self.journal.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "The journal is owned by another directory, this directory is read-only",
+            )
+        })
+    }
+}
+
+/// A write removing its path from the journal once terminated.
+struct JournaledWrite {
+    write: WritePtr,
+    path: PathBuf,
+    journal: Arc<Journal>,
+}
+
+impl Write for JournaledWrite {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write.flush()
+    }
+}
+
+impl TerminatingWrite for JournaledWrite {
+    fn terminate_ref(&mut self, token: AntiCallToken) -> io::Result<()> {
+        self.write.terminate_ref(token)?;
+        let path = &self.path;
+        self.journal.update(|entries| {
+            entries.pending_writes.remove(path);
+        })
+    }
+}
+
+impl<D: Directory + Clone> Directory for JournalingDirectory<D> {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        self.inner.get_file_handle(path)
+    }
+
+    fn open_read(&self, path: &Path) -> Result<FileSlice, OpenReadError> {
+        self.inner.open_read(path)
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        let wrap_io_error = |io_error: io::Error| DeleteError::IoError {
+            io_error: Arc::new(io_error),
+            filepath: path.to_path_buf(),
+        };
+        let journal = self.owned_journal().map_err(wrap_io_error)?;
+        journal
+            .update(|entries| {
+                entries.pending_deletes.insert(path.to_path_buf());
+            })
+            .map_err(wrap_io_error)?;
+        let delete_result = self.inner.delete(path);
+        journal
+            .update(|entries| {
+                entries.pending_deletes.remove(path);
+            })
+            .map_err(wrap_io_error)?;
+        delete_result
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        self.inner.exists(path)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        let wrap_io_error =
+            |io_error: io::Error| OpenWriteError::wrap_io_error(io_error, path.to_path_buf());
+        let journal = self.owned_journal().map_err(wrap_io_error)?;
+        // The existence is checked before recording the write, so that recovering the
+        // journal never deletes a file that was not created by the write.
+        if self.inner.exists(path).map_err(|open_read_error| {
+            wrap_io_error(io::Error::other(open_read_error.to_string()))
+        })? {
+            return Err(OpenWriteError::FileAlreadyExists(path.to_path_buf()));
+        }
+        journal
+            .update(|entries| {
+                entries.pending_writes.insert(path.to_path_buf());
+            })
+            .map_err(wrap_io_error)?;
+        let write = match self.inner.open_write(path) {
+            Ok(write) => write,
+            Err(open_write_error) => {
+                journal
+                    .update(|entries| {
+                        entries.pending_writes.remove(path);
+                    })
+                    .map_err(wrap_io_error)?;
+                return Err(open_write_error);
+            }
+        };
+        Ok(BufWriter::new(Box::new(JournaledWrite {
+            write,
+            path: path.to_path_buf(),
+            journal: journal.clone(),
+        })))
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        self.inner.atomic_read(path)
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.inner.atomic_write(path, data)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        self.inner.sync_directory()
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.inner.acquire_lock(lock)
+    }
+
+    fn prefetch(&self, paths: &[PathBuf]) -> Result<(), OpenReadError> {
+        self.inner.prefetch(paths)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
+        self.inner.watch(watch_callback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use common::TerminatingWrite;
+
+    use super::{JournalingDirectory, JOURNAL_FILEPATH};
+    use crate::directory::{Directory, RamDirectory};
+    use crate::schema::{Schema, TEXT};
+    use crate::{Index, IndexWriter};
+
+    fn journal(directory: &RamDirectory) -> serde_json::Value {
+        serde_json::from_slice(&directory.atomic_read(&JOURNAL_FILEPATH).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_journaling_directory_rolls_back_interrupted_write() -> crate::Result<()> {
+        let ram_directory = RamDirectory::create();
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        let mut write = directory.open_write(Path::new("complete"))?;
+        write.write_all(b"complete")?;
+        write.terminate()?;
+        let mut write = directory.open_write(Path::new("partial"))?;
+        write.write_all(b"part")?;
+        write.flush()?;
+        assert_eq!(
+            journal(&ram_directory)["pending_writes"],
+            serde_json::json!(["partial"])
+        );
+        // The process is interrupted before the write is terminated.
+        drop(write);
+        drop(directory);
+        assert!(ram_directory.exists(Path::new("partial"))?);
+
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        assert!(!directory.exists(Path::new("partial"))?);
+        assert_eq!(
+            directory
+                .open_read(Path::new("complete"))?
+                .read_bytes()?
+                .as_slice(),
+            b"complete"
+        );
+        assert_eq!(
+            journal(&ram_directory),
+            serde_json::json!({"pending_writes": [], "pending_deletes": []})
+        );
+        // The rolled back file can be written again.
+        let mut write = directory.open_write(Path::new("partial"))?;
+        write.write_all(b"partial")?;
+        write.terminate()?;
+        assert_eq!(
+            directory
+                .open_read(Path::new("partial"))?
+                .read_bytes()?
+                .as_slice(),
+            b"partial"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_journaling_directory_replays_interrupted_delete() -> crate::Result<()> {
+        let ram_directory = RamDirectory::create();
+        ram_directory.atomic_write(Path::new("kept"), b"kept")?;
+        ram_directory.atomic_write(Path::new("deleted"), b"deleted")?;
+        // The process was interrupted while deleting `deleted`.
+        ram_directory.atomic_write(
+            &JOURNAL_FILEPATH,
+            br#"{"pending_writes": [], "pending_deletes": ["deleted", "already_deleted"]}"#,
+        )?;
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        assert!(!directory.exists(Path::new("deleted"))?);
+        assert!(directory.exists(Path::new("kept"))?);
+
+        directory.delete(Path::new("kept")).unwrap();
+        assert!(!directory.exists(Path::new("kept"))?);
+        assert!(directory.delete(Path::new("kept")).is_err());
+        assert_eq!(
+            journal(&ram_directory),
+            serde_json::json!({"pending_writes": [], "pending_deletes": []})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_journaling_directory_only_owner_recovers() -> crate::Result<()> {
+        let ram_directory = RamDirectory::create();
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        assert!(directory.owns_journal());
+        let mut write = directory.open_write(Path::new("in_progress"))?;
+        write.write_all(b"in progress")?;
+        write.flush()?;
+        let journal_before = journal(&ram_directory);
+
+        // The write is still in progress: another directory neither rolls it back nor
+        // rewrites the journal.
+        let read_only_directory = JournalingDirectory::open(ram_directory.clone())?;
+        assert!(!read_only_directory.owns_journal());
+        assert!(read_only_directory.exists(Path::new("in_progress"))?);
+        assert_eq!(journal(&ram_directory), journal_before);
+        assert!(read_only_directory.open_write(Path::new("other")).is_err());
+        assert!(read_only_directory
+            .delete(Path::new("in_progress"))
+            .is_err());
+        assert!(ram_directory.exists(Path::new("in_progress"))?);
+
+        write.terminate()?;
+        drop(directory);
+        // The journal is released with its owner.
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        assert!(directory.owns_journal());
+        assert!(directory.exists(Path::new("in_progress"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_journaling_directory_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let ram_directory = RamDirectory::create();
+        let directory = JournalingDirectory::open(ram_directory.clone())?;
+        let index = Index::create(directory, schema_builder.build(), Default::default())?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..3 {
+            index_writer.add_document(doc!(text => format!("doc {i}")))?;
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+        assert_eq!(
+            journal(&ram_directory),
+            serde_json::json!({"pending_writes": [], "pending_deletes": []})
+        );
+        let index = Index::open(JournalingDirectory::open(ram_directory)?)?;
+        assert_eq!(index.reader()?.searcher().num_docs(), 3);
+        Ok(())
+    }
+}
@@ -266,6 +266,11 @@ impl ManagedDirectory {
             .clone();
         managed_paths
     }
+
+    /// Returns the size of a file, including its footer.
+    pub(crate) fn file_num_bytes(&self, path: &Path) -> result::Result<u64, OpenReadError> {
+        Ok(self.directory.get_file_handle(path)?.len() as u64)
+    }
 }
 
 impl Directory for ManagedDirectory {
@@ -315,6 +320,10 @@ impl Directory for ManagedDirectory {
         self.directory.acquire_lock(lock)
     }
 
+    fn prefetch(&self, paths: &[PathBuf]) -> Result<(), OpenReadError> {
+        self.directory.prefetch(paths)
+    }
+
     fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
         self.directory.watch(watch_callback)
     }
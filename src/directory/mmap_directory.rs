@@ -5,6 +5,7 @@ use std::io::{self, BufWriter, Read, Write};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 
 use common::StableDeref;
 use fs4::FileExt;
@@ -227,6 +228,22 @@ impl MmapDirectory {
         Ok(dir)
     }
 
+    /// Opens a MmapDirectory in a directory, coalescing the modifications of the `meta.json`
+    /// file happening within `debounce` of each other into a single watch notification.
+    ///
+    /// This avoids reloading readers repeatedly during bursts of commits. If commits keep
+    /// arriving more often than `debounce`, a notification is still sent at the latest four
+    /// times `debounce` after the first commit not notified yet, so that readers keep reloading.
+    pub fn open_with_watch_debounce(
+        directory_path: impl AsRef<Path>,
+        debounce: Duration,
+    ) -> Result<MmapDirectory, OpenDirectoryError> {
+        let mut dir = Self::open_impl_to_avoid_monomorphization(directory_path.as_ref())?;
+        let inner = Arc::get_mut(&mut dir.inner).expect("the directory was just opened");
+        inner.watcher = FileWatcher::with_debounce(&inner.root_path.join(*META_FILEPATH), debounce);
+        Ok(dir)
+    }
+
     /// Opens a MmapDirectory in a directory.
     ///
     /// Returns an error if the `directory_path` does not
@@ -494,6 +511,25 @@ impl Directory for MmapDirectory {
         })))
     }
 
+    /// Advises the OS to read the files ahead (`madvise(WILLNEED)`), so that they are in the
+    /// page cache by the time they are read.
+    ///
+    /// The mmaps are not kept, the files are not added to the mmap cache. On non-unix
+    /// platforms, this only checks that the files exist.
+    fn prefetch(&self, paths: &[PathBuf]) -> Result<(), OpenReadError> {
+        for path in paths {
+            let mmap_opt = open_mmap(&self.resolve_path(path))?;
+            #[cfg(unix)]
+            if let Some(mmap) = mmap_opt {
+                // We ignore madvise errors, prefetching is only a hint.
+                let _ = mmap.advise(Advice::WillNeed);
+            }
+            #[cfg(not(unix))]
+            drop(mmap_opt);
+        }
+        Ok(())
+    }
+
     fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
         Ok(self.inner.watch(watch_callback))
     }
@@ -532,6 +568,7 @@ mod tests {
     use common::HasLen;
 
     use super::*;
+    use crate::index::SegmentComponent;
     use crate::indexer::LogMergePolicy;
     use crate::schema::{Schema, SchemaBuilder, TEXT};
     use crate::{Index, IndexSettings, IndexWriter, ReloadPolicy};
@@ -635,6 +672,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prefetch() -> crate::Result<()> {
+        let mut schema_builder: SchemaBuilder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let mmap_directory = MmapDirectory::create_from_tempdir()?;
+        let index = Index::create(
+            mmap_directory.clone(),
+            schema_builder.build(),
+            IndexSettings::default(),
+        )?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text_field=>"abc"))?;
+        index_writer.commit()?;
+        let segment = &index.searchable_segments()?[0];
+        let paths = [
+            segment.relative_path(SegmentComponent::Terms),
+            segment.relative_path(SegmentComponent::FastFields),
+        ];
+
+        // The index directory forwards the hint to the mmap directory.
+        index.directory().prefetch(&paths)?;
+        assert!(mmap_directory.get_cache_info().mmapped.is_empty());
+        assert!(matches!(
+            index.directory().prefetch(&[PathBuf::from("missing")]),
+            Err(OpenReadError::FileDoesNotExist(_))
+        ));
+
+        let empty_path = PathBuf::from("empty");
+        mmap_directory.open_write(&empty_path)?.flush()?;
+        mmap_directory.prefetch(&[empty_path])?;
+        Ok(())
+    }
+
     #[test]
     fn test_mmap_released() {
         let mmap_directory = MmapDirectory::create_from_tempdir().unwrap();
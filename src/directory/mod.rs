@@ -3,11 +3,15 @@
 #[cfg(feature = "mmap")]
 mod mmap_directory;
 
+mod copy_index;
 mod directory;
 mod directory_lock;
 mod file_watcher;
 mod footer;
+mod journaling_directory;
 mod managed_directory;
+mod object_store_directory;
+mod overlay_directory;
 mod ram_directory;
 mod watch_event_router;
 
@@ -23,8 +27,12 @@ pub use common::file_slice::{FileHandle, FileSlice};
 pub use common::{AntiCallToken, OwnedBytes, TerminatingWrite};
 
 pub(crate) use self::composite_file::{CompositeFile, CompositeWrite};
+pub use self::copy_index::copy_index;
 pub use self::directory::{Directory, DirectoryClone, DirectoryLock};
 pub use self::directory_lock::{Lock, INDEX_WRITER_LOCK, META_LOCK};
+pub use self::journaling_directory::JournalingDirectory;
+pub use self::object_store_directory::{ObjectStoreBackend, ObjectStoreDirectory};
+pub use self::overlay_directory::OverlayDirectory;
 pub use self::ram_directory::RamDirectory;
 pub use self::watch_event_router::{WatchCallback, WatchCallbackList, WatchHandle};
 
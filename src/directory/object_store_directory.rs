@@ -0,0 +1,493 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use std::{fmt, io, thread};
+
+use common::HasLen;
+use lru::LruCache;
+
+use super::FileHandle;
+use crate::core::META_FILEPATH;
+use crate::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use crate::directory::{
+    Directory, DirectoryLock, Lock, OwnedBytes, WatchCallback, WatchCallbackList, WatchHandle,
+    WritePtr, META_LOCK,
+};
+
+const POLLING_INTERVAL: Duration = Duration::from_millis(if cfg!(test) { 1 } else { 500 });
+
+const DEFAULT_BLOCK_SIZE: usize = 1 << 20;
+const DEFAULT_CACHE_NUM_BLOCKS: usize = 64;
+
+/// Storage serving the files of an [`ObjectStoreDirectory`], e.g. an S3 bucket.
+///
+/// The files are immutable, with the exception of `meta.json`, which is replaced on each
+/// commit.
+pub trait ObjectStoreBackend: Send + Sync + fmt::Debug + 'static {
+    /// Returns the length of the file at `path`, or `None` if it does not exist.
+    fn file_len(&self, path: &Path) -> io::Result<Option<usize>>;
+
+    /// Reads the byte range `range` of the file at `path`.
+    fn read_range(&self, path: &Path, range: Range<usize>) -> io::Result<Vec<u8>>;
+}
+
+/// A read-only [`Directory`] serving an index stored in an object store.
+///
+/// Files are read from an [`ObjectStoreBackend`], by blocks of a fixed size. The most recently
+/// used blocks are kept in an LRU cache shared by the clones of the directory, so that
+/// subsequent reads of the same part of a file do not hit the backend.
+///
+/// `meta.json` is never cached: [`Directory::atomic_read`] always fetches the whole file from
+/// the backend, and [`Directory::watch`] polls it to detect new commits.
+///
+/// All write operations fail. The index is meant to be written elsewhere, and its files
+/// uploaded to the object store.
+#[derive(Clone)]
+pub struct ObjectStoreDirectory {
+    inner: Arc<InnerDirectory>,
+}
+
+struct InnerDirectory {
+    backend: Box<dyn ObjectStoreBackend>,
+    block_size: usize,
+    cache: Mutex<LruCache<(PathBuf, usize), OwnedBytes>>,
+    watch_router: WatchCallbackList,
+    is_polling: AtomicBool,
+}
+
+impl fmt::Debug for ObjectStoreDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObjectStoreDirectory")
+            .field("backend", &self.inner.backend)
+            .field("block_size", &self.inner.block_size)
+            .finish()
+    }
+}
+
+impl ObjectStoreDirectory {
+    /// Creates an `ObjectStoreDirectory` reading from `backend`, with a cache of 64 blocks
+    /// of 1MB.
+    pub fn new<B: ObjectStoreBackend>(backend: B) -> ObjectStoreDirectory {
+        ObjectStoreDirectory::with_cache(backend, DEFAULT_BLOCK_SIZE, DEFAULT_CACHE_NUM_BLOCKS)
+    }
+
+    /// Creates an `ObjectStoreDirectory` reading from `backend` by blocks of `block_size`
+    /// bytes, and keeping at most `cache_num_blocks` blocks in cache.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` or `cache_num_blocks` is 0.
+    pub fn with_cache<B: ObjectStoreBackend>(
+        backend: B,
+        block_size: usize,
+        cache_num_blocks: usize,
+    ) -> ObjectStoreDirectory {
+        assert!(block_size > 0, "The block size must be strictly positive.");
+        let cache_num_blocks = NonZeroUsize::new(cache_num_blocks)
+            .expect("The number of cached blocks must be strictly positive.");
+        ObjectStoreDirectory {
+            inner: Arc::new(InnerDirectory {
+                backend: Box::new(backend),
+                block_size,
+                cache: Mutex::new(LruCache::new(cache_num_blocks)),
+                watch_router: WatchCallbackList::default(),
+                is_polling: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    fn file_len(&self, path: &Path) -> Result<Option<usize>, OpenReadError> {
+        self.inner
+            .backend
+            .file_len(path)
+            .map_err(|io_error| OpenReadError::wrap_io_error(io_error, path.to_path_buf()))
+    }
+
+    fn spawn_meta_poller(&self) {
+        if self.inner.is_polling.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let mut last_meta_opt = self.inner.read_meta();
+        let inner_weak: Weak<InnerDirectory> = Arc::downgrade(&self.inner);
+        thread::Builder::new()
+            .name("thread-tantivy-object-store-meta-poller".to_string())
+            .spawn(move || {
+                // The poller stops when the directory is dropped.
+                while let Some(inner) = inner_weak.upgrade() {
+                    let meta_opt = inner.read_meta();
+                    if meta_opt != last_meta_opt {
+                        info!("Meta file {:?} was modified", META_FILEPATH);
+                        last_meta_opt = meta_opt;
+                        // We actually ignore callbacks failing here.
+                        // We just wait for the end of their execution.
+                        let _ = inner.watch_router.broadcast().wait();
+                    }
+                    drop(inner);
+                    thread::sleep(POLLING_INTERVAL);
+                }
+            })
+            .expect("Failed to spawn object store meta poller thread");
+    }
+}
+
+impl InnerDirectory {
+    fn read_file(&self, path: &Path) -> io::Result<Option<Vec<u8>>> {
+        let Some(len) = self.backend.file_len(path)? else {
+            return Ok(None);
+        };
+        self.backend.read_range(path, 0..len).map(Some)
+    }
+
+    fn read_meta(&self) -> Option<Vec<u8>> {
+        match self.read_file(&META_FILEPATH) {
+            Ok(meta_opt) => meta_opt,
+            Err(io_error) => {
+                warn!(
+                    "Failed to read meta file {:?}: {:?}",
+                    *META_FILEPATH, io_error
+                );
+                None
+            }
+        }
+    }
+
+    fn read_block(&self, path: &Path, file_len: usize, block_id: usize) -> io::Result<OwnedBytes> {
+        let cache_key = (path.to_path_buf(), block_id);
+        if let Some(block) = self.cache.lock().unwrap().get(&cache_key) {
+            return Ok(block.clone());
+        }
+        let block_start = block_id * self.block_size;
+        let block_end = (block_start + self.block_size).min(file_len);
+        let data = self.backend.read_range(path, block_start..block_end)?;
+        if data.len() != block_end - block_start {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Expected {} bytes when reading {:?}, got {}.",
+                    block_end - block_start,
+                    path,
+                    data.len()
+                ),
+            ));
+        }
+        let block = OwnedBytes::new(data);
+        self.cache.lock().unwrap().put(cache_key, block.clone());
+        Ok(block)
+    }
+}
+
+/// A file of an [`ObjectStoreDirectory`].
+struct ObjectStoreFileHandle {
+    path: PathBuf,
+    len: usize,
+    directory: Arc<InnerDirectory>,
+}
+
+impl fmt::Debug for ObjectStoreFileHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ObjectStoreFileHandle({:?}, len={})",
+            self.path, self.len
+        )
+    }
+}
+
+impl HasLen for ObjectStoreFileHandle {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl FileHandle for ObjectStoreFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        assert!(
+            range.end <= self.len,
+            "Range {range:?} is out of the bounds of {:?}, of length {}.",
+            self.path,
+            self.len
+        );
+        if range.is_empty() {
+            return Ok(OwnedBytes::empty());
+        }
+        let block_size = self.directory.block_size;
+        let first_block_id = range.start / block_size;
+        let last_block_id = (range.end - 1) / block_size;
+        if first_block_id == last_block_id {
+            // The common case does not require any copy.
+            let block = self
+                .directory
+                .read_block(&self.path, self.len, first_block_id)?;
+            let block_start = first_block_id * block_size;
+            return Ok(block.slice(range.start - block_start..range.end - block_start));
+        }
+        let mut data = Vec::with_capacity(range.len());
+        for block_id in first_block_id..=last_block_id {
+            let block = self.directory.read_block(&self.path, self.len, block_id)?;
+            let block_start = block_id * block_size;
+            let start = range.start.max(block_start) - block_start;
+            let end = range.end.min(block_start + block.len()) - block_start;
+            data.extend_from_slice(&block.as_slice()[start..end]);
+        }
+        Ok(OwnedBytes::new(data))
+    }
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "ObjectStoreDirectory is read-only.",
+    )
+}
+
+impl Directory for ObjectStoreDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        let len = self
+            .file_len(path)?
+            .ok_or_else(|| OpenReadError::FileDoesNotExist(path.to_path_buf()))?;
+        Ok(Arc::new(ObjectStoreFileHandle {
+            path: path.to_path_buf(),
+            len,
+            directory: self.inner.clone(),
+        }))
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        Err(DeleteError::IoError {
+            io_error: Arc::new(read_only_error()),
+            filepath: path.to_path_buf(),
+        })
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        Ok(self.file_len(path)?.is_some())
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        Err(OpenWriteError::wrap_io_error(
+            read_only_error(),
+            path.to_path_buf(),
+        ))
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        self.inner
+            .read_file(path)
+            .map_err(|io_error| OpenReadError::wrap_io_error(io_error, path.to_path_buf()))?
+            .ok_or_else(|| OpenReadError::FileDoesNotExist(path.to_path_buf()))
+    }
+
+    fn atomic_write(&self, _path: &Path, _data: &[u8]) -> io::Result<()> {
+        Err(read_only_error())
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        // Readers only lock `meta.json` to prevent concurrent writers from deleting the files
+        // they are about to open. Nothing is ever deleted from a read-only directory.
+        if lock.filepath == META_LOCK.filepath {
+            return Ok(DirectoryLock::from(Box::new(())));
+        }
+        Err(LockError::IoError(Arc::new(read_only_error())))
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
+        let watch_handle = self.inner.watch_router.subscribe(watch_callback);
+        self.spawn_meta_poller();
+        Ok(watch_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io;
+    use std::ops::Range;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    use common::HasLen;
+
+    use super::{ObjectStoreBackend, ObjectStoreDirectory};
+    use crate::core::META_FILEPATH;
+    use crate::directory::{Directory, RamDirectory, WatchCallback, INDEX_WRITER_LOCK};
+    use crate::query::QueryParser;
+    use crate::schema::{Schema, TEXT};
+    use crate::{collector, Index, IndexWriter, ReloadPolicy};
+
+    type RangeRead = (PathBuf, Range<usize>);
+
+    #[derive(Clone, Debug, Default)]
+    struct MockBackend {
+        files: Arc<RwLock<HashMap<PathBuf, Vec<u8>>>>,
+        range_reads: Arc<RwLock<Vec<RangeRead>>>,
+    }
+
+    impl MockBackend {
+        fn put(&self, path: &Path, data: Vec<u8>) {
+            self.files.write().unwrap().insert(path.to_path_buf(), data);
+        }
+
+        fn num_range_reads(&self) -> usize {
+            self.range_reads.read().unwrap().len()
+        }
+
+        // Uploads all of the files of an index.
+        fn upload(&self, index: &Index) -> crate::Result<()> {
+            let directory = index.directory();
+            let mut paths: Vec<PathBuf> = directory.list_managed_files().into_iter().collect();
+            paths.push(META_FILEPATH.to_path_buf());
+            for path in paths {
+                if directory.exists(&path)? {
+                    self.put(&path, directory.atomic_read(&path)?);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl ObjectStoreBackend for MockBackend {
+        fn file_len(&self, path: &Path) -> io::Result<Option<usize>> {
+            Ok(self.files.read().unwrap().get(path).map(Vec::len))
+        }
+
+        fn read_range(&self, path: &Path, range: Range<usize>) -> io::Result<Vec<u8>> {
+            self.range_reads
+                .write()
+                .unwrap()
+                .push((path.to_path_buf(), range.clone()));
+            let files = self.files.read().unwrap();
+            let data = files
+                .get(path)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+            Ok(data[range].to_vec())
+        }
+    }
+
+    #[test]
+    fn test_object_store_directory_range_reads_and_cache() -> crate::Result<()> {
+        let backend = MockBackend::default();
+        let path = Path::new("file");
+        let data: Vec<u8> = (0..100u8).collect();
+        backend.put(path, data.clone());
+        let directory = ObjectStoreDirectory::with_cache(backend.clone(), 10, 3);
+        assert!(directory.exists(path)?);
+        assert!(!directory.exists(Path::new("missing"))?);
+        let file_slice = directory.open_read(path)?;
+        assert_eq!(file_slice.len(), 100);
+
+        // Reads within a single block.
+        assert_eq!(
+            file_slice.read_bytes_slice(12..15)?.as_slice(),
+            &data[12..15]
+        );
+        assert_eq!(backend.num_range_reads(), 1);
+        assert_eq!(backend.range_reads.read().unwrap()[0].1, 10..20);
+        // Reading the same block again hits the cache.
+        assert_eq!(
+            file_slice.read_bytes_slice(10..20)?.as_slice(),
+            &data[10..20]
+        );
+        assert_eq!(backend.num_range_reads(), 1);
+
+        // Reads spanning several blocks, the last block being shorter.
+        assert_eq!(
+            file_slice.read_bytes_slice(15..100)?.as_slice(),
+            &data[15..100]
+        );
+        // Blocks 2 to 9 were fetched, and only 3 of them are still in cache.
+        assert_eq!(backend.num_range_reads(), 9);
+        assert_eq!(backend.range_reads.read().unwrap()[8].1, 90..100);
+        assert_eq!(
+            file_slice.read_bytes_slice(75..100)?.as_slice(),
+            &data[75..100]
+        );
+        assert_eq!(backend.num_range_reads(), 9);
+        assert_eq!(file_slice.read_bytes_slice(0..5)?.as_slice(), &data[0..5]);
+        assert_eq!(backend.num_range_reads(), 10);
+        assert!(file_slice.read_bytes_slice(7..7)?.is_empty());
+        assert_eq!(backend.num_range_reads(), 10);
+
+        // The directory is read-only.
+        assert!(directory.atomic_write(path, b"data").is_err());
+        assert!(directory.open_write(Path::new("new_file")).is_err());
+        assert!(directory.delete(path).is_err());
+        assert!(directory.acquire_lock(&INDEX_WRITER_LOCK).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_object_store_directory_serves_an_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create(
+            RamDirectory::create(),
+            schema_builder.build(),
+            Default::default(),
+        )?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text_field => "hello happy tax payer"))?;
+        index_writer.add_document(doc!(text_field => "hello world"))?;
+        index_writer.commit()?;
+        let backend = MockBackend::default();
+        backend.upload(&index)?;
+
+        let object_store_index = Index::open(ObjectStoreDirectory::with_cache(
+            backend.clone(),
+            1_000,
+            1_000,
+        ))?;
+        let reader = object_store_index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+        let query =
+            QueryParser::for_index(&object_store_index, vec![text_field]).parse_query("hello")?;
+        assert_eq!(reader.searcher().search(&query, &collector::Count)?, 2);
+        let num_range_reads = backend.num_range_reads();
+        assert_eq!(reader.searcher().search(&query, &collector::Count)?, 2);
+        // The second search is served by the cache, but for the polling of `meta.json`.
+        let num_block_reads = backend
+            .range_reads
+            .read()
+            .unwrap()
+            .iter()
+            .skip(num_range_reads)
+            .filter(|(path, _)| *path != *META_FILEPATH)
+            .count();
+        assert_eq!(num_block_reads, 0);
+        assert!(object_store_index
+            .writer_for_tests::<crate::TantivyDocument>()
+            .is_err());
+
+        // New commits uploaded to the object store are detected.
+        let counter: Arc<AtomicUsize> = Default::default();
+        let counter_clone = counter.clone();
+        let _watch_handle =
+            object_store_index
+                .directory()
+                .watch(WatchCallback::new(move || {
+                    counter_clone.fetch_add(1, Ordering::SeqCst);
+                }))?;
+        index_writer.add_document(doc!(text_field => "hello again"))?;
+        index_writer.commit()?;
+        backend.upload(&index)?;
+        for _ in 0..1_000 {
+            if reader.searcher().search(&query, &collector::Count)? == 3 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(reader.searcher().search(&query, &collector::Count)?, 3);
+        assert!(counter.load(Ordering::SeqCst) >= 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::FileHandle;
+use crate::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use crate::directory::{
+    Directory, DirectoryLock, FileSlice, Lock, WatchCallback, WatchHandle, WritePtr,
+};
+
+/// A [`Directory`] layering the writes of an overlay directory on top of a read-only base
+/// directory.
+///
+/// Reads are served by the overlay first, and fall through to the base directory. All writes
+/// go to the overlay, so that the base directory is never mutated. Deleting a file of the base
+/// directory records a tombstone instead, after which the file appears to be gone. Tombstones
+/// are kept in memory, and are shared by the clones of the directory.
+///
+/// This makes it possible to open an immutable index, e.g. shared by several processes, and
+/// to index or merge on top of it, without affecting the other users of the base index.
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use tantivy::directory::{OverlayDirectory, RamDirectory};
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, IndexWriter};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let base = RamDirectory::create();
+/// let base_index = Index::create(base.clone(), schema_builder.build(), Default::default())?;
+/// let mut index_writer: IndexWriter = base_index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+/// index_writer.commit()?;
+/// drop(index_writer);
+///
+/// let overlay_directory = OverlayDirectory::new(Arc::new(base), Box::new(RamDirectory::create()));
+/// let index = Index::open(overlay_directory)?;
+/// let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+/// index_writer.add_document(doc!(title => "Of Mice and Men"))?;
+/// index_writer.commit()?;
+///
+/// assert_eq!(index.reader()?.searcher().num_docs(), 2);
+/// assert_eq!(base_index.reader()?.searcher().num_docs(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct OverlayDirectory {
+    base: Arc<dyn Directory>,
+    overlay: Box<dyn Directory>,
+    tombstones: Arc<RwLock<HashSet<PathBuf>>>,
+}
+
+impl OverlayDirectory {
+    /// Creates an `OverlayDirectory` writing to `overlay`, on top of `base`.
+    pub fn new(base: Arc<dyn Directory>, overlay: Box<dyn Directory>) -> OverlayDirectory {
+        OverlayDirectory {
+            base,
+            overlay,
+            tombstones: Default::default(),
+        }
+    }
+
+    fn is_tombstoned(&self, path: &Path) -> bool {
+        self.tombstones.read().unwrap().contains(path)
+    }
+
+    fn exists_in_base(&self, path: &Path) -> Result<bool, Arc<io::Error>> {
+        self.base.exists(path).map_err(into_io_error)
+    }
+}
+
+fn into_io_error(open_read_error: OpenReadError) -> Arc<io::Error> {
+    match open_read_error {
+        OpenReadError::IoError { io_error, .. } => io_error,
+        open_read_error => Arc::new(io::Error::other(open_read_error.to_string())),
+    }
+}
+
+impl Directory for OverlayDirectory {
+    fn get_file_handle(&self, path: &Path) -> Result<Arc<dyn FileHandle>, OpenReadError> {
+        if self.is_tombstoned(path) {
+            return Err(OpenReadError::FileDoesNotExist(path.to_path_buf()));
+        }
+        match self.overlay.get_file_handle(path) {
+            Err(OpenReadError::FileDoesNotExist(_)) => self.base.get_file_handle(path),
+            result => result,
+        }
+    }
+
+    fn open_read(&self, path: &Path) -> Result<FileSlice, OpenReadError> {
+        if self.is_tombstoned(path) {
+            return Err(OpenReadError::FileDoesNotExist(path.to_path_buf()));
+        }
+        match self.overlay.open_read(path) {
+            Err(OpenReadError::FileDoesNotExist(_)) => self.base.open_read(path),
+            result => result,
+        }
+    }
+
+    fn delete(&self, path: &Path) -> Result<(), DeleteError> {
+        let mut tombstones = self.tombstones.write().unwrap();
+        if tombstones.contains(path) {
+            return Err(DeleteError::FileDoesNotExist(path.to_path_buf()));
+        }
+        let exists_in_base =
+            self.exists_in_base(path)
+                .map_err(|io_error| DeleteError::IoError {
+                    io_error,
+                    filepath: path.to_path_buf(),
+                })?;
+        match self.overlay.delete(path) {
+            Err(DeleteError::FileDoesNotExist(_)) if exists_in_base => {}
+            result => result?,
+        }
+        if exists_in_base {
+            tombstones.insert(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> Result<bool, OpenReadError> {
+        if self.is_tombstoned(path) {
+            return Ok(false);
+        }
+        Ok(self.overlay.exists(path)? || self.base.exists(path)?)
+    }
+
+    fn open_write(&self, path: &Path) -> Result<WritePtr, OpenWriteError> {
+        let mut tombstones = self.tombstones.write().unwrap();
+        if !tombstones.contains(path) {
+            let exists_in_base =
+                self.exists_in_base(path)
+                    .map_err(|io_error| OpenWriteError::IoError {
+                        io_error,
+                        filepath: path.to_path_buf(),
+                    })?;
+            if exists_in_base {
+                return Err(OpenWriteError::FileAlreadyExists(path.to_path_buf()));
+            }
+        }
+        let write_ptr = self.overlay.open_write(path)?;
+        tombstones.remove(path);
+        Ok(write_ptr)
+    }
+
+    fn atomic_read(&self, path: &Path) -> Result<Vec<u8>, OpenReadError> {
+        if self.is_tombstoned(path) {
+            return Err(OpenReadError::FileDoesNotExist(path.to_path_buf()));
+        }
+        match self.overlay.atomic_read(path) {
+            Err(OpenReadError::FileDoesNotExist(_)) => self.base.atomic_read(path),
+            result => result,
+        }
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let mut tombstones = self.tombstones.write().unwrap();
+        self.overlay.atomic_write(path, data)?;
+        tombstones.remove(path);
+        Ok(())
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        self.overlay.sync_directory()
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.overlay.acquire_lock(lock)
+    }
+
+    fn prefetch(&self, paths: &[PathBuf]) -> Result<(), OpenReadError> {
+        let mut overlay_paths = Vec::new();
+        let mut base_paths = Vec::new();
+        for path in paths {
+            if self.is_tombstoned(path) {
+                return Err(OpenReadError::FileDoesNotExist(path.to_path_buf()));
+            }
+            if self.overlay.exists(path)? {
+                overlay_paths.push(path.clone());
+            } else {
+                base_paths.push(path.clone());
+            }
+        }
+        self.overlay.prefetch(&overlay_paths)?;
+        self.base.prefetch(&base_paths)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> crate::Result<WatchHandle> {
+        self.overlay.watch(watch_callback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::OverlayDirectory;
+    use crate::directory::error::{DeleteError, OpenReadError, OpenWriteError};
+    use crate::directory::{Directory, RamDirectory};
+    use crate::indexer::NoMergePolicy;
+    use crate::schema::{Schema, TEXT};
+    use crate::{Index, IndexWriter};
+
+    fn base_directory() -> RamDirectory {
+        let base = RamDirectory::create();
+        let mut write = base.open_write(Path::new("base_file")).unwrap();
+        write.write_all(b"base").unwrap();
+        write.flush().unwrap();
+        base.atomic_write(Path::new("base_atomic_file"), b"base atomic")
+            .unwrap();
+        base
+    }
+
+    fn overlay_directory(base: &RamDirectory) -> OverlayDirectory {
+        OverlayDirectory::new(Arc::new(base.clone()), Box::new(RamDirectory::create()))
+    }
+
+    fn read_all(directory: &dyn Directory, path: &str) -> Vec<u8> {
+        directory
+            .open_read(Path::new(path))
+            .unwrap()
+            .read_bytes()
+            .unwrap()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_overlay_directory_read_fall_through() {
+        let base = base_directory();
+        let directory = overlay_directory(&base);
+        assert!(directory.exists(Path::new("base_file")).unwrap());
+        assert_eq!(read_all(&directory, "base_file"), b"base");
+        assert_eq!(
+            directory
+                .atomic_read(Path::new("base_atomic_file"))
+                .unwrap(),
+            b"base atomic"
+        );
+        assert!(!directory.exists(Path::new("missing_file")).unwrap());
+        assert!(matches!(
+            directory.open_read(Path::new("missing_file")),
+            Err(OpenReadError::FileDoesNotExist(_))
+        ));
+    }
+
+    #[test]
+    fn test_overlay_directory_shadowing() {
+        let base = base_directory();
+        let directory = overlay_directory(&base);
+        directory
+            .atomic_write(Path::new("base_atomic_file"), b"overlay atomic")
+            .unwrap();
+        assert_eq!(
+            directory
+                .atomic_read(Path::new("base_atomic_file"))
+                .unwrap(),
+            b"overlay atomic"
+        );
+        // Files of the base directory can not be rewritten.
+        assert!(matches!(
+            directory.open_write(Path::new("base_file")),
+            Err(OpenWriteError::FileAlreadyExists(_))
+        ));
+        let mut write = directory.open_write(Path::new("overlay_file")).unwrap();
+        write.write_all(b"overlay").unwrap();
+        write.flush().unwrap();
+        assert_eq!(read_all(&directory, "overlay_file"), b"overlay");
+
+        // The base directory is left untouched.
+        assert_eq!(
+            base.atomic_read(Path::new("base_atomic_file")).unwrap(),
+            b"base atomic"
+        );
+        assert!(!base.exists(Path::new("overlay_file")).unwrap());
+    }
+
+    #[test]
+    fn test_overlay_directory_tombstones() {
+        let base = base_directory();
+        let directory = overlay_directory(&base);
+        directory.delete(Path::new("base_file")).unwrap();
+        assert!(!directory.exists(Path::new("base_file")).unwrap());
+        assert!(matches!(
+            directory.open_read(Path::new("base_file")),
+            Err(OpenReadError::FileDoesNotExist(_))
+        ));
+        assert!(matches!(
+            directory.delete(Path::new("base_file")),
+            Err(DeleteError::FileDoesNotExist(_))
+        ));
+        // Tombstones are shared by clones.
+        assert!(!directory.clone().exists(Path::new("base_file")).unwrap());
+        assert!(base.exists(Path::new("base_file")).unwrap());
+        assert!(matches!(
+            directory.prefetch(&[PathBuf::from("base_file")]),
+            Err(OpenReadError::FileDoesNotExist(_))
+        ));
+        assert!(directory
+            .prefetch(&[PathBuf::from("base_atomic_file")])
+            .is_ok());
+
+        // A deleted file can be written again.
+        let mut write = directory.open_write(Path::new("base_file")).unwrap();
+        write.write_all(b"overlay").unwrap();
+        write.flush().unwrap();
+        assert_eq!(read_all(&directory, "base_file"), b"overlay");
+        assert_eq!(read_all(&base, "base_file"), b"base");
+
+        directory
+            .atomic_write(Path::new("base_atomic_file"), b"overlay atomic")
+            .unwrap();
+        directory.delete(Path::new("base_atomic_file")).unwrap();
+        assert!(!directory.exists(Path::new("base_atomic_file")).unwrap());
+        assert!(matches!(
+            directory.delete(Path::new("missing_file")),
+            Err(DeleteError::FileDoesNotExist(_))
+        ));
+    }
+
+    #[test]
+    fn test_overlay_directory_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let base = RamDirectory::create();
+        let base_index = Index::create(base.clone(), schema_builder.build(), Default::default())?;
+        let mut index_writer: IndexWriter = base_index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "b"))?;
+        index_writer.commit()?;
+        drop(index_writer);
+        let base_segment_files: Vec<_> = base_index
+            .searchable_segment_metas()?
+            .iter()
+            .flat_map(|segment_meta| segment_meta.list_files())
+            .filter(|file| base.exists(file).unwrap())
+            .collect();
+        assert!(!base_segment_files.is_empty());
+
+        let index = Index::open(overlay_directory(&base))?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(text => "c"))?;
+        index_writer.commit()?;
+        let segment_ids = index.searchable_segment_ids()?;
+        index_writer.merge(&segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+
+        let reader = index.reader()?;
+        assert_eq!(reader.searcher().num_docs(), 3);
+        assert_eq!(reader.searcher().segment_readers().len(), 1);
+        // The merged segments were garbage collected in the overlay only.
+        let directory = index.directory();
+        for file in &base_segment_files {
+            assert!(!directory.exists(file)?);
+            assert!(base.exists(file)?);
+        }
+        assert_eq!(base_index.reader()?.searcher().num_docs(), 2);
+        Ok(())
+    }
+}
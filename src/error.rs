@@ -110,6 +110,9 @@ pub enum TantivyError {
     #[error("Deserialize error: {0}")]
     /// An error occurred while attempting to deserialize a document.
     DeserializeError(DeserializeError),
+    /// The search did not complete before its deadline.
+    #[error("The search did not complete before its deadline")]
+    TimedOut,
 }
 
 impl From<io::Error> for TantivyError {
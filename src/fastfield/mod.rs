@@ -1303,4 +1303,101 @@ mod tests {
         let vals: Vec<i64> = column.values_for_doc(0u32).collect();
         assert_eq!(&vals, &[33]);
     }
+
+    #[test]
+    fn test_bytes_fast_field_payloads() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id_field = schema_builder.add_u64_field("id", FAST);
+        let payload_field = schema_builder.add_bytes_field("payload", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        let payload = |id: u64| -> Option<Vec<u8>> {
+            let len = match id % 6 {
+                0 => return None,
+                1 => 0,
+                2 => 1,
+                3 => 13,
+                4 => 1_000,
+                _ => u16::MAX as usize,
+            };
+            Some((0..len).map(|i| (i as u64 * 31 + id) as u8).collect())
+        };
+        for id in 0..24u64 {
+            let mut doc = doc!(id_field => id);
+            if let Some(payload) = payload(id) {
+                doc.add_bytes(payload_field, &payload);
+            }
+            index_writer.add_document(doc)?;
+            if id == 11 {
+                index_writer.commit()?;
+            }
+        }
+        index_writer.commit()?;
+        // Values longer than `u16::MAX` bytes are rejected.
+        let mut doc = doc!(id_field => 24u64);
+        doc.add_bytes(payload_field, &vec![0u8; u16::MAX as usize + 1]);
+        assert!(matches!(
+            index_writer.add_document(doc),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let mut buffer = Vec::new();
+        for segment_reader in searcher.segment_readers() {
+            let id_column = segment_reader.fast_fields().u64("id")?;
+            let payload_column = segment_reader.fast_fields().bytes("payload")?.unwrap();
+            for doc_id in 0..segment_reader.max_doc() {
+                let id = id_column.first(doc_id).unwrap();
+                let read_payload = payload_column.first(doc_id, &mut buffer)?;
+                assert_eq!(read_payload, payload(id).as_deref());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_str_fast_field_value_len() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let raw_field = schema_builder.add_text_field("raw", STRING | FAST);
+        let untokenized_field =
+            schema_builder.add_text_field("untokenized", TextOptions::default().set_fast(None));
+        let tokenized_field = schema_builder.add_text_field(
+            "tokenized",
+            TextOptions::default().set_fast(Some("default")),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let longest_text = "a".repeat(u16::MAX as usize);
+        let too_long_text = "a".repeat(u16::MAX as usize + 1);
+        // Values longer than `u16::MAX` bytes are rejected.
+        for field in [raw_field, untokenized_field] {
+            index_writer.add_document(doc!(field => longest_text.clone()))?;
+            assert!(matches!(
+                index_writer.add_document(doc!(field => too_long_text.clone())),
+                Err(crate::TantivyError::InvalidArgument(_))
+            ));
+        }
+        // With a tokenizer, the limit applies to the tokens.
+        let long_text_of_short_tokens = "ab ".repeat(u16::MAX as usize);
+        index_writer.add_document(doc!(tokenized_field => long_text_of_short_tokens))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let fast_fields = searcher.segment_reader(0).fast_fields();
+        let mut buffer = String::new();
+        for field_name in ["raw", "untokenized"] {
+            let str_column = fast_fields.str(field_name)?.unwrap();
+            let term_ord = str_column
+                .term_ords(0)
+                .chain(str_column.term_ords(1))
+                .next();
+            assert!(str_column.ord_to_str(term_ord.unwrap(), &mut buffer)?);
+            assert_eq!(buffer, longest_text);
+        }
+        let tokenized_column = fast_fields.str("tokenized")?.unwrap();
+        assert_eq!(tokenized_column.num_terms(), 1);
+        Ok(())
+    }
 }
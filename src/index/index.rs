@@ -1,18 +1,21 @@
 use std::collections::HashSet;
 use std::fmt;
+use std::io::{Read, Write};
 #[cfg(feature = "mmap")]
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread::available_parallelism;
 
 use super::segment::Segment;
 use super::segment_reader::merge_field_meta_data;
-use super::{FieldMetadata, IndexSettings};
+use super::{segment_archive, FieldMetadata, IndexSettings};
+use crate::core::searcher::{SearcherGeneration, SearcherInner};
 use crate::core::{Executor, META_FILEPATH};
 use crate::directory::error::OpenReadError;
 #[cfg(feature = "mmap")]
 use crate::directory::MmapDirectory;
-use crate::directory::{Directory, ManagedDirectory, RamDirectory, INDEX_WRITER_LOCK};
+use crate::directory::{Directory, ManagedDirectory, RamDirectory, INDEX_WRITER_LOCK, META_LOCK};
 use crate::error::{DataCorruption, TantivyError};
 use crate::index::{IndexMeta, SegmentId, SegmentMeta, SegmentMetaInventory};
 use crate::indexer::index_writer::{MAX_NUM_THREAD, MEMORY_BUDGET_NUM_BYTES_MIN};
@@ -20,9 +23,11 @@ use crate::indexer::segment_updater::save_metas;
 use crate::indexer::{IndexWriter, SingleSegmentIndexWriter};
 use crate::reader::{IndexReader, IndexReaderBuilder};
 use crate::schema::document::Document;
-use crate::schema::{Field, FieldType, Schema};
+use crate::schema::{Field, FieldEntry, FieldType, Schema};
+use crate::space_usage::IndexFilesSpaceUsage;
+use crate::store::DOCSTORE_CACHE_CAPACITY;
 use crate::tokenizer::{TextAnalyzer, TokenizerManager};
-use crate::SegmentReader;
+use crate::{Inventory, Searcher, SegmentReader};
 
 fn load_metas(
     directory: &dyn Directory,
@@ -63,6 +68,7 @@ fn save_new_metas(
             index_settings,
             segments: Vec::new(),
             schema,
+            schema_version: 0,
             opstamp: 0u64,
             payload: None,
         },
@@ -221,6 +227,7 @@ impl IndexBuilder {
         let mut index = Index::open(dir)?;
         index.set_tokenizers(self.tokenizer_manager.clone());
         if index.schema() == self.get_expect_schema()? {
+            validate_search_tokenizers(&index.schema(), &self.tokenizer_manager)?;
             Ok(index)
         } else {
             Err(TantivyError::SchemaError(
@@ -230,8 +237,8 @@ impl IndexBuilder {
     }
 
     fn validate(&self) -> crate::Result<()> {
-        if let Some(_schema) = self.schema.as_ref() {
-            Ok(())
+        if let Some(schema) = self.schema.as_ref() {
+            validate_search_tokenizers(schema, &self.tokenizer_manager)
         } else {
             Err(TantivyError::InvalidArgument(
                 "no schema passed".to_string(),
@@ -260,11 +267,45 @@ impl IndexBuilder {
     }
 }
 
+/// Checks that both the indexing and the search tokenizers of the fields with a
+/// [search tokenizer](crate::schema::TextFieldIndexing::set_search_tokenizer) are registered.
+fn validate_search_tokenizers(
+    schema: &Schema,
+    tokenizer_manager: &TokenizerManager,
+) -> crate::Result<()> {
+    for (_field, field_entry) in schema.fields() {
+        let indexing_options_opt = match field_entry.field_type() {
+            FieldType::Str(options) => options.get_indexing_options(),
+            FieldType::JsonObject(options) => options.get_text_indexing_options(),
+            _ => None,
+        };
+        let Some(indexing_options) = indexing_options_opt else {
+            continue;
+        };
+        if !indexing_options.has_search_tokenizer() {
+            continue;
+        }
+        for tokenizer_name in [
+            indexing_options.tokenizer(),
+            indexing_options.search_tokenizer(),
+        ] {
+            if tokenizer_manager.get(tokenizer_name).is_none() {
+                return Err(TantivyError::SchemaError(format!(
+                    "The tokenizer {tokenizer_name:?} of the field {:?} is not registered",
+                    field_entry.name()
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Search Index
 #[derive(Clone)]
 pub struct Index {
     directory: ManagedDirectory,
     schema: Schema,
+    schema_version: u32,
     settings: IndexSettings,
     executor: Executor,
     tokenizers: TokenizerManager,
@@ -385,6 +426,7 @@ impl Index {
             settings: metas.index_settings.clone(),
             directory,
             schema,
+            schema_version: metas.schema_version,
             tokenizers: TokenizerManager::default(),
             fast_field_tokenizers: TokenizerManager::default(),
             executor: Executor::single_thread(),
@@ -457,6 +499,41 @@ impl Index {
         IndexReaderBuilder::new(self.clone())
     }
 
+    /// Creates a [`Searcher`] over a single segment of the index.
+    ///
+    /// Only the files of this segment are opened, which makes it possible to inspect
+    /// or process a segment on its own, without paying for the other segments. The
+    /// deletes recorded for the segment in the last commit are honored.
+    ///
+    /// Returns an error if the segment is not searchable in the last commit.
+    pub fn segment_searcher(&self, segment_id: SegmentId) -> crate::Result<Searcher> {
+        // Prevents segment files from getting deleted while we are in the process of opening them
+        let _meta_lock = self.directory().acquire_lock(&META_LOCK)?;
+        let index_meta = self.load_metas()?;
+        let segment_meta = index_meta
+            .segments
+            .into_iter()
+            .find(|segment_meta| segment_meta.id() == segment_id)
+            .ok_or_else(|| {
+                TantivyError::InvalidArgument(format!(
+                    "Segment {segment_id} is not a searchable segment of the index"
+                ))
+            })?;
+        let segment_readers = vec![SegmentReader::open(&self.segment(segment_meta))?];
+        let searcher_generation = Inventory::default().track(
+            SearcherGeneration::from_segment_readers(&segment_readers, 0),
+        );
+        let searcher_inner = SearcherInner::new(
+            self.schema(),
+            self.clone(),
+            segment_readers,
+            searcher_generation,
+            index_meta.opstamp,
+            DOCSTORE_CACHE_CAPACITY,
+        )?;
+        Ok(Arc::new(searcher_inner).into())
+    }
+
     /// Opens a new directory from an index path.
     #[cfg(feature = "mmap")]
     pub fn open_in_dir<P: AsRef<Path>>(directory_path: P) -> crate::Result<Index> {
@@ -505,6 +582,9 @@ impl Index {
     }
 
     /// Open the index using the provided directory
+    ///
+    /// The tokenizers are not validated, as they are usually registered after opening the
+    /// index. Use [`IndexBuilder::open_or_create()`] to validate them.
     pub fn open<T: Into<Box<dyn Directory>>>(directory: T) -> crate::Result<Index> {
         let directory = directory.into();
         let directory = ManagedDirectory::wrap(directory)?;
@@ -615,6 +695,86 @@ impl Index {
         self.schema.clone()
     }
 
+    /// Returns the version of the schema of the index, as of when the index was opened.
+    ///
+    /// The version starts at 0, and is incremented by [`Index::add_fields`].
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Appends new fields to the schema of the index, and returns their handles.
+    ///
+    /// The existing fields and their handles are unchanged, so that the existing segments do
+    /// not need to be reindexed: they simply do not contain the new fields. Queries on the new
+    /// fields only match documents indexed after the change, and the documents of the
+    /// existing segments have no value for them. For this reason, the new fields cannot be
+    /// required. Once added, a field cannot be removed.
+    ///
+    /// The new schema is committed right away, and the version of the schema is
+    /// incremented. The `Index` objects opened before the change, including the clones of
+    /// this one, keep the previous schema: they cannot be used to create an `IndexWriter`
+    /// anymore, and should be reopened.
+    ///
+    /// # Errors
+    /// Returns an error if an `IndexWriter` is working on the index, if the schema of the
+    /// index has been changed since this `Index` was opened, if one of the field names is
+    /// already taken, or if one of the fields is required.
+    pub fn add_fields(&mut self, field_entries: Vec<FieldEntry>) -> crate::Result<Vec<Field>> {
+        let _directory_lock = self
+            .directory
+            .acquire_lock(&INDEX_WRITER_LOCK)
+            .map_err(|err| {
+                TantivyError::LockFailure(
+                    err,
+                    Some(
+                        "Failed to acquire index lock. Fields cannot be added while an \
+                         `IndexWriter` is working on the index."
+                            .to_string(),
+                    ),
+                )
+            })?;
+        let mut index_meta = self.load_metas()?;
+        if index_meta.schema_version != self.schema_version {
+            return Err(TantivyError::SchemaError(
+                "The schema of the index has been changed since the index was opened".to_string(),
+            ));
+        }
+        let mut field_names: HashSet<String> = self
+            .schema
+            .fields()
+            .map(|(_field, field_entry)| field_entry.name().to_string())
+            .collect();
+        for field_entry in &field_entries {
+            if field_entry.is_required() {
+                return Err(TantivyError::SchemaError(format!(
+                    "Cannot add the required field {:?}: the documents of the existing segments \
+                     have no value for it",
+                    field_entry.name()
+                )));
+            }
+            if !field_names.insert(field_entry.name().to_string()) {
+                return Err(TantivyError::SchemaError(format!(
+                    "Field already exists in schema: {}",
+                    field_entry.name()
+                )));
+            }
+        }
+        let mut schema_builder = Schema::builder();
+        for (_field, field_entry) in self.schema.fields() {
+            schema_builder.add_field(field_entry.clone());
+        }
+        let new_fields: Vec<Field> = field_entries
+            .into_iter()
+            .map(|field_entry| schema_builder.add_field(field_entry))
+            .collect();
+        index_meta.schema = schema_builder.build();
+        index_meta.schema_version += 1;
+        save_metas(&index_meta, self.directory())?;
+        self.schema = index_meta.schema;
+        self.schema_version = index_meta.schema_version;
+        Ok(new_fields)
+    }
+
     /// Returns the list of segments that are searchable
     pub fn searchable_segments(&self) -> crate::Result<Vec<Segment>> {
         Ok(self
@@ -653,6 +813,44 @@ impl Index {
         Ok(self.load_metas()?.segments)
     }
 
+    /// Returns the size of the files of the searchable segments, broken down by segment and by
+    /// file extension.
+    ///
+    /// The sizes are those reported by the directory, e.g. the size of the files for a
+    /// [`MmapDirectory`](crate::directory::MmapDirectory), or the size of the buffers for a
+    /// [`RamDirectory`].
+    pub fn files_space_usage(&self) -> crate::Result<IndexFilesSpaceUsage> {
+        IndexFilesSpaceUsage::compute(self)
+    }
+
+    /// Exports a searchable segment of the index as a self-describing archive.
+    ///
+    /// The archive contains the schema of the index, the meta of the segment and the content
+    /// of all of its files, including its delete bitset. It can be imported in another index
+    /// with the same schema using [`Index::import_segment`].
+    ///
+    /// Returns an error if the segment is not searchable in the last commit.
+    pub fn export_segment<W: Write>(
+        &self,
+        segment_id: SegmentId,
+        mut writer: W,
+    ) -> crate::Result<()> {
+        segment_archive::export_segment(self, segment_id, &mut writer)
+    }
+
+    /// Imports a segment exported with [`Index::export_segment`], and registers it as a
+    /// searchable segment of the index.
+    ///
+    /// The deleted documents of the segment remain deleted. The segment keeps its id, so that
+    /// it cannot be imported twice in the same index.
+    ///
+    /// The segment is committed on its own: no `IndexWriter` may be working on the index
+    /// while importing it. Returns an error if the schema of the archive does not match the
+    /// schema of the index.
+    pub fn import_segment<R: Read>(&self, mut reader: R) -> crate::Result<SegmentId> {
+        segment_archive::import_segment(self, &mut reader)
+    }
+
     /// Returns the list of segment ids that are searchable.
     pub fn searchable_segment_ids(&self) -> crate::Result<Vec<SegmentId>> {
         Ok(self
@@ -681,6 +879,34 @@ impl Index {
         }
         Ok(damaged_files)
     }
+
+    /// Verifies the checksum of every file of the searchable segments, and returns the
+    /// sorted list of the files that are corrupted.
+    ///
+    /// Unlike [`Index::validate_checksum`], the files to check are taken from the segment
+    /// metas rather than from the files managed by the directory, so that indexes opened
+    /// over a read-only directory are checked as well. A file that cannot be read, e.g.
+    /// because it is too short to hold a footer, is reported as corrupted instead of
+    /// interrupting the verification.
+    pub fn verify_integrity(&self) -> crate::Result<Vec<PathBuf>> {
+        let mut segment_files: Vec<PathBuf> = self
+            .searchable_segment_metas()?
+            .iter()
+            .flat_map(|segment_meta| segment_meta.list_files())
+            .collect();
+        segment_files.sort();
+        let mut corrupted_files = Vec::new();
+        for path in segment_files {
+            // Optional components, such as the alive bitset, may not exist.
+            if !self.directory.exists(&path)? {
+                continue;
+            }
+            if !matches!(self.directory.validate_checksum(&path), Ok(true)) {
+                corrupted_files.push(path);
+            }
+        }
+        Ok(corrupted_files)
+    }
 }
 
 impl fmt::Debug for Index {
@@ -39,6 +39,7 @@ impl SegmentMetaInventory {
             max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: None,
+            routing_key: None,
         };
         SegmentMeta::from(self.inventory.track(inner))
     }
@@ -169,6 +170,12 @@ impl SegmentMeta {
             .map(|delete_meta| delete_meta.opstamp)
     }
 
+    /// Returns the routing key of the documents of the segment, if they were added
+    /// with [`IndexWriter::add_document_with_routing_key`](crate::IndexWriter::add_document_with_routing_key).
+    pub fn routing_key(&self) -> Option<&str> {
+        self.tracked.routing_key.as_deref()
+    }
+
     /// Returns true iff the segment meta contains
     /// delete information.
     pub fn has_deletes(&self) -> bool {
@@ -184,6 +191,19 @@ impl SegmentMeta {
             max_doc,
             deletes: None,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            routing_key: inner_meta.routing_key.clone(),
+        });
+        SegmentMeta { tracked }
+    }
+
+    /// Sets the routing key of the segment.
+    pub(crate) fn with_routing_key(self, routing_key: Option<String>) -> SegmentMeta {
+        let tracked = self.tracked.map(move |inner_meta| InnerSegmentMeta {
+            segment_id: inner_meta.segment_id,
+            max_doc: inner_meta.max_doc,
+            deletes: inner_meta.deletes.clone(),
+            include_temp_doc_store: Arc::new(AtomicBool::new(true)),
+            routing_key,
         });
         SegmentMeta { tracked }
     }
@@ -204,6 +224,7 @@ impl SegmentMeta {
             max_doc: inner_meta.max_doc,
             include_temp_doc_store: Arc::new(AtomicBool::new(true)),
             deletes: Some(delete_meta),
+            routing_key: inner_meta.routing_key.clone(),
         });
         SegmentMeta { tracked }
     }
@@ -219,6 +240,10 @@ struct InnerSegmentMeta {
     #[serde(skip)]
     #[serde(default = "default_temp_store")]
     pub(crate) include_temp_doc_store: Arc<AtomicBool>,
+    /// Documents added with different routing keys never share a segment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    routing_key: Option<String>,
 }
 fn default_temp_store() -> Arc<AtomicBool> {
     Arc::new(AtomicBool::new(false))
@@ -310,6 +335,13 @@ pub struct IndexMeta {
     pub segments: Vec<SegmentMeta>,
     /// Index `Schema`
     pub schema: Schema,
+    /// Version of the schema, incremented every time fields are added to the schema with
+    /// [`Index::add_fields`](crate::Index::add_fields).
+    ///
+    /// The segments created before a new version of the schema do not contain the fields
+    /// added to it.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub schema_version: u32,
     /// Opstamp associated with the last `commit` operation.
     pub opstamp: Opstamp,
     /// Payload associated with the last commit.
@@ -321,12 +353,18 @@ pub struct IndexMeta {
     pub payload: Option<String>,
 }
 
+fn is_zero(schema_version: &u32) -> bool {
+    *schema_version == 0
+}
+
 #[derive(Deserialize, Debug)]
 struct UntrackedIndexMeta {
     pub segments: Vec<InnerSegmentMeta>,
     #[serde(default)]
     pub index_settings: IndexSettings,
     pub schema: Schema,
+    #[serde(default)]
+    pub schema_version: u32,
     pub opstamp: Opstamp,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<String>,
@@ -342,6 +380,7 @@ impl UntrackedIndexMeta {
                 .map(|inner_seg_meta| inner_seg_meta.track(inventory))
                 .collect::<Vec<SegmentMeta>>(),
             schema: self.schema,
+            schema_version: self.schema_version,
             opstamp: self.opstamp,
             payload: self.payload,
         }
@@ -359,6 +398,7 @@ impl IndexMeta {
             index_settings: IndexSettings::default(),
             segments: vec![],
             schema,
+            schema_version: 0,
             opstamp: 0u64,
             payload: None,
         }
@@ -406,6 +446,7 @@ mod tests {
             index_settings: IndexSettings::default(),
             segments: Vec::new(),
             schema,
+            schema_version: 0,
             opstamp: 0u64,
             payload: None,
         };
@@ -439,6 +480,7 @@ mod tests {
             },
             segments: Vec::new(),
             schema,
+            schema_version: 0,
             opstamp: 0u64,
             payload: None,
         };
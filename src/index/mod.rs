@@ -6,6 +6,7 @@ mod index;
 mod index_meta;
 mod inverted_index_reader;
 mod segment;
+mod segment_archive;
 mod segment_component;
 mod segment_id;
 mod segment_reader;
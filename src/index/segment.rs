@@ -53,6 +53,14 @@ impl Segment {
         }
     }
 
+    /// Sets the routing key of the documents of the segment.
+    pub(crate) fn with_routing_key(self, routing_key: Option<String>) -> Segment {
+        Segment {
+            index: self.index,
+            meta: self.meta.with_routing_key(routing_key),
+        }
+    }
+
     #[doc(hidden)]
     #[must_use]
     pub fn with_delete_meta(self, num_deleted_docs: u32, opstamp: Opstamp) -> Segment {
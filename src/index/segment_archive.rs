@@ -0,0 +1,184 @@
+//! Portable archive of a single segment.
+//!
+//! An archive is laid out as follows:
+//! - the magic number and the format version,
+//! - the schema of the index, serialized as JSON,
+//! - the segment id, its `max_doc` and its number of deleted documents,
+//! - the number of files, followed by each file, as its component name and its content.
+//!
+//! The content of the files does not include the footer added by the
+//! [`ManagedDirectory`](crate::directory::ManagedDirectory): it is recomputed on import.
+
+use std::io::{self, Read, Write};
+
+use common::{BinarySerializable, TerminatingWrite, VInt};
+
+use crate::directory::{Directory, INDEX_WRITER_LOCK, META_LOCK};
+use crate::error::{DataCorruption, TantivyError};
+use crate::index::{Index, SegmentComponent, SegmentId};
+use crate::indexer::segment_updater::save_metas;
+use crate::schema::Schema;
+
+const SEGMENT_ARCHIVE_MAGIC_NUMBER: u32 = 0x5345_4741;
+const SEGMENT_ARCHIVE_VERSION: u32 = 1;
+
+/// Name identifying a component in an archive.
+///
+/// The delete component is not named after its opstamp, as the opstamps of
+/// the source index are meaningless in the target index.
+fn component_name(component: SegmentComponent) -> Option<&'static str> {
+    match component {
+        SegmentComponent::Postings => Some("idx"),
+        SegmentComponent::Positions => Some("pos"),
+        SegmentComponent::FastFields => Some("fast"),
+        SegmentComponent::FieldNorms => Some("fieldnorm"),
+        SegmentComponent::Terms => Some("term"),
+        SegmentComponent::Store => Some("store"),
+        SegmentComponent::Delete => Some("del"),
+        SegmentComponent::TempStore => None,
+    }
+}
+
+fn component_from_name(name: &str) -> Option<SegmentComponent> {
+    SegmentComponent::iterator()
+        .copied()
+        .find(|&component| component_name(component) == Some(name))
+}
+
+fn corrupted_archive(comment: &str) -> TantivyError {
+    DataCorruption::comment_only(format!("Invalid segment archive: {comment}")).into()
+}
+
+pub(crate) fn export_segment<W: Write>(
+    index: &Index,
+    segment_id: SegmentId,
+    writer: &mut W,
+) -> crate::Result<()> {
+    // Prevents the segment files from getting deleted while we are exporting them.
+    let _meta_lock = index.directory().acquire_lock(&META_LOCK)?;
+    let index_meta = index.load_metas()?;
+    let segment_meta = index_meta
+        .segments
+        .iter()
+        .find(|segment_meta| segment_meta.id() == segment_id)
+        .ok_or_else(|| {
+            TantivyError::InvalidArgument(format!(
+                "Segment {segment_id} is not a searchable segment of the index"
+            ))
+        })?;
+    let directory = index.directory();
+    let mut files = Vec::new();
+    for &component in SegmentComponent::iterator() {
+        let Some(name) = component_name(component) else {
+            continue;
+        };
+        if component == SegmentComponent::Delete && !segment_meta.has_deletes() {
+            continue;
+        }
+        let path = segment_meta.relative_path(component);
+        if !directory.exists(&path)? {
+            continue;
+        }
+        files.push((name, directory.open_read(&path)?.read_bytes()?));
+    }
+
+    SEGMENT_ARCHIVE_MAGIC_NUMBER.serialize(writer)?;
+    SEGMENT_ARCHIVE_VERSION.serialize(writer)?;
+    serde_json::to_string(&index_meta.schema)?.serialize(writer)?;
+    segment_id.uuid_string().serialize(writer)?;
+    segment_meta.max_doc().serialize(writer)?;
+    segment_meta.num_deleted_docs().serialize(writer)?;
+    VInt(files.len() as u64).serialize(writer)?;
+    for (name, bytes) in files {
+        name.to_string().serialize(writer)?;
+        (bytes.len() as u64).serialize(writer)?;
+        writer.write_all(bytes.as_slice())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn import_segment<R: Read>(index: &Index, reader: &mut R) -> crate::Result<SegmentId> {
+    if u32::deserialize(reader)? != SEGMENT_ARCHIVE_MAGIC_NUMBER {
+        return Err(corrupted_archive("wrong magic number"));
+    }
+    let version = u32::deserialize(reader)?;
+    if version != SEGMENT_ARCHIVE_VERSION {
+        return Err(corrupted_archive(&format!("unsupported version {version}")));
+    }
+    let schema: Schema = serde_json::from_str(&String::deserialize(reader)?)
+        .map_err(|_| corrupted_archive("the schema cannot be deserialized"))?;
+    let segment_id = SegmentId::from_uuid_string(&String::deserialize(reader)?)
+        .map_err(|_| corrupted_archive("the segment id cannot be parsed"))?;
+    let max_doc = u32::deserialize(reader)?;
+    let num_deleted_docs = u32::deserialize(reader)?;
+    if num_deleted_docs > max_doc {
+        return Err(corrupted_archive("more deleted documents than documents"));
+    }
+    if schema != index.schema() {
+        return Err(TantivyError::SchemaError(
+            "The schema of the segment archive does not match the schema of the index".to_string(),
+        ));
+    }
+
+    // The segment is registered without going through an `IndexWriter`, so none
+    // may be working on the index at the same time.
+    let _directory_lock = index
+        .directory()
+        .acquire_lock(&INDEX_WRITER_LOCK)
+        .map_err(|err| {
+            TantivyError::LockFailure(
+                err,
+                Some(
+                    "Failed to acquire index lock. A segment cannot be imported while an \
+                     `IndexWriter` is working on the index."
+                        .to_string(),
+                ),
+            )
+        })?;
+    let mut index_meta = index.load_metas()?;
+    if index_meta
+        .segments
+        .iter()
+        .any(|segment_meta| segment_meta.id() == segment_id)
+    {
+        return Err(TantivyError::InvalidArgument(format!(
+            "Segment {segment_id} is already part of the index"
+        )));
+    }
+    let mut segment_meta = index.new_segment_meta(segment_id, max_doc);
+    if num_deleted_docs > 0 {
+        // The deletes of the archive are expressed against the operations of the source
+        // index: they are recorded as of the last commit of the target index.
+        segment_meta = segment_meta.with_delete_meta(num_deleted_docs, index_meta.opstamp);
+    }
+
+    let directory = index.directory();
+    let num_files = VInt::deserialize(reader)?.val();
+    let mut has_delete_file = false;
+    for _ in 0..num_files {
+        let name = String::deserialize(reader)?;
+        let component = component_from_name(&name)
+            .ok_or_else(|| corrupted_archive(&format!("unknown component {name:?}")))?;
+        if component == SegmentComponent::Delete {
+            if num_deleted_docs == 0 {
+                return Err(corrupted_archive("unexpected delete file"));
+            }
+            has_delete_file = true;
+        }
+        let num_bytes = u64::deserialize(reader)?;
+        let mut write = directory.open_write(&segment_meta.relative_path(component))?;
+        let num_copied_bytes = io::copy(&mut reader.by_ref().take(num_bytes), &mut write)?;
+        if num_copied_bytes != num_bytes {
+            return Err(corrupted_archive("truncated file"));
+        }
+        write.terminate()?;
+    }
+    if num_deleted_docs > 0 && !has_delete_file {
+        return Err(corrupted_archive("missing delete file"));
+    }
+
+    index_meta.segments.push(segment_meta);
+    save_metas(&index_meta, directory)?;
+    Ok(segment_id)
+}
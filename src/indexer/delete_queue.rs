@@ -265,6 +265,7 @@ mod tests {
 
         let make_op = |i: usize| DeleteOperation {
             opstamp: i as u64,
+            min_doc_opstamp: 0,
             target: Box::new(DummyWeight),
         };
 
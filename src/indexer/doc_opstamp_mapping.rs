@@ -36,6 +36,17 @@ impl DocToOpstampMapping<'_> {
             Self::None => true,
         }
     }
+
+    /// Assess whether a document was added at or after `opstamp`.
+    ///
+    /// Documents without a mapping belong to a previous commit, and are considered
+    /// anterior to any opstamp but 0.
+    pub fn is_added_since(&self, doc_id: DocId, opstamp: Opstamp) -> bool {
+        match self {
+            Self::WithMap(doc_opstamps) => doc_opstamps[doc_id as usize] >= opstamp,
+            Self::None => opstamp == 0,
+        }
+    }
 }
 
 #[cfg(test)]
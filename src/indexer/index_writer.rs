@@ -1,9 +1,12 @@
+use std::collections::HashMap;
 use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::thread;
 use std::thread::JoinHandle;
+use std::{fmt, thread};
 
 use common::BitSet;
+use crossbeam_channel::TrySendError;
 use smallvec::smallvec;
 
 use super::operation::{AddOperation, UserOperation};
@@ -19,9 +22,10 @@ use crate::indexer::index_writer_status::IndexWriterStatus;
 use crate::indexer::operation::DeleteOperation;
 use crate::indexer::stamper::Stamper;
 use crate::indexer::{MergePolicy, SegmentEntry, SegmentWriter};
-use crate::query::{EnableScoring, Query, TermQuery};
-use crate::schema::document::Document;
-use crate::schema::{IndexRecordOption, TantivyDocument, Term};
+use crate::query::{AllWeight, EnableScoring, Query, TermQuery};
+use crate::schema::document::{Document, Value};
+use crate::schema::{FieldEntry, FieldType, IndexRecordOption, Schema, TantivyDocument, Term};
+use crate::tokenizer::TokenizerManager;
 use crate::{FutureResult, Opstamp};
 
 // Size of the margin for the `memory_arena`. A segment is closed when the remaining memory
@@ -32,6 +36,10 @@ pub const MARGIN_IN_BYTES: usize = 1_000_000;
 pub const MEMORY_BUDGET_NUM_BYTES_MIN: usize = ((MARGIN_IN_BYTES as u32) * 15u32) as usize;
 pub const MEMORY_BUDGET_NUM_BYTES_MAX: usize = u32::MAX as usize - MARGIN_IN_BYTES;
 
+// The memory budget of an indexing thread is shared by its in-flight segments, each of which is
+// given at least this share of the budget.
+const IN_FLIGHT_SEGMENT_MEMORY_BUDGET_MIN: usize = MARGIN_IN_BYTES;
+
 // We impose the number of index writer threads to be at most this.
 pub const MAX_NUM_THREAD: usize = 8;
 
@@ -39,12 +47,119 @@ pub const MAX_NUM_THREAD: usize = 8;
 // reaches `PIPELINE_MAX_SIZE_IN_DOCS`
 const PIPELINE_MAX_SIZE_IN_DOCS: usize = 10_000;
 
+fn check_memory_budget(memory_budget_in_bytes_per_thread: usize) -> crate::Result<()> {
+    if memory_budget_in_bytes_per_thread < MEMORY_BUDGET_NUM_BYTES_MIN {
+        let err_msg = format!(
+            "The memory arena in bytes per thread needs to be at least \
+             {MEMORY_BUDGET_NUM_BYTES_MIN}."
+        );
+        return Err(TantivyError::InvalidArgument(err_msg));
+    }
+    if memory_budget_in_bytes_per_thread >= MEMORY_BUDGET_NUM_BYTES_MAX {
+        let err_msg = format!(
+            "The memory arena in bytes per thread cannot exceed {MEMORY_BUDGET_NUM_BYTES_MAX}"
+        );
+        return Err(TantivyError::InvalidArgument(err_msg));
+    }
+    Ok(())
+}
+
 fn error_in_index_worker_thread(context: &str) -> TantivyError {
     TantivyError::ErrorInThread(format!(
         "{context}. A worker thread encountered an error (io::Error most likely) or panicked."
     ))
 }
 
+/// Returns an error if `document` has no value for one of the required fields of the schema.
+fn check_required_fields<D: Document>(schema: &Schema, document: &D) -> crate::Result<()> {
+    for (field, field_entry) in schema.fields() {
+        if field_entry.is_required()
+            && !document
+                .iter_fields_and_values()
+                .any(|(doc_field, _)| doc_field == field)
+        {
+            return Err(TantivyError::SchemaError(format!(
+                "Document is missing a value for the required field {:?}",
+                field_entry.name()
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Values of a str or bytes fast field are stored in the dictionary of the column, whose keys
+// are limited to this length.
+const MAX_FAST_FIELD_VALUE_LEN: usize = u16::MAX as usize;
+
+fn check_field_values<D: Document>(
+    schema: &Schema,
+    fast_field_tokenizers: &TokenizerManager,
+    document: &D,
+) -> crate::Result<()> {
+    for (field, value) in document.iter_fields_and_values() {
+        let field_entry = schema.get_field_entry(field);
+        let value = value as D::Value<'_>;
+        if let Some(pre_tokenized_text) = value.as_pre_tokenized_text() {
+            pre_tokenized_text.validate()?;
+            if field_entry.is_fast() {
+                let token_lens = pre_tokenized_text
+                    .tokens
+                    .iter()
+                    .map(|token| token.text.len());
+                check_fast_field_value_len(field_entry, token_lens.max().unwrap_or(0))?;
+            }
+            continue;
+        }
+        if !field_entry.is_fast() {
+            continue;
+        }
+        if let Some(bytes) = value.as_bytes() {
+            check_fast_field_value_len(field_entry, bytes.len())?;
+        } else if let Some(text) = value.as_str() {
+            // The tokens are never longer than the text, so that short texts are not tokenized.
+            if text.len() <= MAX_FAST_FIELD_VALUE_LEN {
+                continue;
+            }
+            let fast_field_tokenizer_name = match field_entry.field_type() {
+                FieldType::Str(text_options) => text_options.get_fast_field_tokenizer_name(),
+                _ => None,
+            };
+            let Some(fast_field_tokenizer_name) = fast_field_tokenizer_name else {
+                check_fast_field_value_len(field_entry, text.len())?;
+                continue;
+            };
+            // An unknown tokenizer is reported when the segment writer is created.
+            let Some(mut text_analyzer) = fast_field_tokenizers.get(fast_field_tokenizer_name)
+            else {
+                continue;
+            };
+            let mut max_token_len = 0;
+            text_analyzer.token_stream(text).process(&mut |token| {
+                max_token_len = max_token_len.max(token.text.len());
+            });
+            check_fast_field_value_len(field_entry, max_token_len)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_fast_field_value_len(field_entry: &FieldEntry, value_len: usize) -> crate::Result<()> {
+    if value_len > MAX_FAST_FIELD_VALUE_LEN {
+        return Err(TantivyError::InvalidArgument(format!(
+            "A value of the fast field {:?} is {value_len} bytes long, which exceeds the limit of \
+             {MAX_FAST_FIELD_VALUE_LEN} bytes",
+            field_entry.name(),
+        )));
+    }
+    Ok(())
+}
+
+fn check_document<D: Document>(index: &Index, document: &D) -> crate::Result<()> {
+    let schema = index.schema();
+    check_required_fields(&schema, document)?;
+    check_field_values(&schema, index.fast_field_tokenizer(), document)
+}
+
 /// `IndexWriter` is the user entry-point to add document to an index.
 ///
 /// It manages a small number of indexing thread, as well as a shared
@@ -59,7 +174,9 @@ pub struct IndexWriter<D: Document = TantivyDocument> {
     index: Index,
 
     // The memory budget per thread, after which a commit is triggered.
-    memory_budget_in_bytes_per_thread: usize,
+    //
+    // It is shared with the indexing workers, which read it whenever they start a new segment.
+    memory_budget_in_bytes_per_thread: Arc<AtomicUsize>,
 
     workers_join_handle: Vec<JoinHandle<crate::Result<()>>>,
 
@@ -78,6 +195,63 @@ pub struct IndexWriter<D: Document = TantivyDocument> {
     committed_opstamp: Opstamp,
 }
 
+/// A position in the stream of operations of an [`IndexWriter`], obtained via
+/// [`IndexWriter::rollback_token`].
+///
+/// [`IndexWriter::rollback_to`] discards the documents added after the token was created.
+/// A token is only valid until the next commit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RollbackToken {
+    opstamp: Opstamp,
+    committed_opstamp: Opstamp,
+}
+
+impl RollbackToken {
+    /// Returns the opstamp of the token.
+    ///
+    /// The documents with an opstamp greater than this opstamp are discarded on rollback.
+    pub fn opstamp(&self) -> Opstamp {
+        self.opstamp
+    }
+}
+
+/// Error returned by [`IndexWriter::try_add_document`].
+pub enum TryAddDocumentError<D> {
+    /// The indexing queue is full.
+    ///
+    /// The document was not added: it is handed back so that it can be added again later.
+    QueueFull(D),
+    /// The document could not be added, e.g. because it has no value for a required field, or
+    /// because the indexing threads have failed.
+    Error(TantivyError),
+}
+
+impl<D> From<TantivyError> for TryAddDocumentError<D> {
+    fn from(error: TantivyError) -> Self {
+        TryAddDocumentError::Error(error)
+    }
+}
+
+impl<D> fmt::Debug for TryAddDocumentError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryAddDocumentError::QueueFull(_) => f.write_str("QueueFull(..)"),
+            TryAddDocumentError::Error(error) => f.debug_tuple("Error").field(error).finish(),
+        }
+    }
+}
+
+impl<D> fmt::Display for TryAddDocumentError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryAddDocumentError::QueueFull(_) => f.write_str("The indexing queue is full"),
+            TryAddDocumentError::Error(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl<D> std::error::Error for TryAddDocumentError<D> {}
+
 fn compute_deleted_bitset(
     alive_bitset: &mut BitSet,
     segment_reader: &SegmentReader,
@@ -97,7 +271,10 @@ fn compute_deleted_bitset(
             .target
             .for_each_no_score(segment_reader, &mut |docs_matching_delete_query| {
                 for doc_matching_delete_query in docs_matching_delete_query.iter().cloned() {
-                    if doc_opstamps.is_deleted(doc_matching_delete_query, delete_op.opstamp) {
+                    if doc_opstamps.is_deleted(doc_matching_delete_query, delete_op.opstamp)
+                        && doc_opstamps
+                            .is_added_since(doc_matching_delete_query, delete_op.min_doc_opstamp)
+                    {
                         alive_bitset.remove(doc_matching_delete_query);
                         might_have_changed = true;
                     }
@@ -139,11 +316,16 @@ pub(crate) fn advance_deletes(
 
     let num_deleted_docs_before = segment.meta().num_deleted_docs();
 
+    let doc_opstamps = segment_entry.doc_opstamps().cloned();
+    let doc_to_opstamps = match doc_opstamps.as_deref() {
+        Some(doc_opstamps) => DocToOpstampMapping::WithMap(doc_opstamps),
+        None => DocToOpstampMapping::None,
+    };
     compute_deleted_bitset(
         &mut alive_bitset,
         &segment_reader,
         segment_entry.delete_cursor(),
-        &DocToOpstampMapping::None,
+        &doc_to_opstamps,
         target_opstamp,
     )?;
 
@@ -165,36 +347,119 @@ pub(crate) fn advance_deletes(
     Ok(())
 }
 
-fn index_documents<D: Document>(
-    memory_budget: usize,
+/// A segment being written by an indexing worker.
+struct InFlightSegment {
     segment: Segment,
+    segment_writer: SegmentWriter,
+    delete_cursor: DeleteCursor,
+}
+
+fn index_documents<D: Document>(
+    memory_budget_per_thread: &AtomicUsize,
+    index: &Index,
     grouped_document_iterator: &mut dyn Iterator<Item = AddBatch<D>>,
     segment_updater: &SegmentUpdater,
-    mut delete_cursor: DeleteCursor,
+    delete_cursor: &mut DeleteCursor,
 ) -> crate::Result<()> {
-    let mut segment_writer = SegmentWriter::for_segment(memory_budget, segment.clone())?;
+    // Documents are routed to one in-flight segment per routing key.
+    // The segments are created lazily, to avoid creating a new segment's files
+    // if no document is available.
+    let mut in_flight_segments: HashMap<Option<String>, InFlightSegment> = HashMap::new();
+    let mut memory_budget = memory_budget_per_thread.load(Ordering::Relaxed);
     for document_group in grouped_document_iterator {
-        for doc in document_group {
-            segment_writer.add_document(doc)?;
+        if !in_flight_segments.contains_key(&document_group.routing_key) {
+            // The budget may have been changed via `IndexWriter::set_memory_budget_per_thread`.
+            memory_budget = memory_budget_per_thread.load(Ordering::Relaxed);
+            // The memory budget is shared by the in-flight segments. Before opening a new one,
+            // the largest ones are flushed so that their share never drops below the minimum.
+            while !in_flight_segments.is_empty()
+                && memory_budget / (in_flight_segments.len() + 1)
+                    < IN_FLIGHT_SEGMENT_MEMORY_BUDGET_MIN
+            {
+                let in_flight_segment = remove_largest_in_flight_segment(&mut in_flight_segments);
+                if !segment_updater.is_alive() {
+                    return Ok(());
+                }
+                flush_segment(in_flight_segment, segment_updater)?;
+            }
+            delete_cursor.skip_to(document_group.operations[0].opstamp);
+            let segment = index
+                .new_segment()
+                .with_routing_key(document_group.routing_key.clone());
+            let segment_memory_budget = memory_budget / (in_flight_segments.len() + 1);
+            let segment_writer =
+                SegmentWriter::for_segment(segment_memory_budget, segment.clone())?;
+            in_flight_segments.insert(
+                document_group.routing_key.clone(),
+                InFlightSegment {
+                    segment,
+                    segment_writer,
+                    delete_cursor: delete_cursor.clone(),
+                },
+            );
         }
-        let mem_usage = segment_writer.mem_usage();
+        let in_flight_segment = in_flight_segments
+            .get_mut(&document_group.routing_key)
+            .expect("The in-flight segment of the routing key was just created");
+        for doc in document_group.operations {
+            in_flight_segment.segment_writer.add_document(doc)?;
+        }
+        let mem_usage: usize = in_flight_segments
+            .values()
+            .map(|in_flight_segment| in_flight_segment.segment_writer.mem_usage())
+            .sum();
         if mem_usage >= memory_budget - MARGIN_IN_BYTES {
+            // Only the largest of the in-flight segments is flushed, so that the segments
+            // of the other routing keys can keep growing.
+            let in_flight_segment = remove_largest_in_flight_segment(&mut in_flight_segments);
             info!(
                 "Buffer limit reached, flushing segment with maxdoc={}.",
-                segment_writer.max_doc()
+                in_flight_segment.segment_writer.max_doc()
             );
-            break;
+            if !segment_updater.is_alive() {
+                return Ok(());
+            }
+            flush_segment(in_flight_segment, segment_updater)?;
         }
     }
 
-    if !segment_updater.is_alive() {
-        return Ok(());
+    // No more documents.
+    // It happens when there is a commit, or if the `IndexWriter` was dropped.
+    for in_flight_segment in in_flight_segments.into_values() {
+        if !segment_updater.is_alive() {
+            return Ok(());
+        }
+        flush_segment(in_flight_segment, segment_updater)?;
     }
+    Ok(())
+}
+
+/// Removes the in-flight segment using the most memory.
+///
+/// `in_flight_segments` is required to be non-empty.
+fn remove_largest_in_flight_segment(
+    in_flight_segments: &mut HashMap<Option<String>, InFlightSegment>,
+) -> InFlightSegment {
+    let routing_key = in_flight_segments
+        .iter()
+        .max_by_key(|(_, in_flight_segment)| in_flight_segment.segment_writer.mem_usage())
+        .map(|(routing_key, _)| routing_key.clone())
+        .expect("There is at least one in-flight segment");
+    in_flight_segments.remove(&routing_key).unwrap()
+}
 
+fn flush_segment(
+    in_flight_segment: InFlightSegment,
+    segment_updater: &SegmentUpdater,
+) -> crate::Result<()> {
+    let InFlightSegment {
+        segment,
+        segment_writer,
+        mut delete_cursor,
+    } = in_flight_segment;
     let max_doc = segment_writer.max_doc();
 
-    // this is ensured by the call to peek before starting
-    // the worker thread.
+    // this is ensured by the lazy creation of the in-flight segments.
     assert!(max_doc > 0);
 
     let doc_opstamps: Vec<Opstamp> = segment_writer.finalize()?;
@@ -206,7 +471,8 @@ fn index_documents<D: Document>(
     let meta = segment_with_max_doc.meta().clone();
     meta.untrack_temp_docstore();
     // update segment_updater inventory to remove tempstore
-    let segment_entry = SegmentEntry::new(meta, delete_cursor, alive_bitset_opt);
+    let segment_entry = SegmentEntry::new(meta, delete_cursor, alive_bitset_opt)
+        .with_doc_opstamps(Some(Arc::from(doc_opstamps)));
     segment_updater.schedule_add_segment(segment_entry).wait()?;
     Ok(())
 }
@@ -269,25 +535,21 @@ impl<D: Document> IndexWriter<D> {
         memory_budget_in_bytes_per_thread: usize,
         directory_lock: DirectoryLock,
     ) -> crate::Result<Self> {
-        if memory_budget_in_bytes_per_thread < MEMORY_BUDGET_NUM_BYTES_MIN {
-            let err_msg = format!(
-                "The memory arena in bytes per thread needs to be at least \
-                 {MEMORY_BUDGET_NUM_BYTES_MIN}."
-            );
-            return Err(TantivyError::InvalidArgument(err_msg));
-        }
-        if memory_budget_in_bytes_per_thread >= MEMORY_BUDGET_NUM_BYTES_MAX {
-            let err_msg = format!(
-                "The memory arena in bytes per thread cannot exceed {MEMORY_BUDGET_NUM_BYTES_MAX}"
-            );
-            return Err(TantivyError::InvalidArgument(err_msg));
-        }
+        check_memory_budget(memory_budget_in_bytes_per_thread)?;
         let (document_sender, document_receiver) =
             crossbeam_channel::bounded(PIPELINE_MAX_SIZE_IN_DOCS);
 
         let delete_queue = DeleteQueue::new();
 
-        let current_opstamp = index.load_metas()?.opstamp;
+        let index_meta = index.load_metas()?;
+        if index_meta.schema_version != index.schema_version() {
+            return Err(TantivyError::SchemaError(
+                "The schema of the index has been changed since the index was opened. The index \
+                 needs to be reopened."
+                    .to_string(),
+            ));
+        }
+        let current_opstamp = index_meta.opstamp;
 
         let stamper = Stamper::new(current_opstamp);
 
@@ -297,7 +559,9 @@ impl<D: Document> IndexWriter<D> {
         let mut index_writer = Self {
             _directory_lock: Some(directory_lock),
 
-            memory_budget_in_bytes_per_thread,
+            memory_budget_in_bytes_per_thread: Arc::new(AtomicUsize::new(
+                memory_budget_in_bytes_per_thread,
+            )),
             index: index.clone(),
             index_writer_status: IndexWriterStatus::from(document_receiver),
             operation_sender: document_sender,
@@ -398,48 +662,59 @@ impl<D: Document> IndexWriter<D> {
 
         let mut delete_cursor = self.delete_queue.cursor();
 
-        let mem_budget = self.memory_budget_in_bytes_per_thread;
+        let mem_budget = self.memory_budget_in_bytes_per_thread.clone();
         let index = self.index.clone();
         let join_handle: JoinHandle<crate::Result<()>> = thread::Builder::new()
             .name(format!("thrd-tantivy-index{}", self.worker_id))
             .spawn(move || {
-                loop {
-                    let mut document_iterator = document_receiver_clone
-                        .clone()
-                        .into_iter()
-                        .filter(|batch| !batch.is_empty())
-                        .peekable();
-
-                    // The peeking here is to avoid creating a new segment's files
-                    // if no document are available.
-                    //
-                    // This is a valid guarantee as the peeked document now belongs to
-                    // our local iterator.
-                    if let Some(batch) = document_iterator.peek() {
-                        assert!(!batch.is_empty());
-                        delete_cursor.skip_to(batch[0].opstamp);
-                    } else {
-                        // No more documents.
-                        // It happens when there is a commit, or if the `IndexWriter`
-                        // was dropped.
-                        index_writer_bomb.defuse();
-                        return Ok(());
-                    }
-
-                    index_documents(
-                        mem_budget,
-                        index.new_segment(),
-                        &mut document_iterator,
-                        &segment_updater,
-                        delete_cursor.clone(),
-                    )?;
-                }
+                let mut document_iterator = document_receiver_clone
+                    .into_iter()
+                    .filter(|batch| !batch.operations.is_empty());
+                index_documents(
+                    &mem_budget,
+                    &index,
+                    &mut document_iterator,
+                    &segment_updater,
+                    &mut delete_cursor,
+                )?;
+                index_writer_bomb.defuse();
+                Ok(())
             })?;
         self.worker_id += 1;
         self.workers_join_handle.push(join_handle);
         Ok(())
     }
 
+    /// Returns the number of indexing threads.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads
+    }
+
+    /// Returns the memory budget per indexing thread, in bytes.
+    pub fn memory_budget_per_thread(&self) -> usize {
+        self.memory_budget_in_bytes_per_thread
+            .load(Ordering::Relaxed)
+    }
+
+    /// Sets the memory budget per indexing thread, in bytes.
+    ///
+    /// The new budget does not affect the segments currently being written: each indexing
+    /// thread picks it up when it starts its next segment, e.g. after its next flush.
+    /// Uncommitted documents are preserved.
+    ///
+    /// # Errors
+    /// If the memory arena per thread is too small or too big, returns
+    /// `TantivyError::InvalidArgument`, and the budget is left unchanged.
+    pub fn set_memory_budget_per_thread(
+        &self,
+        memory_budget_in_bytes_per_thread: usize,
+    ) -> crate::Result<()> {
+        check_memory_budget(memory_budget_in_bytes_per_thread)?;
+        self.memory_budget_in_bytes_per_thread
+            .store(memory_budget_in_bytes_per_thread, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Accessor to the merge policy.
     pub fn get_merge_policy(&self) -> Arc<dyn MergePolicy> {
         self.segment_updater.get_merge_policy()
@@ -450,6 +725,26 @@ impl<D: Document> IndexWriter<D> {
         self.segment_updater.set_merge_policy(merge_policy);
     }
 
+    /// Registers a callback called whenever a commit completes.
+    ///
+    /// The callback receives the opstamp of the commit, and the ids of the segments
+    /// that are searchable as of this commit. It is called once the new `meta.json` has been
+    /// durably written, and before [`IndexWriter::commit()`] (or
+    /// [`PreparedCommit::commit()`]) return.
+    ///
+    /// Commits are processed sequentially, on a single thread: callbacks are never
+    /// called concurrently, and are called in increasing order of opstamp. If several
+    /// callbacks are registered, they are called in the order of registration.
+    ///
+    /// Callbacks run on the thread in charge of updating segments, which is blocked
+    /// meanwhile. They should therefore return quickly.
+    ///
+    /// Merges do not trigger the callback.
+    pub fn on_commit<F>(&self, callback: F)
+    where F: Fn(Opstamp, &[SegmentId]) + Send + Sync + 'static {
+        self.segment_updater.add_commit_callback(Arc::new(callback));
+    }
+
     fn start_workers(&mut self) -> crate::Result<()> {
         for _ in 0..self.num_threads {
             self.add_indexing_worker()?;
@@ -556,7 +851,7 @@ impl<D: Document> IndexWriter<D> {
         let new_index_writer = IndexWriter::new(
             &self.index,
             self.num_threads,
-            self.memory_budget_in_bytes_per_thread,
+            self.memory_budget_per_thread(),
             directory_lock,
         )?;
 
@@ -684,12 +979,60 @@ impl<D: Document> IndexWriter<D> {
         let opstamp = self.stamper.stamp();
         let delete_operation = DeleteOperation {
             opstamp,
+            min_doc_opstamp: 0,
             target: weight,
         };
         self.delete_queue.push(delete_operation);
         Ok(opstamp)
     }
 
+    /// Returns a token marking the current position in the stream of operations.
+    ///
+    /// The documents added after this call can be discarded, before the next commit, by
+    /// calling [`IndexWriter::rollback_to`] with the token.
+    pub fn rollback_token(&self) -> RollbackToken {
+        RollbackToken {
+            opstamp: self.stamper.stamp(),
+            committed_opstamp: self.segment_updater.load_meta().opstamp,
+        }
+    }
+
+    /// Discards all of the documents added after the creation of `token`.
+    ///
+    /// Unlike [`IndexWriter::rollback`], the documents added before the token, and the
+    /// documents added after this call are kept, and the pending documents do not need to be
+    /// reindexed. The documents are discarded whether they are still in the indexing pipeline,
+    /// or already in an uncommitted segment.
+    ///
+    /// Documents are ordered by their opstamp: if other threads are adding documents
+    /// concurrently, their documents are discarded as well if they were stamped between
+    /// the creation of the token and this call. The documents stamped after this call
+    /// are kept. Delete operations are not reverted.
+    ///
+    /// Like adds, the rollback itself takes effect on commit.
+    ///
+    /// Returns an error if the token was created before the last commit. The token should not
+    /// be used after a call to
+    /// [`IndexWriter::rollback`] or [`IndexWriter::delete_all_documents`].
+    pub fn rollback_to(&self, token: RollbackToken) -> crate::Result<Opstamp> {
+        let committed_opstamp = self.segment_updater.load_meta().opstamp;
+        let opstamp = self.stamper.stamp();
+        if token.committed_opstamp != committed_opstamp || token.opstamp >= opstamp {
+            return Err(TantivyError::InvalidArgument(format!(
+                "The rollback token with opstamp {} is not valid anymore: it was created before \
+                 the commit with opstamp {committed_opstamp}.",
+                token.opstamp
+            )));
+        }
+        let delete_operation = DeleteOperation {
+            opstamp,
+            min_doc_opstamp: token.opstamp,
+            target: Box::new(AllWeight),
+        };
+        self.delete_queue.push(delete_operation);
+        Ok(opstamp)
+    }
+
     /// Returns the opstamp of the last successful commit.
     ///
     /// This is, for instance, the opstamp the index will
@@ -703,17 +1046,108 @@ impl<D: Document> IndexWriter<D> {
 
     /// Adds a document.
     ///
-    /// If the indexing pipeline is full, this call may block.
+    /// If the indexing pipeline is full, this call may block. See
+    /// [`IndexWriter::try_add_document`] for a non-blocking alternative.
     ///
     /// The opstamp is an increasing `u64` that can
     /// be used by the client to align commits with its own
     /// document queue.
+    ///
+    /// Returns an error if the document has no value for one of the
+    /// required fields of the schema.
     pub fn add_document(&self, document: D) -> crate::Result<Opstamp> {
+        check_document(&self.index, &document)?;
         let opstamp = self.stamper.stamp();
-        self.send_add_documents_batch(smallvec![AddOperation { opstamp, document }])?;
+        self.send_add_documents_batch(AddBatch {
+            routing_key: None,
+            operations: smallvec![AddOperation { opstamp, document }],
+        })?;
         Ok(opstamp)
     }
 
+    /// Adds a document, tagged with a routing key.
+    ///
+    /// Documents sharing the same routing key are indexed in their own segments, which
+    /// never contain documents of another routing key, or documents added without a
+    /// routing key. Each indexing thread keeps one in-flight segment per routing key, and
+    /// flushes them independently. Segments with different routing keys are never merged
+    /// together by the merge policy.
+    ///
+    /// The routing key of a segment is available via [`SegmentMeta::routing_key`].
+    ///
+    /// The memory budget of an indexing thread is shared by its in-flight segments: using
+    /// many distinct routing keys results in small segments. Once the share of each segment
+    /// would become too small, the largest in-flight segment is flushed before a segment is
+    /// opened for another routing key.
+    pub fn add_document_with_routing_key(
+        &self,
+        routing_key: &str,
+        document: D,
+    ) -> crate::Result<Opstamp> {
+        check_document(&self.index, &document)?;
+        let opstamp = self.stamper.stamp();
+        self.send_add_documents_batch(AddBatch {
+            routing_key: Some(routing_key.to_string()),
+            operations: smallvec![AddOperation { opstamp, document }],
+        })?;
+        Ok(opstamp)
+    }
+
+    /// Adds a document, without blocking if the indexing pipeline is full.
+    ///
+    /// If the indexing queue is at capacity, the document is not added and is returned in
+    /// [`TryAddDocumentError::QueueFull`], so that the caller can slow down and retry later,
+    /// e.g. implement its own flow control. Otherwise, this behaves like
+    /// [`IndexWriter::add_document`].
+    ///
+    /// See [`IndexWriter::indexing_queue_len`] to monitor the queue.
+    ///
+    /// The opstamp of the document is assigned before it is queued. If the queue is found to
+    /// be full beforehand, no opstamp is consumed. However, if other threads fill the queue
+    /// concurrently, a rejected document may consume an opstamp that no operation gets: the
+    /// opstamps always increase, but their sequence can then have gaps.
+    pub fn try_add_document(&self, document: D) -> Result<Opstamp, TryAddDocumentError<D>> {
+        check_document(&self.index, &document)?;
+        if !self.index_writer_status.is_alive() {
+            return Err(error_in_index_worker_thread("An index writer was killed.").into());
+        }
+        if self.operation_sender.is_full() {
+            return Err(TryAddDocumentError::QueueFull(document));
+        }
+        let opstamp = self.stamper.stamp();
+        let add_batch = AddBatch {
+            routing_key: None,
+            operations: smallvec![AddOperation { opstamp, document }],
+        };
+        match self.operation_sender.try_send(add_batch) {
+            Ok(()) => Ok(opstamp),
+            Err(TrySendError::Full(mut add_batch)) => {
+                let add_operation = add_batch.operations.pop().unwrap();
+                Err(TryAddDocumentError::QueueFull(add_operation.document))
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                Err(error_in_index_worker_thread("An index writer was killed.").into())
+            }
+        }
+    }
+
+    /// Returns the number of additions waiting in the indexing queue to be picked up by the
+    /// indexing threads.
+    ///
+    /// A call to [`IndexWriter::add_document`] or to [`IndexWriter::run`] counts as one
+    /// addition. Once the queue holds [`IndexWriter::indexing_queue_capacity`] additions, adding
+    /// documents blocks, and [`IndexWriter::try_add_document`] fails.
+    pub fn indexing_queue_len(&self) -> usize {
+        self.operation_sender.len()
+    }
+
+    /// Returns the maximum number of additions the indexing queue can hold.
+    pub fn indexing_queue_capacity(&self) -> usize {
+        self.operation_sender
+            .capacity()
+            .unwrap_or(PIPELINE_MAX_SIZE_IN_DOCS)
+    }
+
     /// Gets a range of stamps from the stamper and "pops" the last stamp
     /// from the range returning a tuple of the last optstamp and the popped
     /// range.
@@ -743,11 +1177,20 @@ impl<D: Document> IndexWriter<D> {
     /// Like adds and deletes (see `IndexWriter.add_document` and
     /// `IndexWriter.delete_term`), the changes made by calling `run` will be
     /// visible to readers only after calling `commit()`.
+    ///
+    /// If one of the added documents has no value for a required field of the schema,
+    /// an error is returned and none of the operations is applied.
     pub fn run<I>(&self, user_operations: I) -> crate::Result<Opstamp>
     where
         I: IntoIterator<Item = UserOperation<D>>,
         I::IntoIter: ExactSizeIterator,
     {
+        let user_operations: Vec<UserOperation<D>> = user_operations.into_iter().collect();
+        for user_op in &user_operations {
+            if let UserOperation::Add(document) = user_op {
+                check_document(&self.index, document)?;
+            }
+        }
         let user_operations_it = user_operations.into_iter();
         let count = user_operations_it.len() as u64;
         if count == 0 {
@@ -765,13 +1208,14 @@ impl<D: Document> IndexWriter<D> {
                         query.weight(EnableScoring::disabled_from_schema(&self.index.schema()))?;
                     let delete_operation = DeleteOperation {
                         opstamp,
+                        min_doc_opstamp: 0,
                         target: weight,
                     };
                     self.delete_queue.push(delete_operation);
                 }
                 UserOperation::Add(document) => {
                     let add_operation = AddOperation { opstamp, document };
-                    adds.push(add_operation);
+                    adds.operations.push(add_operation);
                 }
             }
         }
@@ -802,6 +1246,8 @@ impl<D: Document> Drop for IndexWriter<D> {
 mod tests {
     use std::collections::{HashMap, HashSet};
     use std::net::Ipv6Addr;
+    use std::ops::Bound;
+    use std::sync::{Arc, Mutex};
 
     use columnar::{Column, MonotonicallyMappableToU128};
     use itertools::Itertools;
@@ -811,9 +1257,10 @@ mod tests {
     use crate::collector::{Count, TopDocs};
     use crate::directory::error::LockError;
     use crate::error::*;
+    use crate::index::{SegmentId, SegmentReader};
     use crate::indexer::index_writer::MEMORY_BUDGET_NUM_BYTES_MIN;
-    use crate::indexer::NoMergePolicy;
-    use crate::query::{QueryParser, TermQuery};
+    use crate::indexer::{LogMergePolicy, NoMergePolicy, TryAddDocumentError};
+    use crate::query::{QueryParser, RangeQuery, TermQuery};
     use crate::schema::{
         self, Facet, FacetOptions, IndexRecordOption, IpAddrOptions, JsonObjectOptions,
         NumericOptions, Schema, TextFieldIndexing, TextOptions, Value, FAST, INDEXED, STORED,
@@ -821,8 +1268,8 @@ mod tests {
     };
     use crate::store::DOCSTORE_CACHE_CAPACITY;
     use crate::{
-        DateTime, DocAddress, Index, IndexSettings, IndexWriter, ReloadPolicy, TantivyDocument,
-        Term,
+        DateTime, DocAddress, Index, IndexSettings, IndexWriter, Opstamp, ReloadPolicy,
+        TantivyDocument, Term,
     };
 
     const LOREM: &str = "Doc Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do \
@@ -941,6 +1388,203 @@ mod tests {
         assert_eq!(b_docs.len(), 0);
     }
 
+    #[test]
+    fn test_delete_query_with_range_query() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let age_field = schema_builder.add_u64_field("age", INDEXED | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for age in [10u64, 25, 31, 47] {
+            index_writer.add_document(doc!(age_field=>age))?;
+        }
+        index_writer.commit()?;
+        let range_query = RangeQuery::new(
+            Bound::Excluded(Term::from_field_u64(age_field, 30)),
+            Bound::Unbounded,
+        );
+        index_writer.delete_query(Box::new(range_query))?;
+        // Documents added after the delete operation are not affected by it.
+        index_writer.add_document(doc!(age_field=>52u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        let age_query = |age: u64| {
+            TermQuery::new(
+                Term::from_field_u64(age_field, age),
+                IndexRecordOption::Basic,
+            )
+        };
+        assert_eq!(searcher.search(&age_query(25), &Count)?, 1);
+        assert_eq!(searcher.search(&age_query(31), &Count)?, 0);
+        assert_eq!(searcher.search(&age_query(47), &Count)?, 0);
+        assert_eq!(searcher.search(&age_query(52), &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_query_with_boolean_query() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let status_field = schema_builder.add_text_field("status", STRING);
+        let age_field = schema_builder.add_u64_field("age", INDEXED | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(status_field=>"archived", age_field=>40u64))?;
+        index_writer.add_document(doc!(status_field=>"archived", age_field=>20u64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(status_field=>"active", age_field=>40u64))?;
+        index_writer.add_document(doc!(status_field=>"archived", age_field=>35u64))?;
+        let query_parser = QueryParser::for_index(&index, vec![]);
+        let query = query_parser.parse_query("+status:archived +age:>30")?;
+        index_writer.delete_query(query)?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 2);
+        let count = |query: &str| -> crate::Result<usize> {
+            searcher.search(&query_parser.parse_query(query)?, &Count)
+        };
+        assert_eq!(count("status:archived")?, 1);
+        assert_eq!(count("+status:archived +age:20")?, 1);
+        assert_eq!(count("+status:active +age:40")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_document_pre_tokenized_text() -> crate::Result<()> {
+        use crate::tokenizer::{PreTokenizedString, Token};
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let token = |text: &str, offset_from: usize, position: usize| Token {
+            offset_from,
+            offset_to: offset_from + text.len(),
+            position,
+            text: text.to_string(),
+            position_length: 1,
+            original_text: None,
+        };
+        // The tokens bypass the tokenizer of the field: they are indexed as is.
+        let pre_tokenized_text = PreTokenizedString {
+            text: String::from("New York"),
+            tokens: vec![token("New", 0, 0), token("NYC", 0, 0), token("York", 4, 1)],
+        };
+        let mut document = TantivyDocument::default();
+        document.add_pre_tokenized_text(text_field, pre_tokenized_text);
+        index_writer.add_document(document)?;
+
+        let invalid_pre_tokenized_text = PreTokenizedString {
+            text: String::from("New York"),
+            tokens: vec![token("York", 4, 1), token("New", 0, 0)],
+        };
+        let mut document = TantivyDocument::default();
+        document.add_pre_tokenized_text(text_field, invalid_pre_tokenized_text);
+        assert!(matches!(
+            index_writer.add_document(document),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let term_count = |text: &str| {
+            let query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::Basic,
+            );
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(term_count("NYC"), 1);
+        assert_eq!(term_count("York"), 1);
+        assert_eq!(term_count("york"), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_document_missing_required_field() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let id_field = schema_builder.add_u64_field(
+            "id",
+            NumericOptions::default()
+                .set_indexed()
+                .set_fast()
+                .set_required(),
+        );
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let schema = schema_builder.build();
+        let schema_json = serde_json::to_string(&schema).unwrap();
+        let deserialized_schema: Schema = serde_json::from_str(&schema_json).unwrap();
+        assert!(deserialized_schema.get_field_entry(id_field).is_required());
+        assert!(!deserialized_schema
+            .get_field_entry(text_field)
+            .is_required());
+
+        let index = Index::create_in_ram(schema);
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(id_field=>1u64, text_field=>"hello"))?;
+        let err = index_writer
+            .add_document(doc!(text_field=>"no id"))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TantivyError::SchemaError(msg)
+                if msg == "Document is missing a value for the required field \"id\""
+        ));
+        // None of the operations of the batch is applied.
+        let operations = vec![
+            UserOperation::Delete(Term::from_field_u64(id_field, 1)),
+            UserOperation::Add(doc!(id_field=>2u64)),
+            UserOperation::Add(doc!(text_field=>"no id")),
+        ];
+        assert!(matches!(
+            index_writer.run(operations),
+            Err(TantivyError::SchemaError(_))
+        ));
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_on_commit_callback() {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests().unwrap();
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        let commits = Arc::new(Mutex::new(Vec::<(Opstamp, Vec<SegmentId>)>::new()));
+        let commits_clone = commits.clone();
+        index_writer.on_commit(move |opstamp, segment_ids| {
+            commits_clone
+                .lock()
+                .unwrap()
+                .push((opstamp, segment_ids.to_vec()));
+        });
+        let mut opstamps = Vec::new();
+        for text in ["a", "b", "c"] {
+            index_writer.add_document(doc!(text_field=>text)).unwrap();
+            opstamps.push(index_writer.commit().unwrap());
+            // The callback is called before commit returns.
+            let commits = commits.lock().unwrap();
+            let (opstamp, segment_ids) = commits.last().unwrap();
+            assert_eq!(*opstamp, *opstamps.last().unwrap());
+            let searchable_segment_ids: HashSet<SegmentId> = index
+                .searchable_segment_ids()
+                .unwrap()
+                .into_iter()
+                .collect();
+            assert_eq!(
+                segment_ids.iter().copied().collect::<HashSet<_>>(),
+                searchable_segment_ids
+            );
+        }
+        let commits = commits.lock().unwrap();
+        assert_eq!(commits.len(), 3);
+        let callback_opstamps: Vec<Opstamp> = commits.iter().map(|(opstamp, _)| *opstamp).collect();
+        assert_eq!(callback_opstamps, opstamps);
+        assert!(callback_opstamps.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(commits[2].1.len(), 3);
+    }
+
     #[test]
     fn test_empty_operations_group() {
         let schema_builder = schema::Schema::builder();
@@ -1162,6 +1806,243 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_rollback_to_token() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text_field => "committed"))?;
+        index_writer.commit()?;
+
+        index_writer.add_document(doc!(text_field => "kept"))?;
+        let token = index_writer.rollback_token();
+        let index_writer = Arc::new(index_writer);
+        index_writer.add_document(doc!(text_field => "discarded"))?;
+        // Documents added concurrently by another thread are discarded too.
+        let thread_index_writer = index_writer.clone();
+        std::thread::spawn(move || {
+            thread_index_writer.add_document(doc!(text_field => "discarded"))
+        })
+        .join()
+        .unwrap()?;
+        index_writer.delete_term(Term::from_field_text(text_field, "committed"));
+        let rollback_opstamp = index_writer.rollback_to(token)?;
+        assert!(rollback_opstamp > token.opstamp());
+        index_writer.add_document(doc!(text_field => "added_after_rollback"))?;
+        let mut index_writer = Arc::into_inner(index_writer).unwrap();
+        index_writer.commit()?;
+
+        reader.reload()?;
+        let searcher = reader.searcher();
+        let count = |text: &str| {
+            let query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::Basic,
+            );
+            searcher.search(&query, &Count).unwrap()
+        };
+        // Delete operations are not reverted.
+        assert_eq!(count("committed"), 0);
+        assert_eq!(count("kept"), 1);
+        assert_eq!(count("discarded"), 0);
+        assert_eq!(count("added_after_rollback"), 1);
+        assert_eq!(searcher.num_docs(), 2);
+
+        // The token is not valid anymore after a commit.
+        assert!(matches!(
+            index_writer.rollback_to(token),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_to_token_flushed_and_merged_segments() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter =
+            index.writer_with_num_threads(1, MEMORY_BUDGET_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        // Documents with many different terms, so that the segments are flushed
+        // before the commit.
+        let add_documents = |index_writer: &IndexWriter, tag: &str| -> crate::Result<()> {
+            for i in 0..10_000 {
+                let text = (0..20).map(|j| format!("{tag}_{i}_{j}")).join(" ");
+                index_writer.add_document(doc!(text_field => format!("{tag} {text}")))?;
+            }
+            Ok(())
+        };
+        add_documents(&index_writer, "kept")?;
+        let token = index_writer.rollback_token();
+        add_documents(&index_writer, "discarded")?;
+        // Merge the uncommitted segments flushed so far.
+        let uncommitted_segment_ids: Vec<SegmentId> = loop {
+            let (_, uncommitted_segments) = index_writer.segment_updater().get_mergeable_segments();
+            if uncommitted_segments.len() >= 2 {
+                break uncommitted_segments
+                    .iter()
+                    .map(|segment_meta| segment_meta.id())
+                    .collect();
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        };
+        assert!(index_writer
+            .merge(&uncommitted_segment_ids)
+            .wait()?
+            .is_some());
+        index_writer.rollback_to(token)?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let count = |text: &str| {
+            let query = TermQuery::new(
+                Term::from_field_text(text_field, text),
+                IndexRecordOption::Basic,
+            );
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("kept"), 10_000);
+        assert_eq!(count("discarded"), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_document_with_routing_key() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let key_field = schema_builder.add_text_field("key", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter =
+            index.writer_with_num_threads(1, 2 * MEMORY_BUDGET_NUM_BYTES_MIN)?;
+        let mut merge_policy = LogMergePolicy::default();
+        merge_policy.set_min_num_segments(2);
+        index_writer.set_merge_policy(Box::new(merge_policy));
+        for _ in 0..3 {
+            for _ in 0..10 {
+                index_writer.add_document_with_routing_key("a", doc!(key_field => "a"))?;
+                index_writer.add_document_with_routing_key("b", doc!(key_field => "b"))?;
+                index_writer.add_document(doc!(key_field => "none"))?;
+            }
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+
+        // The segments are merged, but never across routing keys.
+        let segment_metas = index.searchable_segment_metas()?;
+        let mut routing_keys: Vec<Option<&str>> = segment_metas
+            .iter()
+            .map(|segment_meta| segment_meta.routing_key())
+            .collect();
+        routing_keys.sort();
+        assert_eq!(routing_keys, vec![None, Some("a"), Some("b")]);
+        for segment_meta in &segment_metas {
+            let segment_reader = SegmentReader::open(&index.segment(segment_meta.clone()))?;
+            let inverted_index = segment_reader.inverted_index(key_field)?;
+            let key = segment_meta.routing_key().unwrap_or("none");
+            for other_key in ["a", "b", "none"] {
+                let doc_freq =
+                    inverted_index.doc_freq(&Term::from_field_text(key_field, other_key))?;
+                let expected_doc_freq = if other_key == key { 30 } else { 0 };
+                assert_eq!(doc_freq, expected_doc_freq);
+            }
+        }
+
+        // The routing key is persisted in the index meta.
+        let index = Index::open(index.directory().clone())?;
+        let mut routing_keys: Vec<Option<String>> = index
+            .searchable_segment_metas()?
+            .iter()
+            .map(|segment_meta| segment_meta.routing_key().map(str::to_string))
+            .collect();
+        routing_keys.sort();
+        assert_eq!(
+            routing_keys,
+            vec![None, Some("a".to_string()), Some("b".to_string())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_document_with_many_routing_keys() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let key_field = schema_builder.add_text_field("key", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter =
+            index.writer_with_num_threads(1, MEMORY_BUDGET_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        // Sharing the budget between that many in-flight segments would leave each of them
+        // too little memory: the largest ones get flushed instead.
+        let num_routing_keys = 650;
+        for i in 0..num_routing_keys {
+            let key = format!("key{i}");
+            index_writer.add_document_with_routing_key(&key, doc!(key_field => key.clone()))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), num_routing_keys);
+        assert_eq!(searcher.segment_readers().len(), num_routing_keys as usize);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_memory_budget_per_thread() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter =
+            index.writer_with_num_threads(1, MEMORY_BUDGET_NUM_BYTES_MIN * 10)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        assert_eq!(index_writer.num_threads(), 1);
+        assert_eq!(
+            index_writer.memory_budget_per_thread(),
+            MEMORY_BUDGET_NUM_BYTES_MIN * 10
+        );
+        assert!(matches!(
+            index_writer.set_memory_budget_per_thread(MEMORY_BUDGET_NUM_BYTES_MIN - 1),
+            Err(TantivyError::InvalidArgument(_))
+        ));
+        assert_eq!(
+            index_writer.memory_budget_per_thread(),
+            MEMORY_BUDGET_NUM_BYTES_MIN * 10
+        );
+
+        let add_documents = |index_writer: &IndexWriter, start: usize| {
+            for doc_id in start..start + 25_000 {
+                let text = format!("a{doc_id} b{doc_id} c{doc_id} d{doc_id}");
+                index_writer.add_document(doc!(text_field => text)).unwrap();
+            }
+        };
+        // The indexing thread was started with the large budget, and picks up the smaller one
+        // when it starts its first segment.
+        index_writer.set_memory_budget_per_thread(MEMORY_BUDGET_NUM_BYTES_MIN)?;
+        assert_eq!(
+            index_writer.memory_budget_per_thread(),
+            MEMORY_BUDGET_NUM_BYTES_MIN
+        );
+        add_documents(&index_writer, 0);
+        index_writer.commit()?;
+        let num_segments_small_budget = index.searchable_segment_ids()?.len();
+        assert!(num_segments_small_budget > 2);
+
+        // Raising the budget between two batches, without committing, results in fewer
+        // segments for the second batch.
+        add_documents(&index_writer, 25_000);
+        index_writer.set_memory_budget_per_thread(MEMORY_BUDGET_NUM_BYTES_MIN * 10)?;
+        add_documents(&index_writer, 50_000);
+        index_writer.commit()?;
+        let num_segments_two_batches =
+            index.searchable_segment_ids()?.len() - num_segments_small_budget;
+        assert!(num_segments_two_batches < 2 * num_segments_small_budget);
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 75_000);
+        Ok(())
+    }
+
     #[test]
     fn test_prepare_with_commit_message() -> crate::Result<()> {
         let mut schema_builder = schema::Schema::builder();
@@ -1264,6 +2145,61 @@ mod tests {
         assert_eq!(num_docs_containing("a"), 0);
     }
 
+    #[test]
+    fn test_try_add_document_backpressure() -> crate::Result<()> {
+        let mut schema_builder = schema::Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        assert_eq!(index_writer.indexing_queue_len(), 0);
+        let capacity = index_writer.indexing_queue_capacity();
+        assert_eq!(capacity, super::PIPELINE_MAX_SIZE_IN_DOCS);
+
+        // Indexing these documents is much slower than queuing them: the queue fills up.
+        let mut num_added = 0u64;
+        let mut rejected_doc = None;
+        for i in 0..10 * capacity {
+            let text = format!("{LOREM} {i}");
+            match index_writer.try_add_document(doc!(text_field => text)) {
+                Ok(_) => num_added += 1,
+                Err(TryAddDocumentError::QueueFull(doc)) => {
+                    rejected_doc = Some(doc);
+                    break;
+                }
+                Err(TryAddDocumentError::Error(err)) => return Err(err),
+            }
+        }
+        let rejected_doc = rejected_doc.expect("the indexing queue never filled up");
+        assert!(num_added >= capacity as u64);
+        // The rejected document did not consume an opstamp.
+        assert_eq!(index_writer.stamper.stamp(), num_added);
+        assert!(index_writer.indexing_queue_len() <= capacity);
+
+        // The rejected document is handed back, and can be added once the queue drains.
+        assert_eq!(
+            rejected_doc
+                .get_first(text_field)
+                .and_then(|value| value.as_str()),
+            Some(format!("{LOREM} {num_added}").as_str())
+        );
+        index_writer.add_document(rejected_doc)?;
+        index_writer.commit()?;
+        assert_eq!(index_writer.indexing_queue_len(), 0);
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), num_added + 1);
+        let query = TermQuery::new(
+            Term::from_field_text(text_field, &num_added.to_string()),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+
+        // Once the queue has room, the documents are accepted again.
+        assert!(index_writer
+            .try_add_document(doc!(text_field => "hello"))
+            .is_ok());
+        Ok(())
+    }
+
     #[test]
     fn test_delete_all_documents_rollback_correct_stamp() {
         let mut schema_builder = schema::Schema::builder();
@@ -201,11 +201,17 @@ impl IndexMerger {
         let mut fieldnorms_data = Vec::with_capacity(self.max_doc as usize);
         for field in fields {
             fieldnorms_data.clear();
+            // The segments created before the field was added to the schema have no fieldnorms
+            // for it: their documents do not contain the field.
             let fieldnorms_readers: Vec<FieldNormReader> = self
                 .readers
                 .iter()
-                .map(|reader| reader.get_fieldnorms_reader(field))
-                .collect::<Result<_, _>>()?;
+                .map(|reader| {
+                    let fieldnorms_reader_opt = reader.fieldnorms_readers().get_field(field)?;
+                    Ok(fieldnorms_reader_opt
+                        .unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 0)))
+                })
+                .collect::<crate::Result<_>>()?;
             for old_doc_addr in doc_id_mapping.iter_old_doc_addrs() {
                 let fieldnorms_reader = &fieldnorms_readers[old_doc_addr.segment_ord as usize];
                 let fieldnorm_id = fieldnorms_reader.fieldnorm_id(old_doc_addr.doc_id);
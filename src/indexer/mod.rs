@@ -18,6 +18,7 @@ mod merge_operation;
 pub(crate) mod merge_policy;
 pub(crate) mod merger;
 pub(crate) mod operation;
+mod pinned_segments_merge_policy;
 pub(crate) mod prepared_commit;
 mod segment_entry;
 mod segment_manager;
@@ -26,23 +27,27 @@ pub(crate) mod segment_serializer;
 pub(crate) mod segment_updater;
 pub(crate) mod segment_writer;
 pub(crate) mod single_segment_index_writer;
+mod size_tiered_merge_policy;
 mod stamper;
 
 use crossbeam_channel as channel;
 use smallvec::SmallVec;
 
-pub use self::index_writer::IndexWriter;
+pub use self::index_writer::{IndexWriter, RollbackToken, TryAddDocumentError};
 pub use self::log_merge_policy::LogMergePolicy;
 pub use self::merge_operation::MergeOperation;
 pub use self::merge_policy::{MergeCandidate, MergePolicy, NoMergePolicy};
 use self::operation::AddOperation;
 pub use self::operation::UserOperation;
+pub use self::pinned_segments_merge_policy::PinnedSegmentsMergePolicy;
 pub use self::prepared_commit::PreparedCommit;
 pub use self::segment_entry::SegmentEntry;
 pub(crate) use self::segment_serializer::SegmentSerializer;
 pub use self::segment_updater::{merge_filtered_segments, merge_indices};
 pub use self::segment_writer::SegmentWriter;
 pub use self::single_segment_index_writer::SingleSegmentIndexWriter;
+pub use self::size_tiered_merge_policy::SizeTieredMergePolicy;
+use crate::schema::document::Document;
 
 /// Alias for the default merge policy, which is the `LogMergePolicy`.
 pub type DefaultMergePolicy = LogMergePolicy;
@@ -53,7 +58,21 @@ pub type DefaultMergePolicy = LogMergePolicy;
 // - all docs in the operation will happen on the same segment and continuous doc_ids.
 // - all operations in the group are committed at the same time, making the group
 // atomic.
-type AddBatch<D> = SmallVec<[AddOperation<D>; 4]>;
+pub(crate) struct AddBatch<D: Document> {
+    // Documents with different routing keys are never added to the same segment.
+    routing_key: Option<String>,
+    operations: SmallVec<[AddOperation<D>; 4]>,
+}
+
+impl<D: Document> Default for AddBatch<D> {
+    fn default() -> Self {
+        AddBatch {
+            routing_key: None,
+            operations: SmallVec::new(),
+        }
+    }
+}
+
 type AddBatchSender<D> = channel::Sender<AddBatch<D>>;
 type AddBatchReceiver<D> = channel::Receiver<AddBatch<D>>;
 
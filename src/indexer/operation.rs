@@ -6,6 +6,11 @@ use crate::Opstamp;
 /// Timestamped Delete operation.
 pub struct DeleteOperation {
     pub opstamp: Opstamp,
+    /// Only the documents added at or after this opstamp are deleted.
+    ///
+    /// It is 0 for regular delete operations, and the opstamp of the
+    /// [`RollbackToken`](crate::indexer::RollbackToken) for rollbacks.
+    pub min_doc_opstamp: Opstamp,
     pub target: Box<dyn Weight>,
 }
 
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use super::merge_policy::{MergeCandidate, MergePolicy};
+use crate::index::SegmentMeta;
+
+/// `PinnedSegmentsMergePolicy` wraps another merge policy, and prevents some segments
+/// from ever being merged.
+///
+/// A segment is pinned if the predicate returns `true` for its [`SegmentMeta`]. Pinned
+/// segments are hidden from the wrapped merge policy, so they are never part of a merge
+/// candidate. This is useful to keep sealed segments untouched, e.g. to snapshot them or
+/// move them to cold storage.
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use tantivy::index::SegmentId;
+/// use tantivy::indexer::{LogMergePolicy, PinnedSegmentsMergePolicy};
+///
+/// let sealed_segments: HashSet<SegmentId> = HashSet::new();
+/// let merge_policy = PinnedSegmentsMergePolicy::new(
+///     LogMergePolicy::default(),
+///     move |segment_meta| sealed_segments.contains(&segment_meta.id()),
+/// );
+/// ```
+pub struct PinnedSegmentsMergePolicy<TMergePolicy> {
+    merge_policy: TMergePolicy,
+    is_pinned: Box<dyn Fn(&SegmentMeta) -> bool + Send + Sync>,
+}
+
+impl<TMergePolicy: MergePolicy> PinnedSegmentsMergePolicy<TMergePolicy> {
+    /// Creates a new `PinnedSegmentsMergePolicy`, excluding the segments for which
+    /// `is_pinned` returns `true` from the merge candidates of `merge_policy`.
+    pub fn new<TPredicate>(merge_policy: TMergePolicy, is_pinned: TPredicate) -> Self
+    where TPredicate: Fn(&SegmentMeta) -> bool + Send + Sync + 'static {
+        PinnedSegmentsMergePolicy {
+            merge_policy,
+            is_pinned: Box::new(is_pinned),
+        }
+    }
+}
+
+impl<TMergePolicy: fmt::Debug> fmt::Debug for PinnedSegmentsMergePolicy<TMergePolicy> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PinnedSegmentsMergePolicy")
+            .field("merge_policy", &self.merge_policy)
+            .finish()
+    }
+}
+
+impl<TMergePolicy: MergePolicy> MergePolicy for PinnedSegmentsMergePolicy<TMergePolicy> {
+    fn compute_merge_candidates(&self, segments: &[SegmentMeta]) -> Vec<MergeCandidate> {
+        let unpinned_segments: Vec<SegmentMeta> = segments
+            .iter()
+            .filter(|segment| !(self.is_pinned)(segment))
+            .cloned()
+            .collect();
+        self.merge_policy
+            .compute_merge_candidates(&unpinned_segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::index::{SegmentId, SegmentMetaInventory};
+    use crate::indexer::LogMergePolicy;
+    use crate::schema::{Schema, INDEXED};
+    use crate::{Index, IndexWriter};
+
+    static INVENTORY: Lazy<SegmentMetaInventory> = Lazy::new(SegmentMetaInventory::default);
+
+    fn create_random_segment_meta(num_docs: u32) -> SegmentMeta {
+        INVENTORY.new_segment_meta(SegmentId::generate_random(), num_docs)
+    }
+
+    fn log_merge_policy() -> LogMergePolicy {
+        let mut log_merge_policy = LogMergePolicy::default();
+        log_merge_policy.set_min_num_segments(3);
+        log_merge_policy.set_min_layer_size(2);
+        log_merge_policy
+    }
+
+    #[test]
+    fn test_pinned_segment_is_never_a_merge_candidate() {
+        let test_input = vec![
+            create_random_segment_meta(10),
+            create_random_segment_meta(10),
+            create_random_segment_meta(10),
+            create_random_segment_meta(10),
+        ];
+        assert_eq!(
+            log_merge_policy().compute_merge_candidates(&test_input)[0]
+                .0
+                .len(),
+            4
+        );
+
+        let pinned_segment_id = test_input[1].id();
+        let merge_policy = PinnedSegmentsMergePolicy::new(log_merge_policy(), move |segment| {
+            segment.id() == pinned_segment_id
+        });
+        let merge_candidates = merge_policy.compute_merge_candidates(&test_input);
+        assert_eq!(merge_candidates.len(), 1);
+        assert_eq!(merge_candidates[0].0.len(), 3);
+        assert!(!merge_candidates[0].0.contains(&pinned_segment_id));
+
+        // Without the pinned segment, there are not enough segments to merge anymore.
+        let merge_candidates = merge_policy.compute_merge_candidates(&test_input[..3]);
+        assert!(merge_candidates.is_empty());
+    }
+
+    #[test]
+    fn test_pinned_segments_merge_policy_with_index_writer() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let int_field = schema_builder.add_u64_field("intval", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(int_field=>0u64))?;
+        index_writer.commit()?;
+        let pinned_segment_ids: HashSet<SegmentId> =
+            index.searchable_segment_ids()?.into_iter().collect();
+
+        let mut log_merge_policy = log_merge_policy();
+        log_merge_policy.set_min_num_segments(2);
+        let pinned_segment_ids_clone = pinned_segment_ids.clone();
+        index_writer.set_merge_policy(Box::new(PinnedSegmentsMergePolicy::new(
+            log_merge_policy,
+            move |segment| pinned_segment_ids_clone.contains(&segment.id()),
+        )));
+        for i in 1..4u64 {
+            index_writer.add_document(doc!(int_field=>i))?;
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+
+        let segment_ids: HashSet<SegmentId> = index.searchable_segment_ids()?.into_iter().collect();
+        assert!(pinned_segment_ids.is_subset(&segment_ids));
+        // The three unpinned segments were merged, the pinned one was left alone.
+        assert_eq!(segment_ids.len(), 2);
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 use std::fmt;
+use std::sync::Arc;
 
 use common::BitSet;
 
 use crate::index::{SegmentId, SegmentMeta};
 use crate::indexer::delete_queue::DeleteCursor;
+use crate::Opstamp;
 
 /// A segment entry describes the state of
 /// a given segment, at a given instant.
@@ -13,11 +15,14 @@ use crate::indexer::delete_queue::DeleteCursor;
 /// - `alive_bitset` is a bitset describing documents that were alive during the commit itself.
 /// - `delete_cursor` is the position in the delete queue. Deletes happening before the cursor are
 ///   reflected either in the .del file or in the `alive_bitset`.
+/// - `doc_opstamps` is the opstamp of each of the documents of an uncommitted segment. It is
+///   required to roll back to a [`RollbackToken`](crate::indexer::RollbackToken).
 #[derive(Clone)]
 pub struct SegmentEntry {
     meta: SegmentMeta,
     alive_bitset: Option<BitSet>,
     delete_cursor: DeleteCursor,
+    doc_opstamps: Option<Arc<[Opstamp]>>,
 }
 
 impl SegmentEntry {
@@ -31,9 +36,24 @@ impl SegmentEntry {
             meta: segment_meta,
             alive_bitset,
             delete_cursor,
+            doc_opstamps: None,
         }
     }
 
+    /// Sets the opstamps of the documents of the segment, indexed by `DocId`.
+    pub(crate) fn with_doc_opstamps(
+        mut self,
+        doc_opstamps: Option<Arc<[Opstamp]>>,
+    ) -> SegmentEntry {
+        self.doc_opstamps = doc_opstamps;
+        self
+    }
+
+    /// Returns the opstamps of the documents of the segment, if the segment is uncommitted.
+    pub(crate) fn doc_opstamps(&self) -> Option<&Arc<[Opstamp]>> {
+        self.doc_opstamps.as_ref()
+    }
+
     /// Return a reference to the segment entry deleted bitset.
     ///
     /// `DocId` in this bitset are flagged as deleted.
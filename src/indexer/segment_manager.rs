@@ -139,7 +139,10 @@ impl SegmentManager {
         registers_lock.committed.clear();
         registers_lock.uncommitted.clear();
         for segment_entry in segment_entries {
-            registers_lock.committed.add_segment_entry(segment_entry);
+            // The opstamps of the documents are not needed anymore once they are committed.
+            registers_lock
+                .committed
+                .add_segment_entry(segment_entry.with_doc_opstamps(None));
         }
     }
 
@@ -1,5 +1,5 @@
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::io::Write;
 use std::ops::Deref;
 use std::path::PathBuf;
@@ -81,6 +81,18 @@ fn garbage_collect_files(
         .garbage_collect(move || segment_updater.list_files())
 }
 
+/// Splits the segments into groups of segments sharing the same routing key.
+fn group_by_routing_key(segments: Vec<SegmentMeta>) -> Vec<Vec<SegmentMeta>> {
+    let mut groups: BTreeMap<Option<String>, Vec<SegmentMeta>> = BTreeMap::new();
+    for segment in segments {
+        groups
+            .entry(segment.routing_key().map(str::to_string))
+            .or_default()
+            .push(segment);
+    }
+    groups.into_values().collect()
+}
+
 /// Merges a list of segments the list of segment givens in the `segment_entries`.
 /// This function happens in the calling thread and is computationally expensive.
 fn merge(
@@ -120,10 +132,49 @@ fn merge(
 
     let num_docs = merger.write(segment_serializer)?;
 
+    // The documents of a merge of uncommitted segments keep their opstamps, so that they can
+    // still be rolled back.
+    let doc_opstamps: Option<Arc<[Opstamp]>> = if segment_entries
+        .iter()
+        .all(|segment_entry| segment_entry.doc_opstamps().is_some())
+    {
+        // The merger ignores the empty segments.
+        let segment_doc_opstamps: Vec<&Arc<[Opstamp]>> = segment_entries
+            .iter()
+            .filter(|segment_entry| segment_entry.meta().num_docs() > 0)
+            .flat_map(SegmentEntry::doc_opstamps)
+            .collect();
+        let doc_id_mapping = merger.get_doc_id_from_concatenated_data()?;
+        Some(
+            doc_id_mapping
+                .iter_old_doc_addrs()
+                .map(|doc_addr| {
+                    segment_doc_opstamps[doc_addr.segment_ord as usize][doc_addr.doc_id as usize]
+                })
+                .collect(),
+        )
+    } else {
+        None
+    };
+
     let merged_segment_id = merged_segment.id();
 
-    let segment_meta = index.new_segment_meta(merged_segment_id, num_docs);
-    Ok(Some(SegmentEntry::new(segment_meta, delete_cursor, None)))
+    // The merged segment keeps the routing key shared by all of its segments.
+    let routing_key = segments[0].meta().routing_key();
+    let routing_key = if segments[1..]
+        .iter()
+        .all(|segment| segment.meta().routing_key() == routing_key)
+    {
+        routing_key.map(str::to_string)
+    } else {
+        None
+    };
+    let segment_meta = index
+        .new_segment_meta(merged_segment_id, num_docs)
+        .with_routing_key(routing_key);
+    Ok(Some(
+        SegmentEntry::new(segment_meta, delete_cursor, None).with_doc_opstamps(doc_opstamps),
+    ))
 }
 
 /// Advanced: Merges a list of segments from different indices in a new index.
@@ -239,6 +290,7 @@ pub fn merge_filtered_segments<T: Into<Box<dyn Directory>>>(
         index_settings: target_settings, // index_settings of all segments should be the same
         segments: vec![segment_meta],
         schema: target_schema,
+        schema_version: 0,
         opstamp: 0u64,
         payload: Some(stats),
     };
@@ -266,8 +318,13 @@ pub(crate) struct InnerSegmentUpdater {
     killed: AtomicBool,
     stamper: Stamper,
     merge_operations: MergeOperationInventory,
+    commit_callbacks: RwLock<Vec<Arc<CommitCallback>>>,
 }
 
+/// Callback called after a commit, with the opstamp of the commit and the ids of the
+/// committed segments.
+pub(crate) type CommitCallback = dyn Fn(Opstamp, &[SegmentId]) + Send + Sync;
+
 impl SegmentUpdater {
     pub fn create(
         index: Index,
@@ -305,6 +362,7 @@ impl SegmentUpdater {
             killed: AtomicBool::new(false),
             stamper,
             merge_operations: Default::default(),
+            commit_callbacks: Default::default(),
         })))
     }
 
@@ -317,6 +375,26 @@ impl SegmentUpdater {
         *self.merge_policy.write().unwrap() = arc_merge_policy;
     }
 
+    pub fn add_commit_callback(&self, commit_callback: Arc<CommitCallback>) {
+        self.commit_callbacks.write().unwrap().push(commit_callback);
+    }
+
+    fn notify_commit(&self, opstamp: Opstamp) {
+        let commit_callbacks = self.commit_callbacks.read().unwrap();
+        if commit_callbacks.is_empty() {
+            return;
+        }
+        let segment_ids: Vec<SegmentId> = self
+            .load_meta()
+            .segments
+            .iter()
+            .map(SegmentMeta::id)
+            .collect();
+        for commit_callback in commit_callbacks.iter() {
+            commit_callback(opstamp, &segment_ids);
+        }
+    }
+
     fn schedule_task<T: 'static + Send, F: FnOnce() -> crate::Result<T> + 'static + Send>(
         &self,
         task: F,
@@ -397,6 +475,7 @@ impl SegmentUpdater {
                 index_settings: index.settings().clone(),
                 segments: committed_segment_metas,
                 schema: index.schema(),
+                schema_version: index.schema_version(),
                 opstamp,
                 payload: commit_message,
             };
@@ -437,6 +516,9 @@ impl SegmentUpdater {
             let segment_entries = segment_updater.purge_deletes(opstamp)?;
             segment_updater.segment_manager.commit(segment_entries);
             segment_updater.save_metas(opstamp, payload)?;
+            if segment_updater.is_alive() {
+                segment_updater.notify_commit(opstamp);
+            }
             let _ = garbage_collect_files(segment_updater.clone());
             segment_updater.consider_merge_options();
             Ok(opstamp)
@@ -447,7 +529,7 @@ impl SegmentUpdater {
         *self.active_index_meta.write().unwrap() = Arc::new(index_meta.clone());
     }
 
-    fn load_meta(&self) -> Arc<IndexMeta> {
+    pub(crate) fn load_meta(&self) -> Arc<IndexMeta> {
         self.active_index_meta.read().unwrap().clone()
     }
 
@@ -540,35 +622,33 @@ impl SegmentUpdater {
     }
 
     fn consider_merge_options(&self) {
-        let (mut committed_segments, mut uncommitted_segments) = self.get_mergeable_segments();
-        if committed_segments.len() == 1 && committed_segments[0].num_deleted_docs() == 0 {
-            committed_segments.clear();
-        }
-        if uncommitted_segments.len() == 1 && uncommitted_segments[0].num_deleted_docs() == 0 {
-            uncommitted_segments.clear();
-        }
+        let (committed_segments, uncommitted_segments) = self.get_mergeable_segments();
 
-        // Committed segments cannot be merged with uncommitted_segments.
-        // We therefore consider merges using these two sets of segments independently.
+        // Committed segments cannot be merged with uncommitted_segments, and segments
+        // with different routing keys cannot be merged together either.
+        // We therefore consider merges using each of these sets of segments independently.
         let merge_policy = self.get_merge_policy();
 
         let current_opstamp = self.stamper.stamp();
-        let mut merge_candidates: Vec<MergeOperation> = merge_policy
-            .compute_merge_candidates(&uncommitted_segments)
-            .into_iter()
-            .map(|merge_candidate| {
-                MergeOperation::new(&self.merge_operations, current_opstamp, merge_candidate.0)
-            })
-            .collect();
-
         let commit_opstamp = self.load_meta().opstamp;
-        let committed_merge_candidates = merge_policy
-            .compute_merge_candidates(&committed_segments)
-            .into_iter()
-            .map(|merge_candidate: MergeCandidate| {
-                MergeOperation::new(&self.merge_operations, commit_opstamp, merge_candidate.0)
-            });
-        merge_candidates.extend(committed_merge_candidates);
+        let mut merge_candidates: Vec<MergeOperation> = Vec::new();
+        for (segments, opstamp) in [
+            (uncommitted_segments, current_opstamp),
+            (committed_segments, commit_opstamp),
+        ] {
+            for segments in group_by_routing_key(segments) {
+                if segments.len() == 1 && segments[0].num_deleted_docs() == 0 {
+                    continue;
+                }
+                let group_merge_candidates = merge_policy
+                    .compute_merge_candidates(&segments)
+                    .into_iter()
+                    .map(|merge_candidate: MergeCandidate| {
+                        MergeOperation::new(&self.merge_operations, opstamp, merge_candidate.0)
+                    });
+                merge_candidates.extend(group_merge_candidates);
+            }
+        }
 
         for merge_operation in merge_candidates {
             // If a merge cannot be started this is not a fatal error.
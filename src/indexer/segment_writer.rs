@@ -467,6 +467,7 @@ mod tests {
                 position: 0,
                 text: String::from("A"),
                 position_length: 1,
+                original_text: None,
             }],
         };
 
@@ -914,6 +915,7 @@ mod tests {
                 position: 0,
                 text: "rollercoaster".to_string(),
                 position_length: 2,
+                original_text: None,
             }],
         };
         doc.add_pre_tokenized_text(text, tokens.clone());
@@ -954,6 +956,7 @@ mod tests {
                     position: 0,
                     text: "long_token".to_string(),
                     position_length: 3,
+                    original_text: None,
                 },
                 Token {
                     offset_from: 0,
@@ -961,6 +964,7 @@ mod tests {
                     position: 1,
                     text: "short".to_string(),
                     position_length: 1,
+                    original_text: None,
                 },
             ],
         };
@@ -46,6 +46,7 @@ impl<D: Document> SingleSegmentIndexWriter<D> {
             index_settings: index.settings().clone(),
             segments: vec![segment.meta().clone()],
             schema: index.schema(),
+            schema_version: index.schema_version(),
             opstamp: 0,
             payload: None,
         };
@@ -0,0 +1,232 @@
+use std::cmp;
+
+use itertools::Itertools;
+
+use super::merge_policy::{MergeCandidate, MergePolicy};
+use crate::index::SegmentMeta;
+
+const DEFAULT_TIER_RATIO: f64 = 2.0;
+const DEFAULT_MIN_SEGMENT_SIZE: u32 = 10_000;
+const DEFAULT_MIN_SEGMENTS_PER_TIER: usize = 4;
+const DEFAULT_MAX_SEGMENTS_PER_TIER: usize = 10;
+
+/// `SizeTieredMergePolicy` groups segments of similar sizes into tiers, and merges
+/// the segments of a tier together once the tier holds enough of them.
+///
+/// Segments are sorted by size. A tier starts with its smallest segment, and contains all of
+/// the following segments that are at most `tier_ratio` times larger than it. Segments smaller
+/// than the minimum segment size all belong to the first tier.
+///
+/// A large segment is hence only rewritten when other segments of a similar size exist,
+/// which avoids repeatedly merging small segments into a large base segment in append-heavy
+/// workloads.
+///
+/// The size of a segment is measured as its number of alive documents, as segment metas
+/// do not record the number of bytes of a segment.
+#[derive(Debug, Clone)]
+pub struct SizeTieredMergePolicy {
+    tier_ratio: f64,
+    min_segment_size: u32,
+    min_segments_per_tier: usize,
+    max_segments_per_tier: usize,
+}
+
+impl SizeTieredMergePolicy {
+    /// Set the ratio between the size of the largest and the smallest
+    /// segment of a tier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tier_ratio` is lower than 1.
+    pub fn set_tier_ratio(&mut self, tier_ratio: f64) {
+        assert!(tier_ratio >= 1.0, "tier_ratio should be at least 1.");
+        self.tier_ratio = tier_ratio;
+    }
+
+    /// Set the segment size under which all segments belong to the same tier.
+    pub fn set_min_segment_size(&mut self, min_segment_size: u32) {
+        self.min_segment_size = min_segment_size;
+    }
+
+    /// Set the minimum number of segments a tier should hold to be merged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_segments_per_tier` is lower than 2.
+    pub fn set_min_segments_per_tier(&mut self, min_segments_per_tier: usize) {
+        assert!(
+            min_segments_per_tier >= 2,
+            "min_segments_per_tier should be at least 2."
+        );
+        self.min_segments_per_tier = min_segments_per_tier;
+    }
+
+    /// Set the maximum number of segments merged together.
+    ///
+    /// Tiers holding more segments are split into several merge candidates,
+    /// starting with the smallest segments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_segments_per_tier` is lower than 2.
+    pub fn set_max_segments_per_tier(&mut self, max_segments_per_tier: usize) {
+        assert!(
+            max_segments_per_tier >= 2,
+            "max_segments_per_tier should be at least 2."
+        );
+        self.max_segments_per_tier = max_segments_per_tier;
+    }
+
+    fn segment_size(&self, segment: &SegmentMeta) -> u32 {
+        cmp::max(self.min_segment_size, segment.num_docs())
+    }
+}
+
+impl MergePolicy for SizeTieredMergePolicy {
+    fn compute_merge_candidates(&self, segments: &[SegmentMeta]) -> Vec<MergeCandidate> {
+        let size_sorted_segments = segments
+            .iter()
+            .sorted_by_key(|segment| self.segment_size(segment))
+            .collect::<Vec<&SegmentMeta>>();
+
+        let mut tiers: Vec<Vec<&SegmentMeta>> = Vec::new();
+        let mut tier_max_size = 0f64;
+        for segment in size_sorted_segments {
+            let segment_size = f64::from(self.segment_size(segment));
+            match tiers.last_mut() {
+                Some(tier) if segment_size <= tier_max_size => tier.push(segment),
+                _ => {
+                    tier_max_size = segment_size * self.tier_ratio;
+                    tiers.push(vec![segment]);
+                }
+            }
+        }
+
+        // Candidates shorter than `min_segments_per_tier` can only be the last chunk
+        // of a tier: they are left for later merges.
+        let max_segments_per_tier =
+            cmp::max(self.max_segments_per_tier, self.min_segments_per_tier);
+        tiers
+            .iter()
+            .flat_map(|tier| tier.chunks(max_segments_per_tier))
+            .filter(|segments| segments.len() >= self.min_segments_per_tier)
+            .map(|segments| MergeCandidate(segments.iter().map(|segment| segment.id()).collect()))
+            .collect()
+    }
+}
+
+impl Default for SizeTieredMergePolicy {
+    fn default() -> SizeTieredMergePolicy {
+        SizeTieredMergePolicy {
+            tier_ratio: DEFAULT_TIER_RATIO,
+            min_segment_size: DEFAULT_MIN_SEGMENT_SIZE,
+            min_segments_per_tier: DEFAULT_MIN_SEGMENTS_PER_TIER,
+            max_segments_per_tier: DEFAULT_MAX_SEGMENTS_PER_TIER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::index::{SegmentId, SegmentMetaInventory};
+
+    static INVENTORY: Lazy<SegmentMetaInventory> = Lazy::new(SegmentMetaInventory::default);
+
+    fn create_random_segment_meta(num_docs: u32) -> SegmentMeta {
+        INVENTORY.new_segment_meta(SegmentId::generate_random(), num_docs)
+    }
+
+    fn test_merge_policy() -> SizeTieredMergePolicy {
+        let mut merge_policy = SizeTieredMergePolicy::default();
+        merge_policy.set_tier_ratio(2.0);
+        merge_policy.set_min_segment_size(1_000);
+        merge_policy.set_min_segments_per_tier(3);
+        merge_policy
+    }
+
+    fn candidate_ids(candidate: &MergeCandidate) -> Vec<SegmentId> {
+        candidate.0.iter().copied().sorted().collect()
+    }
+
+    fn segment_ids(segments: &[&SegmentMeta]) -> Vec<SegmentId> {
+        segments
+            .iter()
+            .map(|segment| segment.id())
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn test_size_tiered_merge_policy_empty() {
+        assert!(test_merge_policy().compute_merge_candidates(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_size_tiered_merge_policy_tiers() {
+        let test_input = vec![
+            create_random_segment_meta(100_000),
+            create_random_segment_meta(6_000),
+            create_random_segment_meta(100),
+            create_random_segment_meta(5_000),
+            create_random_segment_meta(900),
+            create_random_segment_meta(200),
+            create_random_segment_meta(7_000),
+            create_random_segment_meta(20_000),
+        ];
+        let merge_candidates = test_merge_policy().compute_merge_candidates(&test_input);
+        assert_eq!(merge_candidates.len(), 2);
+        // Segments under the minimum segment size belong to the same tier.
+        assert_eq!(
+            candidate_ids(&merge_candidates[0]),
+            segment_ids(&[&test_input[2], &test_input[4], &test_input[5]])
+        );
+        // 20_000 is more than twice as large as 5_000, and the 100_000 docs segment
+        // has no peer: neither of them is merged.
+        assert_eq!(
+            candidate_ids(&merge_candidates[1]),
+            segment_ids(&[&test_input[1], &test_input[3], &test_input[6]])
+        );
+    }
+
+    #[test]
+    fn test_size_tiered_merge_policy_not_enough_segments() {
+        let test_input = vec![
+            create_random_segment_meta(10_000),
+            create_random_segment_meta(12_000),
+            create_random_segment_meta(50_000),
+            create_random_segment_meta(60_000),
+        ];
+        assert!(test_merge_policy()
+            .compute_merge_candidates(&test_input)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_size_tiered_merge_policy_max_segments_per_tier() {
+        let mut merge_policy = test_merge_policy();
+        merge_policy.set_min_segments_per_tier(2);
+        merge_policy.set_max_segments_per_tier(3);
+        let test_input: Vec<SegmentMeta> =
+            (0..7).map(|i| create_random_segment_meta(10 + i)).collect();
+        let merge_candidates = merge_policy.compute_merge_candidates(&test_input);
+        // The last segment does not make it into a candidate of its own.
+        assert_eq!(merge_candidates.len(), 2);
+        assert_eq!(merge_candidates[0].0.len(), 3);
+        assert_eq!(merge_candidates[1].0.len(), 3);
+    }
+
+    #[test]
+    fn test_size_tiered_merge_policy_ignores_deleted_docs() {
+        let test_input = vec![
+            create_random_segment_meta(40_000).with_delete_meta(30_000, 1),
+            create_random_segment_meta(10_000),
+            create_random_segment_meta(12_000),
+        ];
+        let merge_candidates = test_merge_policy().compute_merge_candidates(&test_input);
+        assert_eq!(merge_candidates.len(), 1);
+        assert_eq!(merge_candidates[0].0.len(), 3);
+    }
+}
@@ -218,7 +218,7 @@ use serde::{Deserialize, Serialize};
 pub use self::docset::{DocSet, COLLECT_BLOCK_BUFFER_LEN, TERMINATED};
 #[doc(hidden)]
 pub use crate::core::json_utils;
-pub use crate::core::{Executor, Searcher, SearcherGeneration};
+pub use crate::core::{Executor, Searcher, SearcherGeneration, TermVector, TermVectorEntry};
 pub use crate::directory::Directory;
 pub use crate::index::{
     Index, IndexBuilder, IndexMeta, IndexSettings, InvertedIndexReader, Order, Segment,
@@ -378,8 +378,9 @@ pub mod tests {
     use time::OffsetDateTime;
 
     use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::directory::{Directory, RamDirectory};
     use crate::docset::{DocSet, TERMINATED};
-    use crate::index::SegmentReader;
+    use crate::index::{SegmentComponent, SegmentReader};
     use crate::merge_policy::NoMergePolicy;
     use crate::postings::Postings;
     use crate::query::BooleanQuery;
@@ -979,6 +980,7 @@ pub mod tests {
                 delimiter: crate::query_grammar::Delimiter::None,
                 slop: 0,
                 prefix: false,
+                fuzzy: None,
             };
             assert_eq!(get_doc_ids(user_input_literal), vec![DocAddress::new(0, 0)]);
         }
@@ -989,6 +991,7 @@ pub mod tests {
                 delimiter: crate::query_grammar::Delimiter::None,
                 slop: 0,
                 prefix: false,
+                fuzzy: None,
             };
             assert_eq!(get_doc_ids(user_input_literal), vec![DocAddress::new(0, 0)]);
         }
@@ -999,6 +1002,7 @@ pub mod tests {
                 delimiter: crate::query_grammar::Delimiter::None,
                 slop: 0,
                 prefix: false,
+                fuzzy: None,
             };
             assert_eq!(get_doc_ids(user_input_literal), vec![DocAddress::new(0, 0)]);
         }
@@ -1009,6 +1013,7 @@ pub mod tests {
                 delimiter: crate::query_grammar::Delimiter::None,
                 slop: 0,
                 prefix: false,
+                fuzzy: None,
             };
             assert_eq!(get_doc_ids(user_input_literal), vec![DocAddress::new(0, 0)]);
         }
@@ -1019,6 +1024,7 @@ pub mod tests {
                 delimiter: crate::query_grammar::Delimiter::None,
                 slop: 0,
                 prefix: false,
+                fuzzy: None,
             };
             assert_eq!(get_doc_ids(user_input_literal), vec![DocAddress::new(0, 0)]);
         }
@@ -1193,6 +1199,47 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_verify_integrity() -> crate::Result<()> {
+        let ram_directory = RamDirectory::create();
+        let mut builder = Schema::builder();
+        let body = builder.add_text_field("body", TEXT | STORED);
+        let index = Index::create(ram_directory.clone(), builder.build(), Default::default())?;
+        let mut writer: IndexWriter = index.writer_for_tests()?;
+        writer.set_merge_policy(Box::new(NoMergePolicy));
+        writer.add_document(doc!(body => "foo"))?;
+        writer.add_document(doc!(body => "bar"))?;
+        writer.commit()?;
+        writer.add_document(doc!(body => "boo"))?;
+        writer.delete_term(Term::from_field_text(body, "foo"));
+        writer.commit()?;
+        assert!(index.verify_integrity()?.is_empty());
+
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 2);
+        let store_path = segment_metas[0].relative_path(SegmentComponent::Store);
+        let terms_path = segment_metas[1].relative_path(SegmentComponent::Terms);
+        for path in [&store_path, &terms_path] {
+            let file_slice = ram_directory.open_read(path)?;
+            let mut bytes = file_slice.read_bytes()?.as_slice().to_vec();
+            bytes[0] ^= 0xff;
+            ram_directory.atomic_write(path, &bytes)?;
+        }
+        // A file too short to hold a footer is corrupted as well.
+        let alive_path = segment_metas
+            .iter()
+            .find(|segment_meta| segment_meta.has_deletes())
+            .unwrap()
+            .relative_path(SegmentComponent::Delete);
+        ram_directory.atomic_write(&alive_path, b"ab")?;
+
+        let mut expected = vec![store_path, terms_path, alive_path];
+        expected.sort();
+        let reopened_index = Index::open(ram_directory)?;
+        assert_eq!(reopened_index.verify_integrity()?, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_datetime() {
         let now = OffsetDateTime::now_utc();
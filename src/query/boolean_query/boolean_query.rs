@@ -131,6 +131,39 @@ use crate::schema::{IndexRecordOption, Term};
 pub struct BooleanQuery {
     subqueries: Vec<(Occur, Box<dyn Query>)>,
     minimum_number_should_match: usize,
+    pure_negation_matches_all: bool,
+}
+
+/// Minimum number of `Should` clauses a document has to match to be returned by a
+/// [`BooleanQuery`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinimumShouldMatch {
+    /// An absolute number of `Should` clauses.
+    Count(usize),
+    /// A percentage, between 0 and 100, of the `Should` clauses of the query.
+    ///
+    /// The resulting number of clauses is rounded down.
+    Percentage(f32),
+}
+
+impl MinimumShouldMatch {
+    /// Returns the minimum number of `Should` clauses that should match, given
+    /// the number of `Should` clauses in the query.
+    pub fn num_clauses(self, num_should_clauses: usize) -> usize {
+        match self {
+            MinimumShouldMatch::Count(count) => count,
+            MinimumShouldMatch::Percentage(percentage) => {
+                let ratio = (percentage / 100.0).clamp(0.0, 1.0);
+                (num_should_clauses as f32 * ratio).floor() as usize
+            }
+        }
+    }
+}
+
+impl From<usize> for MinimumShouldMatch {
+    fn from(count: usize) -> MinimumShouldMatch {
+        MinimumShouldMatch::Count(count)
+    }
 }
 
 impl Clone for BooleanQuery {
@@ -143,6 +176,7 @@ impl Clone for BooleanQuery {
         Self {
             subqueries,
             minimum_number_should_match: self.minimum_number_should_match,
+            pure_negation_matches_all: self.pure_negation_matches_all,
         }
     }
 }
@@ -160,12 +194,15 @@ impl Query for BooleanQuery {
             .iter()
             .map(|(occur, subquery)| Ok((*occur, subquery.weight(enable_scoring)?)))
             .collect::<crate::Result<_>>()?;
-        Ok(Box::new(BooleanWeight::with_minimum_number_should_match(
-            sub_weights,
-            self.minimum_number_should_match,
-            enable_scoring.is_scoring_enabled(),
-            Box::new(SumCombiner::default),
-        )))
+        Ok(Box::new(
+            BooleanWeight::with_minimum_number_should_match(
+                sub_weights,
+                self.minimum_number_should_match,
+                enable_scoring.is_scoring_enabled(),
+                Box::new(SumCombiner::default),
+            )
+            .with_pure_negation_matches_all(self.pure_negation_matches_all),
+        ))
     }
 
     fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
@@ -202,9 +239,41 @@ impl BooleanQuery {
         BooleanQuery {
             subqueries,
             minimum_number_should_match,
+            pure_negation_matches_all: false,
         }
     }
 
+    /// Create a new boolean query requiring at least the given number, or percentage, of its
+    /// `Should` clauses to match.
+    ///
+    /// Documents that do not reach the threshold are skipped by the scorer.
+    /// `MinimumShouldMatch::Count(0)` keeps the plain disjunction behavior, while requiring
+    /// as many clauses as there are `Should` clauses makes them behave as an intersection.
+    pub fn with_minimum_should_match(
+        subqueries: Vec<(Occur, Box<dyn Query>)>,
+        minimum_should_match: impl Into<MinimumShouldMatch>,
+    ) -> BooleanQuery {
+        let mut boolean_query = BooleanQuery::with_minimum_required_clauses(subqueries, 0);
+        boolean_query.set_minimum_should_match(minimum_should_match);
+        boolean_query
+    }
+
+    /// Sets the minimum number, or percentage, of `Should` clauses that have to match.
+    ///
+    /// A percentage is resolved against the current `Should` clauses of the query.
+    pub fn set_minimum_should_match(
+        &mut self,
+        minimum_should_match: impl Into<MinimumShouldMatch>,
+    ) {
+        let num_should_clauses = self
+            .subqueries
+            .iter()
+            .filter(|(occur, _)| *occur == Occur::Should)
+            .count();
+        self.minimum_number_should_match =
+            minimum_should_match.into().num_clauses(num_should_clauses);
+    }
+
     /// Getter for `minimum_number_should_match`
     pub fn get_minimum_number_should_match(&self) -> usize {
         self.minimum_number_should_match
@@ -215,6 +284,22 @@ impl BooleanQuery {
         self.minimum_number_should_match = minimum_number_should_match;
     }
 
+    /// Sets whether a query with only `MustNot` clauses matches all of the documents that
+    /// are not excluded.
+    ///
+    /// By default, such a query matches no document, as it has no positive clause. If set,
+    /// the `MustNot` clauses are applied to an implicit [`AllQuery`](crate::query::AllQuery),
+    /// and all of the matching documents get the score 1.0.
+    pub fn set_pure_negation_matches_all(&mut self, pure_negation_matches_all: bool) {
+        self.pure_negation_matches_all = pure_negation_matches_all;
+    }
+
+    /// Returns whether a query with only `MustNot` clauses matches all of the documents that
+    /// are not excluded.
+    pub fn pure_negation_matches_all(&self) -> bool {
+        self.pure_negation_matches_all
+    }
+
     /// Returns the intersection of the queries.
     pub fn intersection(queries: Vec<Box<dyn Query>>) -> BooleanQuery {
         let subqueries = queries.into_iter().map(|s| (Occur::Must, s)).collect();
@@ -263,9 +348,9 @@ impl BooleanQuery {
 mod tests {
     use std::collections::HashSet;
 
-    use super::BooleanQuery;
-    use crate::collector::{Count, DocSetCollector};
-    use crate::query::{Query, QueryClone, QueryParser, TermQuery};
+    use super::{BooleanQuery, MinimumShouldMatch};
+    use crate::collector::{Count, DocSetCollector, TopDocs};
+    use crate::query::{Occur, Query, QueryClone, QueryParser, QueryParserError, TermQuery};
     use crate::schema::{Field, IndexRecordOption, Schema, TEXT};
     use crate::{DocAddress, DocId, Index, Term};
 
@@ -350,6 +435,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_minimum_should_match() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer_for_tests()?;
+        for doc in ["a b c d", "a b c", "a b", "a", "e"] {
+            writer.add_document(doc!(text => doc))?;
+        }
+        writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let should_clauses = || -> Vec<(Occur, Box<dyn Query>)> {
+            ["a", "b", "c", "d"]
+                .into_iter()
+                .map(|word| -> (Occur, Box<dyn Query>) {
+                    let term = Term::from_field_text(text, word);
+                    let query = TermQuery::new(term, IndexRecordOption::Basic);
+                    (Occur::Should, Box::new(query))
+                })
+                .collect()
+        };
+        let matching_docs = |query: &BooleanQuery| -> crate::Result<Vec<DocId>> {
+            let mut doc_ids: Vec<DocId> = searcher
+                .search(query, &DocSetCollector)?
+                .into_iter()
+                .map(|doc_address| doc_address.doc_id)
+                .collect();
+            doc_ids.sort();
+            Ok(doc_ids)
+        };
+        // A threshold of 0 is the plain disjunction.
+        let query = BooleanQuery::with_minimum_should_match(should_clauses(), 0);
+        assert_eq!(matching_docs(&query)?, vec![0, 1, 2, 3]);
+        assert_eq!(
+            matching_docs(&query)?,
+            matching_docs(&BooleanQuery::new(should_clauses()))?
+        );
+        // Requiring all should clauses behaves like an intersection.
+        let query = BooleanQuery::with_minimum_should_match(should_clauses(), 4);
+        assert_eq!(matching_docs(&query)?, vec![0]);
+        // 50% of 4 clauses.
+        let query = BooleanQuery::with_minimum_should_match(
+            should_clauses(),
+            MinimumShouldMatch::Percentage(50.0),
+        );
+        assert_eq!(query.get_minimum_number_should_match(), 2);
+        assert_eq!(matching_docs(&query)?, vec![0, 1, 2]);
+        // 80% of 4 clauses is rounded down to 3.
+        let mut query = BooleanQuery::new(should_clauses());
+        query.set_minimum_should_match(MinimumShouldMatch::Percentage(80.0));
+        assert_eq!(query.get_minimum_number_should_match(), 3);
+        assert_eq!(matching_docs(&query)?, vec![0, 1]);
+        Ok(())
+    }
+
     #[test]
     fn test_union() -> crate::Result<()> {
         let index = create_test_index()?;
@@ -410,6 +550,73 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pure_negation() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let searcher = index.reader()?.searcher();
+        let text = index.schema().get_field("text").unwrap();
+        let term_query = |text_value: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(text, text_value),
+                IndexRecordOption::Basic,
+            ))
+        };
+        let matching_docs = |query: &BooleanQuery| -> crate::Result<Vec<DocId>> {
+            let mut docs: Vec<DocId> = searcher
+                .search(query, &DocSetCollector)?
+                .into_iter()
+                .map(|doc_address| doc_address.doc_id)
+                .collect();
+            docs.sort_unstable();
+            Ok(docs)
+        };
+        let mut not_a = BooleanQuery::new(vec![(Occur::MustNot, term_query("a"))]);
+        let mut not_b_not_d = BooleanQuery::new(vec![
+            (Occur::MustNot, term_query("b")),
+            (Occur::MustNot, term_query("d")),
+        ]);
+        // By default, a pure negation matches nothing.
+        assert!(!not_a.pure_negation_matches_all());
+        assert!(matching_docs(&not_a)?.is_empty());
+        assert!(matching_docs(&not_b_not_d)?.is_empty());
+
+        not_a.set_pure_negation_matches_all(true);
+        not_b_not_d.set_pure_negation_matches_all(true);
+        assert_eq!(matching_docs(&not_a)?, vec![0]);
+        assert_eq!(matching_docs(&not_b_not_d)?, vec![1]);
+        assert_eq!(searcher.search(&not_b_not_d.clone(), &Count)?, 1);
+        let top_docs = searcher.search(&not_a, &TopDocs::with_limit(10))?;
+        assert_eq!(top_docs, vec![(1.0, DocAddress::new(0, 0))]);
+
+        // A query with a positive clause is unaffected.
+        let mut a_not_b = BooleanQuery::new(vec![
+            (Occur::Must, term_query("a")),
+            (Occur::MustNot, term_query("b")),
+        ]);
+        a_not_b.set_pure_negation_matches_all(true);
+        assert_eq!(matching_docs(&a_not_b)?, vec![1, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_parser_pure_negation() -> crate::Result<()> {
+        let index = create_test_index()?;
+        let searcher = index.reader()?.searcher();
+        let text = index.schema().get_field("text").unwrap();
+        let mut query_parser = QueryParser::for_index(&index, vec![text]);
+        assert!(matches!(
+            query_parser.parse_query("-a"),
+            Err(QueryParserError::AllButQueryForbidden)
+        ));
+        query_parser.set_allow_pure_negation(true);
+        let query = query_parser.parse_query("-a")?;
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        let query = query_parser.parse_query("-text:b -text:c")?;
+        let docs = searcher.search(&query, &DocSetCollector)?;
+        assert_eq!(docs, [DocAddress::new(0, 3)].into_iter().collect());
+        Ok(())
+    }
+
     #[test]
     pub fn test_json_array_pitfall_bag_of_terms() -> crate::Result<()> {
         let mut schema_builder = Schema::builder();
@@ -9,7 +9,7 @@ use crate::query::score_combiner::{DoNothingCombiner, ScoreCombiner};
 use crate::query::term_query::TermScorer;
 use crate::query::weight::{for_each_docset_buffered, for_each_pruning_scorer, for_each_scorer};
 use crate::query::{
-    intersect_scorers, BufferedUnionScorer, EmptyScorer, Exclude, Explanation, Occur,
+    intersect_scorers, AllWeight, BufferedUnionScorer, EmptyScorer, Exclude, Explanation, Occur,
     RequiredOptionalScorer, Scorer, Weight,
 };
 use crate::{DocId, Score};
@@ -96,6 +96,7 @@ pub struct BooleanWeight<TScoreCombiner: ScoreCombiner> {
     weights: Vec<(Occur, Box<dyn Weight>)>,
     minimum_number_should_match: usize,
     scoring_enabled: bool,
+    pure_negation_matches_all: bool,
     score_combiner_fn: Box<dyn Fn() -> TScoreCombiner + Sync + Send>,
 }
 
@@ -109,6 +110,7 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
         BooleanWeight {
             weights,
             scoring_enabled,
+            pure_negation_matches_all: false,
             score_combiner_fn,
             minimum_number_should_match: 1,
         }
@@ -125,10 +127,21 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
             weights,
             minimum_number_should_match,
             scoring_enabled,
+            pure_negation_matches_all: false,
             score_combiner_fn,
         }
     }
 
+    /// Sets whether a weight with only `MustNot` clauses matches all of the documents that
+    /// are not excluded, rather than no document.
+    pub fn with_pure_negation_matches_all(
+        mut self,
+        pure_negation_matches_all: bool,
+    ) -> BooleanWeight<TScoreCombiner> {
+        self.pure_negation_matches_all = pure_negation_matches_all;
+        self
+    }
+
     fn per_occur_scorers(
         &self,
         reader: &SegmentReader,
@@ -227,7 +240,11 @@ impl<TScoreCombiner: ScoreCombiner> BooleanWeight<TScoreCombiner> {
                 SpecializedScorer::Other(intersect_scorers(must_scorers))
             }
             (CombinationMethod::Ignored, None) => {
-                return Ok(SpecializedScorer::Other(Box::new(EmptyScorer)))
+                if self.pure_negation_matches_all && exclude_scorer_opt.is_some() {
+                    SpecializedScorer::Other(AllWeight.scorer(reader, boost)?)
+                } else {
+                    return Ok(SpecializedScorer::Other(Box::new(EmptyScorer)));
+                }
             }
             (CombinationMethod::Required(should_scorer), None) => {
                 SpecializedScorer::Other(should_scorer)
@@ -254,7 +271,13 @@ impl<TScoreCombiner: ScoreCombiner + Sync> Weight for BooleanWeight<TScoreCombin
         } else if self.weights.len() == 1 {
             let &(occur, ref weight) = &self.weights[0];
             if occur == Occur::MustNot {
-                Ok(Box::new(EmptyScorer))
+                if self.pure_negation_matches_all {
+                    let exclude_scorer = weight.scorer(reader, 1.0)?;
+                    let all_scorer = AllWeight.scorer(reader, boost)?;
+                    Ok(Box::new(Exclude::new(all_scorer, exclude_scorer)))
+                } else {
+                    Ok(Box::new(EmptyScorer))
+                }
             } else {
                 weight.scorer(reader, boost)
             }
@@ -0,0 +1,425 @@
+use std::fmt;
+
+use columnar::{Column, ColumnType};
+
+use crate::aggregation::f64_from_fastfield_u64;
+use crate::docset::COLLECT_BLOCK_BUFFER_LEN;
+use crate::fastfield::AliveBitSet;
+use crate::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use crate::{DocId, DocSet, Score, SegmentReader, Term};
+
+const NUMERIC_COLUMN_TYPES: [ColumnType; 4] = [
+    ColumnType::U64,
+    ColumnType::I64,
+    ColumnType::F64,
+    ColumnType::DateTime,
+];
+
+/// The shape of the decay applied by a [`DecayQuery`].
+///
+/// All of the functions are equal to `1.0` at the origin, and to the `decay` of the query at a
+/// distance `scale` from the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecayFunction {
+    /// Decreases linearly, and reaches `0.0` at a distance `scale / (1 - decay)`.
+    Linear,
+    /// Decreases exponentially: `decay ^ (distance / scale)`.
+    Exponential,
+    /// Decreases following a gaussian curve: `decay ^ ((distance / scale) ^ 2)`.
+    Gaussian,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DecayParams {
+    function: DecayFunction,
+    origin: f64,
+    scale: f64,
+    offset: f64,
+    decay: f64,
+}
+
+impl DecayParams {
+    /// Returns the multiplier applied to the score of a document with the given value.
+    fn factor(&self, val: f64) -> f64 {
+        let distance = ((val - self.origin).abs() - self.offset).max(0.0);
+        let relative_distance = distance / self.scale;
+        match self.function {
+            DecayFunction::Linear => (1.0 - relative_distance * (1.0 - self.decay)).max(0.0),
+            DecayFunction::Exponential => self.decay.powf(relative_distance),
+            DecayFunction::Gaussian => self.decay.powf(relative_distance * relative_distance),
+        }
+    }
+}
+
+/// `DecayQuery` is a wrapper over a query decaying its score with the distance between the
+/// value of a numeric fast field and an origin, e.g. to favor recent documents.
+///
+/// The document set matched by the `DecayQuery` is strictly the same as the underlying query.
+/// The score of each document is the score of the underlying query multiplied by the
+/// [`DecayFunction`] of the distance between the first value of the fast field for the document
+/// and `origin`:
+/// - the score is not decayed within a distance `offset` of the origin, `0.0` by default,
+/// - at a distance `offset + scale`, the score is multiplied by `decay`, `0.5` by default.
+///
+/// The fast field can be a `u64`, `i64`, `f64` or date field. The values of date fields are
+/// expressed as timestamps in nanoseconds, see
+/// [`DateTime::into_timestamp_nanos`](crate::DateTime::into_timestamp_nanos).
+/// The score of documents without a value for the field, or in segments in which the field
+/// does not exist, is not decayed.
+///
+/// ```rust
+/// use tantivy::collector::TopDocs;
+/// use tantivy::query::{DecayFunction, DecayQuery, QueryParser};
+/// use tantivy::schema::{Schema, FAST, TEXT};
+/// use tantivy::{doc, DocAddress, Index};
+///
+/// # fn main() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let year = schema_builder.add_u64_field("year", FAST);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// index_writer.add_document(doc!(title => "Rust in action", year => 2010u64))?;
+/// index_writer.add_document(doc!(title => "Rust in action", year => 2024u64))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = QueryParser::for_index(&index, vec![title]).parse_query("rust")?;
+/// // The score of a book published 5 years before 2025 is halved.
+/// let decay_query = DecayQuery::new(query, "year", DecayFunction::Exponential, 2025.0, 5.0);
+/// let top_docs = searcher.search(&decay_query, &TopDocs::with_limit(2))?;
+/// assert_eq!(top_docs[0].1, DocAddress::new(0, 1));
+/// # Ok(())
+/// # }
+/// ```
+pub struct DecayQuery {
+    query: Box<dyn Query>,
+    field: String,
+    params: DecayParams,
+}
+
+impl DecayQuery {
+    /// Builds a decay query over the values of the fast field `field`.
+    ///
+    /// # Panics
+    /// The method panics if `scale` is not a finite, strictly positive number.
+    pub fn new(
+        query: Box<dyn Query>,
+        field: impl ToString,
+        function: DecayFunction,
+        origin: f64,
+        scale: f64,
+    ) -> DecayQuery {
+        assert!(
+            scale.is_finite() && scale > 0.0,
+            "The scale must be a finite, strictly positive number: {scale}"
+        );
+        DecayQuery {
+            query,
+            field: field.to_string(),
+            params: DecayParams {
+                function,
+                origin,
+                scale,
+                offset: 0.0,
+                decay: 0.5,
+            },
+        }
+    }
+
+    /// Sets the distance to the origin within which the score is not decayed.
+    ///
+    /// # Panics
+    /// The method panics if `offset` is not a finite, positive number.
+    #[must_use]
+    pub fn with_offset(mut self, offset: f64) -> DecayQuery {
+        assert!(
+            offset.is_finite() && offset >= 0.0,
+            "The offset must be a finite, positive number: {offset}"
+        );
+        self.params.offset = offset;
+        self
+    }
+
+    /// Sets the factor applied to the score at a distance `offset + scale` from the origin.
+    ///
+    /// # Panics
+    /// The method panics if `decay` is not strictly between `0.0` and `1.0`.
+    #[must_use]
+    pub fn with_decay(mut self, decay: f64) -> DecayQuery {
+        assert!(
+            decay > 0.0 && decay < 1.0,
+            "The decay must be strictly between 0 and 1: {decay}"
+        );
+        self.params.decay = decay;
+        self
+    }
+}
+
+impl Clone for DecayQuery {
+    fn clone(&self) -> Self {
+        DecayQuery {
+            query: self.query.box_clone(),
+            field: self.field.clone(),
+            params: self.params,
+        }
+    }
+}
+
+impl fmt::Debug for DecayQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Decay(query={:?}, field={:?}, function={:?}, origin={}, scale={}, offset={}, \
+             decay={})",
+            self.query,
+            self.field,
+            self.params.function,
+            self.params.origin,
+            self.params.scale,
+            self.params.offset,
+            self.params.decay
+        )
+    }
+}
+
+impl Query for DecayQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        let weight = self.query.weight(enable_scoring)?;
+        if !enable_scoring.is_scoring_enabled() {
+            return Ok(weight);
+        }
+        Ok(Box::new(DecayWeight {
+            weight,
+            field: self.field.clone(),
+            params: self.params,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.query.query_terms(visitor)
+    }
+}
+
+struct DecayWeight {
+    weight: Box<dyn Weight>,
+    field: String,
+    params: DecayParams,
+}
+
+impl DecayWeight {
+    fn column_opt(&self, reader: &SegmentReader) -> crate::Result<Option<(Column, ColumnType)>> {
+        reader
+            .fast_fields()
+            .u64_lenient_for_type(Some(&NUMERIC_COLUMN_TYPES), &self.field)
+    }
+}
+
+impl Weight for DecayWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let underlying = self.weight.scorer(reader, boost)?;
+        let Some((column, column_type)) = self.column_opt(reader)? else {
+            return Ok(underlying);
+        };
+        Ok(Box::new(DecayScorer {
+            underlying,
+            column,
+            column_type,
+            params: self.params,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let underlying_explanation = self.weight.explain(reader, doc)?;
+        let val_opt = self.column_opt(reader)?.and_then(|(column, column_type)| {
+            column
+                .first(doc)
+                .map(|val| f64_from_fastfield_u64(val, &column_type))
+        });
+        let Some(val) = val_opt else {
+            return Ok(underlying_explanation);
+        };
+        let factor = self.params.factor(val);
+        let score = underlying_explanation.value() * factor as Score;
+        let mut explanation = Explanation::new_with_string(
+            format!(
+                "{:?} decay x{factor} of {:?}={val}",
+                self.params.function, self.field
+            ),
+            score,
+        );
+        explanation.add_detail(underlying_explanation);
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+struct DecayScorer {
+    underlying: Box<dyn Scorer>,
+    column: Column,
+    column_type: ColumnType,
+    params: DecayParams,
+}
+
+impl DocSet for DecayScorer {
+    fn advance(&mut self) -> DocId {
+        self.underlying.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.underlying.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId; COLLECT_BLOCK_BUFFER_LEN]) -> usize {
+        self.underlying.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.underlying.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.underlying.size_hint()
+    }
+
+    fn count(&mut self, alive_bitset: &AliveBitSet) -> u32 {
+        self.underlying.count(alive_bitset)
+    }
+
+    fn count_including_deleted(&mut self) -> u32 {
+        self.underlying.count_including_deleted()
+    }
+}
+
+impl Scorer for DecayScorer {
+    fn score(&mut self) -> Score {
+        let score = self.underlying.score();
+        match self.column.first(self.doc()) {
+            Some(val) => {
+                let val = f64_from_fastfield_u64(val, &self.column_type);
+                score * self.params.factor(val) as Score
+            }
+            None => score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DecayFunction, DecayQuery};
+    use crate::collector::TopDocs;
+    use crate::query::{AllQuery, Query};
+    use crate::schema::{Schema, FAST};
+    use crate::{assert_nearly_equals, DateTime, DocAddress, Index, IndexWriter, Score};
+
+    fn decayed_scores(index: &Index, query: &DecayQuery) -> crate::Result<Vec<Score>> {
+        let searcher = index.reader()?.searcher();
+        let mut scores = Vec::new();
+        for doc_id in 0..searcher.num_docs() as u32 {
+            let doc_address = DocAddress::new(0, doc_id);
+            let explanation = query.explain(&searcher, doc_address)?;
+            scores.push(explanation.value());
+        }
+        // The scores of the explanations are the ones computed by the scorer.
+        let mut top_docs = searcher.search(query, &TopDocs::with_limit(scores.len() + 1))?;
+        top_docs.sort_by_key(|(_, doc_address)| doc_address.doc_id);
+        for (&(score, _), &explained_score) in top_docs.iter().zip(&scores) {
+            assert_nearly_equals!(score, explained_score);
+        }
+        Ok(scores)
+    }
+
+    #[test]
+    fn test_decay_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let price = schema_builder.add_i64_field("price", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for price_val in [100i64, 110, 90, 120, 150, 300] {
+            index_writer.add_document(doc!(price => price_val))?;
+        }
+        // A document without a price.
+        index_writer.add_document(doc!())?;
+        index_writer.commit()?;
+
+        let decay_query = |function: DecayFunction| {
+            DecayQuery::new(Box::new(AllQuery), "price", function, 100.0, 20.0)
+        };
+        let scores = decayed_scores(&index, &decay_query(DecayFunction::Linear))?;
+        let expected = [1.0, 0.75, 0.75, 0.5, 0.0, 0.0, 1.0];
+        for (&score, &expected_score) in scores.iter().zip(&expected) {
+            assert_nearly_equals!(score, expected_score);
+        }
+        let scores = decayed_scores(&index, &decay_query(DecayFunction::Exponential))?;
+        let expected = [
+            1.0,
+            0.5f32.sqrt(),
+            0.5f32.sqrt(),
+            0.5,
+            0.5f32.powf(2.5),
+            0.5f32.powi(10),
+        ];
+        for (&score, &expected_score) in scores.iter().zip(&expected) {
+            assert_nearly_equals!(score, expected_score);
+        }
+        assert_eq!(scores[6], 1.0);
+        let scores = decayed_scores(&index, &decay_query(DecayFunction::Gaussian))?;
+        let expected = [
+            1.0,
+            0.5f32.powf(0.25),
+            0.5f32.powf(0.25),
+            0.5,
+            0.5f32.powf(6.25),
+        ];
+        for (&score, &expected_score) in scores.iter().zip(&expected) {
+            assert_nearly_equals!(score, expected_score);
+        }
+
+        // With an offset, the documents close to the origin are not decayed.
+        let query = decay_query(DecayFunction::Exponential)
+            .with_offset(10.0)
+            .with_decay(0.1);
+        let scores = decayed_scores(&index, &query)?;
+        let expected = [1.0, 1.0, 1.0, 0.1f32.sqrt(), 0.1f32.powi(2)];
+        for (&score, &expected_score) in scores.iter().zip(&expected) {
+            assert_nearly_equals!(score, expected_score);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decay_query_recency() -> crate::Result<()> {
+        const DAY_IN_NANOS: f64 = 24.0 * 3_600.0 * 1_000_000_000.0;
+        let mut schema_builder = Schema::builder();
+        let date = schema_builder.add_date_field("date", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        let now = DateTime::from_timestamp_secs(1_700_000_000);
+        for days_ago in [30, 0, 7, 1] {
+            let timestamp_secs = now.into_timestamp_secs() - days_ago * 24 * 3_600;
+            index_writer
+                .add_document(doc!(date => DateTime::from_timestamp_secs(timestamp_secs)))?;
+        }
+        index_writer.commit()?;
+        let query = DecayQuery::new(
+            Box::new(AllQuery),
+            "date",
+            DecayFunction::Gaussian,
+            now.into_timestamp_nanos() as f64,
+            7.0 * DAY_IN_NANOS,
+        );
+        let searcher = index.reader()?.searcher();
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(4))?;
+        let doc_ids: Vec<u32> = top_docs
+            .iter()
+            .map(|(_, doc_address)| doc_address.doc_id)
+            .collect();
+        assert_eq!(doc_ids, vec![1, 3, 2, 0]);
+        // A week old document scores half as much as a new one.
+        assert_nearly_equals!(top_docs[2].0, 0.5 * top_docs[0].0);
+        Ok(())
+    }
+}
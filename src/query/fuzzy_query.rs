@@ -6,28 +6,73 @@ use crate::query::{AutomatonWeight, EnableScoring, Query, Weight};
 use crate::schema::{Term, Type};
 use crate::TantivyError::InvalidArgument;
 
-pub(crate) struct DfaWrapper(pub DFA);
+#[derive(Clone)]
+pub(crate) enum DfaState {
+    /// Number of bytes of the prefix matched so far.
+    Prefix(usize),
+    Dfa(u32),
+    Sink,
+}
+
+/// Matches an exact prefix followed by a string accepted by the DFA.
+pub(crate) struct DfaWrapper {
+    prefix: Vec<u8>,
+    dfa: DFA,
+}
+
+impl DfaWrapper {
+    pub(crate) fn new(prefix: &[u8], dfa: DFA) -> DfaWrapper {
+        DfaWrapper {
+            prefix: prefix.to_vec(),
+            dfa,
+        }
+    }
+
+    fn state_after_prefix(&self, num_prefix_bytes: usize) -> DfaState {
+        if num_prefix_bytes == self.prefix.len() {
+            DfaState::Dfa(self.dfa.initial_state())
+        } else {
+            DfaState::Prefix(num_prefix_bytes)
+        }
+    }
+}
 
 impl Automaton for DfaWrapper {
-    type State = u32;
+    type State = DfaState;
 
     fn start(&self) -> Self::State {
-        self.0.initial_state()
+        self.state_after_prefix(0)
     }
 
     fn is_match(&self, state: &Self::State) -> bool {
-        match self.0.distance(*state) {
-            Distance::Exact(_) => true,
-            Distance::AtLeast(_) => false,
+        match state {
+            DfaState::Dfa(dfa_state) => {
+                matches!(self.dfa.distance(*dfa_state), Distance::Exact(_))
+            }
+            DfaState::Prefix(_) | DfaState::Sink => false,
         }
     }
 
-    fn can_match(&self, state: &u32) -> bool {
-        *state != levenshtein_automata::SINK_STATE
+    fn can_match(&self, state: &Self::State) -> bool {
+        match state {
+            DfaState::Prefix(_) => true,
+            DfaState::Dfa(dfa_state) => *dfa_state != levenshtein_automata::SINK_STATE,
+            DfaState::Sink => false,
+        }
     }
 
     fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
-        self.0.transition(*state, byte)
+        match state {
+            DfaState::Prefix(num_prefix_bytes) => {
+                if self.prefix[*num_prefix_bytes] == byte {
+                    self.state_after_prefix(num_prefix_bytes + 1)
+                } else {
+                    DfaState::Sink
+                }
+            }
+            DfaState::Dfa(dfa_state) => DfaState::Dfa(self.dfa.transition(*dfa_state, byte)),
+            DfaState::Sink => DfaState::Sink,
+        }
     }
 }
 
@@ -86,6 +131,8 @@ pub struct FuzzyTermQuery {
     transposition_cost_one: bool,
     /// is a starts with query
     prefix: bool,
+    /// Number of leading characters of the term that must match exactly
+    prefix_length: usize,
 }
 
 impl FuzzyTermQuery {
@@ -96,6 +143,7 @@ impl FuzzyTermQuery {
             distance,
             transposition_cost_one,
             prefix: false,
+            prefix_length: 0,
         }
     }
 
@@ -106,9 +154,29 @@ impl FuzzyTermQuery {
             distance,
             transposition_cost_one,
             prefix: true,
+            prefix_length: 0,
         }
     }
 
+    /// Sets whether a transposition of two adjacent characters counts as a single edit
+    /// (Damerau-Levenshtein distance), or as two edits (Levenshtein distance).
+    #[must_use]
+    pub fn with_transpositions(mut self, transposition_cost_one: bool) -> FuzzyTermQuery {
+        self.transposition_cost_one = transposition_cost_one;
+        self
+    }
+
+    /// Sets the number of leading characters of the term that must match exactly.
+    /// Only the rest of the term is subject to the edit distance.
+    ///
+    /// This is much cheaper, as only the terms sharing the prefix are visited in the term
+    /// dictionary. Defaults to 0.
+    #[must_use]
+    pub fn with_prefix_length(mut self, prefix_length: usize) -> FuzzyTermQuery {
+        self.prefix_length = prefix_length;
+        self
+    }
+
     fn specialized_weight(&self) -> crate::Result<AutomatonWeight<DfaWrapper>> {
         static AUTOMATON_BUILDER: [[OnceCell<LevenshteinAutomatonBuilder>; 2]; 3] = [
             [OnceCell::new(), OnceCell::new()],
@@ -153,25 +221,37 @@ impl FuzzyTermQuery {
                 InvalidArgument("The fuzzy term query requires a string term.".to_string())
             })?
         };
-        let automaton = if self.prefix {
-            automaton_builder.build_prefix_dfa(term_text)
-        } else {
-            automaton_builder.build_dfa(term_text)
-        };
+        let automaton = self.build_automaton(automaton_builder, term_text);
 
         if let Some((json_path_bytes, _)) = term_value.as_json() {
             Ok(AutomatonWeight::new_for_json_path(
                 self.term.field(),
-                DfaWrapper(automaton),
+                automaton,
                 json_path_bytes,
             ))
         } else {
-            Ok(AutomatonWeight::new(
-                self.term.field(),
-                DfaWrapper(automaton),
-            ))
+            Ok(AutomatonWeight::new(self.term.field(), automaton))
         }
     }
+
+    fn build_automaton(
+        &self,
+        automaton_builder: &LevenshteinAutomatonBuilder,
+        term_text: &str,
+    ) -> DfaWrapper {
+        let prefix_num_bytes = term_text
+            .char_indices()
+            .nth(self.prefix_length)
+            .map(|(offset, _)| offset)
+            .unwrap_or(term_text.len());
+        let (exact_prefix, fuzzy_text) = term_text.split_at(prefix_num_bytes);
+        let dfa = if self.prefix {
+            automaton_builder.build_prefix_dfa(fuzzy_text)
+        } else {
+            automaton_builder.build_dfa(fuzzy_text)
+        };
+        DfaWrapper::new(exact_prefix.as_bytes(), dfa)
+    }
 }
 
 impl Query for FuzzyTermQuery {
@@ -186,7 +266,7 @@ mod test {
     use crate::collector::{Count, TopDocs};
     use crate::indexer::NoMergePolicy;
     use crate::query::QueryParser;
-    use crate::schema::{Schema, STORED, TEXT};
+    use crate::schema::{Field, Schema, STORED, TEXT};
     use crate::{assert_nearly_equals, Index, IndexWriter, TantivyDocument, Term};
 
     #[test]
@@ -343,4 +423,70 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    pub fn test_fuzzy_term_with_transpositions() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "dairy"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |distance: u8, transpositions: bool| {
+            let query = FuzzyTermQuery::new(Term::from_field_text(title, "diary"), distance, false)
+                .with_transpositions(transpositions);
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count(1, true), 1);
+        assert_eq!(count(1, false), 0);
+        assert_eq!(count(2, false), 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_fuzzy_term_with_prefix_length() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for text in ["restaurant", "testaurant", "res", "rés", "ras"] {
+            index_writer.add_document(doc!(title => text))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |text: &str, distance: u8, prefix_length: usize| {
+            let query = FuzzyTermQuery::new(Term::from_field_text(title, text), distance, true)
+                .with_prefix_length(prefix_length);
+            searcher.search(&query, &Count).unwrap()
+        };
+        // `restaurant` and `testaurant`.
+        assert_eq!(count("restauraunt", 2, 0), 2);
+        // The first character of `testaurant` differs.
+        assert_eq!(count("restauraunt", 2, 3), 1);
+        // `res`, `rés` and `ras`.
+        assert_eq!(count("res", 1, 0), 3);
+        // The prefix is counted in characters.
+        assert_eq!(count("rés", 1, 2), 1);
+        // A term shorter than the prefix length must match exactly.
+        assert_eq!(count("res", 1, 3), 1);
+        assert_eq!(count("res", 1, 10), 1);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_fuzzy_term_prefix_length_prunes_automaton() {
+        use levenshtein_automata::LevenshteinAutomatonBuilder;
+        use tantivy_fst::Automaton;
+
+        let term = Term::from_field_text(Field::from_field_id(0), "restaurant");
+        let query = FuzzyTermQuery::new(term, 2, true).with_prefix_length(3);
+        let automaton =
+            query.build_automaton(&LevenshteinAutomatonBuilder::new(2, true), "restaurant");
+        let after_r = automaton.accept(&automaton.start(), b'r');
+        assert!(automaton.can_match(&after_r));
+        // Without prefix, `t` would still be within distance 2 of the first character.
+        assert!(!automaton.can_match(&automaton.accept(&automaton.start(), b't')));
+        assert!(!automaton.can_match(&automaton.accept(&after_r, b'a')));
+    }
 }
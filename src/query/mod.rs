@@ -5,6 +5,7 @@ mod bm25;
 mod boolean_query;
 mod boost_query;
 mod const_score_query;
+mod decay_query;
 mod disjunction;
 mod disjunction_max_query;
 mod empty_query;
@@ -23,9 +24,11 @@ mod regex_query;
 mod reqopt_scorer;
 mod scorer;
 mod set_query;
+mod span_query;
 mod term_query;
 mod union;
 mod weight;
+mod wildcard_query;
 
 #[cfg(test)]
 mod vec_docset;
@@ -37,9 +40,10 @@ pub use self::all_query::{AllQuery, AllScorer, AllWeight};
 pub use self::automaton_weight::AutomatonWeight;
 pub use self::bitset::BitSetDocSet;
 pub use self::bm25::{Bm25StatisticsProvider, Bm25Weight};
-pub use self::boolean_query::{BooleanQuery, BooleanWeight};
+pub use self::boolean_query::{BooleanQuery, BooleanWeight, MinimumShouldMatch};
 pub use self::boost_query::{BoostQuery, BoostWeight};
 pub use self::const_score_query::{ConstScoreQuery, ConstScorer};
+pub use self::decay_query::{DecayFunction, DecayQuery};
 pub use self::disjunction_max_query::DisjunctionMaxQuery;
 pub use self::empty_query::{EmptyQuery, EmptyScorer, EmptyWeight};
 pub use self::exclude::Exclude;
@@ -52,20 +56,22 @@ pub use self::intersection::{intersect_scorers, Intersection};
 pub use self::more_like_this::{MoreLikeThisQuery, MoreLikeThisQueryBuilder};
 pub use self::phrase_prefix_query::PhrasePrefixQuery;
 pub use self::phrase_query::regex_phrase_query::{wildcard_query_to_regex_str, RegexPhraseQuery};
-pub use self::phrase_query::PhraseQuery;
+pub use self::phrase_query::{PhraseQuery, ProximityQuery};
 pub use self::query::{EnableScoring, Query, QueryClone};
 pub use self::query_parser::{QueryParser, QueryParserError};
 pub use self::range_query::*;
-pub use self::regex_query::RegexQuery;
+pub use self::regex_query::{RegexQuery, RegexQueryBuilder};
 pub use self::reqopt_scorer::RequiredOptionalScorer;
 pub use self::score_combiner::{DisjunctionMaxCombiner, ScoreCombiner, SumCombiner};
 pub use self::scorer::Scorer;
 pub use self::set_query::TermSetQuery;
+pub use self::span_query::SpanQuery;
 pub use self::term_query::TermQuery;
 pub use self::union::BufferedUnionScorer;
 #[cfg(test)]
 pub use self::vec_docset::VecDocSet;
 pub use self::weight::Weight;
+pub use self::wildcard_query::{WildcardQuery, WildcardQueryBuilder};
 
 #[cfg(test)]
 mod tests {
@@ -263,4 +263,53 @@ mod tests {
         assert_eq!(phrase_scorer.advance(), TERMINATED);
         Ok(())
     }
+
+    #[test]
+    pub fn test_phrase_prefix_search_as_you_type() -> crate::Result<()> {
+        use crate::collector::DocSetCollector;
+        use crate::query::QueryParser;
+        use crate::DocAddress;
+
+        let index = create_index(&[
+            "the quick brown fox",
+            "quick brown frog",
+            "brown quick fox",
+            "quick brown dog",
+            "quick fox brown",
+            "quick brown flamingo",
+        ])?;
+        let text_field = index.schema().get_field("text").unwrap();
+        let searcher = index.reader()?.searcher();
+        let matching_docs = |query: &dyn Query| -> crate::Result<Vec<u32>> {
+            let mut docs: Vec<u32> = searcher
+                .search(query, &DocSetCollector)?
+                .into_iter()
+                .map(|doc_address: DocAddress| doc_address.doc_id)
+                .collect();
+            docs.sort_unstable();
+            Ok(docs)
+        };
+
+        // The last term is expanded to all of the terms starting with `f`, and the terms have
+        // to appear in the order of the phrase.
+        let query_parser = QueryParser::for_index(&index, vec![text_field]);
+        let query = query_parser.parse_query("\"quick brown f\"*")?;
+        assert_eq!(matching_docs(query.as_ref())?, vec![0, 1, 5]);
+        let query = query_parser.parse_query("\"quick brown fr\"*")?;
+        assert_eq!(matching_docs(query.as_ref())?, vec![1]);
+        let query = query_parser.parse_query("\"brown quick f\"*")?;
+        assert_eq!(matching_docs(query.as_ref())?, vec![2]);
+
+        // The expansion of the prefix is bounded: only the first terms starting with the
+        // prefix, in the order of the term dictionary, are considered.
+        let mut phrase_prefix_query = PhrasePrefixQuery::new(vec![
+            Term::from_field_text(text_field, "quick"),
+            Term::from_field_text(text_field, "brown"),
+            Term::from_field_text(text_field, "f"),
+        ]);
+        phrase_prefix_query.set_max_expansions(2);
+        // `flamingo` and `fox` are the first two terms starting with `f`.
+        assert_eq!(matching_docs(&phrase_prefix_query)?, vec![0, 5]);
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 mod phrase_query;
 mod phrase_scorer;
 mod phrase_weight;
+mod proximity_query;
+mod proximity_weight;
 pub mod regex_phrase_query;
 mod regex_phrase_weight;
 
@@ -8,6 +10,7 @@ pub use self::phrase_query::PhraseQuery;
 pub(crate) use self::phrase_scorer::intersection_count;
 pub use self::phrase_scorer::PhraseScorer;
 pub use self::phrase_weight::PhraseWeight;
+pub use self::proximity_query::ProximityQuery;
 
 #[cfg(test)]
 pub(crate) mod tests {
@@ -0,0 +1,263 @@
+use super::proximity_weight::ProximityWeight;
+use crate::query::bm25::Bm25Weight;
+use crate::query::{EnableScoring, Query, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+
+/// `ProximityQuery` matches documents containing a set of terms close to each other.
+///
+/// Unlike [`PhraseQuery`](crate::query::PhraseQuery), terms do not have to be consecutive:
+///
+/// - In unordered mode (the default), the terms may appear in any order, as long as they all fit in
+///   a window containing at most `slop` extra positions. The slop is the sum of the slops between
+///   adjacent terms. For instance, the query `[quick, fox]` with a slop of 1 matches "quick brown
+///   fox", "fox quick" and "fox, the quick".
+/// - In ordered mode, the terms have to appear in the order of the query, and each pair of adjacent
+///   terms may be separated by at most the slop configured for this pair (see
+///   [`ProximityQuery::set_slop_between`]).
+///
+/// Documents are scored with BM25, using the number of matches as the term frequency. The score
+/// is then divided by `1 + slack`, where `slack` is the number of extra positions of the tightest
+/// match in the document, so that tight matches rank higher.
+///
+/// Using a `ProximityQuery` on a field requires positions to be indexed for this field.
+#[derive(Clone, Debug)]
+pub struct ProximityQuery {
+    field: Field,
+    terms: Vec<Term>,
+    slops: Vec<u32>,
+    ordered: bool,
+}
+
+impl ProximityQuery {
+    /// Creates a new unordered `ProximityQuery` given a list of terms and a slop applied
+    /// between each pair of adjacent terms.
+    ///
+    /// There must be at least two terms, and all terms must belong to the same field.
+    pub fn new(terms: Vec<Term>, slop: u32) -> ProximityQuery {
+        assert!(
+            terms.len() > 1,
+            "A proximity query is required to have strictly more than one term."
+        );
+        let field = terms[0].field();
+        assert!(
+            terms[1..].iter().all(|term| term.field() == field),
+            "All terms from a proximity query must belong to the same field"
+        );
+        let slops = vec![slop; terms.len() - 1];
+        ProximityQuery {
+            field,
+            terms,
+            slops,
+            ordered: false,
+        }
+    }
+
+    /// Sets whether the terms have to appear in the order of the query.
+    pub fn set_ordered(&mut self, ordered: bool) {
+        self.ordered = ordered;
+    }
+
+    /// Sets the slop between the term at position `term_ord` and the following one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no term after `term_ord`.
+    pub fn set_slop_between(&mut self, term_ord: usize, slop: u32) {
+        assert!(
+            term_ord + 1 < self.terms.len(),
+            "No term follows the term {term_ord} in the proximity query."
+        );
+        self.slops[term_ord] = slop;
+    }
+
+    /// The [`Field`] this `ProximityQuery` is targeting.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The `Term`s of the query.
+    pub fn terms(&self) -> &[Term] {
+        &self.terms
+    }
+
+    /// The slops between each pair of adjacent terms.
+    pub fn slops(&self) -> &[u32] {
+        &self.slops
+    }
+
+    /// Returns true if the terms have to appear in the order of the query.
+    pub fn is_ordered(&self) -> bool {
+        self.ordered
+    }
+
+    pub(crate) fn proximity_weight(
+        &self,
+        enable_scoring: EnableScoring<'_>,
+    ) -> crate::Result<ProximityWeight> {
+        let schema = enable_scoring.schema();
+        let field_entry = schema.get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied proximity query on field {field_name:?}, which does not have positions \
+                 indexed"
+            )));
+        }
+        let bm25_weight_opt = match enable_scoring {
+            EnableScoring::Enabled {
+                statistics_provider,
+                ..
+            } => Some(Bm25Weight::for_terms(statistics_provider, &self.terms)?),
+            EnableScoring::Disabled { .. } => None,
+        };
+        Ok(ProximityWeight::new(
+            self.terms.clone(),
+            self.slops.clone(),
+            self.ordered,
+            bm25_weight_opt,
+        ))
+    }
+}
+
+impl Query for ProximityQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        let proximity_weight = self.proximity_weight(enable_scoring)?;
+        Ok(Box::new(proximity_weight))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        for term in &self.terms {
+            visitor(term, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::create_index;
+    use super::ProximityQuery;
+    use crate::collector::tests::TEST_COLLECTOR_WITH_SCORE;
+    use crate::collector::Count;
+    use crate::query::{PhraseQuery, Query};
+    use crate::schema::{Schema, Term, STRING};
+    use crate::{DocAddress, Index, Score};
+
+    fn search(index: &Index, query: &dyn Query) -> Vec<(DocAddress, Score)> {
+        let searcher = index.reader().unwrap().searcher();
+        let fruit = searcher.search(query, &TEST_COLLECTOR_WITH_SCORE).unwrap();
+        fruit
+            .docs()
+            .iter()
+            .cloned()
+            .zip(fruit.scores().iter().cloned())
+            .collect()
+    }
+
+    fn matching_docs(index: &Index, query: &dyn Query) -> Vec<u32> {
+        search(index, query)
+            .into_iter()
+            .map(|(doc_address, _)| doc_address.doc_id)
+            .collect()
+    }
+
+    fn terms(index: &Index, words: &[&str]) -> Vec<Term> {
+        let text_field = index.schema().get_field("text").unwrap();
+        words
+            .iter()
+            .map(|word| Term::from_field_text(text_field, word))
+            .collect()
+    }
+
+    #[test]
+    fn test_proximity_query_ordered_vs_unordered() -> crate::Result<()> {
+        let index = create_index(&[
+            "quick brown fox",
+            "fox brown quick",
+            "fox quick",
+            "quick fox",
+            "quick a b c fox",
+        ])?;
+        let mut query = ProximityQuery::new(terms(&index, &["quick", "fox"]), 1);
+        assert_eq!(matching_docs(&index, &query), vec![0, 1, 2, 3]);
+        query.set_ordered(true);
+        assert_eq!(matching_docs(&index, &query), vec![0, 3]);
+
+        // A phrase query with the same slop does not match the reversed words either.
+        let mut phrase_query = PhraseQuery::new(terms(&index, &["quick", "fox"]));
+        phrase_query.set_slop(1);
+        assert_eq!(matching_docs(&index, &phrase_query), vec![0, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_query_three_terms() -> crate::Result<()> {
+        let index = create_index(&["a x b c", "c b a", "a b x x c", "c x a b", "a b"])?;
+        let mut query = ProximityQuery::new(terms(&index, &["a", "b", "c"]), 0);
+        // The total slop budget is 0.
+        assert_eq!(matching_docs(&index, &query), vec![1]);
+        query.set_slop_between(0, 1);
+        assert_eq!(matching_docs(&index, &query), vec![0, 1, 3]);
+        query.set_ordered(true);
+        assert_eq!(matching_docs(&index, &query), vec![0]);
+        query.set_slop_between(1, 2);
+        assert_eq!(matching_docs(&index, &query), vec![0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_query_duplicate_term() -> crate::Result<()> {
+        let index = create_index(&["a b", "a b a", "a x x x a"])?;
+        // The term has to occur twice.
+        let mut query = ProximityQuery::new(terms(&index, &["a", "a"]), 1);
+        assert_eq!(matching_docs(&index, &query), vec![1]);
+        query.set_ordered(true);
+        assert_eq!(matching_docs(&index, &query), vec![1]);
+        let query = ProximityQuery::new(terms(&index, &["a", "b", "a"]), 0);
+        assert_eq!(matching_docs(&index, &query), vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_query_scores_tighter_matches_higher() -> crate::Result<()> {
+        let index = create_index(&["a b z z z z", "a z b z z z", "a z z b z z"])?;
+        let query = ProximityQuery::new(terms(&index, &["a", "b"]), 2);
+        let results = search(&index, &query);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].1 > results[1].1);
+        assert!(results[1].1 > results[2].1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_query_count_without_scoring() -> crate::Result<()> {
+        let index = create_index(&["b a", "a b", "a c", "b c a"])?;
+        let query = ProximityQuery::new(terms(&index, &["a", "b"]), 0);
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.search(&query, &Count)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proximity_query_requires_positions() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let query = ProximityQuery::new(
+            vec![
+                Term::from_field_text(text_field, "a"),
+                Term::from_field_text(text_field, "b"),
+            ],
+            1,
+        );
+        let searcher = index.reader().unwrap().searcher();
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+    }
+}
@@ -0,0 +1,507 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::fieldnorm::FieldNormReader;
+use crate::index::SegmentReader;
+use crate::postings::{Postings, SegmentPostings};
+use crate::query::bm25::Bm25Weight;
+use crate::query::explanation::does_not_match;
+use crate::query::{EmptyScorer, Explanation, Intersection, Scorer, Weight};
+use crate::schema::{IndexRecordOption, Term};
+use crate::{DocId, Score};
+
+pub struct ProximityWeight {
+    terms: Vec<Term>,
+    slops: Vec<u32>,
+    ordered: bool,
+    similarity_weight_opt: Option<Bm25Weight>,
+}
+
+impl ProximityWeight {
+    /// Creates a new proximity weight.
+    /// If `similarity_weight_opt` is None, then scoring is disabled
+    pub fn new(
+        terms: Vec<Term>,
+        slops: Vec<u32>,
+        ordered: bool,
+        similarity_weight_opt: Option<Bm25Weight>,
+    ) -> ProximityWeight {
+        ProximityWeight {
+            terms,
+            slops,
+            ordered,
+            similarity_weight_opt,
+        }
+    }
+
+    fn fieldnorm_reader(&self, reader: &SegmentReader) -> crate::Result<FieldNormReader> {
+        let field = self.terms[0].field();
+        if self.similarity_weight_opt.is_some() {
+            if let Some(fieldnorm_reader) = reader.fieldnorms_readers().get_field(field)? {
+                return Ok(fieldnorm_reader);
+            }
+        }
+        Ok(FieldNormReader::constant(reader.max_doc(), 1))
+    }
+
+    pub(crate) fn proximity_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Option<ProximityScorer>> {
+        let similarity_weight_opt = self
+            .similarity_weight_opt
+            .as_ref()
+            .map(|similarity_weight| similarity_weight.boost_by(boost));
+        let fieldnorm_reader = self.fieldnorm_reader(reader)?;
+        let inverted_index = reader.inverted_index(self.terms[0].field())?;
+        let mut term_postings_list = Vec::with_capacity(self.terms.len());
+        for (term_ord, term) in self.terms.iter().enumerate() {
+            if let Some(postings) =
+                inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions)?
+            {
+                term_postings_list.push(PostingsWithOrd { term_ord, postings });
+            } else {
+                return Ok(None);
+            }
+        }
+        Ok(Some(ProximityScorer::new(
+            term_postings_list,
+            self.slops.clone(),
+            self.ordered,
+            similarity_weight_opt,
+            fieldnorm_reader,
+        )))
+    }
+}
+
+impl Weight for ProximityWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        if let Some(scorer) = self.proximity_scorer(reader, boost)? {
+            Ok(Box::new(scorer))
+        } else {
+            Ok(Box::new(EmptyScorer))
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let Some(mut scorer) = self.proximity_scorer(reader, 1.0)? else {
+            return Err(does_not_match(doc));
+        };
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = self.fieldnorm_reader(reader)?.fieldnorm_id(doc);
+        let mut explanation = Explanation::new("Proximity Scorer", scorer.score());
+        if let Some(similarity_weight) = self.similarity_weight_opt.as_ref() {
+            explanation.add_detail(similarity_weight.explain(fieldnorm_id, scorer.match_count()));
+            explanation.add_const("Slack of the tightest match", scorer.min_slack() as Score);
+        }
+        Ok(explanation)
+    }
+}
+
+/// Postings of the term at position `term_ord` in the query.
+pub(crate) struct PostingsWithOrd {
+    term_ord: usize,
+    postings: SegmentPostings,
+}
+
+impl DocSet for PostingsWithOrd {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+pub(crate) struct ProximityScorer {
+    intersection_docset: Intersection<PostingsWithOrd, PostingsWithOrd>,
+    slops: Vec<u32>,
+    ordered: bool,
+    similarity_weight_opt: Option<Bm25Weight>,
+    fieldnorm_reader: FieldNormReader,
+    // positions of each term, indexed by term ord.
+    positions: Vec<Vec<u32>>,
+    buffers: MatchBuffers,
+    match_count: u32,
+    min_slack: u32,
+}
+
+impl ProximityScorer {
+    fn new(
+        term_postings_list: Vec<PostingsWithOrd>,
+        slops: Vec<u32>,
+        ordered: bool,
+        similarity_weight_opt: Option<Bm25Weight>,
+        fieldnorm_reader: FieldNormReader,
+    ) -> ProximityScorer {
+        let num_terms = term_postings_list.len();
+        let mut scorer = ProximityScorer {
+            intersection_docset: Intersection::new(term_postings_list),
+            slops,
+            ordered,
+            similarity_weight_opt,
+            fieldnorm_reader,
+            positions: vec![Vec::new(); num_terms],
+            buffers: MatchBuffers::default(),
+            match_count: 0,
+            min_slack: 0,
+        };
+        if scorer.doc() != TERMINATED && !scorer.proximity_match() {
+            scorer.advance();
+        }
+        scorer
+    }
+
+    /// Number of matches in the current document.
+    pub fn match_count(&self) -> u32 {
+        self.match_count
+    }
+
+    /// Number of extra positions in the tightest match of the current document.
+    pub fn min_slack(&self) -> u32 {
+        self.min_slack
+    }
+
+    fn load_positions(&mut self) {
+        for i in 0..self.positions.len() {
+            let docset = self.intersection_docset.docset_mut_specialized(i);
+            docset
+                .postings
+                .positions(&mut self.positions[docset.term_ord]);
+        }
+    }
+
+    fn proximity_match(&mut self) -> bool {
+        self.load_positions();
+        let (match_count, min_slack) = if self.ordered {
+            ordered_matches(&self.positions, &self.slops, &mut self.buffers)
+        } else {
+            let budget = self.slops.iter().sum();
+            unordered_matches(&self.positions, budget, &mut self.buffers)
+        };
+        self.match_count = match_count;
+        self.min_slack = min_slack;
+        match_count > 0
+    }
+}
+
+/// Buffers reused across documents to look for the matches.
+#[derive(Default)]
+struct MatchBuffers {
+    // For each term, the smallest slack of a chain from each of its positions to the last term,
+    // used in ordered mode.
+    chain_slacks: Vec<Vec<u32>>,
+    // (position, term ord) pairs, used in unordered mode.
+    merged_positions: Vec<(u32, usize)>,
+    distinct_positions: DistinctPositions,
+}
+
+/// Marks the positions from which no chain reaches the last term.
+const NO_CHAIN: u32 = u32::MAX;
+
+/// Counts the chains of positions respecting the order of the terms and the slops
+/// between adjacent terms. Chains starting at the same position of the first term are
+/// counted once.
+///
+/// Returns the number of chains and the smallest slack among them.
+fn ordered_matches(
+    positions: &[Vec<u32>],
+    slops: &[u32],
+    buffers: &mut MatchBuffers,
+) -> (u32, u32) {
+    let num_terms = positions.len();
+    let chain_slacks = &mut buffers.chain_slacks;
+    chain_slacks.resize_with(num_terms, Vec::new);
+    chain_slacks[num_terms - 1].clear();
+    chain_slacks[num_terms - 1].resize(positions[num_terms - 1].len(), 0);
+    // The closest position of the next term does not necessarily lead to a chain, so that
+    // the chains are computed backward from the last term, for all of the positions.
+    for term_ord in (0..num_terms - 1).rev() {
+        let (current_slacks, next_slacks) = chain_slacks.split_at_mut(term_ord + 1);
+        let current_slacks = &mut current_slacks[term_ord];
+        let next_slacks = &next_slacks[0];
+        let next_positions = &positions[term_ord + 1];
+        let slop = slops[term_ord];
+        current_slacks.clear();
+        let mut next_start = 0;
+        for &position in &positions[term_ord] {
+            while next_start < next_positions.len() && next_positions[next_start] <= position {
+                next_start += 1;
+            }
+            let mut chain_slack = NO_CHAIN;
+            for (&next_position, &next_slack) in next_positions[next_start..]
+                .iter()
+                .zip(&next_slacks[next_start..])
+            {
+                let gap = next_position - position - 1;
+                if gap > slop {
+                    break;
+                }
+                if next_slack != NO_CHAIN {
+                    chain_slack = chain_slack.min(gap + next_slack);
+                }
+            }
+            current_slacks.push(chain_slack);
+        }
+    }
+    chain_slacks[0]
+        .iter()
+        .filter(|&&chain_slack| chain_slack != NO_CHAIN)
+        .fold((0, u32::MAX), |(match_count, min_slack), &chain_slack| {
+            (match_count + 1, min_slack.min(chain_slack))
+        })
+}
+
+/// Counts the windows containing a distinct position for every term, in any order, with at
+/// most `budget` extra positions. One window is counted for each position closing it.
+///
+/// The positions of the terms have to be distinct, so that e.g. a term repeated in the query
+/// has to occur several times.
+///
+/// Returns the number of windows and the smallest slack among them.
+fn unordered_matches(
+    positions: &[Vec<u32>],
+    budget: u32,
+    buffers: &mut MatchBuffers,
+) -> (u32, u32) {
+    let num_terms = positions.len();
+    let MatchBuffers {
+        merged_positions,
+        distinct_positions,
+        ..
+    } = buffers;
+    merged_positions.clear();
+    for (term_ord, term_positions) in positions.iter().enumerate() {
+        merged_positions.extend(term_positions.iter().map(|&position| (position, term_ord)));
+    }
+    merged_positions.sort_unstable();
+    let max_span = budget + num_terms as u32;
+    let mut left = 0;
+    let mut match_count = 0;
+    let mut min_slack = u32::MAX;
+    for right in 0..merged_positions.len() {
+        let right_position = merged_positions[right].0;
+        // Wider windows cannot match.
+        while right_position - merged_positions[left].0 + 1 > max_span {
+            left += 1;
+        }
+        if !distinct_positions.exist(&merged_positions[left..=right], num_terms) {
+            continue;
+        }
+        // Shrink the window as long as each term still gets a position of its own.
+        while distinct_positions.exist(&merged_positions[left + 1..=right], num_terms) {
+            left += 1;
+        }
+        let span = right_position - merged_positions[left].0 + 1;
+        match_count += 1;
+        min_slack = min_slack.min(span - num_terms as u32);
+    }
+    (match_count, min_slack)
+}
+
+/// Assigns distinct positions to the terms of a window.
+#[derive(Default)]
+struct DistinctPositions {
+    // The candidate positions of each term, as indexes among the distinct positions.
+    term_positions: Vec<Vec<usize>>,
+    position_owners: Vec<Option<usize>>,
+    visited: Vec<bool>,
+}
+
+impl DistinctPositions {
+    /// Returns true if each of the `num_terms` terms can be assigned a distinct position among
+    /// the `(position, term_ord)` entries of `window`, sorted by position.
+    fn exist(&mut self, window: &[(u32, usize)], num_terms: usize) -> bool {
+        self.term_positions.resize_with(num_terms, Vec::new);
+        for term_positions in &mut self.term_positions {
+            term_positions.clear();
+        }
+        let mut num_positions = 0;
+        let mut previous_position = None;
+        for &(position, term_ord) in window {
+            if previous_position != Some(position) {
+                previous_position = Some(position);
+                num_positions += 1;
+            }
+            self.term_positions[term_ord].push(num_positions - 1);
+        }
+        if num_positions < num_terms || self.term_positions.iter().any(Vec::is_empty) {
+            return false;
+        }
+        if num_positions == window.len() {
+            // No two terms share a position.
+            return true;
+        }
+        self.position_owners.clear();
+        self.position_owners.resize(num_positions, None);
+        (0..num_terms).all(|term_ord| {
+            self.visited.clear();
+            self.visited.resize(num_positions, false);
+            assign_position(
+                term_ord,
+                &self.term_positions,
+                &mut self.position_owners,
+                &mut self.visited,
+            )
+        })
+    }
+}
+
+/// Assigns a position to `term_ord`, possibly reassigning the positions of the other terms.
+fn assign_position(
+    term_ord: usize,
+    term_positions: &[Vec<usize>],
+    position_owners: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &position in &term_positions[term_ord] {
+        if visited[position] {
+            continue;
+        }
+        visited[position] = true;
+        let is_free = match position_owners[position] {
+            Some(owner) => assign_position(owner, term_positions, position_owners, visited),
+            None => true,
+        };
+        if is_free {
+            position_owners[position] = Some(term_ord);
+            return true;
+        }
+    }
+    false
+}
+
+impl DocSet for ProximityScorer {
+    fn advance(&mut self) -> DocId {
+        loop {
+            let doc = self.intersection_docset.advance();
+            if doc == TERMINATED || self.proximity_match() {
+                return doc;
+            }
+        }
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        debug_assert!(target >= self.doc());
+        let doc = self.intersection_docset.seek(target);
+        if doc == TERMINATED || self.proximity_match() {
+            return doc;
+        }
+        self.advance()
+    }
+
+    fn doc(&self) -> DocId {
+        self.intersection_docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.intersection_docset.size_hint()
+    }
+}
+
+impl Scorer for ProximityScorer {
+    fn score(&mut self) -> Score {
+        let doc = self.doc();
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(doc);
+        if let Some(similarity_weight) = self.similarity_weight_opt.as_ref() {
+            similarity_weight.score(fieldnorm_id, self.match_count) / (1 + self.min_slack) as Score
+        } else {
+            1.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ordered_matches, unordered_matches, MatchBuffers};
+
+    #[test]
+    fn test_ordered_matches() {
+        let mut buffers = MatchBuffers::default();
+        assert_eq!(
+            ordered_matches(&[vec![0, 5], vec![1, 7]], &[0], &mut buffers),
+            (1, 0)
+        );
+        assert_eq!(
+            ordered_matches(&[vec![0, 5], vec![1, 7]], &[1], &mut buffers),
+            (2, 0)
+        );
+        assert_eq!(
+            ordered_matches(&[vec![3], vec![1]], &[10], &mut buffers),
+            (0, u32::MAX)
+        );
+        assert_eq!(
+            ordered_matches(&[vec![0], vec![2], vec![3]], &[1, 0], &mut buffers),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_ordered_matches_not_through_closest_position() {
+        let mut buffers = MatchBuffers::default();
+        // The closest position of `b` is too far from `c`, the chain goes through the other one.
+        assert_eq!(
+            ordered_matches(&[vec![0], vec![1, 2], vec![4]], &[1, 1], &mut buffers),
+            (1, 2)
+        );
+        assert_eq!(
+            ordered_matches(&[vec![0], vec![1, 2], vec![4]], &[0, 1], &mut buffers),
+            (0, u32::MAX)
+        );
+        // The tightest chain is kept for each position of the first term.
+        assert_eq!(
+            ordered_matches(&[vec![0, 1], vec![2, 3], vec![4]], &[2, 1], &mut buffers),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn test_unordered_matches() {
+        let mut buffers = MatchBuffers::default();
+        assert_eq!(
+            unordered_matches(&[vec![2], vec![0]], 1, &mut buffers),
+            (1, 1)
+        );
+        assert_eq!(
+            unordered_matches(&[vec![2], vec![0]], 0, &mut buffers),
+            (0, u32::MAX)
+        );
+        assert_eq!(
+            unordered_matches(&[vec![0, 4], vec![1, 3]], 0, &mut buffers),
+            (2, 0)
+        );
+    }
+
+    #[test]
+    fn test_unordered_matches_duplicate_terms() {
+        let mut buffers = MatchBuffers::default();
+        // A term repeated in the query has to occur twice.
+        assert_eq!(
+            unordered_matches(&[vec![3], vec![3]], 10, &mut buffers),
+            (0, u32::MAX)
+        );
+        assert_eq!(
+            unordered_matches(&[vec![3, 5], vec![3, 5]], 1, &mut buffers),
+            (2, 1)
+        );
+        // Terms sharing a position, e.g. synonyms, need a position each.
+        assert_eq!(
+            unordered_matches(&[vec![2], vec![2, 4], vec![4]], 10, &mut buffers),
+            (0, u32::MAX)
+        );
+        assert_eq!(
+            unordered_matches(&[vec![2], vec![2, 3], vec![4]], 0, &mut buffers),
+            (1, 0)
+        );
+    }
+}
@@ -1,13 +1,18 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::ops::Bound;
 
-use crate::query::Occur;
-use crate::schema::Term;
+use crate::query::{Occur, WildcardQuery};
+use crate::schema::{Field, Term};
 use crate::Score;
 
 #[derive(Clone)]
 pub enum LogicalLiteral {
     Term(Term),
+    Fuzzy {
+        term: Term,
+        distance: u8,
+    },
     Phrase {
         terms: Vec<(usize, Term)>,
         slop: u32,
@@ -20,13 +25,45 @@ pub enum LogicalLiteral {
     Set {
         elements: Vec<Term>,
     },
+    Wildcard(WildcardQuery),
     All,
 }
 
+impl LogicalLiteral {
+    /// Adds the fields targeted by this literal to `fields`.
+    pub fn collect_fields(&self, fields: &mut HashSet<Field>) {
+        match self {
+            LogicalLiteral::Term(term) | LogicalLiteral::Fuzzy { term, .. } => {
+                fields.insert(term.field());
+            }
+            LogicalLiteral::Phrase { terms, .. } => {
+                fields.extend(terms.iter().map(|(_, term)| term.field()));
+            }
+            LogicalLiteral::Range { lower, upper } => {
+                for bound in [lower, upper] {
+                    if let Bound::Included(term) | Bound::Excluded(term) = bound {
+                        fields.insert(term.field());
+                    }
+                }
+            }
+            LogicalLiteral::Set { elements } => {
+                fields.extend(elements.iter().map(Term::field));
+            }
+            LogicalLiteral::Wildcard(wildcard_query) => {
+                fields.insert(wildcard_query.field());
+            }
+            LogicalLiteral::All => {}
+        }
+    }
+}
+
 pub enum LogicalAst {
     Clause(Vec<(Occur, LogicalAst)>),
     Leaf(Box<LogicalLiteral>),
     Boost(Box<LogicalAst>, Score),
+    /// The disjuncts and the tie breaker of a
+    /// [`DisjunctionMaxQuery`](crate::query::DisjunctionMaxQuery).
+    DisjunctionMax(Vec<LogicalAst>, Score),
 }
 
 impl LogicalAst {
@@ -38,6 +75,24 @@ impl LogicalAst {
         }
     }
 
+    /// Adds the fields targeted by the literals of this AST to `fields`.
+    pub fn collect_fields(&self, fields: &mut HashSet<Field>) {
+        match self {
+            LogicalAst::Clause(clauses) => {
+                for (_, sub_ast) in clauses {
+                    sub_ast.collect_fields(fields);
+                }
+            }
+            LogicalAst::Leaf(literal) => literal.collect_fields(fields),
+            LogicalAst::Boost(ast, _) => ast.collect_fields(fields),
+            LogicalAst::DisjunctionMax(disjuncts, _) => {
+                for disjunct in disjuncts {
+                    disjunct.collect_fields(fields);
+                }
+            }
+        }
+    }
+
     pub fn simplify(self) -> LogicalAst {
         match self {
             LogicalAst::Clause(clauses) => {
@@ -62,6 +117,10 @@ impl LogicalAst {
 
                 LogicalAst::Clause(new_clauses)
             }
+            LogicalAst::DisjunctionMax(disjuncts, tie_breaker) => LogicalAst::DisjunctionMax(
+                disjuncts.into_iter().map(LogicalAst::simplify).collect(),
+                tie_breaker,
+            ),
             LogicalAst::Leaf(_) | LogicalAst::Boost(_, _) => self,
         }
     }
@@ -92,6 +151,16 @@ impl fmt::Debug for LogicalAst {
                 Ok(())
             }
             LogicalAst::Boost(ref ast, boost) => write!(formatter, "{ast:?}^{boost}"),
+            LogicalAst::DisjunctionMax(ref disjuncts, tie_breaker) => {
+                formatter.write_str("DisMax(")?;
+                for (i, disjunct) in disjuncts.iter().enumerate() {
+                    if i > 0 {
+                        formatter.write_str(" | ")?;
+                    }
+                    write!(formatter, "{disjunct:?}")?;
+                }
+                write!(formatter, ")~{tie_breaker}")
+            }
             LogicalAst::Leaf(ref literal) => write!(formatter, "{literal:?}"),
         }
     }
@@ -107,6 +176,9 @@ impl fmt::Debug for LogicalLiteral {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match *self {
             LogicalLiteral::Term(ref term) => write!(formatter, "{term:?}"),
+            LogicalLiteral::Fuzzy { ref term, distance } => {
+                write!(formatter, "{term:?}~{distance}")
+            }
             LogicalLiteral::Phrase {
                 ref terms,
                 slop,
@@ -146,6 +218,12 @@ impl fmt::Debug for LogicalLiteral {
                 }
                 write!(formatter, "]")
             }
+            LogicalLiteral::Wildcard(ref wildcard_query) => write!(
+                formatter,
+                "Wildcard(field={}, {:?})",
+                wildcard_query.field().field_id(),
+                wildcard_query.pattern()
+            ),
             LogicalLiteral::All => write!(formatter, "*"),
         }
     }
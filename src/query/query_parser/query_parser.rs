@@ -1,21 +1,23 @@
-use std::net::{AddrParseError, IpAddr};
+use std::collections::HashSet;
+use std::net::{AddrParseError, IpAddr, Ipv6Addr};
 use std::num::{ParseFloatError, ParseIntError};
-use std::ops::Bound;
+use std::ops::{Bound, RangeInclusive};
 use std::str::{FromStr, ParseBoolError};
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use itertools::Itertools;
-use query_grammar::{UserInputAst, UserInputBound, UserInputLeaf, UserInputLiteral};
+use query_grammar::{Delimiter, UserInputAst, UserInputBound, UserInputLeaf, UserInputLiteral};
 use rustc_hash::FxHashMap;
 
 use super::logical_ast::*;
 use crate::index::Index;
 use crate::json_utils::convert_to_fast_value_and_append_to_json_term;
 use crate::query::range_query::{is_type_valid_for_fastfield_range_query, RangeQuery};
+use crate::query::wildcard_query::{has_leading_wildcard, has_wildcard};
 use crate::query::{
-    AllQuery, BooleanQuery, BoostQuery, EmptyQuery, FuzzyTermQuery, Occur, PhrasePrefixQuery,
-    PhraseQuery, Query, TermQuery, TermSetQuery,
+    AllQuery, BooleanQuery, BoostQuery, DisjunctionMaxQuery, EmptyQuery, FuzzyTermQuery, Occur,
+    PhrasePrefixQuery, PhraseQuery, Query, TermQuery, TermSetQuery, WildcardQuery,
 };
 use crate::schema::{
     Facet, FacetParseError, Field, FieldType, IndexRecordOption, IntoIpv6Addr, JsonObjectOptions,
@@ -55,6 +57,8 @@ pub enum QueryParserError {
     #[error("Expected a bool value: '{0:?}'")]
     ExpectedBool(#[from] ParseBoolError),
     /// It is forbidden queries that are only "excluding". (e.g. -title:pop)
+    ///
+    /// See [`QueryParser::set_allow_pure_negation`].
     #[error("Invalid query: Only excluding terms given")]
     AllButQueryForbidden,
     /// If no default field is declared, running a query without any
@@ -102,6 +106,22 @@ pub enum QueryParserError {
     /// The format for the ip field is invalid.
     #[error("The ip field is malformed: {0}")]
     IpFormatError(#[from] AddrParseError),
+    /// The fuzzy distance requested through the `~` operator exceeds the maximum
+    /// allowed by the query parser.
+    #[error("The fuzzy distance {distance} exceeds the maximum allowed distance {max_distance}")]
+    FuzzyDistanceTooLarge {
+        /// The distance requested in the query
+        distance: u32,
+        /// The maximum distance configured on the query parser
+        max_distance: u8,
+    },
+    /// The wildcard pattern starts with a wildcard, which is not allowed by the query parser.
+    #[error("Leading wildcards are not allowed: '{0}'")]
+    LeadingWildcardNotAllowed(String),
+    /// The boost factor given through the `^` operator is not a finite, strictly positive
+    /// number.
+    #[error("The boost factor must be a finite positive number: '{0}'")]
+    InvalidBoost(String),
 }
 
 /// Recursively remove empty clause from the AST
@@ -122,6 +142,14 @@ fn trim_ast(logical_ast: LogicalAst) -> Option<LogicalAst> {
                 Some(LogicalAst::Clause(trimmed_children))
             }
         }
+        LogicalAst::DisjunctionMax(disjuncts, tie_breaker) => {
+            let trimmed_disjuncts = disjuncts.into_iter().flat_map(trim_ast).collect::<Vec<_>>();
+            if trimmed_disjuncts.is_empty() {
+                None
+            } else {
+                Some(LogicalAst::DisjunctionMax(trimmed_disjuncts, tie_breaker))
+            }
+        }
         _ => Some(logical_ast),
     }
 }
@@ -179,11 +207,16 @@ fn trim_ast(logical_ast: LogicalAst) -> Option<LogicalAst> {
 ///   `"2002-10-02T15:00:00.05Z"` or `some_date_field:[2002-10-02T15:00:00Z TO
 ///   2002-10-02T18:00:00Z}`
 ///
+/// * ip subnets: Ip address fields can be matched against a subnet in CIDR notation, e.g.
+///   `ip:10.0.0.0/8` or `ip:"2001:db8::/32"`. IPv6 subnets need to be quoted, as they contain
+///   colons.
+///
 /// * all docs query: A plain `*` will match all documents in the index.
 ///
 /// Parts of the queries can be boosted by appending `^boostfactor`.
 /// For instance, `"SRE"^2.0 OR devops^0.4` will boost documents containing `SRE` instead of
-/// devops. Negative boosts are not allowed.
+/// devops. The boost applies to the clause immediately preceding it, and must be a finite,
+/// strictly positive number.
 ///
 /// It is also possible to define a boost for a some specific field, at the query parser level.
 /// (See [`set_field_boost(...)`](QueryParser::set_field_boost)). Typically you may want to boost a
@@ -192,20 +225,38 @@ fn trim_ast(logical_ast: LogicalAst) -> Option<LogicalAst> {
 /// Additionally, specific fields can be marked to use fuzzy term queries for each literal
 /// via the [`QueryParser::set_field_fuzzy`] method.
 ///
+/// Unquoted terms support the `~` fuzzy operator, which turns them into a fuzzy term query with
+/// the given Levenshtein distance. `rust~2` matches documents containing `rust` up to two edits
+/// away, and `rust~` is equivalent to `rust~1`. The distance cannot exceed the maximum set via
+/// [`QueryParser::set_max_fuzzy_distance`].
+///
 /// Phrase terms support the `~` slop operator which allows to set the phrase's matching
 /// distance in words. `"big wolf"~1` will return documents containing the phrase `"big bad wolf"`.
 ///
 /// Phrase terms also support the `*` prefix operator which switches the phrase's matching
 /// to consider all documents which contain the last term as a prefix, e.g. `"big bad wo"*` will
 /// match `"big bad wolf"`.
+///
+/// Unquoted terms containing the `*` or `?` wildcards become [wildcard queries][`WildcardQuery`]
+/// on text fields, e.g. `title:ja*n` matches `japan` and `jargon`. `*` matches any sequence of
+/// characters, and `?` matches exactly one. The pattern is matched against the indexed terms as
+/// is, without going through the tokenizer of the field. Patterns starting with a wildcard are
+/// rejected unless allowed via [`QueryParser::set_allow_leading_wildcard`]. Note that a leading
+/// `*` is parsed as the all docs or exists operator, so only `?` can start a wildcard term.
 #[derive(Clone)]
 pub struct QueryParser {
     schema: Schema,
     default_fields: Vec<Field>,
     conjunction_by_default: bool,
+    conjunction_by_default_for_field: FxHashMap<Field, bool>,
     tokenizer_manager: TokenizerManager,
     boost: FxHashMap<Field, Score>,
     fuzzy: FxHashMap<Field, Fuzzy>,
+    max_fuzzy_distance: u8,
+    allow_leading_wildcard: bool,
+    allow_pure_negation: bool,
+    aliases: FxHashMap<String, Field>,
+    disjunction_max_tie_breaker: Option<Score>,
 }
 
 #[derive(Clone)]
@@ -217,7 +268,7 @@ struct Fuzzy {
 
 fn all_negative(ast: &LogicalAst) -> bool {
     match ast {
-        LogicalAst::Leaf(_) => false,
+        LogicalAst::Leaf(_) | LogicalAst::DisjunctionMax(..) => false,
         LogicalAst::Boost(ref child_ast, _) => all_negative(child_ast),
         LogicalAst::Clause(children) => children
             .iter()
@@ -228,12 +279,15 @@ fn all_negative(ast: &LogicalAst) -> bool {
 // Make an all-negative ast into a normal ast. Must not be used on an already okay ast.
 fn make_non_negative(ast: &mut LogicalAst) {
     match ast {
-        LogicalAst::Leaf(_) => (),
+        LogicalAst::Leaf(_) | LogicalAst::DisjunctionMax(..) => (),
         LogicalAst::Boost(ref mut child_ast, _) => make_non_negative(child_ast),
         LogicalAst::Clause(children) => children.push((Occur::Should, LogicalLiteral::All.into())),
     }
 }
 
+/// Largest Levenshtein distance supported by [`FuzzyTermQuery`].
+const MAX_SUPPORTED_FUZZY_DISTANCE: u8 = 2;
+
 /// Similar to the try/? macro, but returns a tuple of (None, Vec<Error>) instead of Err(Error)
 macro_rules! try_tuple {
     ($expr:expr) => {{
@@ -244,6 +298,29 @@ macro_rules! try_tuple {
     }};
 }
 
+/// Returns the range of addresses of a subnet given in CIDR notation, e.g. `10.0.0.0/8`.
+///
+/// IPv4 addresses are indexed as IPv4-mapped IPv6 addresses, so the prefix length of an IPv4
+/// subnet is shifted accordingly: `10.0.0.0/8` ranges from `::ffff:10.0.0.0` to
+/// `::ffff:10.255.255.255`.
+fn parse_ip_subnet(
+    ip_addr: &str,
+    prefix_len: &str,
+) -> Result<RangeInclusive<Ipv6Addr>, QueryParserError> {
+    let ip_addr = IpAddr::from_str(ip_addr)?;
+    let prefix_len: u32 = prefix_len.parse()?;
+    let max_prefix_len = if ip_addr.is_ipv4() { 32 } else { 128 };
+    if prefix_len > max_prefix_len {
+        return Err(QueryParserError::UnsupportedQuery(format!(
+            "The prefix length of the subnet {ip_addr}/{prefix_len} exceeds {max_prefix_len}"
+        )));
+    }
+    let ipv6_prefix_len = prefix_len + 128 - max_prefix_len;
+    let host_mask = u128::MAX.checked_shr(ipv6_prefix_len).unwrap_or(0);
+    let first_addr = u128::from(ip_addr.into_ipv6_addr()) & !host_mask;
+    Ok(Ipv6Addr::from(first_addr)..=Ipv6Addr::from(first_addr | host_mask))
+}
+
 impl QueryParser {
     /// Creates a `QueryParser`, given
     /// * schema - index Schema
@@ -258,17 +335,45 @@ impl QueryParser {
             default_fields,
             tokenizer_manager,
             conjunction_by_default: false,
+            conjunction_by_default_for_field: Default::default(),
             boost: Default::default(),
             fuzzy: Default::default(),
+            max_fuzzy_distance: MAX_SUPPORTED_FUZZY_DISTANCE,
+            allow_leading_wildcard: false,
+            allow_pure_negation: false,
+            aliases: Default::default(),
+            disjunction_max_tie_breaker: None,
         }
     }
 
     // Splits a full_path as written in a query, into a field name and a
     // json path.
     pub(crate) fn split_full_path<'a>(&self, full_path: &'a str) -> Option<(Field, &'a str)> {
+        if let Some(alias_match) = self.split_alias_path(full_path) {
+            return Some(alias_match);
+        }
         self.schema.find_field(full_path)
     }
 
+    // Same as `split_full_path`, for a full path starting with an alias.
+    // The longest matching alias wins.
+    fn split_alias_path<'a>(&self, full_path: &'a str) -> Option<(Field, &'a str)> {
+        if self.aliases.is_empty() {
+            return None;
+        }
+        if let Some(field) = self.aliases.get(full_path) {
+            return Some((*field, ""));
+        }
+        self.aliases
+            .iter()
+            .filter_map(|(alias, field)| {
+                let json_path = full_path.strip_prefix(alias.as_str())?.strip_prefix('.')?;
+                Some((alias.len(), *field, json_path))
+            })
+            .max_by_key(|(alias_len, _, _)| *alias_len)
+            .map(|(_, field, json_path)| (field, json_path))
+    }
+
     /// Creates a `QueryParser`, given
     ///  * an index
     ///  * a set of default fields used to search if no field is specifically defined in the query.
@@ -285,6 +390,22 @@ impl QueryParser {
         self.conjunction_by_default = true;
     }
 
+    /// Overrides the default way to compose queries for a specific field.
+    ///
+    /// When a clause does not specify an explicit operator (`+`, `-`, `AND`, `OR`), the
+    /// occurrence of each of its members is decided by the field it targets: if the member
+    /// explicitly targets a field registered here (e.g. `title:a` or `title:(a b)`), the
+    /// per-field setting is used. Otherwise, the parser falls back to the global setting (see
+    /// [`set_conjunction_by_default`](QueryParser::set_conjunction_by_default)).
+    ///
+    /// For instance, with `title` set to disjunction and `tags` set to conjunction,
+    /// `title:(a b) tags:(x y)` is interpreted as `(title:a OR title:b) (tags:x AND tags:y)`,
+    /// the two groups being composed according to the global setting.
+    pub fn set_conjunction_by_default_for_field(&mut self, field: Field, conjunction: bool) {
+        self.conjunction_by_default_for_field
+            .insert(field, conjunction);
+    }
+
     /// Sets a boost for a specific field.
     ///
     /// The parse query will automatically boost this field.
@@ -292,6 +413,9 @@ impl QueryParser {
     /// If the query defines a query boost through the query language (e.g: `country:France^3.0`),
     /// the two boosts (the one defined in the query, and the one defined in the `QueryParser`)
     /// are multiplied together.
+    ///
+    /// This boost replaces the default boost defined in the schema through
+    /// [`TextFieldIndexing::set_boost`](crate::schema::TextFieldIndexing::set_boost).
     pub fn set_field_boost(&mut self, field: Field, boost: Score) {
         self.boost.insert(field, boost);
     }
@@ -320,6 +444,57 @@ impl QueryParser {
         );
     }
 
+    /// Registers an alias for a field.
+    ///
+    /// In queries, `alias:foo` is then equivalent to `field_name:foo`, and for JSON fields,
+    /// `alias.path:foo` to `field_name.path:foo`. An alias takes precedence over a schema
+    /// field with the same name.
+    ///
+    /// Aliases only live in the `QueryParser`: the schema, and therefore the index, is left
+    /// untouched.
+    pub fn set_field_alias(&mut self, alias: &str, field: Field) {
+        self.aliases.insert(alias.to_string(), field);
+    }
+
+    /// Sets the maximum distance users can request through the `~` fuzzy operator.
+    ///
+    /// Queries requesting a larger distance are rejected with
+    /// [`QueryParserError::FuzzyDistanceTooLarge`]. By default, and at most, the maximum
+    /// distance is 2: larger values are capped.
+    pub fn set_max_fuzzy_distance(&mut self, max_distance: u8) {
+        self.max_fuzzy_distance = max_distance.min(MAX_SUPPORTED_FUZZY_DISTANCE);
+    }
+
+    /// Sets whether wildcard terms may start with a wildcard, e.g. `*son`.
+    ///
+    /// Such terms have to scan the whole term dictionary of the fields they target, so they
+    /// are rejected with [`QueryParserError::LeadingWildcardNotAllowed`] by default.
+    pub fn set_allow_leading_wildcard(&mut self, allow_leading_wildcard: bool) {
+        self.allow_leading_wildcard = allow_leading_wildcard;
+    }
+
+    /// Sets whether queries made only of excluding clauses, e.g. `-status:deleted`, are
+    /// allowed.
+    ///
+    /// Such queries match all of the documents except the excluded ones. They are rejected
+    /// with [`QueryParserError::AllButQueryForbidden`] by default.
+    pub fn set_allow_pure_negation(&mut self, allow_pure_negation: bool) {
+        self.allow_pure_negation = allow_pure_negation;
+    }
+
+    /// Sets how the queries a term expands to are combined when it targets several fields,
+    /// e.g. the default fields.
+    ///
+    /// By default, the scores of the per-field queries are summed, as in a boolean `OR`, so a
+    /// term matching in several fields outranks a term matching in a single field.
+    /// With a tie breaker, the per-field queries are combined in a [`DisjunctionMaxQuery`]: the
+    /// score is the best per-field score, plus `tie_breaker` times the sum of the other
+    /// per-field scores. A tie breaker of `0.0` only keeps the best score, while `1.0` is
+    /// equivalent to the sum.
+    pub fn set_disjunction_max_tie_breaker(&mut self, tie_breaker: Option<Score>) {
+        self.disjunction_max_tie_breaker = tie_breaker;
+    }
+
     /// Parse a query
     ///
     /// Note that `parse_query` returns an error if the input
@@ -329,6 +504,23 @@ impl QueryParser {
         Ok(convert_to_query(&self.fuzzy, logical_ast))
     }
 
+    /// Parse a query and returns it alongside the set of fields it references.
+    ///
+    /// Unqualified terms are reported with the default fields they got expanded to.
+    /// This makes it possible to reject a query targeting a field the user is not
+    /// allowed to search, without having to parse it twice.
+    ///
+    /// Like `parse_query`, it returns an error if the input is not a valid query.
+    pub fn parse_query_with_fields(
+        &self,
+        query: &str,
+    ) -> Result<(Box<dyn Query>, HashSet<Field>), QueryParserError> {
+        let logical_ast = self.parse_query_to_logical_ast(query)?;
+        let mut fields = HashSet::new();
+        logical_ast.collect_fields(&mut fields);
+        Ok((convert_to_query(&self.fuzzy, logical_ast), fields))
+    }
+
     /// Parse a query leniently
     ///
     /// This variant parses invalid query on a best effort basis. If some part of the query can't
@@ -411,7 +603,9 @@ impl QueryParser {
             }
         }
         if all_negative(&ast) {
-            err.push(QueryParserError::AllButQueryForbidden);
+            if !self.allow_pure_negation {
+                err.push(QueryParserError::AllButQueryForbidden);
+            }
             make_non_negative(&mut ast);
         }
         (ast, err)
@@ -465,13 +659,13 @@ impl QueryParser {
                     // This should have been seen earlier really.
                     QueryParserError::FieldNotIndexed(field_entry.name().to_string())
                 })?;
-                let mut text_analyzer =
-                    self.tokenizer_manager
-                        .get(option.tokenizer())
-                        .ok_or_else(|| QueryParserError::UnknownTokenizer {
-                            field: field_entry.name().to_string(),
-                            tokenizer: option.tokenizer().to_string(),
-                        })?;
+                let mut text_analyzer = self
+                    .tokenizer_manager
+                    .get(option.search_tokenizer())
+                    .ok_or_else(|| QueryParserError::UnknownTokenizer {
+                        field: field_entry.name().to_string(),
+                        tokenizer: option.search_tokenizer().to_string(),
+                    })?;
                 let mut terms: Vec<Term> = Vec::new();
                 let mut token_stream = text_analyzer.token_stream(phrase);
                 token_stream.process(&mut |token| {
@@ -578,10 +772,10 @@ impl QueryParser {
                 })?;
                 let mut text_analyzer = self
                     .tokenizer_manager
-                    .get(indexing_options.tokenizer())
+                    .get(indexing_options.search_tokenizer())
                     .ok_or_else(|| QueryParserError::UnknownTokenizer {
                         field: field_name.to_string(),
-                        tokenizer: indexing_options.tokenizer().to_string(),
+                        tokenizer: indexing_options.search_tokenizer().to_string(),
                     })?;
                 Ok(generate_literals_for_str(
                     field_name,
@@ -591,9 +785,7 @@ impl QueryParser {
                     prefix,
                     indexing_options,
                     &mut text_analyzer,
-                )?
-                .into_iter()
-                .collect())
+                )?)
             }
             FieldType::JsonObject(ref json_options) => generate_literals_for_json_object(
                 field_name,
@@ -618,6 +810,13 @@ impl QueryParser {
                 Ok(vec![LogicalLiteral::Term(bytes_term)])
             }
             FieldType::IpAddr(_) => {
+                if let Some((ip_addr, prefix_len)) = phrase.split_once('/') {
+                    let subnet = parse_ip_subnet(ip_addr, prefix_len)?;
+                    return Ok(vec![LogicalLiteral::Range {
+                        lower: Bound::Included(Term::from_field_ip_addr(field, *subnet.start())),
+                        upper: Bound::Included(Term::from_field_ip_addr(field, *subnet.end())),
+                    }]);
+                }
                 let ip_v6 = IpAddr::from_str(phrase)?.into_ipv6_addr();
                 let term = Term::from_field_ip_addr(field, ip_v6);
                 Ok(vec![LogicalLiteral::Term(term)])
@@ -633,6 +832,31 @@ impl QueryParser {
         }
     }
 
+    /// Returns the default occur for a member of a clause, taking in account
+    /// the field specific overrides.
+    fn default_occur_for_ast(&self, user_input_ast: &UserInputAst) -> Occur {
+        let full_path_opt = match user_input_ast {
+            UserInputAst::Leaf(leaf) => match leaf.as_ref() {
+                UserInputLeaf::Literal(literal) => literal.field_name.as_deref(),
+                UserInputLeaf::Range { field, .. } | UserInputLeaf::Set { field, .. } => {
+                    field.as_deref()
+                }
+                UserInputLeaf::Exists { field } => Some(field.as_str()),
+                UserInputLeaf::All => None,
+            },
+            UserInputAst::Boost(ast, _) => return self.default_occur_for_ast(ast),
+            UserInputAst::Clause(_) => None,
+        };
+        let conjunction_opt = full_path_opt
+            .and_then(|full_path| self.split_full_path(full_path))
+            .and_then(|(field, _)| self.conjunction_by_default_for_field.get(&field));
+        match conjunction_opt {
+            Some(true) => Occur::Must,
+            Some(false) => Occur::Should,
+            None => self.default_occur(),
+        }
+    }
+
     fn resolve_bound(
         &self,
         field: Field,
@@ -656,21 +880,25 @@ impl QueryParser {
     ) -> (LogicalAst, Vec<QueryParserError>) {
         match user_input_ast {
             UserInputAst::Clause(sub_queries) => {
-                let default_occur = self.default_occur();
                 let mut logical_sub_queries: Vec<(Occur, LogicalAst)> = Vec::new();
                 let mut errors = Vec::new();
                 for (occur_opt, sub_ast) in sub_queries {
+                    let occur = occur_opt.unwrap_or_else(|| self.default_occur_for_ast(&sub_ast));
                     let (sub_ast, mut sub_errors) =
                         self.compute_logical_ast_with_occur_lenient(sub_ast);
-                    let occur = occur_opt.unwrap_or(default_occur);
                     logical_sub_queries.push((occur, sub_ast));
                     errors.append(&mut sub_errors);
                 }
                 (LogicalAst::Clause(logical_sub_queries), errors)
             }
             UserInputAst::Boost(ast, boost) => {
-                let (ast, errors) = self.compute_logical_ast_with_occur_lenient(*ast);
-                (ast.boost(boost as Score), errors)
+                let (ast, mut errors) = self.compute_logical_ast_with_occur_lenient(*ast);
+                let boost = boost as Score;
+                if !boost.is_finite() || boost <= 0.0 {
+                    errors.push(QueryParserError::InvalidBoost(boost.to_string()));
+                    return (ast, errors);
+                }
+                (ast.boost(boost), errors)
             }
             UserInputAst::Leaf(leaf) => {
                 let (ast, errors) = self.compute_logical_ast_from_leaf_lenient(*leaf);
@@ -684,8 +912,61 @@ impl QueryParser {
         }
     }
 
+    fn resolve_fuzzy_distance(
+        &self,
+        distance: Option<u32>,
+    ) -> Result<Option<u8>, QueryParserError> {
+        let Some(distance) = distance else {
+            return Ok(None);
+        };
+        if distance > self.max_fuzzy_distance as u32 {
+            return Err(QueryParserError::FuzzyDistanceTooLarge {
+                distance,
+                max_distance: self.max_fuzzy_distance,
+            });
+        }
+        Ok(Some(distance as u8))
+    }
+
+    /// Returns a wildcard literal if `phrase` contains wildcards and `field` is a text field.
+    fn compute_wildcard_literal(
+        &self,
+        field: Field,
+        json_path: &str,
+        phrase: &str,
+    ) -> Result<Option<LogicalLiteral>, QueryParserError> {
+        let field_type = self.schema.get_field_entry(field).field_type();
+        if !json_path.is_empty()
+            || !matches!(field_type, FieldType::Str(_))
+            || !field_type.is_indexed()
+            || !has_wildcard(phrase)
+        {
+            return Ok(None);
+        }
+        if !self.allow_leading_wildcard && has_leading_wildcard(phrase) {
+            return Err(QueryParserError::LeadingWildcardNotAllowed(
+                phrase.to_string(),
+            ));
+        }
+        let wildcard_query = WildcardQuery::builder(phrase, field)
+            .allow_leading_wildcard(true)
+            .build()
+            .map_err(|err| QueryParserError::UnsupportedQuery(err.to_string()))?;
+        Ok(Some(LogicalLiteral::Wildcard(wildcard_query)))
+    }
+
     fn field_boost(&self, field: Field) -> Score {
-        self.boost.get(&field).cloned().unwrap_or(1.0)
+        if let Some(boost) = self.boost.get(&field) {
+            return *boost;
+        }
+        let text_indexing_options = match self.schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => text_options.get_indexing_options(),
+            FieldType::JsonObject(json_options) => json_options.get_text_indexing_options(),
+            _ => None,
+        };
+        text_indexing_options
+            .map(|text_indexing_options| text_indexing_options.boost())
+            .unwrap_or(1.0)
     }
 
     fn default_indexed_json_fields(&self) -> impl Iterator<Item = Field> + '_ {
@@ -756,9 +1037,30 @@ impl QueryParser {
             UserInputLeaf::Literal(literal) => {
                 let term_phrases: Vec<(Field, &str, &str)> =
                     try_tuple!(self.compute_path_triplets_for_literal(&literal));
+                let fuzzy_distance: Option<u8> =
+                    try_tuple!(self.resolve_fuzzy_distance(literal.fuzzy));
+                let is_wildcard_candidate = literal.delimiter == Delimiter::None
+                    && literal.fuzzy.is_none()
+                    && !literal.prefix;
                 let mut asts: Vec<LogicalAst> = Vec::new();
                 let mut errors: Vec<QueryParserError> = Vec::new();
                 for (field, json_path, phrase) in term_phrases {
+                    if is_wildcard_candidate {
+                        match self.compute_wildcard_literal(field, json_path, phrase) {
+                            Ok(Some(wildcard_literal)) => {
+                                let boost = self.field_boost(field);
+                                asts.push(
+                                    LogicalAst::Leaf(Box::new(wildcard_literal)).boost(boost),
+                                );
+                                continue;
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                errors.push(e);
+                                continue;
+                            }
+                        }
+                    }
                     let unboosted_asts = match self.compute_logical_ast_for_leaf(
                         field,
                         json_path,
@@ -772,6 +1074,17 @@ impl QueryParser {
                             continue;
                         }
                     };
+                    let unboosted_asts = if let Some(distance) = fuzzy_distance {
+                        match make_fuzzy_literals(unboosted_asts, distance) {
+                            Ok(asts) => asts,
+                            Err(e) => {
+                                errors.push(e);
+                                continue;
+                            }
+                        }
+                    } else {
+                        unboosted_asts
+                    };
                     for ast in unboosted_asts {
                         // Apply some field specific boost defined at the query parser level.
                         let boost = self.field_boost(field);
@@ -780,6 +1093,8 @@ impl QueryParser {
                 }
                 let result_ast: LogicalAst = if asts.len() == 1 {
                     asts.into_iter().next().unwrap()
+                } else if let Some(tie_breaker) = self.disjunction_max_tie_breaker {
+                    LogicalAst::DisjunctionMax(asts, tie_breaker)
                 } else {
                     LogicalAst::Clause(asts.into_iter().map(|ast| (Occur::Should, ast)).collect())
                 };
@@ -858,11 +1173,55 @@ impl QueryParser {
     }
 }
 
+/// Turns the text terms of `literals` into fuzzy terms, as requested by the `~` operator.
+///
+/// Non-text terms emitted for a json path are kept as exact terms.
+fn make_fuzzy_literals(
+    literals: Vec<LogicalLiteral>,
+    distance: u8,
+) -> Result<Vec<LogicalLiteral>, QueryParserError> {
+    literals
+        .into_iter()
+        .map(|literal| match literal {
+            LogicalLiteral::Term(term) => match term.typ() {
+                Type::Str if distance == 0 => Ok(LogicalLiteral::Term(term)),
+                Type::Str => Ok(LogicalLiteral::Fuzzy { term, distance }),
+                Type::Json if distance > 0 && term.value().json_path_type() == Some(Type::Str) => {
+                    Ok(LogicalLiteral::Fuzzy { term, distance })
+                }
+                Type::Json => Ok(LogicalLiteral::Term(term)),
+                _ => Err(QueryParserError::UnsupportedQuery(
+                    "The fuzzy operator is only supported on text terms.".to_string(),
+                )),
+            },
+            _ => Err(QueryParserError::UnsupportedQuery(
+                "The fuzzy operator is only supported on single terms.".to_string(),
+            )),
+        })
+        .collect()
+}
+
 fn convert_literal_to_query(
     fuzzy: &FxHashMap<Field, Fuzzy>,
     logical_literal: LogicalLiteral,
 ) -> Box<dyn Query> {
     match logical_literal {
+        LogicalLiteral::Fuzzy { term, distance } => {
+            // The distance requested in the query overrides the one configured for the field.
+            let (prefix, transpose_cost_one) = fuzzy
+                .get(&term.field())
+                .map(|fuzzy| (fuzzy.prefix, fuzzy.transpose_cost_one))
+                .unwrap_or((false, true));
+            if prefix {
+                Box::new(FuzzyTermQuery::new_prefix(
+                    term,
+                    distance,
+                    transpose_cost_one,
+                ))
+            } else {
+                Box::new(FuzzyTermQuery::new(term, distance, transpose_cost_one))
+            }
+        }
         LogicalLiteral::Term(term) => {
             if let Some(fuzzy) = fuzzy.get(&term.field()) {
                 if fuzzy.prefix {
@@ -895,6 +1254,7 @@ fn convert_literal_to_query(
         }
         LogicalLiteral::Range { lower, upper } => Box::new(RangeQuery::new(lower, upper)),
         LogicalLiteral::Set { elements, .. } => Box::new(TermSetQuery::new(elements)),
+        LogicalLiteral::Wildcard(wildcard_query) => Box::new(wildcard_query),
         LogicalLiteral::All => Box::new(AllQuery),
     }
 }
@@ -907,36 +1267,45 @@ fn generate_literals_for_str(
     prefix: bool,
     indexing_options: &TextFieldIndexing,
     text_analyzer: &mut TextAnalyzer,
-) -> Result<Option<LogicalLiteral>, QueryParserError> {
+) -> Result<Vec<LogicalLiteral>, QueryParserError> {
     let mut terms: Vec<(usize, Term)> = Vec::new();
+    // With a search tokenizer, a single word expanded into several tokens sharing its position
+    // and offsets, e.g. by a `SynonymFilter`, matches any of these tokens.
+    let mut is_single_word = true;
+    let mut first_token_span: Option<(usize, usize, usize)> = None;
     let mut token_stream = text_analyzer.token_stream(phrase);
     token_stream.process(&mut |token| {
         let term = Term::from_field_text(field, &token.text);
         terms.push((token.position, term));
+        let token_span = (token.position, token.offset_from, token.offset_to);
+        if *first_token_span.get_or_insert(token_span) != token_span {
+            is_single_word = false;
+        }
     });
-    if terms.len() <= 1 {
+    let is_expanded_word = is_single_word && !prefix && indexing_options.has_search_tokenizer();
+    if terms.len() <= 1 || is_expanded_word {
         if prefix {
             return Err(QueryParserError::PhrasePrefixRequiresAtLeastTwoTerms {
                 phrase: phrase.to_owned(),
-                tokenizer: indexing_options.tokenizer().to_owned(),
+                tokenizer: indexing_options.search_tokenizer().to_owned(),
             });
         }
-        let term_literal_opt = terms
+        let term_literals = terms
             .into_iter()
-            .next()
-            .map(|(_, term)| LogicalLiteral::Term(term));
-        return Ok(term_literal_opt);
+            .map(|(_, term)| LogicalLiteral::Term(term))
+            .collect();
+        return Ok(term_literals);
     }
     if !indexing_options.index_option().has_positions() {
         return Err(QueryParserError::FieldDoesNotHavePositionsIndexed(
             field_name.to_string(),
         ));
     }
-    Ok(Some(LogicalLiteral::Phrase {
+    Ok(vec![LogicalLiteral::Phrase {
         terms,
         slop,
         prefix,
-    }))
+    }])
 }
 
 fn generate_literals_for_json_object(
@@ -952,10 +1321,10 @@ fn generate_literals_for_json_object(
         QueryParserError::FieldNotIndexed(field_name.to_string())
     })?;
     let mut text_analyzer = tokenizer_manager
-        .get(text_options.tokenizer())
+        .get(text_options.search_tokenizer())
         .ok_or_else(|| QueryParserError::UnknownTokenizer {
             field: field_name.to_string(),
-            tokenizer: text_options.tokenizer().to_string(),
+            tokenizer: text_options.search_tokenizer().to_string(),
         })?;
     let index_record_option = text_options.index_option();
     let mut logical_literals = Vec::new();
@@ -1019,6 +1388,16 @@ fn convert_to_query(fuzzy: &FxHashMap<Field, Fuzzy>, logical_ast: LogicalAst) ->
             let boosted_query = BoostQuery::new(query, boost);
             Box::new(boosted_query)
         }
+        Some(LogicalAst::DisjunctionMax(disjuncts, tie_breaker)) => {
+            let disjuncts = disjuncts
+                .into_iter()
+                .map(|disjunct| convert_to_query(fuzzy, disjunct))
+                .collect();
+            Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                disjuncts,
+                tie_breaker,
+            ))
+        }
         None => Box::new(EmptyQuery),
     }
 }
@@ -1029,6 +1408,7 @@ mod test {
 
     use super::super::logical_ast::*;
     use super::{QueryParser, QueryParserError};
+    use crate::collector::Count;
     use crate::query::Query;
     use crate::schema::{
         FacetOptions, Field, IndexRecordOption, Schema, Term, TextFieldIndexing, TextOptions, FAST,
@@ -1157,6 +1537,88 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_boost_syntax() {
+        let query_parser = make_query_parser();
+        let parse = |query: &str| format!("{:?}", query_parser.parse_query(query).unwrap());
+        assert_eq!(
+            parse("title:rust^3"),
+            r#"Boost(query=TermQuery(Term(field=0, type=Str, "rust")), boost=3)"#
+        );
+        assert_eq!(
+            parse(r#"title:"data structures"^2.5"#),
+            r#"Boost(query=PhraseQuery { field: Field(0), phrase_terms: [(0, Term(field=0, type=Str, "data")), (1, Term(field=0, type=Str, "structures"))], slop: 0 }, boost=2.5)"#
+        );
+        // The boost only applies to the preceding clause.
+        assert_eq!(
+            parse("title:a title:b^2"),
+            r#"BooleanQuery { subqueries: [(Should, TermQuery(Term(field=0, type=Str, "a"))), (Should, Boost(query=TermQuery(Term(field=0, type=Str, "b")), boost=2))], minimum_number_should_match: 1, pure_negation_matches_all: false }"#
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_invalid_boost() {
+        let query_parser = make_query_parser();
+        for query in [
+            "title:rust^0",
+            "title:rust^0.0",
+            &format!("title:rust^{}", "9".repeat(40)),
+        ] {
+            assert!(matches!(
+                query_parser.parse_query(query),
+                Err(QueryParserError::InvalidBoost(_))
+            ));
+        }
+        for query in ["title:rust^", "title:rust^-2", "title:rust^abc"] {
+            assert!(matches!(
+                query_parser.parse_query(query),
+                Err(QueryParserError::SyntaxError(_))
+            ));
+        }
+        // The lenient parser ignores the invalid boost.
+        let (query, errors) = query_parser.parse_query_lenient("title:rust^0");
+        assert_eq!(
+            format!("{query:?}"),
+            r#"TermQuery(Term(field=0, type=Str, "rust"))"#
+        );
+        assert_eq!(
+            errors,
+            vec![QueryParserError::InvalidBoost("0".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_with_fields() {
+        let query_parser = make_query_parser();
+        let schema = make_schema();
+        let fields_for_query = |query: &str| {
+            let (_, fields) = query_parser.parse_query_with_fields(query).unwrap();
+            let mut field_names: Vec<&str> = fields
+                .into_iter()
+                .map(|field| schema.get_field_name(field))
+                .collect();
+            field_names.sort();
+            field_names
+        };
+        assert_eq!(fields_for_query("title:hello"), vec!["title"]);
+        // unqualified terms target the default fields.
+        assert_eq!(fields_for_query("hello"), vec!["text", "title"]);
+        assert_eq!(
+            fields_for_query("title:a AND (signed:2 OR -(unsigned:3 +bool:true))"),
+            vec!["bool", "signed", "title", "unsigned"]
+        );
+        assert_eq!(
+            fields_for_query(r#"with_stop_words:"big wolf" float:[1.0 TO *]"#),
+            vec!["float", "with_stop_words"]
+        );
+        assert_eq!(fields_for_query("u64_ff: IN [1 2 3]"), vec!["u64_ff"]);
+        assert!(fields_for_query("*").is_empty());
+        assert_matches!(
+            query_parser.parse_query_with_fields("-nottokenized:a"),
+            Err(QueryParserError::AllButQueryForbidden)
+        );
+    }
+
     #[test]
     pub fn test_parse_nonindexed_field_yields_error() {
         let query_parser = make_query_parser();
@@ -1534,6 +1996,37 @@ mod test {
             r#"(Included(Term(field=18, type=U64, 7)) TO Included(Term(field=18, type=U64, 77)))"#,
             false,
         );
+        test_parse_query_to_logical_ast_helper(
+            "unsigned:[10 TO *]",
+            r#"(Included(Term(field=3, type=U64, 10)) TO Unbounded)"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "date:{2020-01-01T00:00:00Z TO 2021-01-01T00:00:00Z}",
+            r#"(Excluded(Term(field=9, type=Date, 2020-01-01T00:00:00Z)) TO Excluded(Term(field=9, type=Date, 2021-01-01T00:00:00Z)))"#,
+            false,
+        );
+    }
+
+    #[test]
+    pub fn test_parse_query_range_invalid_bound() {
+        let query_parser = make_query_parser();
+        assert_matches!(
+            query_parser.parse_query("unsigned:[10 TO 1a]"),
+            Err(QueryParserError::ExpectedInt(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:[a TO *]"),
+            Err(QueryParserError::ExpectedInt(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("float:[* TO 1.5x]"),
+            Err(QueryParserError::ExpectedFloat(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("date:[2020-01-01 TO *]"),
+            Err(QueryParserError::DateFormatError(_))
+        );
     }
 
     #[test]
@@ -1705,6 +2198,41 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_parse_query_conjunction_by_default_for_field() {
+        let mut query_parser = make_query_parser();
+        let schema = make_schema();
+        let title = schema.get_field("title").unwrap();
+        let text = schema.get_field("text").unwrap();
+        query_parser.set_conjunction_by_default();
+        query_parser.set_conjunction_by_default_for_field(title, false);
+        let query = query_parser
+            .parse_query_to_logical_ast("title:(a b) text:(x y)")
+            .unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"(+(Term(field=0, type=Str, "a") Term(field=0, type=Str, "b")) +Term(field=1, type=Str, "x") +Term(field=1, type=Str, "y"))"#
+        );
+
+        let mut query_parser = make_query_parser();
+        query_parser.set_conjunction_by_default_for_field(text, true);
+        let query = query_parser
+            .parse_query_to_logical_ast("title:a title:b text:x text:y")
+            .unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"(Term(field=0, type=Str, "a") Term(field=0, type=Str, "b") +Term(field=1, type=Str, "x") +Term(field=1, type=Str, "y"))"#
+        );
+        // Explicit operators still take precedence.
+        let query = query_parser
+            .parse_query_to_logical_ast("title:a text:x OR text:y")
+            .unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"(Term(field=0, type=Str, "a") Term(field=1, type=Str, "x") Term(field=1, type=Str, "y"))"#
+        );
+    }
+
     #[test]
     pub fn test_parse_query_negative() {
         test_parse_query_to_logical_ast_helper(
@@ -1764,6 +2292,109 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_query_with_field_alias() {
+        let schema = make_schema();
+        let title = schema.get_field("title").unwrap();
+        let json = schema.get_field("json").unwrap();
+        let mut query_parser =
+            QueryParser::new(schema.clone(), Vec::new(), TokenizerManager::default());
+        query_parser.set_field_alias("headline", title);
+        query_parser.set_field_alias("attrs", json);
+        // An alias shadows the schema field with the same name.
+        query_parser.set_field_alias("text", title);
+        let query = query_parser.parse_query("headline:hello").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            format!("{:?}", query_parser.parse_query("title:hello").unwrap())
+        );
+        let query = query_parser.parse_query("text:hello").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            format!("{:?}", query_parser.parse_query("title:hello").unwrap())
+        );
+        let query = query_parser.parse_query("attrs.color:red").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            format!("{:?}", query_parser.parse_query("json.color:red").unwrap())
+        );
+        assert_eq!(
+            query_parser.split_full_path("attrs.color"),
+            Some((json, "color"))
+        );
+        assert_eq!(query_parser.split_full_path("attrsy"), None);
+        assert!(matches!(
+            query_parser.parse_query("headliney:hello"),
+            Err(QueryParserError::FieldDoesNotExist(_))
+        ));
+        // Aliases do not leak into the schema.
+        assert!(schema.get_field("headline").is_err());
+    }
+
+    #[test]
+    fn test_parse_ip_subnet() {
+        let subnet = |cidr: &str| {
+            let (ip_addr, prefix_len) = cidr.split_once('/').unwrap();
+            super::parse_ip_subnet(ip_addr, prefix_len)
+                .map(|subnet| (subnet.start().to_string(), subnet.end().to_string()))
+        };
+        assert_eq!(
+            subnet("10.1.2.3/8").unwrap(),
+            (
+                "::ffff:10.0.0.0".to_string(),
+                "::ffff:10.255.255.255".to_string()
+            )
+        );
+        assert_eq!(
+            subnet("192.168.1.7/32").unwrap(),
+            (
+                "::ffff:192.168.1.7".to_string(),
+                "::ffff:192.168.1.7".to_string()
+            )
+        );
+        assert_eq!(
+            subnet("0.0.0.0/0").unwrap(),
+            (
+                "::ffff:0.0.0.0".to_string(),
+                "::ffff:255.255.255.255".to_string()
+            )
+        );
+        assert_eq!(
+            subnet("2001:db8::1/32").unwrap(),
+            (
+                "2001:db8::".to_string(),
+                "2001:db8:ffff:ffff:ffff:ffff:ffff:ffff".to_string()
+            )
+        );
+        assert_eq!(
+            subnet("::/0").unwrap(),
+            (
+                "::".to_string(),
+                "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".to_string()
+            )
+        );
+        assert!(matches!(
+            subnet("10.0.0.0/33"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        ));
+        assert!(matches!(
+            subnet("10.0.0.0/4294967295"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        ));
+        assert!(matches!(
+            subnet("2001:db8::/129"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        ));
+        assert!(matches!(
+            subnet("10.0.0.0/a"),
+            Err(QueryParserError::ExpectedInt(_))
+        ));
+        assert!(matches!(
+            subnet("10.0.0/8"),
+            Err(QueryParserError::IpFormatError(_))
+        ));
+    }
+
     #[test]
     fn test_split_full_path() {
         let mut schema_builder = Schema::builder();
@@ -1816,6 +2447,47 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn test_same_position_tokens_only_expand_with_search_tokenizer() {
+        use std::collections::HashMap;
+
+        use crate::tokenizer::SynonymFilter;
+
+        let synonyms_indexing = TextFieldIndexing::default()
+            .set_tokenizer("synonyms")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let mut schema_builder = Schema::builder();
+        let indexed = schema_builder.add_text_field(
+            "indexed",
+            TextOptions::default().set_indexing_options(synonyms_indexing.clone()),
+        );
+        let searched = schema_builder.add_text_field(
+            "searched",
+            TextOptions::default()
+                .set_indexing_options(synonyms_indexing.set_search_tokenizer("synonyms")),
+        );
+        let tokenizer_manager = TokenizerManager::default();
+        tokenizer_manager.register(
+            "synonyms",
+            TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(SynonymFilter::new(HashMap::from([(
+                    "tv".to_string(),
+                    vec!["television".to_string()],
+                )])))
+                .build(),
+        );
+        let query_parser = QueryParser::new(
+            schema_builder.build(),
+            vec![indexed, searched],
+            tokenizer_manager,
+        );
+        let query = query_parser.parse_query_to_logical_ast("tv").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            r#"("[(0, Term(field=0, type=Str, "tv")), (0, Term(field=0, type=Str, "television"))]" Term(field=1, type=Str, "tv") Term(field=1, type=Str, "television"))"#
+        );
+    }
+
     #[test]
     pub fn test_phrase_prefix() {
         test_parse_query_to_logical_ast_helper(
@@ -1834,7 +2506,7 @@ mod test {
              (Should, PhrasePrefixQuery { field: Field(1), phrase_terms: [(0, Term(field=1, \
              type=Str, \"big\")), (1, Term(field=1, type=Str, \"bad\"))], prefix: (2, \
              Term(field=1, type=Str, \"wo\")), max_expansions: 50 })], \
-             minimum_number_should_match: 1 }"
+             minimum_number_should_match: 1, pure_negation_matches_all: false }"
         );
     }
 
@@ -1898,9 +2570,9 @@ mod test {
             assert_eq!(
                 format!("{query:?}"),
                 "BooleanQuery { subqueries: [(Should, FuzzyTermQuery { term: Term(field=0, \
-                 type=Str, \"abc\"), distance: 1, transposition_cost_one: true, prefix: false }), \
-                 (Should, TermQuery(Term(field=1, type=Str, \"abc\")))], \
-                 minimum_number_should_match: 1 }"
+                 type=Str, \"abc\"), distance: 1, transposition_cost_one: true, prefix: false, \
+                 prefix_length: 0 }), (Should, TermQuery(Term(field=1, type=Str, \"abc\")))], \
+                 minimum_number_should_match: 1, pure_negation_matches_all: false }"
             );
         }
 
@@ -1917,9 +2589,296 @@ mod test {
                 format!("{query:?}"),
                 "BooleanQuery { subqueries: [(Should, TermQuery(Term(field=0, type=Str, \
                  \"abc\"))), (Should, FuzzyTermQuery { term: Term(field=1, type=Str, \"abc\"), \
-                 distance: 2, transposition_cost_one: false, prefix: true })], \
-                 minimum_number_should_match: 1 }"
+                 distance: 2, transposition_cost_one: false, prefix: true, prefix_length: 0 })], \
+                 minimum_number_should_match: 1, pure_negation_matches_all: false }"
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_fuzzy_operator() {
+        let query_parser = make_query_parser();
+        test_parse_query_to_logical_ast_helper(
+            "title:rust~2",
+            r#"Term(field=0, type=Str, "rust")~2"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:rust~",
+            r#"Term(field=0, type=Str, "rust")~1"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:rust~0",
+            r#"Term(field=0, type=Str, "rust")"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "rust~1",
+            r#"(Term(field=0, type=Str, "rust")~1 Term(field=1, type=Str, "rust")~1)"#,
+            false,
+        );
+        let query = query_parser.parse_query("title:rust~2").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "FuzzyTermQuery { term: Term(field=0, type=Str, \"rust\"), distance: 2, \
+             transposition_cost_one: true, prefix: false, prefix_length: 0 }"
+        );
+        assert_eq!(
+            query_parser.parse_query("title:rust~3").unwrap_err(),
+            QueryParserError::FuzzyDistanceTooLarge {
+                distance: 3,
+                max_distance: 2
+            }
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:2~1"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+        assert_matches!(
+            query_parser.parse_query("title:big-wolf~1"),
+            Err(QueryParserError::UnsupportedQuery(_))
+        );
+    }
+
+    #[test]
+    pub fn test_set_max_fuzzy_distance() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_max_fuzzy_distance(1);
+        assert!(query_parser.parse_query("title:rust~1").is_ok());
+        assert_eq!(query_parser.parse_query("title:rust~").map(|_| ()), Ok(()));
+        assert_eq!(
+            query_parser.parse_query("title:rust~2").unwrap_err(),
+            QueryParserError::FuzzyDistanceTooLarge {
+                distance: 2,
+                max_distance: 1
+            }
+        );
+        // Distances beyond what the fuzzy query supports are capped.
+        query_parser.set_max_fuzzy_distance(5);
+        assert_matches!(
+            query_parser.parse_query("title:rust~3"),
+            Err(QueryParserError::FuzzyDistanceTooLarge {
+                max_distance: 2,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_fuzzy_operator_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for text in ["rust", "trust", "rusty", "bust", "crate"] {
+            index_writer.add_document(doc!(title => text))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("rust"), 1);
+        assert_eq!(count("rust~0"), 1);
+        assert_eq!(count("rist~"), 1);
+        assert_eq!(count("rust~1"), 4);
+        assert_eq!(count("title:rists~2"), 2);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_wildcard_terms() {
+        test_parse_query_to_logical_ast_helper("title:ja*", r#"Wildcard(field=0, "ja*")"#, false);
+        test_parse_query_to_logical_ast_helper(
+            "j?p*n",
+            r#"(Wildcard(field=0, "j?p*n") Wildcard(field=1, "j?p*n"))"#,
+            false,
+        );
+        // Escaped and quoted wildcards are left to the tokenizer.
+        test_parse_query_to_logical_ast_helper(
+            r"title:ja\*",
+            r#"Term(field=0, type=Str, "ja")"#,
+            false,
+        );
+        test_parse_query_to_logical_ast_helper(
+            "title:\"ja*\"",
+            r#"Term(field=0, type=Str, "ja")"#,
+            false,
+        );
+        let query_parser = make_query_parser();
+        assert_eq!(
+            query_parser.parse_query("title:?ason").unwrap_err(),
+            QueryParserError::LeadingWildcardNotAllowed("?ason".to_string())
+        );
+        assert_matches!(
+            query_parser.parse_query("signed:1*"),
+            Err(QueryParserError::ExpectedInt(_))
+        );
+    }
+
+    #[test]
+    pub fn test_wildcard_terms_search() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        for text in ["japan", "jargon", "java", "ninja", "jaguar"] {
+            index_writer.add_document(doc!(title => text))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title]);
+        let count = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count(&query_parser, "title:ja*"), 4);
+        assert_eq!(count(&query_parser, "ja*n"), 2);
+        assert_eq!(count(&query_parser, "ja?a"), 1);
+        assert_eq!(count(&query_parser, "ja*n OR ninja"), 3);
+        assert!(query_parser.parse_query("?inja").is_err());
+        query_parser.set_allow_leading_wildcard(true);
+        assert_eq!(count(&query_parser, "?inja"), 1);
+        assert_eq!(count(&query_parser, "?a*"), 4);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_schema_default_field_boost() -> crate::Result<()> {
+        use crate::collector::TopDocs;
+        use crate::{assert_nearly_equals, DocAddress};
+        let mut schema_builder = Schema::builder();
+        let boosted_text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+                .set_boost(3.0),
+        );
+        let title = schema_builder.add_text_field("title", boosted_text_options);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let schema = schema_builder.build();
+        // The boost is part of the schema.
+        let schema_json = serde_json::to_string(&schema).unwrap();
+        let deserialized_schema: Schema = serde_json::from_str(&schema_json).unwrap();
+        assert_eq!(deserialized_schema, schema);
+        let index = Index::create_in_ram(schema);
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc!(title => "go", body => "rust"))?;
+        index_writer.add_document(doc!(title => "rust", body => "go"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let top_docs = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            searcher.search(&query, &TopDocs::with_limit(2)).unwrap()
+        };
+        // Both fields have the same statistics: only the boost tells the two documents apart.
+        let results = top_docs(&query_parser, "rust");
+        assert_eq!(results[0].1, DocAddress::new(0, 1));
+        assert_eq!(results[1].1, DocAddress::new(0, 0));
+        assert_nearly_equals!(results[0].0, 3.0 * results[1].0);
+        // The default boost is multiplied with the boosts of the query.
+        let results = top_docs(&query_parser, "title:rust^2 OR body:rust");
+        assert_nearly_equals!(results[0].0, 6.0 * results[1].0);
+        // ... and replaced by the boosts of the query parser.
+        query_parser.set_field_boost(title, 0.5);
+        let results = top_docs(&query_parser, "rust");
+        assert_eq!(results[0].1, DocAddress::new(0, 0));
+        assert_nearly_equals!(results[1].0, 0.5 * results[0].0);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_parse_query_disjunction_max() {
+        let mut query_parser = make_query_parser();
+        query_parser.set_disjunction_max_tie_breaker(Some(0.3));
+        let query = query_parser
+            .parse_query_to_logical_ast("+toto title:tata")
+            .unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "(+DisMax(Term(field=0, type=Str, \"toto\") | Term(field=1, type=Str, \"toto\"))~0.3 \
+             Term(field=0, type=Str, \"tata\"))"
+        );
+        // Only the terms expanding to several fields are affected.
+        let query = query_parser.parse_query("title:tata").unwrap();
+        assert_eq!(
+            format!("{query:?}"),
+            "TermQuery(Term(field=0, type=Str, \"tata\"))"
+        );
+        let query = query_parser.parse_query("toto").unwrap();
+        assert!(format!("{query:?}").starts_with("DisjunctionMaxQuery"));
+    }
+
+    #[test]
+    pub fn test_disjunction_max_scoring() -> crate::Result<()> {
+        use crate::collector::TopDocs;
+        use crate::query::TermQuery;
+        use crate::{assert_nearly_equals, DocAddress, Score};
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_for_tests()?;
+        // Matches `rust` in both fields, but weakly.
+        index_writer.add_document(doc!(
+            title => "learning rust and other languages",
+            body => "rust is mentioned once among many other words here"
+        ))?;
+        // Match `rust` in a single field, but strongly.
+        index_writer.add_document(doc!(title => "rust", body => "a book"))?;
+        index_writer.add_document(doc!(title => "cooking", body => "rust and cooking"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let field_score = |field: Field, doc: DocAddress| -> Score {
+            let query = TermQuery::new(
+                Term::from_field_text(field, "rust"),
+                IndexRecordOption::WithFreqs,
             );
+            // The explanation of a field not matching the document is an error.
+            query
+                .explain(&searcher, doc)
+                .map(|explanation| explanation.value())
+                .unwrap_or(0.0)
+        };
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let top_docs = |query_parser: &QueryParser| {
+            let query = query_parser.parse_query("rust").unwrap();
+            searcher.search(&query, &TopDocs::with_limit(3)).unwrap()
+        };
+
+        // By default, the per-field scores are summed.
+        let or_results = top_docs(&query_parser);
+        assert_eq!(or_results.len(), 3);
+        assert_eq!(or_results[0].1, DocAddress::new(0, 0));
+        for &(score, doc) in &or_results {
+            assert_nearly_equals!(score, field_score(title, doc) + field_score(body, doc));
+        }
+
+        // The best per-field score wins, plus the tie breaker times the other scores.
+        for tie_breaker in [0.0, 0.3] {
+            query_parser.set_disjunction_max_tie_breaker(Some(tie_breaker));
+            let dismax_results = top_docs(&query_parser);
+            assert_eq!(dismax_results.len(), 3);
+            for &(score, doc) in &dismax_results {
+                let (title_score, body_score) = (field_score(title, doc), field_score(body, doc));
+                let expected_score =
+                    title_score.max(body_score) + tie_breaker * title_score.min(body_score);
+                assert_nearly_equals!(score, expected_score);
+            }
+            // Unlike the sum, the strong single-field match now ranks first.
+            assert_eq!(dismax_results[0].1, DocAddress::new(0, 1));
+        }
+
+        // A tie breaker of 1 is equivalent to the sum.
+        query_parser.set_disjunction_max_tie_breaker(Some(1.0));
+        let results = top_docs(&query_parser);
+        for (&(score, doc), &(or_score, or_doc)) in results.iter().zip(&or_results) {
+            assert_eq!(doc, or_doc);
+            assert_nearly_equals!(score, or_score);
         }
+        Ok(())
     }
 }
@@ -510,6 +510,57 @@ mod tests {
         search_ip_range_test_opt(true);
     }
 
+    #[test]
+    fn search_ip_subnet_test() {
+        search_ip_subnet_test_opt(true);
+        search_ip_subnet_test_opt(false);
+    }
+
+    fn search_ip_subnet_test_opt(with_fast_field: bool) {
+        let mut schema_builder = Schema::builder();
+        let ip_field = if with_fast_field {
+            schema_builder.add_ip_addr_field("ip", INDEXED | FAST)
+        } else {
+            schema_builder.add_ip_addr_field("ip", INDEXED)
+        };
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer: IndexWriter = index.writer_for_tests().unwrap();
+            for ip_addr in [
+                "192.168.1.1",
+                "192.168.1.255",
+                "192.168.2.1",
+                "10.0.0.1",
+                "2001:db8::1",
+                "2001:db9::1",
+            ] {
+                let ip_addr = IpAddr::from_str(ip_addr).unwrap().into_ipv6_addr();
+                index_writer
+                    .add_document(doc!(ip_field => ip_addr))
+                    .unwrap();
+            }
+            index_writer.commit().unwrap();
+        }
+        let searcher = index.reader().unwrap().searcher();
+        let get_num_hits = |query: &str| {
+            let query = QueryParser::for_index(&index, vec![])
+                .parse_query(query)
+                .unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(get_num_hits("ip:192.168.1.0/24"), 2);
+        assert_eq!(get_num_hits("ip:192.168.1.1/32"), 1);
+        assert_eq!(get_num_hits("ip:192.168.0.0/16"), 3);
+        // IPv4 subnets do not match IPv6 addresses, even with an empty prefix.
+        assert_eq!(get_num_hits("ip:0.0.0.0/0"), 4);
+        // IPv6 subnets contain colons, so they need to be quoted.
+        assert_eq!(get_num_hits("ip:\"2001:db8::/32\""), 1);
+        assert_eq!(get_num_hits("ip:\"::/0\""), 6);
+        // The IPv4-mapped IPv6 notation of a subnet is equivalent to its IPv4 notation.
+        assert_eq!(get_num_hits("ip:\"::ffff:192.168.1.0/120\""), 2);
+        assert_eq!(get_num_hits("ip:192.168.1.0/24 OR ip:\"2001:db9::/32\""), 3);
+    }
+
     fn search_ip_range_test_opt(with_fast_field: bool) {
         let mut schema_builder = Schema::builder();
         let ip_field = if with_fast_field {
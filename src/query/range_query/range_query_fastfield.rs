@@ -148,12 +148,19 @@ impl Weight for FastFieldRangeWeight {
             else {
                 return Ok(Box::new(EmptyScorer));
             };
+            let Some((min_value, max_value)) = ip_addr_column.min_max() else {
+                return Ok(Box::new(EmptyScorer));
+            };
             let value_range = bound_range_inclusive_ip(
                 &bounds.lower_bound,
                 &bounds.upper_bound,
-                ip_addr_column.min_value(),
-                ip_addr_column.max_value(),
+                min_value,
+                max_value,
             );
+            // The segment can be skipped if its values are all out of the range.
+            if value_range.start() > &max_value || value_range.end() < &min_value {
+                return Ok(Box::new(EmptyScorer));
+            }
             let docset = RangeDocSet::new(value_range, ip_addr_column);
             Ok(Box::new(ConstScorer::new(docset, boost)))
         } else if field_type.is_str() {
@@ -402,15 +409,22 @@ fn search_on_u64_ff(
     boost: Score,
     bounds: BoundsRange<u64>,
 ) -> crate::Result<Box<dyn Scorer>> {
+    let Some((min_value, max_value)) = column.min_max() else {
+        return Ok(Box::new(EmptyScorer));
+    };
     #[expect(clippy::reversed_empty_ranges)]
     let value_range = bound_to_value_range(
         &bounds.lower_bound,
         &bounds.upper_bound,
-        column.min_value(),
-        column.max_value(),
+        min_value,
+        max_value,
     )
     .unwrap_or(1..=0); // empty range
-    if value_range.is_empty() {
+
+    // The segment can be skipped if its values are all out of the range, without
+    // evaluating a single document.
+    if value_range.is_empty() || *value_range.start() > max_value || *value_range.end() < min_value
+    {
         return Ok(Box::new(EmptyScorer));
     }
     let docset = RangeDocSet::new(value_range, column);
@@ -486,7 +500,7 @@ mod tests {
     use crate::collector::{Count, TopDocs};
     use crate::fastfield::FastValue;
     use crate::query::range_query::range_query_fastfield::FastFieldRangeWeight;
-    use crate::query::{QueryParser, RangeQuery, Weight};
+    use crate::query::{EmptyScorer, QueryParser, RangeQuery, Weight};
     use crate::schema::{
         DateOptions, Field, NumericOptions, Schema, SchemaBuilder, FAST, INDEXED, STORED, STRING,
         TEXT,
@@ -1128,6 +1142,72 @@ mod tests {
         assert_eq!(scorer.doc(), TERMINATED);
     }
 
+    #[test]
+    fn test_range_query_skips_segment_out_of_range() {
+        let mut schema_builder = SchemaBuilder::new();
+        let field = schema_builder.add_u64_field("test_field", FAST);
+        let schema = schema_builder.build();
+        let index = Index::create_in_ram(schema);
+        let mut writer: IndexWriter = index.writer_for_tests().unwrap();
+        for val in 0..10u64 {
+            writer.add_document(doc!(field=>val)).unwrap();
+        }
+        writer.commit().unwrap();
+        for val in 100..110u64 {
+            writer.add_document(doc!(field=>val)).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let low_segment_ord = searcher
+            .segment_readers()
+            .iter()
+            .position(|segment_reader| {
+                let column = segment_reader.fast_fields().u64("test_field").unwrap();
+                column.min_max() == Some((0, 9))
+            })
+            .unwrap();
+
+        let range_weight = |lower: u64, upper: u64| {
+            FastFieldRangeWeight::new(BoundsRange::new(
+                Bound::Included(Term::from_field_u64(field, lower)),
+                Bound::Included(Term::from_field_u64(field, upper)),
+            ))
+        };
+        let is_skipped = |range_weight: &FastFieldRangeWeight, segment_ord: usize| {
+            let scorer = range_weight
+                .scorer(searcher.segment_reader(segment_ord as u32), 1.0f32)
+                .unwrap();
+            scorer.is::<EmptyScorer>()
+        };
+        let high_segment_ord = 1 - low_segment_ord;
+
+        // Above the max of the low segment.
+        let range_weight_50_200 = range_weight(50, 200);
+        assert!(is_skipped(&range_weight_50_200, low_segment_ord));
+        assert!(!is_skipped(&range_weight_50_200, high_segment_ord));
+        // Below the min of the high segment.
+        let range_weight_5_50 = range_weight(5, 50);
+        assert!(!is_skipped(&range_weight_5_50, low_segment_ord));
+        assert!(is_skipped(&range_weight_5_50, high_segment_ord));
+        // In between the two segments.
+        let range_weight_20_30 = range_weight(20, 30);
+        assert!(is_skipped(&range_weight_20_30, low_segment_ord));
+        assert!(is_skipped(&range_weight_20_30, high_segment_ord));
+
+        let count = |query: RangeQuery| searcher.search(&query, &Count).unwrap();
+        let range_query = |lower: u64, upper: u64| {
+            RangeQuery::new(
+                Bound::Included(Term::from_field_u64(field, lower)),
+                Bound::Included(Term::from_field_u64(field, upper)),
+            )
+        };
+        assert_eq!(count(range_query(50, 200)), 10);
+        assert_eq!(count(range_query(5, 50)), 5);
+        assert_eq!(count(range_query(20, 30)), 0);
+        assert_eq!(count(range_query(9, 100)), 2);
+    }
+
     #[test]
     fn range_regression3_test() {
         let ops = vec![doc_from_id_1(1), doc_from_id_1(2), doc_from_id_1(3)];
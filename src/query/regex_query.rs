@@ -11,8 +11,14 @@ use crate::schema::Field;
 /// containing a specific term that matches
 /// a regex pattern.
 ///
-/// Wildcard queries (e.g. ho*se) can be achieved
-/// by converting them to their regex counterparts.
+/// For wildcard queries (e.g. ho*se), see
+/// [`WildcardQuery`](crate::query::WildcardQuery).
+///
+/// By default, the pattern is anchored: it has to match the
+/// whole term, not just a part of it. The pattern `a.c` matches
+/// the term `abc`, but not `xabcy`. Use [`RegexQuery::new_unanchored`]
+/// to match terms containing the pattern anywhere, or
+/// [`RegexQuery::builder`] to also make the matching case-insensitive.
 ///
 /// ```rust
 /// use tantivy::collector::Count;
@@ -67,6 +73,24 @@ impl RegexQuery {
         Ok(RegexQuery::from_regex(regex, field))
     }
 
+    /// Creates a new RegexQuery matching all of the terms containing
+    /// the pattern anywhere, e.g. `a.c` matches `xabcy`.
+    pub fn new_unanchored(regex_pattern: &str, field: Field) -> crate::Result<Self> {
+        RegexQuery::builder(regex_pattern, field)
+            .anchored(false)
+            .build()
+    }
+
+    /// Returns a [`RegexQueryBuilder`], to configure how the pattern is compiled.
+    pub fn builder(regex_pattern: &str, field: Field) -> RegexQueryBuilder {
+        RegexQueryBuilder {
+            regex_pattern: regex_pattern.to_string(),
+            field,
+            anchored: true,
+            case_insensitive: false,
+        }
+    }
+
     /// Creates a new RegexQuery from a fully built Regex
     pub fn from_regex<T: Into<Arc<Regex>>>(regex: T, field: Field) -> Self {
         RegexQuery {
@@ -80,6 +104,45 @@ impl RegexQuery {
     }
 }
 
+/// Builder for a [`RegexQuery`].
+#[derive(Debug, Clone)]
+pub struct RegexQueryBuilder {
+    regex_pattern: String,
+    field: Field,
+    anchored: bool,
+    case_insensitive: bool,
+}
+
+impl RegexQueryBuilder {
+    /// Sets whether the pattern has to match the whole term (the default),
+    /// or may occur anywhere in the term.
+    pub fn anchored(mut self, anchored: bool) -> Self {
+        self.anchored = anchored;
+        self
+    }
+
+    /// Sets whether the pattern is compiled case-insensitively.
+    ///
+    /// Defaults to false.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Compiles the pattern and builds the `RegexQuery`.
+    pub fn build(self) -> crate::Result<RegexQuery> {
+        let mut regex_pattern = if self.anchored {
+            self.regex_pattern
+        } else {
+            format!("(?s:.*)(?:{})(?s:.*)", self.regex_pattern)
+        };
+        if self.case_insensitive {
+            regex_pattern.insert_str(0, "(?i)");
+        }
+        RegexQuery::from_pattern(&regex_pattern, self.field)
+    }
+}
+
 impl Query for RegexQuery {
     fn weight(&self, _enabled_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
         Ok(Box::new(self.specialized_weight()))
@@ -93,8 +156,8 @@ mod test {
     use tantivy_fst::Regex;
 
     use super::RegexQuery;
-    use crate::collector::TopDocs;
-    use crate::schema::{Field, Schema, TEXT};
+    use crate::collector::{Count, TopDocs};
+    use crate::schema::{Field, Schema, STRING, TEXT};
     use crate::{assert_nearly_equals, Index, IndexReader, IndexWriter};
 
     fn build_test_index() -> crate::Result<(IndexReader, Field)> {
@@ -188,4 +251,42 @@ mod test {
             res => panic!("unexpected result: {res:?}"),
         }
     }
+
+    #[test]
+    pub fn test_regex_query_unanchored() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let code_field = schema_builder.add_text_field("code", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer: IndexWriter = index.writer_for_tests()?;
+            index_writer.add_document(doc!(code_field => "xabcy"))?;
+            index_writer.add_document(doc!(code_field => "XABCY"))?;
+            index_writer.add_document(doc!(code_field => "ab"))?;
+            index_writer.commit()?;
+        }
+        let searcher = index.reader()?.searcher();
+        let count = |query: RegexQuery| searcher.search(&query, &Count).unwrap();
+
+        assert_eq!(count(RegexQuery::from_pattern("a.c", code_field)?), 0);
+        assert_eq!(count(RegexQuery::new_unanchored("a.c", code_field)?), 1);
+        assert_eq!(count(RegexQuery::new_unanchored("a|z", code_field)?), 2);
+        assert_eq!(
+            count(
+                RegexQuery::builder("a.c", code_field)
+                    .anchored(false)
+                    .case_insensitive(true)
+                    .build()?
+            ),
+            2
+        );
+        assert_eq!(
+            count(
+                RegexQuery::builder("xabcy", code_field)
+                    .case_insensitive(true)
+                    .build()?
+            ),
+            2
+        );
+        Ok(())
+    }
 }
@@ -114,7 +114,7 @@ impl Automaton for SetDfaWrapper {
 #[cfg(test)]
 mod tests {
     use crate::collector::TopDocs;
-    use crate::query::{QueryParser, TermSetQuery};
+    use crate::query::{Query, QueryParser, TermSetQuery};
     use crate::schema::{Schema, TEXT};
     use crate::{assert_nearly_equals, Index, IndexWriter, Term};
 
@@ -253,4 +253,45 @@ mod tests {
         assert_eq!(top_docs.len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn test_term_set_query_large_numeric_set() -> crate::Result<()> {
+        use crate::collector::DocSetCollector;
+        use crate::query::{BooleanQuery, TermQuery};
+        use crate::schema::{IndexRecordOption, INDEXED};
+
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_u64_field("id", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for id_value in 0..10_000u64 {
+            index_writer.add_document(doc!(id => id_value))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        // Thousands of ids, some of which are not in the index.
+        let id_values: Vec<u64> = (0..3_000u64).map(|i| i * 7 % 12_000).collect();
+        let terms: Vec<Term> = id_values
+            .iter()
+            .map(|&id_value| Term::from_field_u64(id, id_value))
+            .collect();
+        let term_set_docs = searcher.search(&TermSetQuery::new(terms.clone()), &DocSetCollector)?;
+        let boolean_or = BooleanQuery::union(
+            terms
+                .into_iter()
+                .map(|term| {
+                    Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>
+                })
+                .collect(),
+        );
+        let boolean_or_docs = searcher.search(&boolean_or, &DocSetCollector)?;
+        let num_expected_docs = id_values
+            .iter()
+            .filter(|&&id_value| id_value < 10_000)
+            .count();
+        assert_eq!(term_set_docs.len(), num_expected_docs);
+        assert_eq!(term_set_docs, boolean_or_docs);
+        Ok(())
+    }
 }
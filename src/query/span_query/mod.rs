@@ -0,0 +1,208 @@
+mod span_query;
+mod span_weight;
+mod spans;
+
+pub use self::span_query::SpanQuery;
+
+#[cfg(test)]
+mod tests {
+    use super::SpanQuery;
+    use crate::collector::{Count, DocSetCollector};
+    use crate::query::{EnableScoring, PhraseQuery, Query};
+    use crate::schema::{Field, Schema, STRING, TEXT};
+    use crate::{DocAddress, Index, IndexWriter, Searcher, Term};
+
+    fn create_searcher(texts: &[&str]) -> crate::Result<(Searcher, Field)> {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for &text in texts {
+            index_writer.add_document(doc!(text_field => text))?;
+        }
+        index_writer.commit()?;
+        Ok((index.reader()?.searcher(), text_field))
+    }
+
+    fn matching_docs(searcher: &Searcher, query: &dyn Query) -> crate::Result<Vec<u32>> {
+        let mut docs: Vec<u32> = searcher
+            .search(query, &DocSetCollector)?
+            .into_iter()
+            .map(|doc_address: DocAddress| doc_address.doc_id)
+            .collect();
+        docs.sort_unstable();
+        Ok(docs)
+    }
+
+    #[test]
+    fn test_span_near_slop_and_order() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&["a b c", "a x x b", "b a", "a"])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        let near =
+            |slop: u32, in_order: bool| SpanQuery::near(vec![term("a"), term("b")], slop, in_order);
+        assert_eq!(matching_docs(&searcher, &near(0, true))?, vec![0]);
+        assert_eq!(matching_docs(&searcher, &near(2, true))?, vec![0, 1]);
+        assert_eq!(matching_docs(&searcher, &near(0, false))?, vec![0, 2]);
+        assert_eq!(matching_docs(&searcher, &near(2, false))?, vec![0, 1, 2]);
+        // An exact ordered span near is equivalent to a phrase query.
+        let phrase_query = PhraseQuery::new(vec![
+            Term::from_field_text(field, "a"),
+            Term::from_field_text(field, "b"),
+        ]);
+        assert_eq!(
+            matching_docs(&searcher, &phrase_query)?,
+            matching_docs(&searcher, &near(0, true))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_near_duplicate_term() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&["a b", "a b a", "b a x a"])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        // The term has to occur twice.
+        let near = |slop: u32| SpanQuery::near(vec![term("a"), term("a")], slop, false);
+        assert_eq!(matching_docs(&searcher, &near(10))?, vec![1, 2]);
+        assert_eq!(matching_docs(&searcher, &near(1))?, vec![1, 2]);
+        assert_eq!(matching_docs(&searcher, &near(0))?, Vec::<u32>::new());
+        // The term overlapping with the first clause is skipped for the other one.
+        let (searcher, field) = create_searcher(&["x y", "x"])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        let near = SpanQuery::near(
+            vec![term("x"), SpanQuery::or(vec![term("x"), term("y")])],
+            0,
+            false,
+        );
+        assert_eq!(matching_docs(&searcher, &near)?, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_near_nested_in_span_or() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&[
+            "quick brown fox",
+            "quick red fox",
+            "brown quick fox",
+            "lazy dog",
+            "quick brown lazy dog",
+        ])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        let quick_brown = SpanQuery::near(vec![term("quick"), term("brown")], 0, true);
+        let lazy_dog = SpanQuery::near(vec![term("lazy"), term("dog")], 0, true);
+        let quick_brown_or_lazy_dog = SpanQuery::or(vec![quick_brown.clone(), lazy_dog]);
+        assert_eq!(
+            matching_docs(&searcher, &quick_brown_or_lazy_dog)?,
+            vec![0, 3, 4]
+        );
+
+        // The spans of the `SpanOr` can themselves be used in a `SpanNear`.
+        let quick_brown_or_red = SpanQuery::near(
+            vec![
+                term("quick"),
+                SpanQuery::or(vec![term("brown"), term("red")]),
+                term("fox"),
+            ],
+            0,
+            true,
+        );
+        assert_eq!(matching_docs(&searcher, &quick_brown_or_red)?, vec![0, 1]);
+        let nested = SpanQuery::near(vec![quick_brown_or_lazy_dog, term("fox")], 0, true);
+        assert_eq!(matching_docs(&searcher, &nested)?, vec![0]);
+        let nested = SpanQuery::near(
+            vec![SpanQuery::or(vec![quick_brown, term("red")]), term("dog")],
+            1,
+            true,
+        );
+        assert_eq!(matching_docs(&searcher, &nested)?, vec![4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_near_nested_clauses_of_different_lengths() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&["a b c d", "a b x d"])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        // The `b .. d` span of the second clause starts first, but only `c` is followed by `d`.
+        let nested = SpanQuery::near(
+            vec![
+                term("a"),
+                SpanQuery::or(vec![
+                    SpanQuery::near(vec![term("b"), term("d")], 10, true),
+                    term("c"),
+                ]),
+                term("d"),
+            ],
+            1,
+            true,
+        );
+        assert_eq!(matching_docs(&searcher, &nested)?, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_not_exclusion() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&[
+            "new york city",
+            "new york yankees",
+            "york new city",
+            "new york",
+            "city of new york",
+        ])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        let new_york = SpanQuery::near(vec![term("new"), term("york")], 0, true);
+        assert_eq!(matching_docs(&searcher, &new_york)?, vec![0, 1, 3, 4]);
+        // `city` never overlaps `new york`.
+        let query = SpanQuery::not(new_york.clone(), term("city"));
+        assert_eq!(matching_docs(&searcher, &query)?, vec![0, 1, 3, 4]);
+        // ... but may follow it, or precede it.
+        let query = SpanQuery::not_within(new_york.clone(), term("city"), 0, 1);
+        assert_eq!(matching_docs(&searcher, &query)?, vec![1, 3, 4]);
+        let query = SpanQuery::not_within(new_york.clone(), term("city"), 2, 0);
+        assert_eq!(matching_docs(&searcher, &query)?, vec![0, 1, 3]);
+        // `york` overlaps every match.
+        let query = SpanQuery::not(new_york, term("york"));
+        assert_eq!(searcher.search(&query, &Count)?, 0);
+        // A span not can be nested as well.
+        let query = SpanQuery::near(
+            vec![SpanQuery::not(term("new"), term("city")), term("york")],
+            0,
+            false,
+        );
+        assert_eq!(matching_docs(&searcher, &query)?, vec![0, 1, 2, 3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_query_scoring() -> crate::Result<()> {
+        let (searcher, field) = create_searcher(&["a b", "a b a b", "b a"])?;
+        let term = |text: &str| SpanQuery::term(Term::from_field_text(field, text));
+        let query = SpanQuery::near(vec![term("a"), term("b")], 0, true);
+        let weight = query.weight(EnableScoring::enabled_from_searcher(&searcher))?;
+        let segment_reader = searcher.segment_reader(0);
+        let mut scorer = weight.scorer(segment_reader, 1.0)?;
+        assert_eq!(scorer.doc(), 0);
+        let single_match_score = scorer.score();
+        assert_eq!(scorer.advance(), 1);
+        // Two matches in a longer document.
+        let two_matches_score = scorer.score();
+        assert!(two_matches_score > single_match_score);
+        assert_eq!(scorer.advance(), crate::TERMINATED);
+        let explanation = weight.explain(segment_reader, 1)?;
+        assert_eq!(explanation.value(), two_matches_score);
+        assert!(weight.explain(segment_reader, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_query_requires_positions() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let field = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let searcher = index.reader()?.searcher();
+        let query = SpanQuery::term(Term::from_field_text(field, "a"));
+        assert!(matches!(
+            searcher.search(&query, &Count),
+            Err(crate::TantivyError::SchemaError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use super::span_weight::SpanWeight;
+use crate::query::bm25::Bm25Weight;
+use crate::query::{EnableScoring, Query, Weight};
+use crate::schema::{Field, IndexRecordOption, Term};
+
+/// `SpanQuery` matches ranges of positions, called spans, within a single field.
+///
+/// Span queries are built from terms, and composed with each other:
+/// - [`SpanQuery::term`] matches each position of a term.
+/// - [`SpanQuery::near`] matches a span of each of its clauses, close to each other.
+/// - [`SpanQuery::or`] matches the spans of any of its clauses.
+/// - [`SpanQuery::not`] matches the spans of a clause that do not overlap the spans of another
+///   clause.
+///
+/// All of the terms of a span query must belong to the same field, which must have its
+/// positions indexed.
+///
+/// Documents are scored with BM25, using the number of spans matching in the document as the
+/// term frequency.
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::SpanQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, IndexWriter, Term};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// {
+///     let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+///     index_writer.add_document(doc!(title => "The Old Man and the Sea"))?;
+///     index_writer.add_document(doc!(title => "The Sea Wolf"))?;
+///     index_writer.add_document(doc!(title => "The Old Man of the Sea"))?;
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+/// let term = |text: &str| SpanQuery::term(Term::from_field_text(title, text));
+///
+/// // `old` followed by `sea`, with at most 3 positions in between.
+/// let old_near_sea = SpanQuery::near(vec![term("old"), term("sea")], 3, true);
+/// assert_eq!(searcher.search(&old_near_sea, &Count)?, 2);
+///
+/// // ... without `and` in between.
+/// let query = SpanQuery::not(old_near_sea, term("and"));
+/// assert_eq!(searcher.search(&query, &Count)?, 1);
+/// # Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpanQuery {
+    field: Field,
+    kind: SpanQueryKind,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum SpanQueryKind {
+    Term(Term),
+    Near {
+        clauses: Vec<SpanQuery>,
+        slop: u32,
+        in_order: bool,
+    },
+    Or(Vec<SpanQuery>),
+    Not {
+        include: Box<SpanQuery>,
+        exclude: Box<SpanQuery>,
+        pre: u32,
+        post: u32,
+    },
+}
+
+fn clauses_field(clauses: &[SpanQuery]) -> Field {
+    assert!(
+        !clauses.is_empty(),
+        "A span query is required to have at least one clause."
+    );
+    let field = clauses[0].field;
+    assert!(
+        clauses[1..].iter().all(|clause| clause.field == field),
+        "All clauses from a span query must belong to the same field"
+    );
+    field
+}
+
+impl SpanQuery {
+    /// Creates a span query matching a span of length 1 at each position of `term`.
+    pub fn term(term: Term) -> SpanQuery {
+        SpanQuery {
+            field: term.field(),
+            kind: SpanQueryKind::Term(term),
+        }
+    }
+
+    /// Creates a span query matching a span of each of the `clauses`, with at most `slop`
+    /// positions between them overall.
+    ///
+    /// If `in_order` is true, the spans have to follow the order of the clauses, and must not
+    /// overlap. A match spans from the start of its first span to the end of its last span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `clauses` is empty, or if the clauses do not belong to the same field.
+    pub fn near(clauses: Vec<SpanQuery>, slop: u32, in_order: bool) -> SpanQuery {
+        SpanQuery {
+            field: clauses_field(&clauses),
+            kind: SpanQueryKind::Near {
+                clauses,
+                slop,
+                in_order,
+            },
+        }
+    }
+
+    /// Creates a span query matching the spans of any of the `clauses`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `clauses` is empty, or if the clauses do not belong to the same field.
+    pub fn or(clauses: Vec<SpanQuery>) -> SpanQuery {
+        SpanQuery {
+            field: clauses_field(&clauses),
+            kind: SpanQueryKind::Or(clauses),
+        }
+    }
+
+    /// Creates a span query matching the spans of `include` that do not overlap any span of
+    /// `exclude`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clauses do not belong to the same field.
+    pub fn not(include: SpanQuery, exclude: SpanQuery) -> SpanQuery {
+        SpanQuery::not_within(include, exclude, 0, 0)
+    }
+
+    /// Creates a span query matching the spans of `include` that have no span of `exclude`
+    /// overlapping them, nor starting less than `post` positions after them, nor ending less
+    /// than `pre` positions before them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clauses do not belong to the same field.
+    pub fn not_within(include: SpanQuery, exclude: SpanQuery, pre: u32, post: u32) -> SpanQuery {
+        assert_eq!(
+            include.field, exclude.field,
+            "All clauses from a span query must belong to the same field"
+        );
+        SpanQuery {
+            field: include.field,
+            kind: SpanQueryKind::Not {
+                include: Box::new(include),
+                exclude: Box::new(exclude),
+                pre,
+                post,
+            },
+        }
+    }
+
+    /// The field of the span query.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub(crate) fn kind(&self) -> &SpanQueryKind {
+        &self.kind
+    }
+
+    /// Visits the terms the matching spans are made of, i.e. all of the terms but the ones
+    /// of excluded clauses.
+    fn visit_matching_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term)) {
+        match &self.kind {
+            SpanQueryKind::Term(term) => visitor(term),
+            SpanQueryKind::Near { clauses, .. } | SpanQueryKind::Or(clauses) => {
+                for clause in clauses {
+                    clause.visit_matching_terms(visitor);
+                }
+            }
+            SpanQueryKind::Not { include, .. } => include.visit_matching_terms(visitor),
+        }
+    }
+
+    pub(crate) fn span_weight(
+        &self,
+        enable_scoring: EnableScoring<'_>,
+    ) -> crate::Result<SpanWeight> {
+        let schema = enable_scoring.schema();
+        let field_entry = schema.get_field_entry(self.field);
+        let has_positions = field_entry
+            .field_type()
+            .get_index_record_option()
+            .map(IndexRecordOption::has_positions)
+            .unwrap_or(false);
+        if !has_positions {
+            let field_name = field_entry.name();
+            return Err(crate::TantivyError::SchemaError(format!(
+                "Applied span query on field {field_name:?}, which does not have positions indexed"
+            )));
+        }
+        let bm25_weight_opt = match enable_scoring {
+            EnableScoring::Enabled {
+                statistics_provider,
+                ..
+            } => {
+                let mut terms = Vec::new();
+                self.visit_matching_terms(&mut |term| terms.push(term.clone()));
+                Some(Bm25Weight::for_terms(statistics_provider, &terms)?)
+            }
+            EnableScoring::Disabled { .. } => None,
+        };
+        Ok(SpanWeight::new(self.clone(), bm25_weight_opt))
+    }
+}
+
+impl Query for SpanQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        let span_weight = self.span_weight(enable_scoring)?;
+        Ok(Box::new(span_weight))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.visit_matching_terms(&mut |term| visitor(term, true));
+    }
+}
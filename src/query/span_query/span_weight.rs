@@ -0,0 +1,151 @@
+use super::span_query::{SpanQuery, SpanQueryKind};
+use super::spans::{EmptySpans, NearSpans, NotSpans, OrSpans, Spans, TermSpans};
+use crate::fieldnorm::FieldNormReader;
+use crate::index::SegmentReader;
+use crate::query::bm25::Bm25Weight;
+use crate::query::explanation::does_not_match;
+use crate::query::{Explanation, Scorer, Weight};
+use crate::schema::IndexRecordOption;
+use crate::{DocId, DocSet, Score};
+
+pub(crate) struct SpanWeight {
+    query: SpanQuery,
+    similarity_weight_opt: Option<Bm25Weight>,
+}
+
+impl SpanWeight {
+    /// Creates a new span weight.
+    /// If `similarity_weight_opt` is None, then scoring is disabled
+    pub fn new(query: SpanQuery, similarity_weight_opt: Option<Bm25Weight>) -> SpanWeight {
+        SpanWeight {
+            query,
+            similarity_weight_opt,
+        }
+    }
+
+    fn fieldnorm_reader(&self, reader: &SegmentReader) -> crate::Result<FieldNormReader> {
+        if self.similarity_weight_opt.is_some() {
+            if let Some(fieldnorm_reader) =
+                reader.fieldnorms_readers().get_field(self.query.field())?
+            {
+                return Ok(fieldnorm_reader);
+            }
+        }
+        Ok(FieldNormReader::constant(reader.max_doc(), 1))
+    }
+
+    fn span_scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<SpanScorer> {
+        let similarity_weight_opt = self
+            .similarity_weight_opt
+            .as_ref()
+            .map(|similarity_weight| similarity_weight.boost_by(boost));
+        Ok(SpanScorer {
+            spans: build_spans(&self.query, reader)?,
+            similarity_weight_opt,
+            fieldnorm_reader: self.fieldnorm_reader(reader)?,
+        })
+    }
+}
+
+fn build_spans(query: &SpanQuery, reader: &SegmentReader) -> crate::Result<Box<dyn Spans>> {
+    let build_all = |clauses: &[SpanQuery]| -> crate::Result<Vec<Box<dyn Spans>>> {
+        clauses
+            .iter()
+            .map(|clause| build_spans(clause, reader))
+            .collect()
+    };
+    let spans: Box<dyn Spans> = match query.kind() {
+        SpanQueryKind::Term(term) => {
+            let postings_opt = reader
+                .inverted_index(term.field())?
+                .read_postings(term, IndexRecordOption::WithFreqsAndPositions)?;
+            match postings_opt {
+                Some(postings) => Box::new(TermSpans::new(postings)),
+                None => Box::new(EmptySpans),
+            }
+        }
+        SpanQueryKind::Near {
+            clauses,
+            slop,
+            in_order,
+        } => Box::new(NearSpans::new(build_all(clauses)?, *slop, *in_order)),
+        SpanQueryKind::Or(clauses) => Box::new(OrSpans::new(build_all(clauses)?)),
+        SpanQueryKind::Not {
+            include,
+            exclude,
+            pre,
+            post,
+        } => Box::new(NotSpans::new(
+            build_spans(include, reader)?,
+            build_spans(exclude, reader)?,
+            *pre,
+            *post,
+        )),
+    };
+    Ok(spans)
+}
+
+impl Weight for SpanWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.span_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.span_scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(does_not_match(doc));
+        }
+        let fieldnorm_id = scorer.fieldnorm_reader.fieldnorm_id(doc);
+        let span_count = scorer.span_count();
+        let mut explanation = Explanation::new("Span Scorer", scorer.score());
+        if let Some(similarity_weight) = self.similarity_weight_opt.as_ref() {
+            explanation.add_detail(similarity_weight.explain(fieldnorm_id, span_count));
+        }
+        Ok(explanation)
+    }
+}
+
+/// Scores the documents matching a span query, using the number of matching spans as the term
+/// frequency.
+pub(crate) struct SpanScorer {
+    spans: Box<dyn Spans>,
+    similarity_weight_opt: Option<Bm25Weight>,
+    fieldnorm_reader: FieldNormReader,
+}
+
+impl SpanScorer {
+    /// Number of spans matching in the current document.
+    fn span_count(&mut self) -> u32 {
+        self.spans.spans().len() as u32
+    }
+}
+
+impl DocSet for SpanScorer {
+    fn advance(&mut self) -> DocId {
+        self.spans.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.spans.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.spans.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.spans.size_hint()
+    }
+}
+
+impl Scorer for SpanScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        let span_count = self.span_count();
+        if let Some(similarity_weight) = self.similarity_weight_opt.as_ref() {
+            similarity_weight.score(fieldnorm_id, span_count)
+        } else {
+            1.0
+        }
+    }
+}
@@ -0,0 +1,598 @@
+use crate::docset::{DocSet, TERMINATED};
+use crate::postings::{Postings, SegmentPostings};
+use crate::DocId;
+
+/// Range of positions `[start, end)` matched by a span query in a document.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) struct Span {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// `DocSet` over the documents having at least one span matching a span query.
+pub(crate) trait Spans: DocSet {
+    /// Returns the spans of the current document, sorted by start and end positions.
+    ///
+    /// The current document must not be `TERMINATED`.
+    fn spans(&mut self) -> &[Span];
+}
+
+impl Spans for Box<dyn Spans> {
+    fn spans(&mut self) -> &[Span] {
+        (**self).spans()
+    }
+}
+
+/// Spans of a term absent from the segment.
+pub(crate) struct EmptySpans;
+
+impl DocSet for EmptySpans {
+    fn advance(&mut self) -> DocId {
+        TERMINATED
+    }
+
+    fn doc(&self) -> DocId {
+        TERMINATED
+    }
+
+    fn size_hint(&self) -> u32 {
+        0
+    }
+}
+
+impl Spans for EmptySpans {
+    fn spans(&mut self) -> &[Span] {
+        &[]
+    }
+}
+
+/// Spans of length 1, at each position of a term.
+pub(crate) struct TermSpans {
+    postings: SegmentPostings,
+    positions: Vec<u32>,
+    spans: Vec<Span>,
+    // Whether `spans` holds the spans of the current document.
+    loaded: bool,
+}
+
+impl TermSpans {
+    pub fn new(postings: SegmentPostings) -> TermSpans {
+        TermSpans {
+            postings,
+            positions: Vec::new(),
+            spans: Vec::new(),
+            loaded: false,
+        }
+    }
+}
+
+impl DocSet for TermSpans {
+    fn advance(&mut self) -> DocId {
+        self.loaded = false;
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.loaded = false;
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Spans for TermSpans {
+    fn spans(&mut self) -> &[Span] {
+        if !self.loaded {
+            self.postings.positions(&mut self.positions);
+            self.spans.clear();
+            self.spans
+                .extend(self.positions.iter().map(|&position| Span {
+                    start: position,
+                    end: position + 1,
+                }));
+            self.loaded = true;
+        }
+        &self.spans
+    }
+}
+
+/// Union of the spans of several clauses.
+pub(crate) struct OrSpans {
+    clauses: Vec<Box<dyn Spans>>,
+    doc: DocId,
+    spans: Vec<Span>,
+    // Whether `spans` holds the spans of the current document.
+    loaded: bool,
+}
+
+impl OrSpans {
+    pub fn new(clauses: Vec<Box<dyn Spans>>) -> OrSpans {
+        let mut or_spans = OrSpans {
+            clauses,
+            doc: TERMINATED,
+            spans: Vec::new(),
+            loaded: false,
+        };
+        or_spans.update_doc();
+        or_spans
+    }
+
+    fn update_doc(&mut self) -> DocId {
+        self.loaded = false;
+        self.doc = self
+            .clauses
+            .iter()
+            .map(|clause| clause.doc())
+            .min()
+            .unwrap_or(TERMINATED);
+        self.doc
+    }
+}
+
+impl DocSet for OrSpans {
+    fn advance(&mut self) -> DocId {
+        for clause in &mut self.clauses {
+            if clause.doc() == self.doc {
+                clause.advance();
+            }
+        }
+        self.update_doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        for clause in &mut self.clauses {
+            if clause.doc() < target {
+                clause.seek(target);
+            }
+        }
+        self.update_doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Spans for OrSpans {
+    fn spans(&mut self) -> &[Span] {
+        if !self.loaded {
+            self.spans.clear();
+            for clause in &mut self.clauses {
+                if clause.doc() == self.doc {
+                    self.spans.extend_from_slice(clause.spans());
+                }
+            }
+            self.spans.sort_unstable();
+            self.spans.dedup();
+            self.loaded = true;
+        }
+        &self.spans
+    }
+}
+
+/// Spans made of one span of each clause, separated by at most `slop` positions overall.
+///
+/// A match spans from the start of its first span to the end of its last span.
+pub(crate) struct NearSpans {
+    clauses: Vec<Box<dyn Spans>>,
+    slop: u32,
+    in_order: bool,
+    clause_spans: Vec<Vec<Span>>,
+    // (span, clause) pairs, used in unordered mode.
+    merged_spans: Vec<(Span, usize)>,
+    used_clauses: Vec<bool>,
+    spans: Vec<Span>,
+    doc: DocId,
+}
+
+impl NearSpans {
+    pub fn new(clauses: Vec<Box<dyn Spans>>, slop: u32, in_order: bool) -> NearSpans {
+        let num_clauses = clauses.len();
+        let first_candidate = clauses
+            .iter()
+            .map(|clause| clause.doc())
+            .max()
+            .unwrap_or(TERMINATED);
+        let mut near_spans = NearSpans {
+            clauses,
+            slop,
+            in_order,
+            clause_spans: vec![Vec::new(); num_clauses],
+            merged_spans: Vec::new(),
+            used_clauses: Vec::new(),
+            spans: Vec::new(),
+            doc: TERMINATED,
+        };
+        near_spans.doc = near_spans.find_match(first_candidate);
+        near_spans
+    }
+
+    /// Seeks all of the clauses to the first document greater than or equal to `target`
+    /// they all match.
+    fn align(&mut self, mut target: DocId) -> DocId {
+        'align: loop {
+            for clause in &mut self.clauses {
+                let doc = if clause.doc() < target {
+                    clause.seek(target)
+                } else {
+                    clause.doc()
+                };
+                if doc > target {
+                    target = doc;
+                    continue 'align;
+                }
+            }
+            return target;
+        }
+    }
+
+    fn find_match(&mut self, mut target: DocId) -> DocId {
+        loop {
+            let doc = self.align(target);
+            if doc == TERMINATED || self.load_spans() {
+                return doc;
+            }
+            target = doc + 1;
+        }
+    }
+
+    fn load_spans(&mut self) -> bool {
+        for (clause, clause_spans) in self.clauses.iter_mut().zip(&mut self.clause_spans) {
+            clause_spans.clear();
+            clause_spans.extend_from_slice(clause.spans());
+        }
+        self.spans.clear();
+        if self.in_order {
+            ordered_near_spans(&self.clause_spans, self.slop, &mut self.spans);
+        } else {
+            unordered_near_spans(
+                &self.clause_spans,
+                self.slop,
+                &mut self.merged_spans,
+                &mut self.used_clauses,
+                &mut self.spans,
+            );
+        }
+        self.spans.sort_unstable();
+        self.spans.dedup();
+        !self.spans.is_empty()
+    }
+}
+
+/// Appends the spans made of consecutive spans of each clause, in the order of the clauses.
+///
+/// The tightest match is looked for from each span of the first clause.
+fn ordered_near_spans(clause_spans: &[Vec<Span>], slop: u32, output: &mut Vec<Span>) {
+    for first in &clause_spans[0] {
+        let mut tightest = None;
+        tightest_ordered_chain(&clause_spans[1..], first.end, 0, slop, &mut tightest);
+        if let Some((_, end)) = tightest {
+            output.push(Span {
+                start: first.start,
+                end,
+            });
+        }
+    }
+}
+
+/// Looks for the chains made of a span of each clause of `clause_spans` in order, starting at
+/// `end` or after, with at most `slop` positions between the spans in total, `gap` included.
+///
+/// The closest span of a clause does not necessarily lead to a chain, e.g. if it is a long
+/// span of a nested clause, so that all of the spans within the slop are tried. The `(gap,
+/// end)` of the tightest chain found is kept in `tightest`.
+fn tightest_ordered_chain(
+    clause_spans: &[Vec<Span>],
+    end: u32,
+    gap: u32,
+    slop: u32,
+    tightest: &mut Option<(u32, u32)>,
+) {
+    let Some((spans, following_clause_spans)) = clause_spans.split_first() else {
+        keep_tightest(tightest, gap, end);
+        return;
+    };
+    let next_idx = spans.partition_point(|span| span.start < end);
+    for next in &spans[next_idx..] {
+        let next_gap = gap + next.start - end;
+        if next_gap > slop || tightest.is_some_and(|(tightest_gap, _)| next_gap > tightest_gap) {
+            break;
+        }
+        tightest_ordered_chain(following_clause_spans, next.end, next_gap, slop, tightest);
+    }
+}
+
+/// Appends the spans made of a span of each clause, in any order.
+///
+/// The spans of the different clauses must not overlap, so that e.g. a term repeated in two
+/// clauses has to occur twice. The tightest match is looked for from each span.
+fn unordered_near_spans(
+    clause_spans: &[Vec<Span>],
+    slop: u32,
+    merged_spans: &mut Vec<(Span, usize)>,
+    used_clauses: &mut Vec<bool>,
+    output: &mut Vec<Span>,
+) {
+    merged_spans.clear();
+    for (clause, spans) in clause_spans.iter().enumerate() {
+        merged_spans.extend(spans.iter().map(|&span| (span, clause)));
+    }
+    merged_spans.sort_unstable();
+    used_clauses.clear();
+    used_clauses.resize(clause_spans.len(), false);
+    for &(first, clause) in merged_spans.iter() {
+        let mut tightest = None;
+        used_clauses[clause] = true;
+        tightest_unordered_chain(
+            merged_spans,
+            used_clauses,
+            clause_spans.len() - 1,
+            first.end,
+            0,
+            slop,
+            &mut tightest,
+        );
+        used_clauses[clause] = false;
+        if let Some((_, end)) = tightest {
+            output.push(Span {
+                start: first.start,
+                end,
+            });
+        }
+    }
+}
+
+/// Same as [`tightest_ordered_chain`], for the `num_missing` clauses not marked in
+/// `used_clauses`, in any order. `merged_spans` holds the spans of all of the clauses, sorted.
+fn tightest_unordered_chain(
+    merged_spans: &[(Span, usize)],
+    used_clauses: &mut [bool],
+    num_missing: usize,
+    end: u32,
+    gap: u32,
+    slop: u32,
+    tightest: &mut Option<(u32, u32)>,
+) {
+    if num_missing == 0 {
+        keep_tightest(tightest, gap, end);
+        return;
+    }
+    let next_idx = merged_spans.partition_point(|(span, _)| span.start < end);
+    for &(next, clause) in &merged_spans[next_idx..] {
+        let next_gap = gap + next.start - end;
+        if next_gap > slop || tightest.is_some_and(|(tightest_gap, _)| next_gap > tightest_gap) {
+            break;
+        }
+        if used_clauses[clause] {
+            continue;
+        }
+        used_clauses[clause] = true;
+        tightest_unordered_chain(
+            merged_spans,
+            used_clauses,
+            num_missing - 1,
+            next.end,
+            next_gap,
+            slop,
+            tightest,
+        );
+        used_clauses[clause] = false;
+    }
+}
+
+fn keep_tightest(tightest: &mut Option<(u32, u32)>, gap: u32, end: u32) {
+    if tightest.map_or(true, |tightest_chain| (gap, end) < tightest_chain) {
+        *tightest = Some((gap, end));
+    }
+}
+
+impl DocSet for NearSpans {
+    fn advance(&mut self) -> DocId {
+        if self.doc != TERMINATED {
+            self.doc = self.find_match(self.doc + 1);
+        }
+        self.doc
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.doc = self.find_match(target);
+        self.doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.clauses
+            .iter()
+            .map(|clause| clause.size_hint())
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl Spans for NearSpans {
+    fn spans(&mut self) -> &[Span] {
+        &self.spans
+    }
+}
+
+/// Spans of `include` that are not within `pre` positions before, or `post` positions after,
+/// a span of `exclude`.
+pub(crate) struct NotSpans {
+    include: Box<dyn Spans>,
+    exclude: Box<dyn Spans>,
+    pre: u32,
+    post: u32,
+    spans: Vec<Span>,
+}
+
+impl NotSpans {
+    pub fn new(include: Box<dyn Spans>, exclude: Box<dyn Spans>, pre: u32, post: u32) -> NotSpans {
+        let mut not_spans = NotSpans {
+            include,
+            exclude,
+            pre,
+            post,
+            spans: Vec::new(),
+        };
+        not_spans.find_match();
+        not_spans
+    }
+
+    /// Advances `include` to the first document, from the current one, with spans left.
+    fn find_match(&mut self) -> DocId {
+        loop {
+            let doc = self.include.doc();
+            if doc == TERMINATED || self.load_spans(doc) {
+                return doc;
+            }
+            self.include.advance();
+        }
+    }
+
+    fn load_spans(&mut self, doc: DocId) -> bool {
+        self.spans.clear();
+        self.spans.extend_from_slice(self.include.spans());
+        if self.exclude.doc() < doc {
+            self.exclude.seek(doc);
+        }
+        if self.exclude.doc() == doc {
+            let (pre, post) = (self.pre, self.post);
+            let excluded_spans = self.exclude.spans();
+            self.spans.retain(|span| {
+                !excluded_spans.iter().any(|excluded_span| {
+                    excluded_span.start < span.end + post && excluded_span.end + pre > span.start
+                })
+            });
+        }
+        !self.spans.is_empty()
+    }
+}
+
+impl DocSet for NotSpans {
+    fn advance(&mut self) -> DocId {
+        self.include.advance();
+        self.find_match()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.include.seek(target);
+        self.find_match()
+    }
+
+    fn doc(&self) -> DocId {
+        self.include.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.include.size_hint()
+    }
+}
+
+impl Spans for NotSpans {
+    fn spans(&mut self) -> &[Span] {
+        &self.spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ordered_near_spans, unordered_near_spans, Span};
+
+    fn spans(ranges: &[(u32, u32)]) -> Vec<Span> {
+        ranges
+            .iter()
+            .map(|&(start, end)| Span { start, end })
+            .collect()
+    }
+
+    fn unordered_near_spans_helper(clause_spans: &[Vec<Span>], slop: u32) -> Vec<Span> {
+        let mut output = Vec::new();
+        unordered_near_spans(
+            clause_spans,
+            slop,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut output,
+        );
+        output
+    }
+
+    #[test]
+    fn test_ordered_near_spans() {
+        let clause_spans = vec![spans(&[(0, 1), (4, 5)]), spans(&[(2, 4), (6, 7)])];
+        let mut output = Vec::new();
+        ordered_near_spans(&clause_spans, 0, &mut output);
+        assert_eq!(output, spans(&[]));
+        ordered_near_spans(&clause_spans, 1, &mut output);
+        assert_eq!(output, spans(&[(0, 4), (4, 7)]));
+        // The spans of a clause have to start after the end of the previous clause.
+        let clause_spans = vec![spans(&[(3, 5)]), spans(&[(4, 5)])];
+        output.clear();
+        ordered_near_spans(&clause_spans, 10, &mut output);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_ordered_near_spans_not_through_closest_span() {
+        // The closest span of the second clause ends too far for the third clause.
+        let clause_spans = vec![
+            spans(&[(0, 1)]),
+            spans(&[(1, 10), (2, 3)]),
+            spans(&[(3, 4)]),
+        ];
+        let mut output = Vec::new();
+        ordered_near_spans(&clause_spans, 1, &mut output);
+        assert_eq!(output, spans(&[(0, 4)]));
+        let clause_spans = vec![spans(&[(1, 10), (2, 3)]), spans(&[(3, 4)])];
+        output.clear();
+        ordered_near_spans(&clause_spans, 0, &mut output);
+        assert_eq!(output, spans(&[(2, 4)]));
+    }
+
+    #[test]
+    fn test_unordered_near_spans() {
+        let clause_spans = vec![spans(&[(3, 4), (9, 10)]), spans(&[(1, 2), (7, 8)])];
+        assert_eq!(
+            unordered_near_spans_helper(&clause_spans, 1),
+            spans(&[(1, 4), (7, 10)])
+        );
+        assert!(unordered_near_spans_helper(&clause_spans, 0).is_empty());
+    }
+
+    #[test]
+    fn test_unordered_near_spans_duplicate_clauses() {
+        // The spans of two clauses matching the same term cannot be the same span.
+        let clause_spans = vec![spans(&[(3, 4)]), spans(&[(3, 4)])];
+        assert!(unordered_near_spans_helper(&clause_spans, 10).is_empty());
+        let clause_spans = vec![spans(&[(3, 4), (5, 6)]), spans(&[(3, 4), (5, 6)])];
+        let mut output = unordered_near_spans_helper(&clause_spans, 1);
+        output.dedup();
+        assert_eq!(output, spans(&[(3, 6)]));
+        // Overlapping spans of different clauses do not match either.
+        let clause_spans = vec![spans(&[(2, 5)]), spans(&[(3, 4)])];
+        assert!(unordered_near_spans_helper(&clause_spans, 10).is_empty());
+        // After an overlap, the other spans of the overlapping clauses are still tried.
+        let clause_spans = vec![spans(&[(3, 4)]), spans(&[(3, 4), (4, 5)])];
+        assert_eq!(
+            unordered_near_spans_helper(&clause_spans, 0),
+            spans(&[(3, 5)])
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use tantivy_fst::Regex;
+
+use crate::error::TantivyError;
+use crate::query::{AutomatonWeight, EnableScoring, Query, Weight};
+use crate::schema::Field;
+
+/// A Wildcard Query matches all of the documents
+/// containing a term that matches a glob pattern.
+///
+/// In the pattern, `*` matches any sequence of characters, including the
+/// empty one, and `?` matches exactly one character. Any character can be
+/// escaped with a backslash, e.g. `\*` matches a literal star.
+///
+/// The pattern has to match the whole term, and is compiled into an automaton
+/// that is intersected with the term dictionary. It is matched against the terms
+/// as they were indexed: it does not go through the tokenizer of the field.
+///
+/// A pattern starting with a wildcard (e.g. `*son`) cannot use the term dictionary
+/// to skip terms, and has to scan all of them. Such patterns are refused unless
+/// explicitly allowed with [`WildcardQueryBuilder::allow_leading_wildcard`].
+///
+/// ```rust
+/// use tantivy::collector::Count;
+/// use tantivy::query::WildcardQuery;
+/// use tantivy::schema::{Schema, TEXT};
+/// use tantivy::{doc, Index, IndexWriter};
+///
+/// # fn test() -> tantivy::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// {
+///     let mut index_writer: IndexWriter = index.writer(15_000_000)?;
+///     index_writer.add_document(doc!(title => "The Diary of Muadib"))?;
+///     index_writer.add_document(doc!(title => "A Dairy Cow"))?;
+///     index_writer.add_document(doc!(title => "The Name of the Wind"))?;
+///     index_writer.commit()?;
+/// }
+/// let searcher = index.reader()?.searcher();
+///
+/// let query = WildcardQuery::new("d??ry", title)?;
+/// assert_eq!(searcher.search(&query, &Count)?, 2);
+///
+/// assert!(WildcardQuery::new("*ind", title).is_err());
+/// let query = WildcardQuery::builder("*ind", title)
+///     .allow_leading_wildcard(true)
+///     .build()?;
+/// assert_eq!(searcher.search(&query, &Count)?, 1);
+/// Ok(())
+/// # }
+/// # assert!(test().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WildcardQuery {
+    pattern: String,
+    regex: Arc<Regex>,
+    field: Field,
+}
+
+impl WildcardQuery {
+    /// Creates a new WildcardQuery from a given pattern.
+    ///
+    /// Returns an error if the pattern starts with a wildcard.
+    pub fn new(pattern: &str, field: Field) -> crate::Result<Self> {
+        WildcardQuery::builder(pattern, field).build()
+    }
+
+    /// Returns a [`WildcardQueryBuilder`], to configure how the pattern is compiled.
+    pub fn builder(pattern: &str, field: Field) -> WildcardQueryBuilder {
+        WildcardQueryBuilder {
+            pattern: pattern.to_string(),
+            field,
+            allow_leading_wildcard: false,
+        }
+    }
+
+    /// Returns the field targeted by the query.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Returns the glob pattern of the query.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    fn specialized_weight(&self) -> AutomatonWeight<Regex> {
+        AutomatonWeight::new(self.field, self.regex.clone())
+    }
+}
+
+/// Builder for a [`WildcardQuery`].
+#[derive(Debug, Clone)]
+pub struct WildcardQueryBuilder {
+    pattern: String,
+    field: Field,
+    allow_leading_wildcard: bool,
+}
+
+impl WildcardQueryBuilder {
+    /// Sets whether the pattern may start with a wildcard.
+    ///
+    /// Defaults to false, as matching such a pattern requires scanning the whole
+    /// term dictionary of the field.
+    pub fn allow_leading_wildcard(mut self, allow_leading_wildcard: bool) -> Self {
+        self.allow_leading_wildcard = allow_leading_wildcard;
+        self
+    }
+
+    /// Compiles the pattern and builds the `WildcardQuery`.
+    pub fn build(self) -> crate::Result<WildcardQuery> {
+        if !self.allow_leading_wildcard && has_leading_wildcard(&self.pattern) {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Leading wildcards are not allowed: {}",
+                self.pattern
+            )));
+        }
+        let regex = Regex::new(&wildcard_pattern_to_regex(&self.pattern))
+            .map_err(|err| TantivyError::InvalidArgument(format!("WildcardQueryError: {err}")))?;
+        Ok(WildcardQuery {
+            pattern: self.pattern,
+            regex: Arc::new(regex),
+            field: self.field,
+        })
+    }
+}
+
+/// Returns true if `pattern` contains a `*` or `?` wildcard that is not escaped.
+pub(crate) fn has_wildcard(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Returns true if `pattern` starts with a wildcard.
+pub(crate) fn has_leading_wildcard(pattern: &str) -> bool {
+    pattern.starts_with(['*', '?'])
+}
+
+fn wildcard_pattern_to_regex(pattern: &str) -> String {
+    let mut regex = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    let mut buffer = [0u8; 4];
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str("(?s:.*)"),
+            '?' => regex.push_str("(?s:.)"),
+            '\\' => {
+                // A trailing backslash stands for itself.
+                let escaped = chars.next().unwrap_or('\\');
+                regex.push_str(&regex::escape(escaped.encode_utf8(&mut buffer)));
+            }
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut buffer))),
+        }
+    }
+    regex
+}
+
+impl Query for WildcardQuery {
+    fn weight(&self, _enabled_scoring: EnableScoring<'_>) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(self.specialized_weight()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{has_wildcard, wildcard_pattern_to_regex, WildcardQuery};
+    use crate::collector::Count;
+    use crate::schema::{Schema, STRING};
+    use crate::{Index, IndexWriter, Searcher};
+
+    fn build_test_searcher() -> crate::Result<(Searcher, crate::schema::Field)> {
+        let mut schema_builder = Schema::builder();
+        let name_field = schema_builder.add_text_field("name", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        {
+            let mut index_writer: IndexWriter = index.writer_for_tests()?;
+            for name in ["jackson", "jack", "jameson", "johnson", "ja*", "élan"] {
+                index_writer.add_document(doc!(name_field => name))?;
+            }
+            index_writer.commit()?;
+        }
+        Ok((index.reader()?.searcher(), name_field))
+    }
+
+    #[test]
+    fn test_wildcard_pattern_to_regex() {
+        assert_eq!(wildcard_pattern_to_regex("ja*"), "ja(?s:.*)");
+        assert_eq!(wildcard_pattern_to_regex("j?n.s"), r"j(?s:.)n\.s");
+        assert_eq!(wildcard_pattern_to_regex(r"ja\*"), r"ja\*");
+        assert!(has_wildcard("ja*"));
+        assert!(has_wildcard("j?"));
+        assert!(!has_wildcard(r"ja\*"));
+        assert!(!has_wildcard("jack"));
+    }
+
+    #[test]
+    fn test_wildcard_query_trailing_and_middle() -> crate::Result<()> {
+        let (searcher, field) = build_test_searcher()?;
+        let count = |pattern: &str| {
+            let query = WildcardQuery::new(pattern, field).unwrap();
+            searcher.search(&query, &Count).unwrap()
+        };
+        assert_eq!(count("ja*"), 4);
+        assert_eq!(count("jack*"), 2);
+        assert_eq!(count("j*son"), 3);
+        assert_eq!(count("ja?k"), 1);
+        assert_eq!(count("ja??"), 1);
+        assert_eq!(count(r"ja\*"), 1);
+        assert_eq!(count("é?an"), 1);
+        assert_eq!(count("jack"), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wildcard_query_leading() -> crate::Result<()> {
+        let (searcher, field) = build_test_searcher()?;
+        assert!(matches!(
+            WildcardQuery::new("*son", field),
+            Err(crate::TantivyError::InvalidArgument(_))
+        ));
+        assert!(WildcardQuery::new("?ack", field).is_err());
+        let query = WildcardQuery::builder("*son", field)
+            .allow_leading_wildcard(true)
+            .build()?;
+        assert_eq!(searcher.search(&query, &Count)?, 3);
+        let query = WildcardQuery::builder("?lan", field)
+            .allow_leading_wildcard(true)
+            .build()?;
+        assert_eq!(searcher.search(&query, &Count)?, 1);
+        Ok(())
+    }
+}
@@ -6,11 +6,11 @@ use std::sync::{atomic, Arc, Weak};
 use arc_swap::ArcSwap;
 pub use warming::Warmer;
 
-use self::warming::WarmingState;
+use self::warming::{CallbackWarmer, WarmingState};
 use crate::core::searcher::{SearcherGeneration, SearcherInner};
 use crate::directory::{Directory, WatchCallback, WatchHandle, META_LOCK};
 use crate::store::DOCSTORE_CACHE_CAPACITY;
-use crate::{Index, Inventory, Searcher, SegmentReader, TrackedObject};
+use crate::{Index, Inventory, Opstamp, Searcher, SegmentReader, TrackedObject};
 
 /// Defines when a new version of the index should be reloaded.
 ///
@@ -34,7 +34,7 @@ pub enum ReloadPolicy {
 ///
 /// It makes it possible to configure:
 /// - [`ReloadPolicy`] defining when new index versions are detected
-/// - [`Warmer`] implementations
+/// - [`Warmer`] implementations and warming callbacks
 /// - number of warming threads, for parallelizing warming work
 /// - The cache size of the underlying doc store readers.
 #[derive(Clone)]
@@ -42,6 +42,7 @@ pub struct IndexReaderBuilder {
     reload_policy: ReloadPolicy,
     index: Index,
     warmers: Vec<Weak<dyn Warmer>>,
+    callback_warmers: Vec<Arc<dyn Warmer>>,
     num_warming_threads: usize,
     doc_store_cache_num_blocks: usize,
 }
@@ -53,6 +54,7 @@ impl IndexReaderBuilder {
             reload_policy: ReloadPolicy::OnCommitWithDelay,
             index,
             warmers: Vec::new(),
+            callback_warmers: Vec::new(),
             num_warming_threads: 1,
             doc_store_cache_num_blocks: DOCSTORE_CACHE_CAPACITY,
         }
@@ -65,15 +67,18 @@ impl IndexReaderBuilder {
     /// of time and it may return an error.
     pub fn try_into(self) -> crate::Result<IndexReader> {
         let searcher_generation_inventory = Inventory::default();
+        let mut warmers = self.warmers;
+        warmers.extend(self.callback_warmers.iter().map(Arc::downgrade));
         let warming_state = WarmingState::new(
             self.num_warming_threads,
-            self.warmers,
+            warmers,
             searcher_generation_inventory.clone(),
         )?;
         let inner_reader = InnerIndexReader::new(
             self.doc_store_cache_num_blocks,
             self.index,
             warming_state,
+            self.callback_warmers,
             searcher_generation_inventory,
         )?;
         let inner_reader_arc = Arc::new(inner_reader);
@@ -133,6 +138,23 @@ impl IndexReaderBuilder {
         self
     }
 
+    /// Registers a callback invoked with each new [`Searcher`] generation, before it gets
+    /// returned by [`IndexReader::searcher()`], e.g. to fill per-segment caches.
+    ///
+    /// Reloading blocks until the callback returns, while the previous searcher keeps
+    /// serving. If the callback returns an error, the reload fails and the previous searcher
+    /// is kept.
+    ///
+    /// Contrary to the [`Warmer`]s, the callback is owned by the reader, and its garbage
+    /// collection is a no-op.
+    #[must_use]
+    pub fn warming<F>(mut self, callback: F) -> IndexReaderBuilder
+    where F: Fn(&Searcher) -> crate::Result<()> + Send + Sync + 'static {
+        let warmer = Arc::new(CallbackWarmer(callback));
+        self.callback_warmers.push(warmer);
+        self
+    }
+
     /// Sets the number of warming threads.
     ///
     /// This allows parallelizing warming work when there are multiple [`Warmer`] registered with
@@ -156,6 +178,8 @@ struct InnerIndexReader {
     doc_store_cache_num_blocks: usize,
     index: Index,
     warming_state: WarmingState,
+    // The warming state only holds weak references to the warmers.
+    _callback_warmers: Vec<Arc<dyn Warmer>>,
     searcher: arc_swap::ArcSwap<SearcherInner>,
     searcher_generation_counter: Arc<AtomicU64>,
     searcher_generation_inventory: Inventory<SearcherGeneration>,
@@ -166,6 +190,7 @@ impl InnerIndexReader {
         doc_store_cache_num_blocks: usize,
         index: Index,
         warming_state: WarmingState,
+        callback_warmers: Vec<Arc<dyn Warmer>>,
         // The searcher_generation_inventory is not used as source, but as target to track the
         // loaded segments.
         searcher_generation_inventory: Inventory<SearcherGeneration>,
@@ -183,6 +208,7 @@ impl InnerIndexReader {
             doc_store_cache_num_blocks,
             index,
             warming_state,
+            _callback_warmers: callback_warmers,
             searcher: ArcSwap::from(searcher),
             searcher_generation_counter,
             searcher_generation_inventory,
@@ -192,15 +218,18 @@ impl InnerIndexReader {
     ///
     /// This function acquires a lock to prevent GC from removing files
     /// as we are opening our index.
-    fn open_segment_readers(index: &Index) -> crate::Result<Vec<SegmentReader>> {
+    ///
+    /// Returns the opstamp of the commit the segments belong to, along with their readers.
+    fn open_segment_readers(index: &Index) -> crate::Result<(Opstamp, Vec<SegmentReader>)> {
         // Prevents segment files from getting deleted while we are in the process of opening them
         let _meta_lock = index.directory().acquire_lock(&META_LOCK)?;
-        let searchable_segments = index.searchable_segments()?;
-        let segment_readers = searchable_segments
-            .iter()
-            .map(SegmentReader::open)
+        let index_meta = index.load_metas()?;
+        let segment_readers = index_meta
+            .segments
+            .into_iter()
+            .map(|segment_meta| SegmentReader::open(&index.segment(segment_meta)))
             .collect::<crate::Result<_>>()?;
-        Ok(segment_readers)
+        Ok((index_meta.opstamp, segment_readers))
     }
 
     fn track_segment_readers_in_inventory(
@@ -221,7 +250,7 @@ impl InnerIndexReader {
         searcher_generation_counter: &Arc<AtomicU64>,
         searcher_generation_inventory: &Inventory<SearcherGeneration>,
     ) -> crate::Result<Arc<SearcherInner>> {
-        let segment_readers = Self::open_segment_readers(index)?;
+        let (opstamp, segment_readers) = Self::open_segment_readers(index)?;
         let searcher_generation = Self::track_segment_readers_in_inventory(
             &segment_readers,
             searcher_generation_counter,
@@ -234,6 +263,7 @@ impl InnerIndexReader {
             index.clone(),
             segment_readers,
             searcher_generation,
+            opstamp,
             doc_store_cache_num_blocks,
         )?);
 
@@ -291,6 +321,38 @@ impl IndexReader {
         self.inner.reload()
     }
 
+    /// Returns the opstamp of the commit reflected by the current searcher.
+    pub fn opstamp(&self) -> Opstamp {
+        self.searcher().opstamp()
+    }
+
+    /// Returns the opstamp of the last commit of the index.
+    ///
+    /// This only reads the index meta file, and does not reload the searchers.
+    pub fn latest_opstamp(&self) -> crate::Result<Opstamp> {
+        Ok(self.inner.index.load_metas()?.opstamp)
+    }
+
+    /// Reloads the searchers, only if a commit newer than the one reflected by the current
+    /// searcher exists.
+    ///
+    /// This is a cheap way to poll for new commits with the [`ReloadPolicy::Manual`] policy,
+    /// as the segments are only reopened when needed.
+    ///
+    /// Segment merges do not create a new commit: their result is only picked up by the next
+    /// reload.
+    ///
+    /// Returns the `(old, new)` opstamps if the searchers were reloaded, and `None` if they were
+    /// already up to date.
+    pub fn reload_if_newer(&self) -> crate::Result<Option<(Opstamp, Opstamp)>> {
+        let old_opstamp = self.opstamp();
+        if self.latest_opstamp()? <= old_opstamp {
+            return Ok(None);
+        }
+        self.reload()?;
+        Ok(Some((old_opstamp, self.opstamp())))
+    }
+
     /// Returns a searcher
     ///
     /// This method should be called every single time a search
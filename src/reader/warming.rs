@@ -19,6 +19,19 @@ pub trait Warmer: Sync + Send {
     fn garbage_collect(&self, live_generations: &[&SearcherGeneration]);
 }
 
+/// [`Warmer`] calling a closure on each new [`Searcher`], and keeping no state.
+pub(crate) struct CallbackWarmer<F>(pub F);
+
+impl<F> Warmer for CallbackWarmer<F>
+where F: Fn(&Searcher) -> crate::Result<()> + Send + Sync
+{
+    fn warm(&self, searcher: &Searcher) -> crate::Result<()> {
+        (self.0)(searcher)
+    }
+
+    fn garbage_collect(&self, _live_generations: &[&SearcherGeneration]) {}
+}
+
 /// Warming-related state with interior mutability.
 #[derive(Clone)]
 pub(crate) struct WarmingState(Arc<Mutex<WarmingStateInner>>);
@@ -342,4 +355,55 @@ mod tests {
     fn warming_four_threads() -> crate::Result<()> {
         test_warming(4)
     }
+
+    #[test]
+    fn test_warming_callback() -> crate::Result<()> {
+        use std::sync::{mpsc, Mutex};
+
+        use crate::collector::Count;
+        use crate::query::AllQuery;
+        use crate::schema::STRING;
+        use crate::IndexWriter;
+
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "a"))?;
+        index_writer.commit()?;
+
+        let (warming_started_sender, warming_started_receiver) = mpsc::channel::<u64>();
+        let (warming_done_sender, warming_done_receiver) = mpsc::channel::<()>();
+        let warming_done_receiver = Mutex::new(warming_done_receiver);
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .warming(move |searcher: &Searcher| {
+                warming_started_sender.send(searcher.num_docs()).unwrap();
+                // The first generation is warmed when the reader is built.
+                if searcher.num_docs() > 1 {
+                    warming_done_receiver.lock().unwrap().recv().unwrap();
+                }
+                Ok(())
+            })
+            .try_into()?;
+        assert_eq!(warming_started_receiver.recv().unwrap(), 1);
+
+        index_writer.add_document(doc!(text => "b"))?;
+        index_writer.commit()?;
+        let reload_handle = {
+            let reader = reader.clone();
+            std::thread::spawn(move || reader.reload())
+        };
+        // The callback receives the new searcher...
+        assert_eq!(warming_started_receiver.recv().unwrap(), 2);
+        // ... while the old one keeps serving queries.
+        for _ in 0..10 {
+            assert_eq!(reader.searcher().search(&AllQuery, &Count)?, 1);
+        }
+        warming_done_sender.send(()).unwrap();
+        reload_handle.join().unwrap()?;
+        assert_eq!(reader.searcher().search(&AllQuery, &Count)?, 2);
+        Ok(())
+    }
 }
@@ -11,6 +11,13 @@ pub struct BytesOptions {
     fieldnorms: bool,
     fast: bool,
     stored: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !val
 }
 
 /// For backward compatibility we add an intermediary to interpret the
@@ -25,6 +32,8 @@ struct BytesOptionsDeser {
     fieldnorms: Option<bool>,
     fast: bool,
     stored: bool,
+    #[serde(default)]
+    required: bool,
 }
 
 impl From<BytesOptionsDeser> for BytesOptions {
@@ -34,6 +43,7 @@ impl From<BytesOptionsDeser> for BytesOptions {
             fieldnorms: deser.fieldnorms.unwrap_or(deser.indexed),
             fast: deser.fast,
             stored: deser.stored,
+            required: deser.required,
         }
     }
 }
@@ -85,7 +95,11 @@ impl BytesOptions {
 
     /// Set the field as a fast field.
     ///
-    /// Fast fields are designed for random access.
+    /// Fast fields are designed for random access. The value of a document can be read
+    /// during collection with `BytesColumn::first`.
+    ///
+    /// Values of a bytes fast field are limited to `u16::MAX` bytes: adding a document with a
+    /// longer value returns an error.
     #[must_use]
     pub fn set_fast(mut self) -> BytesOptions {
         self.fast = true;
@@ -101,6 +115,22 @@ impl BytesOptions {
         self.stored = true;
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> BytesOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl<T: Into<BytesOptions>> BitOr<T> for BytesOptions {
@@ -113,6 +143,7 @@ impl<T: Into<BytesOptions>> BitOr<T> for BytesOptions {
             fieldnorms: self.fieldnorms | other.fieldnorms,
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
+            required: self.required | other.required,
         }
     }
 }
@@ -130,6 +161,7 @@ impl From<FastFlag> for BytesOptions {
             fieldnorms: false,
             stored: false,
             fast: true,
+            required: false,
         }
     }
 }
@@ -141,6 +173,7 @@ impl From<StoredFlag> for BytesOptions {
             fieldnorms: false,
             stored: true,
             fast: false,
+            required: false,
         }
     }
 }
@@ -152,6 +185,7 @@ impl From<IndexedFlag> for BytesOptions {
             fieldnorms: true,
             stored: false,
             fast: false,
+            required: false,
         }
     }
 }
@@ -228,7 +262,8 @@ mod tests {
                 indexed: true,
                 fieldnorms: true,
                 fast: false,
-                stored: false
+                stored: false,
+                required: false,
             }
         );
     }
@@ -247,7 +282,8 @@ mod tests {
                 indexed: false,
                 fieldnorms: false,
                 fast: false,
-                stored: false
+                stored: false,
+                required: false,
             }
         );
     }
@@ -267,7 +303,8 @@ mod tests {
                 indexed: true,
                 fieldnorms: false,
                 fast: false,
-                stored: false
+                stored: false,
+                required: false,
             }
         );
     }
@@ -288,7 +325,8 @@ mod tests {
                 indexed: false,
                 fieldnorms: true,
                 fast: false,
-                stored: false
+                stored: false,
+                required: false,
             }
         );
     }
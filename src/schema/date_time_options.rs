@@ -21,6 +21,13 @@ pub struct DateOptions {
     // compression on fast fields.
     #[serde(default)]
     precision: DateTimePrecision,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !val
 }
 
 impl DateOptions {
@@ -106,6 +113,22 @@ impl DateOptions {
     pub fn get_precision(&self) -> DateTimePrecision {
         self.precision
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> DateOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl From<()> for DateOptions {
@@ -153,6 +176,7 @@ impl<T: Into<DateOptions>> BitOr<T> for DateOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             precision: self.precision,
+            required: self.required | other.required,
         }
     }
 }
@@ -145,6 +145,55 @@ impl Facet {
         Facet(facet_string)
     }
 
+    /// Returns a `Facet` from the segments of its path, escaping them so that any string can be
+    /// used as a segment.
+    ///
+    /// Unlike [`Facet::from_path`], a segment containing the null byte, which separates the
+    /// levels of the binary representation, does not create a spurious level. Within each
+    /// segment, `\` is encoded as `\\` and the null byte as `\0`. Slashes do not need any
+    /// escaping, as they are only reserved in the text representation.
+    ///
+    /// Use [`Facet::to_path_segments`] to get the original segments back. An empty slice, as
+    /// well as a single empty segment, is the root facet.
+    ///
+    /// ```rust
+    /// use tantivy::schema::Facet;
+    ///
+    /// let facet = Facet::from_path_segments(&["a/b", "c\0d"]);
+    /// assert_eq!(facet.to_path_segments(), vec!["a/b", "c\0d"]);
+    /// assert_eq!(facet.to_path().len(), 2);
+    /// ```
+    pub fn from_path_segments(segments: &[&str]) -> Facet {
+        let mut facet_string = String::new();
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                facet_string.push(FACET_SEP_CHAR);
+            }
+            for c in segment.chars() {
+                match c {
+                    '\\' => facet_string.push_str("\\\\"),
+                    FACET_SEP_CHAR => facet_string.push_str("\\0"),
+                    _ => facet_string.push(c),
+                }
+            }
+        }
+        Facet(facet_string)
+    }
+
+    /// Returns the unescaped segments of the path of the facet.
+    ///
+    /// This function is the inverse of [`Facet::from_path_segments`]. The root facet has no
+    /// segments.
+    pub fn to_path_segments(&self) -> Vec<Cow<'_, str>> {
+        if self.is_root() {
+            return Vec::new();
+        }
+        self.encoded_str()
+            .split(FACET_SEP_CHAR)
+            .map(unescape_segment)
+            .collect()
+    }
+
     /// Returns `true` if other is a `strict` subfacet of `self`.
     ///
     /// Disclaimer: By strict we mean that the relation is not reflexive.
@@ -212,7 +261,32 @@ impl Display for Facet {
 
 fn escape_slashes(s: &str) -> Cow<'_, str> {
     static SLASH_PTN: Lazy<Regex> = Lazy::new(|| Regex::new(r"[\\/]").unwrap());
-    SLASH_PTN.replace_all(s, "\\/")
+    SLASH_PTN.replace_all(s, "\\$0")
+}
+
+fn unescape_segment(segment: &str) -> Cow<'_, str> {
+    if !segment.contains('\\') {
+        return Cow::Borrowed(segment);
+    }
+    let mut unescaped = String::with_capacity(segment.len());
+    let mut chars = segment.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('0') => unescaped.push(FACET_SEP_CHAR),
+            // Not produced by `from_path_segments`: the backslash is kept as is.
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    Cow::Owned(unescaped)
 }
 
 impl Serialize for Facet {
@@ -345,4 +419,32 @@ mod tests {
             "Failed to parse the facet string: 'foo/bar'"
         );
     }
+
+    #[test]
+    fn test_path_segments_round_trip() {
+        let segments = ["top", "a/b", "c\u{0}d", "back\\slash", "\\0", ""];
+        let facet = Facet::from_path_segments(&segments);
+        assert_eq!(facet.to_path().len(), segments.len());
+        assert_eq!(facet.to_path_segments(), segments);
+        // The slash is escaped in the text representation and does not create a new level.
+        assert_eq!(Facet::from_text(&facet.to_path_string()).unwrap(), facet);
+    }
+
+    #[test]
+    fn test_path_segments_null_byte_is_not_a_level() {
+        let parent = Facet::from_path_segments(&["a"]);
+        let facet = Facet::from_path_segments(&["a\u{0}b"]);
+        assert!(!parent.is_prefix_of(&facet));
+        assert_eq!(facet.to_path_segments(), vec!["a\u{0}b"]);
+        let child = Facet::from_path_segments(&["a", "b"]);
+        assert!(parent.is_prefix_of(&child));
+        assert_ne!(facet, child);
+    }
+
+    #[test]
+    fn test_path_segments_root() {
+        assert_eq!(Facet::from_path_segments(&[]), Facet::root());
+        assert!(Facet::root().to_path_segments().is_empty());
+        assert_eq!(Facet::from_path_segments(&["a"]), Facet::from("/a"));
+    }
 }
@@ -10,6 +10,13 @@ use crate::schema::flags::{IndexedFlag, SchemaFlagList, StoredFlag};
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct FacetOptions {
     stored: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !val
 }
 
 impl FacetOptions {
@@ -28,6 +35,22 @@ impl FacetOptions {
         self.stored = true;
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> FacetOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl From<()> for FacetOptions {
@@ -38,7 +61,10 @@ impl From<()> for FacetOptions {
 
 impl From<StoredFlag> for FacetOptions {
     fn from(_: StoredFlag) -> Self {
-        FacetOptions { stored: true }
+        FacetOptions {
+            stored: true,
+            required: false,
+        }
     }
 }
 
@@ -49,6 +75,7 @@ impl<T: Into<FacetOptions>> BitOr<T> for FacetOptions {
         let other = other.into();
         FacetOptions {
             stored: self.stored | other.stored,
+            required: self.required | other.required,
         }
     }
 }
@@ -66,7 +93,10 @@ where
 
 impl From<IndexedFlag> for FacetOptions {
     fn from(_: IndexedFlag) -> Self {
-        FacetOptions { stored: false }
+        FacetOptions {
+            stored: false,
+            required: false,
+        }
     }
 }
 
@@ -4,7 +4,7 @@ use super::ip_options::IpAddrOptions;
 use crate::schema::bytes_options::BytesOptions;
 use crate::schema::{
     is_valid_field_name, DateOptions, FacetOptions, FieldType, JsonObjectOptions, NumericOptions,
-    TextOptions,
+    TextOptions, Type,
 };
 
 /// A `FieldEntry` represents a field and its configuration.
@@ -80,6 +80,31 @@ impl FieldEntry {
         Self::new(field_name, FieldType::JsonObject(json_object_options))
     }
 
+    /// Creates a field entry for a stored-only field of the given type.
+    ///
+    /// A stored-only field is neither indexed nor fast: no inverted index, fieldnorm or
+    /// fast field structure is built for it. Its values can only be retrieved from the
+    /// document store, and querying it returns an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value_type` is [`Type::Facet`], as facet fields are always indexed.
+    pub fn new_stored_only(field_name: String, value_type: Type) -> FieldEntry {
+        let field_type = match value_type {
+            Type::Str => FieldType::Str(TextOptions::default().set_stored()),
+            Type::U64 => FieldType::U64(NumericOptions::default().set_stored()),
+            Type::I64 => FieldType::I64(NumericOptions::default().set_stored()),
+            Type::F64 => FieldType::F64(NumericOptions::default().set_stored()),
+            Type::Bool => FieldType::Bool(NumericOptions::default().set_stored()),
+            Type::Date => FieldType::Date(DateOptions::default().set_stored()),
+            Type::Bytes => FieldType::Bytes(BytesOptions::default().set_stored()),
+            Type::Json => FieldType::JsonObject(JsonObjectOptions::default().set_stored()),
+            Type::IpAddr => FieldType::IpAddr(IpAddrOptions::default().set_stored()),
+            Type::Facet => panic!("Facet fields are always indexed and cannot be stored-only"),
+        };
+        Self::new(field_name, field_type)
+    }
+
     /// Returns the name of the field
     pub fn name(&self) -> &str {
         &self.name
@@ -107,6 +132,13 @@ impl FieldEntry {
         self.field_type.is_fast()
     }
 
+    /// Returns true if the field is stored, but neither indexed nor fast.
+    ///
+    /// See [`FieldEntry::new_stored_only`].
+    pub fn is_stored_only(&self) -> bool {
+        self.is_stored() && !self.is_indexed() && !self.is_fast()
+    }
+
     /// Returns true if the field has the expand dots option set (for json fields)
     pub fn is_expand_dots_enabled(&self) -> bool {
         match self.field_type {
@@ -131,6 +163,22 @@ impl FieldEntry {
             FieldType::IpAddr(ref options) => options.is_stored(),
         }
     }
+
+    /// Returns true if documents must have a value for this field.
+    pub fn is_required(&self) -> bool {
+        match self.field_type {
+            FieldType::U64(ref options)
+            | FieldType::I64(ref options)
+            | FieldType::F64(ref options)
+            | FieldType::Bool(ref options) => options.is_required(),
+            FieldType::Date(ref options) => options.is_required(),
+            FieldType::Str(ref options) => options.is_required(),
+            FieldType::Facet(ref options) => options.is_required(),
+            FieldType::Bytes(ref options) => options.is_required(),
+            FieldType::JsonObject(ref options) => options.is_required(),
+            FieldType::IpAddr(ref options) => options.is_required(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -761,6 +761,7 @@ mod tests {
                     position: 0,
                     text: String::from("The"),
                     position_length: 1,
+                    original_text: None,
                 },
                 Token {
                     offset_from: 4,
@@ -768,6 +769,7 @@ mod tests {
                     position: 1,
                     text: String::from("Old"),
                     position_length: 1,
+                    original_text: None,
                 },
                 Token {
                     offset_from: 8,
@@ -775,6 +777,7 @@ mod tests {
                     position: 2,
                     text: String::from("Man"),
                     position_length: 1,
+                    original_text: None,
                 },
             ],
         });
@@ -27,6 +27,13 @@ pub struct IpAddrOptions {
     stored: bool,
     indexed: bool,
     fieldnorms: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !val
 }
 
 impl IpAddrOptions {
@@ -92,6 +99,22 @@ impl IpAddrOptions {
         self.fast = true;
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> IpAddrOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl From<()> for IpAddrOptions {
@@ -107,6 +130,7 @@ impl From<FastFlag> for IpAddrOptions {
             indexed: false,
             stored: false,
             fast: true,
+            required: false,
         }
     }
 }
@@ -118,6 +142,7 @@ impl From<StoredFlag> for IpAddrOptions {
             indexed: false,
             stored: true,
             fast: false,
+            required: false,
         }
     }
 }
@@ -129,6 +154,7 @@ impl From<IndexedFlag> for IpAddrOptions {
             indexed: true,
             stored: false,
             fast: false,
+            required: false,
         }
     }
 }
@@ -143,6 +169,7 @@ impl<T: Into<IpAddrOptions>> BitOr<T> for IpAddrOptions {
             indexed: self.indexed | other.indexed,
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
+            required: self.required | other.required,
         }
     }
 }
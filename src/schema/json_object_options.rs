@@ -42,6 +42,13 @@ pub struct JsonObjectOptions {
     /// `root.child.with.dot:hello`
     #[serde(default)]
     expand_dots_enabled: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+fn is_false(val: &bool) -> bool {
+    !val
 }
 
 impl JsonObjectOptions {
@@ -154,6 +161,22 @@ impl JsonObjectOptions {
         self.indexing = Some(indexing);
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> JsonObjectOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl From<StoredFlag> for JsonObjectOptions {
@@ -163,6 +186,7 @@ impl From<StoredFlag> for JsonObjectOptions {
             indexing: None,
             fast: FastFieldTextOptions::default(),
             expand_dots_enabled: false,
+            required: false,
         }
     }
 }
@@ -174,6 +198,7 @@ impl From<FastFlag> for JsonObjectOptions {
             indexing: None,
             fast: FastFieldTextOptions::IsEnabled(true),
             expand_dots_enabled: false,
+            required: false,
         }
     }
 }
@@ -194,6 +219,7 @@ impl<T: Into<JsonObjectOptions>> BitOr<T> for JsonObjectOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             expand_dots_enabled: self.expand_dots_enabled | other.expand_dots_enabled,
+            required: self.required | other.required,
         }
     }
 }
@@ -216,6 +242,7 @@ impl From<TextOptions> for JsonObjectOptions {
             indexing: text_options.get_indexing_options().cloned(),
             fast: text_options.fast,
             expand_dots_enabled: false,
+            required: false,
         }
     }
 }
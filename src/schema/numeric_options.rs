@@ -16,6 +16,9 @@ pub struct NumericOptions {
     stored: bool,
     #[serde(skip_serializing_if = "is_false")]
     coerce: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
 }
 
 fn is_false(val: &bool) -> bool {
@@ -37,6 +40,8 @@ struct NumericOptionsDeser {
     stored: bool,
     #[serde(default)]
     coerce: bool,
+    #[serde(default)]
+    required: bool,
 }
 
 impl From<NumericOptionsDeser> for NumericOptions {
@@ -47,6 +52,7 @@ impl From<NumericOptionsDeser> for NumericOptions {
             fast: deser.fast,
             stored: deser.stored,
             coerce: deser.coerce,
+            required: deser.required,
         }
     }
 }
@@ -129,6 +135,22 @@ impl NumericOptions {
         self.fast = true;
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> NumericOptions {
+        self.required = true;
+        self
+    }
 }
 
 impl From<()> for NumericOptions {
@@ -145,6 +167,7 @@ impl From<CoerceFlag> for NumericOptions {
             stored: false,
             fast: false,
             coerce: true,
+            required: false,
         }
     }
 }
@@ -157,6 +180,7 @@ impl From<FastFlag> for NumericOptions {
             stored: false,
             fast: true,
             coerce: false,
+            required: false,
         }
     }
 }
@@ -169,6 +193,7 @@ impl From<StoredFlag> for NumericOptions {
             stored: true,
             fast: false,
             coerce: false,
+            required: false,
         }
     }
 }
@@ -181,6 +206,7 @@ impl From<IndexedFlag> for NumericOptions {
             stored: false,
             fast: false,
             coerce: false,
+            required: false,
         }
     }
 }
@@ -196,6 +222,7 @@ impl<T: Into<NumericOptions>> BitOr<T> for NumericOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             coerce: self.coerce | other.coerce,
+            required: self.required | other.required,
         }
     }
 }
@@ -230,6 +257,7 @@ mod tests {
                 fast: false,
                 stored: false,
                 coerce: false,
+                required: false,
             }
         );
     }
@@ -249,6 +277,7 @@ mod tests {
                 fast: false,
                 stored: false,
                 coerce: false,
+                required: false,
             }
         );
     }
@@ -269,6 +298,7 @@ mod tests {
                 fast: false,
                 stored: false,
                 coerce: false,
+                required: false,
             }
         );
     }
@@ -290,6 +320,7 @@ mod tests {
                 fast: false,
                 stored: false,
                 coerce: false,
+                required: false,
             }
         );
     }
@@ -312,6 +343,7 @@ mod tests {
                 fast: false,
                 stored: false,
                 coerce: true,
+                required: false,
             }
         );
     }
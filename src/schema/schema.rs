@@ -194,6 +194,20 @@ impl SchemaBuilder {
         self.add_field(field_entry)
     }
 
+    /// Adds a stored-only field to the schema.
+    ///
+    /// Stored-only fields are meant for payloads that are never searched, like JSON blobs
+    /// or thumbnails: no inverted index, fieldnorm or fast field structure is built for
+    /// them, and their values can only be retrieved from the document store.
+    ///
+    /// # Panics
+    ///
+    /// Panics when field already exists, or if `value_type` is [`Type::Facet`].
+    pub fn add_stored_only_field(&mut self, field_name: &str, value_type: Type) -> Field {
+        let field_entry = FieldEntry::new_stored_only(field_name.to_string(), value_type);
+        self.add_field(field_entry)
+    }
+
     /// Adds a field entry to the schema in build.
     pub fn add_field(&mut self, field_entry: FieldEntry) -> Field {
         let field = Field::from_field_id(self.fields.len() as u32);
@@ -7,7 +7,7 @@ use common::json_path_writer::{JSON_END_OF_PATH, JSON_PATH_SEGMENT_SEP_STR};
 use common::JsonPathWriter;
 
 use super::date_time_options::DATE_TIME_PRECISION_INDEXED;
-use super::{Field, Schema};
+use super::{Field, FieldType, Schema};
 use crate::fastfield::FastValue;
 use crate::json_utils::split_json_path;
 use crate::schema::{Facet, Type};
@@ -114,6 +114,22 @@ impl Term {
         self.0.len() == TERM_METADATA_LENGTH
     }
 
+    /// Builds a term given a field and the serialized representation of its value, as
+    /// returned by [`Term::serialized_value_bytes()`].
+    ///
+    /// This is the inverse of `serialized_value_bytes()`, and makes it possible to ship terms
+    /// across processes, e.g. for distributed deletes, without going through the typed
+    /// constructors. The type of the term is the value type of `field_type`.
+    ///
+    /// Returns an error if `bytes` is not a well-formed value for `field_type`.
+    pub fn from_bytes(field: Field, field_type: &FieldType, bytes: &[u8]) -> crate::Result<Term> {
+        let typ = field_type.value_type();
+        validate_value_bytes(typ, bytes)?;
+        let mut term = Self::with_type_and_field(typ, field);
+        term.0.extend_from_slice(bytes);
+        Ok(term)
+    }
+
     /// Builds a term given a field, and a `Ipv6Addr`-value
     pub fn from_field_ip_addr(field: Field, ip_addr: Ipv6Addr) -> Term {
         let mut term = Self::with_type_and_field(Type::IpAddr, field);
@@ -309,6 +325,10 @@ where B: AsRef<[u8]>
     /// If the term is a string, its value is utf-8 encoded.
     /// If the term is a u64, its value is encoded according
     /// to `byteorder::BigEndian`.
+    ///
+    /// Unlike [`Term::serialized_term()`], this representation is stable: it is the one used
+    /// in the term dictionary, and it can be turned back into a term with
+    /// [`Term::from_bytes()`].
     pub fn serialized_value_bytes(&self) -> &[u8] {
         &self.0.as_ref()[TERM_METADATA_LENGTH..]
     }
@@ -330,6 +350,43 @@ where B: AsRef<[u8]>
     }
 }
 
+/// Checks that `bytes` is a well-formed serialized value of type `typ`.
+fn validate_value_bytes(typ: Type, bytes: &[u8]) -> crate::Result<()> {
+    let is_valid = match typ {
+        Type::Str | Type::Facet => str::from_utf8(bytes).is_ok(),
+        Type::U64 | Type::I64 | Type::F64 | Type::Date => bytes.len() == 8,
+        Type::Bool => bytes.len() == 8 && bytes[..7].iter().all(|&b| b == 0) && bytes[7] <= 1,
+        Type::IpAddr => bytes.len() == 16,
+        Type::Bytes => true,
+        Type::Json => {
+            // `[JSON path][JSON_END_OF_PATH][type code][serialized value]`
+            let Some(pos) = bytes.iter().position(|&b| b == JSON_END_OF_PATH) else {
+                return Err(invalid_value_bytes(typ));
+            };
+            if str::from_utf8(&bytes[..pos]).is_err() {
+                return Err(invalid_value_bytes(typ));
+            }
+            let value_bytes = &bytes[pos + 1..];
+            return match value_bytes.first().copied().and_then(Type::from_code) {
+                Some(Type::Json) | None => Err(invalid_value_bytes(typ)),
+                Some(value_type) => validate_value_bytes(value_type, &value_bytes[1..]),
+            };
+        }
+    };
+    if is_valid {
+        Ok(())
+    } else {
+        Err(invalid_value_bytes(typ))
+    }
+}
+
+fn invalid_value_bytes(typ: Type) -> crate::TantivyError {
+    crate::TantivyError::InvalidArgument(format!(
+        "Invalid serialized value for a term of type {}",
+        typ.name()
+    ))
+}
+
 /// ValueBytes represents a serialized value.
 ///
 /// The value can be of any type of [`Type`] (e.g. string, u64, f64, bool, date, JSON).
@@ -668,4 +725,65 @@ mod tests {
         assert_eq!(term.serialized_term().len(), FAST_VALUE_TERM_LEN);
         assert_eq!(term.value().as_bool(), Some(true))
     }
+
+    #[test]
+    pub fn test_term_from_bytes_round_trip() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let i64_field = schema_builder.add_i64_field("i64", INDEXED);
+        let u64_field = schema_builder.add_u64_field("u64", INDEXED);
+        let date_field = schema_builder.add_date_field("date", INDEXED);
+        let schema = schema_builder.build();
+        let terms = [
+            Term::from_field_text(text_field, "héllo"),
+            Term::from_field_i64(i64_field, -17),
+            Term::from_field_u64(u64_field, u64::MAX),
+            Term::from_field_date(
+                date_field,
+                crate::DateTime::from_timestamp_secs(1_700_000_000),
+            ),
+        ];
+        for term in terms {
+            let field_type = schema.get_field_entry(term.field()).field_type();
+            let round_tripped =
+                Term::from_bytes(term.field(), field_type, term.serialized_value_bytes()).unwrap();
+            assert_eq!(round_tripped, term);
+        }
+    }
+
+    #[test]
+    pub fn test_term_from_bytes_json_round_trip() {
+        let mut schema_builder = Schema::builder();
+        let json_field = schema_builder.add_json_field("json", STRING);
+        let schema = schema_builder.build();
+        let field_type = schema.get_field_entry(json_field).field_type();
+        let mut term = Term::from_field_json_path(json_field, "k8s.node.id", false);
+        term.append_type_and_fast_value(5u64);
+        let round_tripped =
+            Term::from_bytes(json_field, field_type, term.serialized_value_bytes()).unwrap();
+        assert_eq!(round_tripped, term);
+        // A path without a value is not a valid JSON value.
+        let path_only = Term::from_field_json_path(json_field, "k8s.node.id", false);
+        assert!(
+            Term::from_bytes(json_field, field_type, path_only.serialized_value_bytes()).is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_term_from_bytes_invalid() {
+        let mut schema_builder = Schema::builder();
+        let text_field = schema_builder.add_text_field("text", STRING);
+        let u64_field = schema_builder.add_u64_field("u64", INDEXED);
+        let bool_field = schema_builder.add_bool_field("bool", INDEXED);
+        let ip_field = schema_builder.add_ip_addr_field("ip", INDEXED);
+        let schema = schema_builder.build();
+        let field_type = |field| schema.get_field_entry(field).field_type();
+        assert!(Term::from_bytes(text_field, field_type(text_field), &[0xff, 0xfe]).is_err());
+        assert!(Term::from_bytes(u64_field, field_type(u64_field), &[0, 1, 2]).is_err());
+        assert!(Term::from_bytes(bool_field, field_type(bool_field), &2u64.to_be_bytes()).is_err());
+        assert!(Term::from_bytes(ip_field, field_type(ip_field), &1u64.to_be_bytes()).is_err());
+        let term =
+            Term::from_bytes(bool_field, field_type(bool_field), &1u64.to_be_bytes()).unwrap();
+        assert_eq!(term.value().as_bool(), Some(true));
+    }
 }
@@ -1,11 +1,12 @@
 use std::borrow::Cow;
 use std::ops::BitOr;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use super::flags::{CoerceFlag, FastFlag};
 use crate::schema::flags::{SchemaFlagList, StoredFlag};
 use crate::schema::IndexRecordOption;
+use crate::Score;
 
 /// Define how a text field should be handled by tantivy.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
@@ -21,6 +22,9 @@ pub struct TextOptions {
     #[serde(skip_serializing_if = "is_false")]
     /// coerce values into string if they are not of type string
     coerce: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    required: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -125,6 +129,9 @@ impl TextOptions {
     /// The original text can be retrieved via
     /// [`TermDictionary::ord_to_term()`](crate::termdict::TermDictionary::ord_to_term)
     /// from the dictionary.
+    ///
+    /// The stored values, i.e. the text or its tokens, are limited to `u16::MAX` bytes: adding
+    /// a document with a longer value returns an error.
     #[must_use]
     pub fn set_fast(mut self, tokenizer_name: Option<&str>) -> TextOptions {
         if let Some(tokenizer) = tokenizer_name {
@@ -158,6 +165,22 @@ impl TextOptions {
         self.indexing = Some(indexing);
         self
     }
+
+    /// Returns true if documents must have a value for this field.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Sets the field as required.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) then rejects the
+    /// documents without any value for this field.
+    #[must_use]
+    pub fn set_required(mut self) -> TextOptions {
+        self.required = true;
+        self
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Eq, Serialize, Deserialize)]
@@ -191,10 +214,13 @@ impl TokenizerName {
 /// - The amount of information that should be stored about the presence of a term in a document.
 ///   Essentially, should we store the term frequency and/or the positions (See
 ///   [`IndexRecordOption`]).
-/// - The name of the `Tokenizer` that should be used to process the field.
+/// - The name of the `Tokenizer` that should be used to process the field, and optionally the name
+///   of a different `Tokenizer` to process the queries on the field.
 /// - Flag indicating, if fieldnorms should be stored (See [fieldnorm](crate::fieldnorm)). Defaults
 ///   to `true`.
-#[derive(Clone, PartialEq, Debug, Eq, Serialize, Deserialize)]
+/// - The boost applied by default to the matches of the field by the
+///   [`QueryParser`](crate::query::QueryParser). Defaults to `1.0`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TextFieldIndexing {
     #[serde(default)]
     record: IndexRecordOption,
@@ -202,6 +228,29 @@ pub struct TextFieldIndexing {
     fieldnorms: bool,
     #[serde(default)]
     tokenizer: TokenizerName,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search_tokenizer: Option<TokenizerName>,
+    #[serde(default, deserialize_with = "deserialize_boost")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boost: Option<Score>,
+}
+
+// The boost is finite, both when set with `set_boost` and when deserialized, so the equality is
+// total.
+impl Eq for TextFieldIndexing {}
+
+fn deserialize_boost<'de, D>(deserializer: D) -> Result<Option<Score>, D::Error>
+where D: Deserializer<'de> {
+    let boost_opt: Option<Score> = Deserialize::deserialize(deserializer)?;
+    if let Some(boost) = boost_opt {
+        if !(boost.is_finite() && boost > 0.0) {
+            return Err(serde::de::Error::custom(format!(
+                "The boost must be a finite, strictly positive number: {boost}"
+            )));
+        }
+    }
+    Ok(boost_opt)
 }
 
 pub(crate) fn default_fieldnorms() -> bool {
@@ -214,6 +263,8 @@ impl Default for TextFieldIndexing {
             tokenizer: TokenizerName::default(),
             record: IndexRecordOption::default(),
             fieldnorms: default_fieldnorms(),
+            search_tokenizer: None,
+            boost: None,
         }
     }
 }
@@ -231,6 +282,43 @@ impl TextFieldIndexing {
         self.tokenizer.name()
     }
 
+    /// Sets the tokenizer used by the [`QueryParser`](crate::query::QueryParser) to process
+    /// the queries on this field.
+    ///
+    /// By default, queries are processed by the same tokenizer as the indexed text. A
+    /// different tokenizer makes it possible to analyze queries differently, e.g. to expand
+    /// synonyms at query time only. It should still produce terms consistent with the ones
+    /// produced at indexing time, e.g. apply the same lowercasing and stemming.
+    ///
+    /// A query word expanded into several tokens sharing its position and offsets, like the
+    /// synonyms of a [`SynonymFilter`](crate::tokenizer::SynonymFilter), matches any of them.
+    /// Within a phrase query, all of the tokens at a position still have to match.
+    ///
+    /// When the index is created or opened through an
+    /// [`IndexBuilder`](crate::IndexBuilder), both tokenizers must be registered in its
+    /// tokenizer manager.
+    #[must_use]
+    pub fn set_search_tokenizer(mut self, tokenizer_name: &str) -> TextFieldIndexing {
+        self.search_tokenizer = Some(TokenizerName::from_name(tokenizer_name));
+        self
+    }
+
+    /// Returns the tokenizer used to process the queries on this field.
+    ///
+    /// Unless a search tokenizer was set, this is the indexing [tokenizer](Self::tokenizer).
+    pub fn search_tokenizer(&self) -> &str {
+        self.search_tokenizer
+            .as_ref()
+            .unwrap_or(&self.tokenizer)
+            .name()
+    }
+
+    /// Returns true if a search tokenizer was set with
+    /// [`TextFieldIndexing::set_search_tokenizer`].
+    pub(crate) fn has_search_tokenizer(&self) -> bool {
+        self.search_tokenizer.is_some()
+    }
+
     /// Sets fieldnorms
     #[must_use]
     pub fn set_fieldnorms(mut self, fieldnorms: bool) -> TextFieldIndexing {
@@ -258,6 +346,34 @@ impl TextFieldIndexing {
     pub fn index_option(&self) -> IndexRecordOption {
         self.record
     }
+
+    /// Sets the boost applied by default to the matches of the field.
+    ///
+    /// The [`QueryParser`](crate::query::QueryParser) boosts the queries on the field by this
+    /// factor, e.g. to make matches on a `title` field count more than matches on a `body` field.
+    /// It is multiplied with the boosts given in the query through the `^` operator.
+    /// A boost defined with
+    /// [`QueryParser::set_field_boost`](crate::query::QueryParser::set_field_boost)
+    /// replaces it.
+    ///
+    /// Queries built programmatically are not affected.
+    ///
+    /// # Panics
+    /// The method panics if the boost is not a finite, strictly positive number.
+    #[must_use]
+    pub fn set_boost(mut self, boost: Score) -> TextFieldIndexing {
+        assert!(
+            boost.is_finite() && boost > 0.0,
+            "The boost must be a finite, strictly positive number: {boost}"
+        );
+        self.boost = Some(boost);
+        self
+    }
+
+    /// Returns the boost applied by default to the matches of the field.
+    pub fn boost(&self) -> Score {
+        self.boost.unwrap_or(1.0)
+    }
 }
 
 /// The field will be untokenized and indexed.
@@ -266,10 +382,13 @@ pub const STRING: TextOptions = TextOptions {
         tokenizer: TokenizerName::from_static(NO_TOKENIZER_NAME),
         fieldnorms: true,
         record: IndexRecordOption::Basic,
+        search_tokenizer: None,
+        boost: None,
     }),
     stored: false,
     fast: FastFieldTextOptions::IsEnabled(false),
     coerce: false,
+    required: false,
 };
 
 /// The field will be tokenized and indexed.
@@ -278,10 +397,13 @@ pub const TEXT: TextOptions = TextOptions {
         tokenizer: TokenizerName::from_static(DEFAULT_TOKENIZER_NAME),
         fieldnorms: true,
         record: IndexRecordOption::WithFreqsAndPositions,
+        search_tokenizer: None,
+        boost: None,
     }),
     stored: false,
     coerce: false,
     fast: FastFieldTextOptions::IsEnabled(false),
+    required: false,
 };
 
 impl<T: Into<TextOptions>> BitOr<T> for TextOptions {
@@ -294,6 +416,7 @@ impl<T: Into<TextOptions>> BitOr<T> for TextOptions {
             stored: self.stored | other.stored,
             fast: self.fast | other.fast,
             coerce: self.coerce | other.coerce,
+            required: self.required | other.required,
         }
     }
 }
@@ -311,6 +434,7 @@ impl From<StoredFlag> for TextOptions {
             stored: true,
             fast: FastFieldTextOptions::default(),
             coerce: false,
+            required: false,
         }
     }
 }
@@ -322,6 +446,7 @@ impl From<CoerceFlag> for TextOptions {
             stored: false,
             fast: FastFieldTextOptions::default(),
             coerce: true,
+            required: false,
         }
     }
 }
@@ -333,6 +458,7 @@ impl From<FastFlag> for TextOptions {
             stored: false,
             fast: FastFieldTextOptions::IsEnabled(true),
             coerce: false,
+            required: false,
         }
     }
 }
@@ -368,6 +494,20 @@ mod tests {
                 if text_options.get_indexing_options().unwrap().tokenizer() == "default"));
     }
 
+    #[test]
+    fn test_deserialize_boost() {
+        let indexing: TextFieldIndexing = serde_json::from_str(r#"{"boost": 2.5}"#).unwrap();
+        assert_eq!(indexing.boost(), 2.5);
+        assert_eq!(indexing, TextFieldIndexing::default().set_boost(2.5));
+        let indexing: TextFieldIndexing = serde_json::from_str("{}").unwrap();
+        assert_eq!(indexing.boost(), 1.0);
+        for invalid_boost in ["0.0", "-1.0", "1e40"] {
+            let json = format!(r#"{{"boost": {invalid_boost}}}"#);
+            let err = serde_json::from_str::<TextFieldIndexing>(&json).unwrap_err();
+            assert!(err.to_string().contains("strictly positive"), "{err}");
+        }
+    }
+
     #[test]
     fn test_cmp_index_record_option() {
         assert!(IndexRecordOption::WithFreqsAndPositions > IndexRecordOption::WithFreqs);
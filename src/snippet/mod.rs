@@ -56,7 +56,7 @@
 //! the `SnippetGenerator` should generate the snippets.
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::Range;
 
 use htmlescape::encode_minimal;
@@ -71,6 +71,7 @@ const DEFAULT_MAX_NUM_CHARS: usize = 150;
 
 const DEFAULT_SNIPPET_PREFIX: &str = "<b>";
 const DEFAULT_SNIPPET_POSTFIX: &str = "</b>";
+const DEFAULT_FRAGMENT_SEPARATOR: &str = " ... ";
 
 #[derive(Debug)]
 pub(crate) struct FragmentCandidate {
@@ -227,6 +228,63 @@ fn search_fragments(
     fragments
 }
 
+/// Returns a Snippet made of up to `max_num_fragments` fragments.
+///
+/// The fragments with the highest scores are selected, skipping the ones overlapping an
+/// already selected fragment. They are then joined by `separator`, in the order in which
+/// they appear in the text.
+fn select_best_fragments(
+    fragments: &[FragmentCandidate],
+    text: &str,
+    max_num_fragments: usize,
+    separator: &str,
+) -> Snippet {
+    if max_num_fragments <= 1 {
+        return select_best_fragment_combination(fragments, text);
+    }
+    let mut ranked_fragments: Vec<&FragmentCandidate> = fragments.iter().collect();
+    ranked_fragments.sort_by(|left, right| {
+        right
+            .score
+            .partial_cmp(&left.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| {
+                (left.start_offset, left.stop_offset).cmp(&(right.start_offset, right.stop_offset))
+            })
+    });
+    let mut selected_fragments: Vec<&FragmentCandidate> = Vec::with_capacity(max_num_fragments);
+    for fragment in ranked_fragments {
+        if selected_fragments.len() == max_num_fragments {
+            break;
+        }
+        let overlaps_selected_fragment = selected_fragments.iter().any(|selected| {
+            fragment.start_offset < selected.stop_offset
+                && selected.start_offset < fragment.stop_offset
+        });
+        if !overlaps_selected_fragment {
+            selected_fragments.push(fragment);
+        }
+    }
+    if selected_fragments.is_empty() {
+        return Snippet::empty();
+    }
+    selected_fragments.sort_by_key(|fragment| fragment.start_offset);
+    let mut snippet_text = String::new();
+    let mut highlighted = Vec::new();
+    for (ord, fragment) in selected_fragments.into_iter().enumerate() {
+        if ord > 0 {
+            snippet_text.push_str(separator);
+        }
+        let fragment_start = snippet_text.len();
+        snippet_text.push_str(&text[fragment.start_offset..fragment.stop_offset]);
+        highlighted.extend(fragment.highlighted.iter().map(|item| {
+            item.start - fragment.start_offset + fragment_start
+                ..item.end - fragment.start_offset + fragment_start
+        }));
+    }
+    Snippet::new(&snippet_text, highlighted)
+}
+
 /// Returns a Snippet
 ///
 /// Takes a vector of `FragmentCandidate`s and the text.
@@ -379,6 +437,10 @@ pub struct SnippetGenerator {
     tokenizer: TextAnalyzer,
     field: Field,
     max_num_chars: usize,
+    max_num_fragments: usize,
+    fragment_separator: String,
+    snippet_prefix: String,
+    snippet_postfix: String,
 }
 
 impl SnippetGenerator {
@@ -394,6 +456,10 @@ impl SnippetGenerator {
             tokenizer,
             field,
             max_num_chars,
+            max_num_fragments: 1,
+            fragment_separator: DEFAULT_FRAGMENT_SEPARATOR.to_string(),
+            snippet_prefix: DEFAULT_SNIPPET_PREFIX.to_string(),
+            snippet_postfix: DEFAULT_SNIPPET_POSTFIX.to_string(),
         }
     }
     /// Creates a new snippet generator
@@ -402,40 +468,48 @@ impl SnippetGenerator {
         query: &dyn Query,
         field: Field,
     ) -> crate::Result<SnippetGenerator> {
-        let mut terms: BTreeSet<&Term> = BTreeSet::new();
-        query.query_terms(&mut |term, _| {
-            if term.field() == field {
-                terms.insert(term);
-            }
-        });
-        let mut terms_text: BTreeMap<String, Score> = Default::default();
-        for term in terms {
-            let term_value = term.value();
-            let term_str = if let Some(term_str) = term_value.as_str() {
-                term_str
-            } else {
-                continue;
-            };
-            let doc_freq = searcher.doc_freq(term)?;
-            if doc_freq > 0 {
-                let score = 1.0 / (1.0 + doc_freq as Score);
-                terms_text.insert(term_str.to_string(), score);
-            }
-        }
+        let terms_text = terms_text_per_field(searcher, query, &[field])?
+            .remove(&field)
+            .unwrap_or_default();
         let tokenizer = searcher.index().tokenizer_for_field(field)?;
-        Ok(SnippetGenerator {
+        Ok(SnippetGenerator::new(
             terms_text,
             tokenizer,
             field,
-            max_num_chars: DEFAULT_MAX_NUM_CHARS,
-        })
+            DEFAULT_MAX_NUM_CHARS,
+        ))
     }
 
     /// Sets a maximum number of chars. Default is 150.
+    ///
+    /// When several fragments are returned, the limit applies to each fragment.
     pub fn set_max_num_chars(&mut self, max_num_chars: usize) {
         self.max_num_chars = max_num_chars;
     }
 
+    /// Sets the maximum number of fragments of a snippet. Default is 1.
+    ///
+    /// The best non-overlapping fragments are selected, ranked by the sum of the scores of
+    /// the terms they contain, and joined by the fragment separator in the order of the text.
+    pub fn set_max_num_fragments(&mut self, max_num_fragments: usize) {
+        self.max_num_fragments = max_num_fragments;
+    }
+
+    /// Sets the separator inserted between the fragments of a snippet. Default is `" ... "`.
+    pub fn set_fragment_separator(&mut self, separator: &str) {
+        self.fragment_separator = separator.to_string();
+    }
+
+    /// Sets the markers surrounding highlighted terms in the snippets. Default is `<b>` and
+    /// `</b>`.
+    ///
+    /// This is equivalent to calling [`Snippet::set_snippet_prefix_postfix`] on every
+    /// generated snippet.
+    pub fn set_snippet_prefix_postfix(&mut self, prefix: &str, postfix: &str) {
+        self.snippet_prefix = prefix.to_string();
+        self.snippet_postfix = postfix.to_string();
+    }
+
     #[cfg(test)]
     pub(crate) fn terms_text(&self) -> &BTreeMap<String, Score> {
         &self.terms_text
@@ -470,7 +544,155 @@ impl SnippetGenerator {
             &self.terms_text,
             self.max_num_chars,
         );
-        select_best_fragment_combination(&fragment_candidates[..], text)
+        let mut snippet = select_best_fragments(
+            &fragment_candidates[..],
+            text,
+            self.max_num_fragments,
+            &self.fragment_separator,
+        );
+        if !snippet.is_empty() {
+            snippet.set_snippet_prefix_postfix(&self.snippet_prefix, &self.snippet_postfix);
+        }
+        snippet
+    }
+}
+
+/// Returns the text of the terms of `query` targeting one of the `fields`, along with their
+/// score, grouped by field.
+///
+/// Terms that do not appear in the index are ignored.
+fn terms_text_per_field(
+    searcher: &Searcher,
+    query: &dyn Query,
+    fields: &[Field],
+) -> crate::Result<HashMap<Field, BTreeMap<String, Score>>> {
+    let mut terms: BTreeSet<&Term> = BTreeSet::new();
+    query.query_terms(&mut |term, _| {
+        if fields.contains(&term.field()) {
+            terms.insert(term);
+        }
+    });
+    let mut terms_text_per_field: HashMap<Field, BTreeMap<String, Score>> = HashMap::new();
+    for term in terms {
+        let term_value = term.value();
+        let term_str = if let Some(term_str) = term_value.as_str() {
+            term_str
+        } else {
+            continue;
+        };
+        let doc_freq = searcher.doc_freq(term)?;
+        if doc_freq > 0 {
+            let score = 1.0 / (1.0 + doc_freq as Score);
+            terms_text_per_field
+                .entry(term.field())
+                .or_default()
+                .insert(term_str.to_string(), score);
+        }
+    }
+    Ok(terms_text_per_field)
+}
+
+/// `MultiFieldSnippetGenerator` generates snippets for several fields of a document at once.
+///
+/// The terms of the query are collected and scored once for all of the fields, and the
+/// tokenizer of each field is only fetched when the generator is created.
+///
+/// # Example
+///
+/// ```rust
+/// # use tantivy::query::QueryParser;
+/// # use tantivy::schema::{Schema, TEXT};
+/// # use tantivy::{doc, Index};
+/// use tantivy::snippet::MultiFieldSnippetGenerator;
+///
+/// # fn main() -> tantivy::Result<()> {
+/// #    let mut schema_builder = Schema::builder();
+/// #    let title = schema_builder.add_text_field("title", TEXT);
+/// #    let body = schema_builder.add_text_field("body", TEXT);
+/// #    let index = Index::create_in_ram(schema_builder.build());
+/// #    let mut index_writer = index.writer_with_num_threads(1, 20_000_000)?;
+/// #    let doc = doc!(
+/// #        title => "The Old Man and the Sea",
+/// #        body => "He was an old man who fished alone in a skiff in the Gulf Stream.",
+/// #    );
+/// #    index_writer.add_document(doc.clone())?;
+/// #    index_writer.commit()?;
+/// #    let searcher = index.reader()?.searcher();
+/// let query_parser = QueryParser::for_index(&index, vec![title, body]);
+/// let query = query_parser.parse_query("sea fished")?;
+/// let snippet_generator = MultiFieldSnippetGenerator::create(&searcher, &*query, &[title, body])?;
+/// let snippets = snippet_generator.snippets_from_doc(&doc);
+/// assert_eq!(snippets[&title].to_html(), "The Old Man and the <b>Sea</b>");
+/// assert_eq!(
+///     snippets[&body].to_html(),
+///     "He was an old man who <b>fished</b> alone in a skiff in the Gulf Stream"
+/// );
+/// #    Ok(())
+/// # }
+/// ```
+pub struct MultiFieldSnippetGenerator {
+    snippet_generators: HashMap<Field, SnippetGenerator>,
+}
+
+impl MultiFieldSnippetGenerator {
+    /// Creates a new multi-field snippet generator, generating snippets for each of the
+    /// `fields`.
+    pub fn create(
+        searcher: &Searcher,
+        query: &dyn Query,
+        fields: &[Field],
+    ) -> crate::Result<MultiFieldSnippetGenerator> {
+        let mut terms_text_per_field = terms_text_per_field(searcher, query, fields)?;
+        let mut snippet_generators = HashMap::with_capacity(fields.len());
+        for &field in fields {
+            let terms_text = terms_text_per_field.remove(&field).unwrap_or_default();
+            let tokenizer = searcher.index().tokenizer_for_field(field)?;
+            snippet_generators.insert(
+                field,
+                SnippetGenerator::new(terms_text, tokenizer, field, DEFAULT_MAX_NUM_CHARS),
+            );
+        }
+        Ok(MultiFieldSnippetGenerator { snippet_generators })
+    }
+
+    /// Sets the maximum number of chars of the snippets of `field`. Default is 150.
+    ///
+    /// Fields that are not handled by the generator are ignored.
+    pub fn set_max_num_chars(&mut self, field: Field, max_num_chars: usize) {
+        if let Some(snippet_generator) = self.snippet_generators.get_mut(&field) {
+            snippet_generator.set_max_num_chars(max_num_chars);
+        }
+    }
+
+    /// Returns the `SnippetGenerator` used for `field`, if any.
+    pub fn snippet_generator(&self, field: Field) -> Option<&SnippetGenerator> {
+        self.snippet_generators.get(&field)
+    }
+
+    /// Generates a snippet for each of the fields of the generator, given a `Document`.
+    ///
+    /// The document is scanned once. Fields without any matching term get an empty snippet.
+    pub fn snippets_from_doc<D: Document>(&self, doc: &D) -> HashMap<Field, Snippet> {
+        let mut texts: HashMap<Field, String> =
+            HashMap::with_capacity(self.snippet_generators.len());
+        for (field, value) in doc.iter_fields_and_values() {
+            let value = value as D::Value<'_>;
+            if !self.snippet_generators.contains_key(&field) {
+                continue;
+            }
+            if let Some(val) = value.as_str() {
+                let text = texts.entry(field).or_default();
+                text.push(' ');
+                text.push_str(val);
+            }
+        }
+        self.snippet_generators
+            .iter()
+            .map(|(&field, snippet_generator)| {
+                let text = texts.get(&field).map(String::as_str).unwrap_or_default();
+                (field, snippet_generator.snippet(text.trim()))
+            })
+            .collect()
     }
 }
 
@@ -483,8 +705,8 @@ mod tests {
 
     use super::{collapse_overlapped_ranges, search_fragments, select_best_fragment_combination};
     use crate::query::QueryParser;
-    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT};
-    use crate::snippet::SnippetGenerator;
+    use crate::schema::{Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, TEXT};
+    use crate::snippet::{MultiFieldSnippetGenerator, SnippetGenerator};
     use crate::tokenizer::{NgramTokenizer, SimpleTokenizer};
     use crate::Index;
 
@@ -771,6 +993,112 @@ Survey in 2016, 2017, and 2018."#;
         Ok(())
     }
 
+    #[test]
+    fn test_multi_field_snippet_generator() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let other = schema_builder.add_text_field("other", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let doc = doc!(
+            title => "Rust, a language empowering everyone",
+            body => TEST_TEXT,
+            body => "Rust has a mascot",
+            other => "rust",
+        );
+        let mut index_writer = index.writer_for_tests()?;
+        index_writer.add_document(doc.clone())?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let query = query_parser.parse_query("+title:language +body:mascot")?;
+        let mut snippet_generator =
+            MultiFieldSnippetGenerator::create(&searcher, &*query, &[title, body])?;
+        snippet_generator.set_max_num_chars(body, 20);
+        assert_eq!(
+            snippet_generator
+                .snippet_generator(title)
+                .unwrap()
+                .terms_text(),
+            &btreemap!("language".to_string() => 0.5)
+        );
+        assert!(snippet_generator.snippet_generator(other).is_none());
+
+        let snippets = snippet_generator.snippets_from_doc(&doc);
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(
+            snippets[&title].to_html(),
+            "Rust, a <b>language</b> empowering everyone"
+        );
+        // The values of a multi-valued field are concatenated.
+        assert_eq!(snippets[&body].to_html(), "Rust has a <b>mascot</b>");
+
+        let query = query_parser.parse_query("title:language")?;
+        let snippet_generator =
+            MultiFieldSnippetGenerator::create(&searcher, &*query, &[title, body])?;
+        let snippets = snippet_generator.snippets_from_doc(&doc);
+        assert!(!snippets[&title].is_empty());
+        assert!(snippets[&body].is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snippet_generator_custom_tags() {
+        let mut snippet_generator = SnippetGenerator::new(
+            btreemap! { String::from("rust") => 1.0 },
+            From::from(SimpleTokenizer::default()),
+            Field::from_field_id(0),
+            20,
+        );
+        snippet_generator.set_snippet_prefix_postfix("\x1b[1m", "\x1b[0m");
+        let snippet = snippet_generator.snippet("Rust is a systems language");
+        assert_eq!(snippet.to_html(), "\x1b[1mRust\x1b[0m is a systems");
+        snippet_generator.set_snippet_prefix_postfix("<mark>", "</mark>");
+        let snippet = snippet_generator.snippet("Rust is a systems language");
+        assert_eq!(snippet.to_html(), "<mark>Rust</mark> is a systems");
+    }
+
+    #[test]
+    fn test_snippet_generator_max_num_fragments() {
+        let mut snippet_generator = SnippetGenerator::new(
+            btreemap! {
+                String::from("rust") => 1.0,
+                String::from("language") => 0.9,
+                String::from("mozilla") => 0.5,
+            },
+            From::from(SimpleTokenizer::default()),
+            Field::from_field_id(0),
+            30,
+        );
+        let snippet = snippet_generator.snippet(TEST_TEXT);
+        assert_eq!(
+            snippet.to_html(),
+            "<b>language</b> sponsored by\n<b>Mozilla</b>"
+        );
+
+        snippet_generator.set_max_num_fragments(2);
+        let snippet = snippet_generator.snippet(TEST_TEXT);
+        // `language sponsored by Mozilla` has the highest summed score. It is followed by
+        // fragments with a single `Rust`, of which the first one is picked. Fragments are
+        // then joined in the order of the text.
+        assert_eq!(
+            snippet.to_html(),
+            "<b>Rust</b> is a systems programming ... <b>language</b> sponsored by\n<b>Mozilla</b>"
+        );
+        for range in snippet.highlighted() {
+            let highlighted_text = snippet.fragment()[range.clone()].to_lowercase();
+            assert!(["rust", "language", "mozilla"].contains(&highlighted_text.as_str()));
+        }
+
+        snippet_generator.set_fragment_separator(" | ");
+        snippet_generator.set_max_num_fragments(100);
+        let snippet =
+            snippet_generator.snippet("rust a b c d e f g h i j k l m n o p q r s t rust");
+        assert_eq!(snippet.fragment().matches(" | ").count(), 1);
+        assert_eq!(snippet.highlighted().len(), 2);
+        assert!(snippet_generator.snippet("no match").is_empty());
+    }
+
     #[test]
     fn test_snippet_with_overlapped_highlighted_ranges() {
         let text = "abc";
@@ -7,12 +7,13 @@
 //! storage-level details into consideration. For example, if your file system block size is 4096
 //! bytes, we can under-count actual resultant space usage by up to 4095 bytes per file.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use common::ByteCount;
 use serde::{Deserialize, Serialize};
 
-use crate::index::SegmentComponent;
+use crate::directory::Directory;
+use crate::index::{Index, SegmentComponent, SegmentId};
 use crate::schema::Field;
 
 /// Enum containing any of the possible space usage results for segment components.
@@ -288,6 +289,103 @@ impl FieldUsage {
     }
 }
 
+/// Represents the size of the files of the segments of an index, as reported by its directory.
+///
+/// Contrary to [`SearcherSpaceUsage`], this does not require opening the segments, and is
+/// broken down by file extension rather than by field.
+/// It does not account for the files that are not part of a segment, like `meta.json`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IndexFilesSpaceUsage {
+    segments: Vec<SegmentFilesSpaceUsage>,
+    extensions: BTreeMap<String, ByteCount>,
+    total: ByteCount,
+}
+
+impl IndexFilesSpaceUsage {
+    pub(crate) fn compute(index: &Index) -> crate::Result<IndexFilesSpaceUsage> {
+        let directory = index.directory();
+        let mut index_files_space_usage = IndexFilesSpaceUsage {
+            segments: Vec::new(),
+            extensions: BTreeMap::new(),
+            total: ByteCount::default(),
+        };
+        for segment_meta in index.searchable_segment_metas()? {
+            let mut segment_files_space_usage = SegmentFilesSpaceUsage {
+                segment_id: segment_meta.id(),
+                extensions: BTreeMap::new(),
+                total: ByteCount::default(),
+            };
+            for path in segment_meta.list_files() {
+                // The delete file only exists if the segment has deletes.
+                if !directory.exists(&path)? {
+                    continue;
+                }
+                let num_bytes = ByteCount::from(directory.file_num_bytes(&path)?);
+                let extension = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .unwrap_or_default()
+                    .to_string();
+                *segment_files_space_usage
+                    .extensions
+                    .entry(extension.clone())
+                    .or_default() += num_bytes;
+                segment_files_space_usage.total += num_bytes;
+                *index_files_space_usage
+                    .extensions
+                    .entry(extension)
+                    .or_default() += num_bytes;
+                index_files_space_usage.total += num_bytes;
+            }
+            index_files_space_usage
+                .segments
+                .push(segment_files_space_usage);
+        }
+        Ok(index_files_space_usage)
+    }
+
+    /// Per segment space usage.
+    pub fn segments(&self) -> &[SegmentFilesSpaceUsage] {
+        &self.segments[..]
+    }
+
+    /// Space usage per file extension (e.g. `store`, `fast`, `term`, `pos`...), summed over all
+    /// of the segments.
+    pub fn extensions(&self) -> &BTreeMap<String, ByteCount> {
+        &self.extensions
+    }
+
+    /// Returns the total byte usage of the files of the segments.
+    pub fn total(&self) -> ByteCount {
+        self.total
+    }
+}
+
+/// Represents the size of the files of a segment, broken down by file extension.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SegmentFilesSpaceUsage {
+    segment_id: SegmentId,
+    extensions: BTreeMap<String, ByteCount>,
+    total: ByteCount,
+}
+
+impl SegmentFilesSpaceUsage {
+    /// Id of the segment.
+    pub fn segment_id(&self) -> SegmentId {
+        self.segment_id
+    }
+
+    /// Space usage per file extension.
+    pub fn extensions(&self) -> &BTreeMap<String, ByteCount> {
+        &self.extensions
+    }
+
+    /// Returns the total byte usage of the files of the segment.
+    pub fn total(&self) -> ByteCount {
+        self.total
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::index::Index;
@@ -295,6 +393,69 @@ mod test {
     use crate::space_usage::PerFieldSpaceUsage;
     use crate::{IndexWriter, Term};
 
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_index_files_space_usage() -> crate::Result<()> {
+        use common::ByteCount;
+
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT | STORED);
+        let num = schema_builder.add_u64_field("num", FAST | INDEXED);
+        let index_dir = tempfile::TempDir::new()?;
+        let index = Index::create_in_dir(index_dir.path(), schema_builder.build())?;
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        for i in 0..10u64 {
+            index_writer.add_document(doc!(text => format!("hello {i}"), num => i))?;
+        }
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "world", num => 10u64))?;
+        index_writer.delete_term(Term::from_field_u64(num, 0));
+        index_writer.commit()?;
+
+        let files_space_usage = index.files_space_usage()?;
+        assert_eq!(files_space_usage.segments().len(), 2);
+        let mut total = 0;
+        for segment_files_space_usage in files_space_usage.segments() {
+            let segment_id = segment_files_space_usage.segment_id();
+            let mut segment_total = 0;
+            for (extension, num_bytes) in segment_files_space_usage.extensions() {
+                let suffix = format!(".{extension}");
+                let file_sizes: Vec<u64> = std::fs::read_dir(index_dir.path())?
+                    .map(|entry| entry.unwrap())
+                    .filter(|entry| {
+                        let file_name = entry.file_name().into_string().unwrap();
+                        file_name.starts_with(&segment_id.uuid_string())
+                            && file_name.ends_with(&suffix)
+                    })
+                    .map(|entry| entry.metadata().unwrap().len())
+                    .collect();
+                assert_eq!(file_sizes.len(), 1);
+                assert_eq!(*num_bytes, ByteCount::from(file_sizes[0]));
+                segment_total += file_sizes[0];
+            }
+            assert_eq!(segment_files_space_usage.total(), segment_total);
+            total += segment_total;
+        }
+        assert_eq!(files_space_usage.total(), total);
+        // Only the first segment has deletes.
+        let extensions: Vec<&str> = files_space_usage
+            .extensions()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            extensions,
+            &["del", "fast", "fieldnorm", "idx", "pos", "store", "term"]
+        );
+        let num_delete_files = files_space_usage
+            .segments()
+            .iter()
+            .filter(|segment| segment.extensions().contains_key("del"))
+            .count();
+        assert_eq!(num_delete_files, 1);
+        Ok(())
+    }
+
     #[test]
     fn test_empty() {
         let schema = Schema::builder().build();
@@ -181,6 +181,14 @@ impl StoreReader {
         self.skip_index.checkpoints()
     }
 
+    /// Returns the number of compressed blocks of the doc store.
+    ///
+    /// Documents are decompressed a block at a time: this bounds the number of decompressions
+    /// needed to read all of the documents.
+    pub fn num_blocks(&self) -> usize {
+        self.block_checkpoints().count()
+    }
+
     pub(crate) fn decompressor(&self) -> Decompressor {
         self.decompressor
     }
@@ -245,6 +253,42 @@ impl StoreReader {
         D::deserialize(deserializer).map_err(crate::TantivyError::from)
     }
 
+    /// Reads several documents at once, and returns them in the order of `doc_ids`.
+    ///
+    /// Documents are read in doc id order, so that each block is decompressed at most
+    /// once, regardless of the order of `doc_ids` and of the state of the LRU cache.
+    /// This makes it much cheaper than calling [`get`](Self::get) in a loop, e.g. to
+    /// fetch a page of results.
+    pub fn get_many<D: DocumentDeserialize>(&self, doc_ids: &[DocId]) -> crate::Result<Vec<D>> {
+        let mut doc_ords: Vec<usize> = (0..doc_ids.len()).collect();
+        doc_ords.sort_unstable_by_key(|&doc_ord| doc_ids[doc_ord]);
+        let mut docs: Vec<Option<D>> = std::iter::repeat_with(|| None)
+            .take(doc_ids.len())
+            .collect();
+        let mut current_block: Option<(Checkpoint, Block)> = None;
+        for doc_ord in doc_ords {
+            let doc_id = doc_ids[doc_ord];
+            let (checkpoint, block) = match current_block.take() {
+                Some((checkpoint, block)) if checkpoint.doc_range.contains(&doc_id) => {
+                    (checkpoint, block)
+                }
+                _ => {
+                    let checkpoint = self.block_checkpoint(doc_id)?;
+                    let block = self.read_block(&checkpoint)?;
+                    (checkpoint, block)
+                }
+            };
+            let mut doc_bytes =
+                Self::get_document_bytes_from_block(block.clone(), doc_id, &checkpoint)?;
+            let deserializer =
+                BinaryDocumentDeserializer::from_reader(&mut doc_bytes, self.doc_store_version)
+                    .map_err(crate::TantivyError::from)?;
+            docs[doc_ord] = Some(D::deserialize(deserializer).map_err(crate::TantivyError::from)?);
+            current_block = Some((checkpoint, block));
+        }
+        Ok(docs.into_iter().flatten().collect())
+    }
+
     /// Returns raw bytes of a given document.
     ///
     /// Calling `.get(doc)` is relatively costly as it requires
@@ -278,14 +322,30 @@ impl StoreReader {
         &'b self,
         alive_bitset: Option<&'a AliveBitSet>,
     ) -> impl Iterator<Item = crate::Result<D>> + 'b {
-        self.iter_raw(alive_bitset).map(|doc_bytes_res| {
-            let mut doc_bytes = doc_bytes_res?;
+        self.iter_with_doc_ids(alive_bitset)
+            .map(|res| res.map(|(_, doc)| doc))
+    }
 
-            let deserializer =
-                BinaryDocumentDeserializer::from_reader(&mut doc_bytes, self.doc_store_version)
-                    .map_err(crate::TantivyError::from)?;
-            D::deserialize(deserializer).map_err(crate::TantivyError::from)
-        })
+    /// Iterator over all Documents and their `DocId`, in their order as they are stored in the
+    /// doc store.
+    ///
+    /// The blocks are decompressed one after the other, and only the current block is kept in
+    /// memory (besides the LRU cache): each block is decompressed once, contrary to calling
+    /// [`get`](Self::get) for each `DocId` when the cache is too small.
+    /// The `alive_bitset` has to be forwarded from the `SegmentReader` or the results may be wrong.
+    pub fn iter_with_doc_ids<'a: 'b, 'b, D: DocumentDeserialize>(
+        &'b self,
+        alive_bitset: Option<&'a AliveBitSet>,
+    ) -> impl Iterator<Item = crate::Result<(DocId, D)>> + 'b {
+        self.iter_raw_with_doc_ids(alive_bitset)
+            .map(|doc_bytes_res| {
+                let (doc_id, mut doc_bytes) = doc_bytes_res?;
+                let deserializer =
+                    BinaryDocumentDeserializer::from_reader(&mut doc_bytes, self.doc_store_version)
+                        .map_err(crate::TantivyError::from)?;
+                let doc = D::deserialize(deserializer).map_err(crate::TantivyError::from)?;
+                Ok((doc_id, doc))
+            })
     }
 
     /// Iterator over all raw Documents in their order as they are stored in the doc store.
@@ -295,6 +355,14 @@ impl StoreReader {
         &'b self,
         alive_bitset: Option<&'a AliveBitSet>,
     ) -> impl Iterator<Item = crate::Result<OwnedBytes>> + 'b {
+        self.iter_raw_with_doc_ids(alive_bitset)
+            .map(|doc_bytes_res| doc_bytes_res.map(|(_doc_id, doc_bytes)| doc_bytes))
+    }
+
+    fn iter_raw_with_doc_ids<'a: 'b, 'b>(
+        &'b self,
+        alive_bitset: Option<&'a AliveBitSet>,
+    ) -> impl Iterator<Item = crate::Result<(DocId, OwnedBytes)>> + 'b {
         let last_doc_id = self
             .block_checkpoints()
             .last()
@@ -322,14 +390,14 @@ impl StoreReader {
 
                 let alive = alive_bitset.map_or(true, |bitset| bitset.is_alive(doc_id));
                 let res = if alive {
-                    Some((curr_block.clone(), doc_pos))
+                    Some((doc_id, curr_block.clone(), doc_pos))
                 } else {
                     None
                 };
                 doc_pos += 1;
                 res
             })
-            .map(move |(block, doc_pos)| {
+            .map(move |(doc_id, block, doc_pos)| {
                 let block = block
                     .ok_or_else(|| {
                         DataCorruption::comment_only(
@@ -342,7 +410,7 @@ impl StoreReader {
                     })?;
 
                 let range = block_read_index(&block, doc_pos)?;
-                Ok(block.slice(range))
+                Ok((doc_id, block.slice(range)))
             })
     }
 
@@ -501,4 +569,83 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_store_get_many() -> crate::Result<()> {
+        let directory = RamDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500, Compressor::default(), BLOCK_SIZE, true);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+        // Without a cache, every block read is a miss.
+        let store = StoreReader::open(store_file, 0)?;
+        assert!(store.block_checkpoints().count() > 1);
+
+        let doc_ids = [499, 0, 498, 1, 0];
+        let docs: Vec<TantivyDocument> = store.get_many(&doc_ids)?;
+        let titles: Vec<&str> = docs
+            .iter()
+            .map(|doc| get_text_field(doc, &title).unwrap())
+            .collect();
+        assert_eq!(
+            titles,
+            vec!["Doc 499", "Doc 0", "Doc 498", "Doc 1", "Doc 0"]
+        );
+        // One block read for the first docs, and one for the last docs.
+        assert_eq!(store.cache_stats().cache_misses, 2);
+
+        for doc_id in doc_ids {
+            let doc: TantivyDocument = store.get(doc_id)?;
+            assert_eq!(
+                get_text_field(&doc, &title),
+                Some(format!("Doc {doc_id}").as_str())
+            );
+        }
+        assert_eq!(store.cache_stats().cache_misses, 7);
+
+        assert!(store.get_many::<TantivyDocument>(&[]).unwrap().is_empty());
+        assert!(store.get_many::<TantivyDocument>(&[0, 500]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_iter_with_doc_ids() -> crate::Result<()> {
+        let directory = RamDirectory::create();
+        let path = Path::new("store");
+        let writer = directory.open_write(path)?;
+        let schema = write_lorem_ipsum_store(writer, 500, Compressor::default(), BLOCK_SIZE, true);
+        let title = schema.get_field("title").unwrap();
+        let store_file = directory.open_read(path)?;
+        let store = StoreReader::open(store_file, 0)?;
+        let num_blocks = store.num_blocks();
+        assert!(num_blocks > 1);
+
+        let mut num_docs = 0;
+        for doc_res in store.iter_with_doc_ids::<TantivyDocument>(None) {
+            let (doc_id, doc) = doc_res?;
+            assert_eq!(doc_id, num_docs);
+            let expected_doc: TantivyDocument = store.get(doc_id)?;
+            assert_eq!(doc, expected_doc);
+            assert_eq!(
+                get_text_field(&doc, &title),
+                Some(format!("Doc {doc_id}").as_str())
+            );
+            num_docs += 1;
+        }
+        assert_eq!(num_docs, 500);
+        // Each block is decompressed once by the iterator, and once per document by `get`.
+        assert_eq!(store.cache_stats().cache_misses, num_blocks + 500);
+
+        let alive_bitset = AliveBitSet::for_test_from_deleted_docs(&[0, 250, 499], 500);
+        let doc_ids: Vec<DocId> = store
+            .iter_with_doc_ids::<TantivyDocument>(Some(&alive_bitset))
+            .map(|doc_res| doc_res.map(|(doc_id, _)| doc_id))
+            .collect::<crate::Result<_>>()?;
+        assert_eq!(doc_ids.len(), 497);
+        assert!(!doc_ids.contains(&250));
+        assert_eq!(doc_ids.first(), Some(&1));
+        assert_eq!(doc_ids.last(), Some(&498));
+        Ok(())
+    }
 }
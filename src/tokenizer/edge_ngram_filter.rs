@@ -0,0 +1,203 @@
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+use crate::TantivyError;
+
+/// A [`TokenFilter`] replacing each token with its leading n-grams, also known as edge n-grams.
+///
+/// Unlike [`NgramTokenizer::prefix_only`](crate::tokenizer::NgramTokenizer::prefix_only),
+/// which only considers the beginning of the whole text, the n-grams are anchored at the
+/// beginning of every token emitted by the underlying tokenizer. This is typically used to
+/// implement autocomplete on multi-word fields.
+///
+/// Tokens shorter than `min_gram` characters are removed. All of the n-grams of a token keep
+/// the position and the offsets of the original token, so that highlighting covers the whole
+/// word.
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+///     .filter(EdgeNgramFilter::new(1, 3).unwrap())
+///     .build();
+/// let mut stream = tokenizer.token_stream("hello world");
+/// for text in ["h", "he", "hel", "w", "wo", "wor"] {
+///     assert_eq!(stream.next().unwrap().text, text);
+/// }
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdgeNgramFilter {
+    min_gram: usize,
+    max_gram: usize,
+}
+
+impl EdgeNgramFilter {
+    /// Creates an `EdgeNgramFilter` emitting the leading n-grams of `min_gram` to `max_gram`
+    /// characters of each token.
+    pub fn new(min_gram: usize, max_gram: usize) -> crate::Result<EdgeNgramFilter> {
+        if min_gram == 0 {
+            return Err(TantivyError::InvalidArgument(
+                "min_gram must be greater than 0".to_string(),
+            ));
+        }
+        if min_gram > max_gram {
+            return Err(TantivyError::InvalidArgument(
+                "min_gram must not be greater than max_gram".to_string(),
+            ));
+        }
+        Ok(EdgeNgramFilter { min_gram, max_gram })
+    }
+}
+
+impl TokenFilter for EdgeNgramFilter {
+    type Tokenizer<T: Tokenizer> = EdgeNgramFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> EdgeNgramFilterWrapper<T> {
+        EdgeNgramFilterWrapper {
+            min_gram: self.min_gram,
+            max_gram: self.max_gram,
+            inner: tokenizer,
+            token: Token::default(),
+            char_ends: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EdgeNgramFilterWrapper<T> {
+    min_gram: usize,
+    max_gram: usize,
+    inner: T,
+    token: Token,
+    char_ends: Vec<usize>,
+}
+
+impl<T: Tokenizer> Tokenizer for EdgeNgramFilterWrapper<T> {
+    type TokenStream<'a> = EdgeNgramTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.token.reset();
+        self.char_ends.clear();
+        EdgeNgramTokenStream {
+            min_gram: self.min_gram,
+            max_gram: self.max_gram,
+            tail: self.inner.token_stream(text),
+            token: &mut self.token,
+            char_ends: &mut self.char_ends,
+            gram_len: 0,
+        }
+    }
+}
+
+pub struct EdgeNgramTokenStream<'a, T> {
+    min_gram: usize,
+    max_gram: usize,
+    tail: T,
+    token: &'a mut Token,
+    // Byte offsets of the end of each character of the current token of `tail`.
+    char_ends: &'a mut Vec<usize>,
+    // Number of characters of the next n-gram.
+    gram_len: usize,
+}
+
+impl<T: TokenStream> TokenStream for EdgeNgramTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        loop {
+            if self.gram_len >= self.min_gram
+                && self.gram_len <= self.max_gram.min(self.char_ends.len())
+            {
+                let gram_end = self.char_ends[self.gram_len - 1];
+                self.token.text.clear();
+                self.token
+                    .text
+                    .push_str(&self.tail.token().text[..gram_end]);
+                self.gram_len += 1;
+                return true;
+            }
+            if !self.tail.advance() {
+                return false;
+            }
+            let token = self.tail.token();
+            self.char_ends.clear();
+            self.char_ends.extend(
+                token
+                    .text
+                    .char_indices()
+                    .map(|(offset, c)| offset + c.len_utf8()),
+            );
+            self.token.clone_from(token);
+            self.gram_len = self.min_gram;
+        }
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EdgeNgramFilter;
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{
+        NgramTokenizer, SimpleTokenizer, TextAnalyzer, Token, TokenStream, Tokenizer,
+    };
+
+    fn edge_ngrams(min_gram: usize, max_gram: usize, text: &str) -> Vec<Token> {
+        let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(EdgeNgramFilter::new(min_gram, max_gram).unwrap())
+            .build();
+        let mut tokens: Vec<Token> = vec![];
+        tokenizer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_edge_ngram_filter_vs_ngram_tokenizer() {
+        let mut ngram_tokenizer = NgramTokenizer::all_ngrams(2, 3).unwrap();
+        let mut ngrams: Vec<String> = vec![];
+        ngram_tokenizer
+            .token_stream("ab cde")
+            .process(&mut |token: &Token| ngrams.push(token.text.clone()));
+        // The ngram tokenizer works on the whole text, regardless of word boundaries.
+        assert_eq!(
+            ngrams,
+            vec!["ab", "ab ", "b ", "b c", " c", " cd", "cd", "cde", "de"]
+        );
+
+        let tokens = edge_ngrams(2, 3, "ab cde");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "ab", 0, 2);
+        assert_token(&tokens[1], 1, "cd", 3, 6);
+        assert_token(&tokens[2], 1, "cde", 3, 6);
+    }
+
+    #[test]
+    fn test_edge_ngram_filter_min_max_gram() {
+        let tokens = edge_ngrams(2, 4, "I love autocompletion");
+        let texts: Vec<&str> = tokens.iter().map(|token| token.text.as_str()).collect();
+        // `I` is shorter than `min_gram`.
+        assert_eq!(texts, vec!["lo", "lov", "love", "au", "aut", "auto"]);
+        assert_token(&tokens[5], 2, "auto", 7, 21);
+    }
+
+    #[test]
+    fn test_edge_ngram_filter_non_ascii() {
+        let tokens = edge_ngrams(1, 10, "hεllo");
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "h", 0, 6);
+        assert_token(&tokens[1], 0, "hε", 0, 6);
+        assert_token(&tokens[4], 0, "hεllo", 0, 6);
+    }
+
+    #[test]
+    fn test_edge_ngram_filter_invalid_arguments() {
+        assert!(EdgeNgramFilter::new(0, 2).is_err());
+        assert!(EdgeNgramFilter::new(3, 2).is_err());
+    }
+}
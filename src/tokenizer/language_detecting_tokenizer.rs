@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use rustc_hash::FxHashSet;
+
+use super::stemmer::StemmerTokenStream;
+use super::{Language, StopWordFilter, Tokenizer};
+
+/// Languages that can be detected, i.e. the languages with a list of stop words.
+const SUPPORTED_LANGUAGES: [Language; 13] = [
+    Language::Danish,
+    Language::Dutch,
+    Language::English,
+    Language::Finnish,
+    Language::French,
+    Language::German,
+    Language::Hungarian,
+    Language::Italian,
+    Language::Norwegian,
+    Language::Portuguese,
+    Language::Russian,
+    Language::Spanish,
+    Language::Swedish,
+];
+
+/// Only the first words of a text are used to detect its language.
+const MAX_NUM_DETECTION_WORDS: usize = 1_000;
+
+const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.3;
+
+/// A [`Tokenizer`] wrapper that detects the language of each text, and stems the tokens of
+/// the inner tokenizer with the [`Stemmer`](super::Stemmer) of the detected language.
+///
+/// The language detector is deliberately lightweight: it counts the stop words of each
+/// candidate language among the first words of the text. The confidence of the detection is
+/// the margin between the best and the second best languages, relative to the best
+/// language. If no stop word is found, or if the confidence is below the confidence
+/// threshold, the text is stemmed with the default language.
+///
+/// # Ordering
+///
+/// The detection runs on the raw text, before the inner tokenizer and its filters. The
+/// stemming happens after the inner tokenizer: as the `Stemmer` expects lowercased tokens,
+/// the inner tokenizer should include a [`LowerCaser`](super::LowerCaser). The filters added
+/// on top of the `LanguageDetectingTokenizer` see the stemmed tokens, e.g. a
+/// [`StopWordFilter`] should rather be part of the inner tokenizer.
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let tokenizer = LanguageDetectingTokenizer::new(
+///     LowerCaser.transform(SimpleTokenizer::default()),
+///     Language::English,
+/// );
+/// assert_eq!(
+///     tokenizer.detect_language("Les enfants jouent dans le jardin"),
+///     Language::French
+/// );
+/// let mut analyzer = TextAnalyzer::builder(tokenizer)
+///     .filter(RemoveLongFilter::limit(40))
+///     .build();
+/// let mut stream = analyzer.token_stream("The children are playing in the gardens");
+/// let tokens: Vec<String> = std::iter::from_fn(|| stream.next().map(|t| t.text.clone())).collect();
+/// assert_eq!(tokens, vec!["the", "children", "are", "play", "in", "the", "garden"]);
+/// ```
+#[derive(Clone)]
+pub struct LanguageDetectingTokenizer<T> {
+    inner: T,
+    default_language: Language,
+    confidence_threshold: f32,
+    candidates: Arc<Vec<(Language, FxHashSet<String>)>>,
+}
+
+impl<T> LanguageDetectingTokenizer<T> {
+    /// Creates a `LanguageDetectingTokenizer` detecting all of the
+    /// [supported languages](Self::supported_languages), and falling back to
+    /// `default_language`.
+    ///
+    /// The default language does not need to be a supported language.
+    pub fn new(inner: T, default_language: Language) -> LanguageDetectingTokenizer<T> {
+        LanguageDetectingTokenizer {
+            inner,
+            default_language,
+            confidence_threshold: DEFAULT_CONFIDENCE_THRESHOLD,
+            candidates: Arc::new(candidates(SUPPORTED_LANGUAGES)),
+        }
+    }
+
+    /// Returns the languages that can be detected.
+    pub fn supported_languages() -> &'static [Language] {
+        &SUPPORTED_LANGUAGES
+    }
+
+    /// Restricts the detection to the given languages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the languages is not supported.
+    #[must_use]
+    pub fn with_languages<L: IntoIterator<Item = Language>>(
+        self,
+        languages: L,
+    ) -> LanguageDetectingTokenizer<T> {
+        LanguageDetectingTokenizer {
+            candidates: Arc::new(candidates(languages)),
+            ..self
+        }
+    }
+
+    /// Sets the minimum confidence, between 0 and 1, for the detected language to be used.
+    /// Defaults to 0.3.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the threshold is not within `[0, 1]`.
+    #[must_use]
+    pub fn with_confidence_threshold(
+        self,
+        confidence_threshold: f32,
+    ) -> LanguageDetectingTokenizer<T> {
+        assert!(
+            (0.0..=1.0).contains(&confidence_threshold),
+            "The confidence threshold must be within [0, 1], got {confidence_threshold}."
+        );
+        LanguageDetectingTokenizer {
+            confidence_threshold,
+            ..self
+        }
+    }
+
+    /// Returns the language used to stem `text`: the detected language, or the default
+    /// language if the detection is uncertain.
+    pub fn detect_language(&self, text: &str) -> Language {
+        let mut num_stop_words = vec![0u32; self.candidates.len()];
+        let mut word = String::new();
+        for raw_word in text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|raw_word| !raw_word.is_empty())
+            .take(MAX_NUM_DETECTION_WORDS)
+        {
+            word.clear();
+            word.extend(raw_word.chars().flat_map(char::to_lowercase));
+            for ((_, stop_words), count) in self.candidates.iter().zip(&mut num_stop_words) {
+                if stop_words.contains(&word) {
+                    *count += 1;
+                }
+            }
+        }
+        let mut best: Option<(usize, u32)> = None;
+        let mut second_best_count = 0;
+        for (ord, &count) in num_stop_words.iter().enumerate() {
+            match best {
+                Some((_, best_count)) if count <= best_count => {
+                    second_best_count = second_best_count.max(count);
+                }
+                _ => {
+                    second_best_count = best.map(|(_, best_count)| best_count).unwrap_or(0);
+                    best = Some((ord, count));
+                }
+            }
+        }
+        match best {
+            Some((ord, best_count)) if best_count > 0 => {
+                let confidence = (best_count - second_best_count) as f32 / best_count as f32;
+                if confidence >= self.confidence_threshold {
+                    self.candidates[ord].0
+                } else {
+                    self.default_language
+                }
+            }
+            _ => self.default_language,
+        }
+    }
+}
+
+fn candidates<L: IntoIterator<Item = Language>>(
+    languages: L,
+) -> Vec<(Language, FxHashSet<String>)> {
+    languages
+        .into_iter()
+        .map(|language| {
+            let stop_word_filter = StopWordFilter::new(language).unwrap_or_else(|| {
+                panic!("Language {language:?} is not supported by the language detector.")
+            });
+            (language, stop_word_filter.words().cloned().collect())
+        })
+        .collect()
+}
+
+impl<T: Tokenizer> Tokenizer for LanguageDetectingTokenizer<T> {
+    type TokenStream<'a> = StemmerTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        let language = self.detect_language(text);
+        StemmerTokenStream::new(self.inner.token_stream(text), language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LanguageDetectingTokenizer;
+    use crate::tokenizer::{
+        Language, LowerCaser, SimpleTokenizer, Stemmer, TextAnalyzer, Token, TokenFilter,
+    };
+
+    fn token_texts(mut analyzer: TextAnalyzer, text: &str) -> Vec<String> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens: Vec<String> = vec![];
+        token_stream.process(&mut |token: &Token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    fn stemmed(language: Language, text: &str) -> Vec<String> {
+        let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(Stemmer::new(language))
+            .build();
+        token_texts(analyzer, text)
+    }
+
+    fn language_detecting_tokenizer() -> LanguageDetectingTokenizer<impl crate::tokenizer::Tokenizer>
+    {
+        LanguageDetectingTokenizer::new(
+            LowerCaser.transform(SimpleTokenizer::default()),
+            Language::German,
+        )
+    }
+
+    #[test]
+    fn test_language_detecting_tokenizer_stems_per_language() {
+        let english = "The cats are running in the gardens, and they were jumping over the fences";
+        let french = "Les chanteuses chantaient des chansons dans les jardins de la ville, et \
+                      nous les écoutions";
+        let tokenizer = language_detecting_tokenizer();
+        assert_eq!(tokenizer.detect_language(english), Language::English);
+        assert_eq!(tokenizer.detect_language(french), Language::French);
+
+        let analyzer = TextAnalyzer::from(tokenizer);
+        let english_tokens = token_texts(analyzer.clone(), english);
+        assert_eq!(english_tokens, stemmed(Language::English, english));
+        assert_ne!(english_tokens, stemmed(Language::French, english));
+        assert!(english_tokens.contains(&"garden".to_string()));
+        let french_tokens = token_texts(analyzer, french);
+        assert_eq!(french_tokens, stemmed(Language::French, french));
+        assert_ne!(french_tokens, stemmed(Language::English, french));
+        assert!(french_tokens.contains(&"jardin".to_string()));
+    }
+
+    #[test]
+    fn test_language_detecting_tokenizer_fallback() {
+        let tokenizer = language_detecting_tokenizer();
+        // No stop words.
+        assert_eq!(tokenizer.detect_language("Zyxxy qwerty"), Language::German);
+        assert_eq!(tokenizer.detect_language(""), Language::German);
+        // "de" is a stop word in several languages.
+        assert_eq!(tokenizer.detect_language("de"), Language::German);
+
+        // The candidates can be restricted.
+        let tokenizer = language_detecting_tokenizer().with_languages([Language::Spanish]);
+        assert_eq!(tokenizer.detect_language("de"), Language::Spanish);
+        let tokenizer = tokenizer.with_confidence_threshold(1.0);
+        assert_eq!(tokenizer.detect_language("de"), Language::Spanish);
+    }
+
+    #[test]
+    fn test_language_detecting_tokenizer_supported_languages() {
+        let supported_languages =
+            LanguageDetectingTokenizer::<SimpleTokenizer>::supported_languages();
+        assert!(supported_languages.contains(&Language::English));
+        assert!(supported_languages.contains(&Language::French));
+        assert!(!supported_languages.contains(&Language::Arabic));
+    }
+
+    #[test]
+    #[should_panic(expected = "not supported")]
+    fn test_language_detecting_tokenizer_unsupported_language() {
+        let _ = language_detecting_tokenizer().with_languages([Language::Tamil]);
+    }
+}
@@ -0,0 +1,157 @@
+//! # Example
+//! ```rust
+//! use tantivy::tokenizer::*;
+//!
+//! let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+//!   .filter(LowerCaser)
+//!   .filter(LengthFilter::new(2, Some(5)))
+//!   .build();
+//!
+//! let mut stream = tokenizer.token_stream("a toolong nice");
+//! // `a` is shorter than 2 characters, and `toolong` is longer than 5 characters:
+//! // they are filtered out of the token stream.
+//! assert_eq!(stream.next().unwrap().text, "nice");
+//! assert!(stream.next().is_none());
+//! ```
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// `LengthFilter` removes tokens whose number of characters is outside of a given range.
+///
+/// Unlike [`RemoveLongFilter`](super::RemoveLongFilter), the length of a token is measured in
+/// characters rather than in bytes, so that multi-byte characters count as one.
+#[derive(Clone)]
+pub struct LengthFilter {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl LengthFilter {
+    /// Creates a `LengthFilter` keeping the tokens having between `min` and `max` characters,
+    /// bounds included.
+    ///
+    /// If `max` is `None`, tokens are not limited in length.
+    pub fn new(min: usize, max: Option<usize>) -> LengthFilter {
+        LengthFilter { min, max }
+    }
+}
+
+impl<T> LengthFilterStream<T> {
+    fn predicate(&self, token: &Token) -> bool {
+        // Counting the characters stops as soon as the upper bound is exceeded.
+        let limit = self
+            .max
+            .map_or(self.min, |max| max.max(self.min))
+            .saturating_add(1);
+        let num_chars = token.text.chars().take(limit).count();
+        num_chars >= self.min && self.max.map_or(true, |max| num_chars <= max)
+    }
+}
+
+impl TokenFilter for LengthFilter {
+    type Tokenizer<T: Tokenizer> = LengthFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> LengthFilterWrapper<T> {
+        LengthFilterWrapper {
+            min: self.min,
+            max: self.max,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LengthFilterWrapper<T: Tokenizer> {
+    min: usize,
+    max: Option<usize>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for LengthFilterWrapper<T> {
+    type TokenStream<'a> = LengthFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        LengthFilterStream {
+            min: self.min,
+            max: self.max,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct LengthFilterStream<T> {
+    min: usize,
+    max: Option<usize>,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for LengthFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if self.predicate(self.tail.token()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{LengthFilter, LowerCaser, SimpleTokenizer, TextAnalyzer, Token};
+
+    fn token_stream_helper(length_filter: LengthFilter, text: &str) -> Vec<Token> {
+        let mut a = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(length_filter)
+            .build();
+        let mut token_stream = a.token_stream(text);
+        let mut tokens: Vec<Token> = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
+    #[test]
+    fn test_length_filter() {
+        let tokens = token_stream_helper(LengthFilter::new(2, Some(5)), "A bb CCC ddddd eeeeee");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 1, "bb", 2, 4);
+        assert_token(&tokens[1], 2, "ccc", 5, 8);
+        assert_token(&tokens[2], 3, "ddddd", 9, 14);
+    }
+
+    #[test]
+    fn test_length_filter_no_upper_bound() {
+        let text = "a bb ".to_string() + &"c".repeat(1_000);
+        let tokens = token_stream_helper(LengthFilter::new(2, None), &text);
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 1, "bb", 2, 4);
+        assert_eq!(tokens[1].text.len(), 1_000);
+    }
+
+    #[test]
+    fn test_length_filter_counts_characters() {
+        // `日本語` is 3 characters, but 9 bytes long.
+        let tokens = token_stream_helper(LengthFilter::new(3, Some(3)), "日本語 ab été Ünïcödé");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "日本語", 0, 9);
+        assert_token(&tokens[1], 2, "été", 13, 18);
+
+        // `ünïcödé` is 7 characters, and the lowercased `Ü` is still a single character.
+        let tokens = token_stream_helper(LengthFilter::new(7, None), "日本語 ünïcödé Ünïcödé");
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].text, "ünïcödé");
+        assert_eq!(tokens[1].text, "ünïcödé");
+    }
+}
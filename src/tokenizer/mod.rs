@@ -123,10 +123,16 @@
 //! ```
 mod alphanum_only;
 mod ascii_folding_filter;
+mod edge_ngram_filter;
 mod empty_tokenizer;
 mod facet_tokenizer;
+#[cfg(feature = "stopwords")]
+mod language_detecting_tokenizer;
+mod length_filter;
 mod lower_caser;
 mod ngram_tokenizer;
+mod path_tokenizer;
+mod preserve_original_filter;
 mod raw_tokenizer;
 mod regex_tokenizer;
 mod remove_long;
@@ -134,8 +140,10 @@ mod simple_tokenizer;
 mod split_compound_words;
 mod stemmer;
 mod stop_word_filter;
+mod synonym_filter;
 mod tokenized_string;
 mod tokenizer;
+mod tokenizer_config;
 mod tokenizer_manager;
 mod whitespace_tokenizer;
 
@@ -143,9 +151,15 @@ pub use tokenizer_api::{BoxTokenStream, Token, TokenFilter, TokenStream, Tokeniz
 
 pub use self::alphanum_only::AlphaNumOnlyFilter;
 pub use self::ascii_folding_filter::AsciiFoldingFilter;
+pub use self::edge_ngram_filter::EdgeNgramFilter;
 pub use self::facet_tokenizer::FacetTokenizer;
+#[cfg(feature = "stopwords")]
+pub use self::language_detecting_tokenizer::LanguageDetectingTokenizer;
+pub use self::length_filter::LengthFilter;
 pub use self::lower_caser::LowerCaser;
 pub use self::ngram_tokenizer::NgramTokenizer;
+pub use self::path_tokenizer::PathTokenizer;
+pub use self::preserve_original_filter::PreserveOriginalFilter;
 pub use self::raw_tokenizer::RawTokenizer;
 pub use self::regex_tokenizer::RegexTokenizer;
 pub use self::remove_long::RemoveLongFilter;
@@ -153,8 +167,10 @@ pub use self::simple_tokenizer::{SimpleTokenStream, SimpleTokenizer};
 pub use self::split_compound_words::SplitCompoundWords;
 pub use self::stemmer::{Language, Stemmer};
 pub use self::stop_word_filter::StopWordFilter;
+pub use self::synonym_filter::SynonymFilter;
 pub use self::tokenized_string::{PreTokenizedStream, PreTokenizedString};
 pub use self::tokenizer::{TextAnalyzer, TextAnalyzerBuilder};
+pub use self::tokenizer_config::{BaseTokenizerConfig, TokenFilterConfig, TokenizerConfig};
 pub use self::tokenizer_manager::TokenizerManager;
 pub use self::whitespace_tokenizer::WhitespaceTokenizer;
 
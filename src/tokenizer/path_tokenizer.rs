@@ -0,0 +1,228 @@
+use super::{Token, TokenStream, Tokenizer};
+
+/// Tokenize a path into the hierarchy of its components.
+///
+/// By default, the tokenizer emits all of the prefixes of the path, ending on a
+/// component boundary. For instance, `/usr/local/bin` is tokenized as
+/// `/usr`, `/usr/local` and `/usr/local/bin`.
+///
+/// In suffix mode, the tokenizer emits all of the suffixes of the path instead, starting on a
+/// component boundary. This is typically useful for domain names: with `.` as delimiter,
+/// `www.example.com` is tokenized as `www.example.com`, `example.com` and `com`.
+///
+/// Empty components, e.g. in `/a//b/`, are skipped and never end (or start) a token.
+///
+/// All of the tokens are emitted at position 0, as they all describe the same path. Offsets
+/// point to the part of the text covered by each token, so that highlighting works as usual.
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut tokenizer = PathTokenizer::default();
+/// let mut stream = tokenizer.token_stream("/usr/local/bin");
+/// {
+///     let token = stream.next().unwrap();
+///     assert_eq!(token.text, "/usr");
+///     assert_eq!(token.offset_from, 0);
+///     assert_eq!(token.offset_to, 4);
+/// }
+/// {
+///     let token = stream.next().unwrap();
+///     assert_eq!(token.text, "/usr/local");
+///     assert_eq!(token.offset_from, 0);
+///     assert_eq!(token.offset_to, 10);
+/// }
+/// {
+///     let token = stream.next().unwrap();
+///     assert_eq!(token.text, "/usr/local/bin");
+///     assert_eq!(token.offset_from, 0);
+///     assert_eq!(token.offset_to, 14);
+/// }
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathTokenizer {
+    delimiter: char,
+    suffix: bool,
+    token: Token,
+}
+
+impl Default for PathTokenizer {
+    /// Creates a `PathTokenizer` emitting the prefixes of `/` separated paths.
+    fn default() -> PathTokenizer {
+        PathTokenizer::new('/', false)
+    }
+}
+
+impl PathTokenizer {
+    /// Creates a new `PathTokenizer` splitting paths on `delimiter`.
+    ///
+    /// If `suffix` is true, the tokenizer emits the suffixes of the path rather than its
+    /// prefixes.
+    pub fn new(delimiter: char, suffix: bool) -> PathTokenizer {
+        PathTokenizer {
+            delimiter,
+            suffix,
+            token: Token::default(),
+        }
+    }
+}
+
+/// TokenStream associated with the `PathTokenizer`
+pub struct PathTokenStream<'a> {
+    text: &'a str,
+    delimiter: char,
+    suffix: bool,
+    // In prefix mode, end of the last emitted prefix.
+    // In suffix mode, start of the text that has not been emitted as a suffix yet.
+    cursor: usize,
+    // End of the last non-empty component.
+    path_end: usize,
+    token: &'a mut Token,
+}
+
+impl Tokenizer for PathTokenizer {
+    type TokenStream<'a> = PathTokenStream<'a>;
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> PathTokenStream<'a> {
+        self.token.reset();
+        let path_end = text.trim_end_matches(self.delimiter).len();
+        PathTokenStream {
+            text,
+            delimiter: self.delimiter,
+            suffix: self.suffix,
+            cursor: 0,
+            path_end,
+            token: &mut self.token,
+        }
+    }
+}
+
+impl PathTokenStream<'_> {
+    /// Returns the start of the first non-empty component after `from`.
+    fn component_start(&self, from: usize) -> Option<usize> {
+        self.text[from..self.path_end]
+            .find(|c| c != self.delimiter)
+            .map(|pos| from + pos)
+    }
+
+    /// Returns the end of the component starting at `start`.
+    fn component_end(&self, start: usize) -> usize {
+        self.text[start..]
+            .find(self.delimiter)
+            .map(|pos| start + pos)
+            .unwrap_or(self.text.len())
+    }
+
+    fn set_token(&mut self, offset_from: usize, offset_to: usize) {
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[offset_from..offset_to]);
+        self.token.offset_from = offset_from;
+        self.token.offset_to = offset_to;
+        self.token.position = 0;
+    }
+}
+
+impl TokenStream for PathTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        let Some(start) = self.component_start(self.cursor) else {
+            self.cursor = self.path_end;
+            return false;
+        };
+        let end = self.component_end(start);
+        if self.suffix {
+            self.set_token(start, self.path_end);
+        } else {
+            self.set_token(0, end);
+        }
+        self.cursor = end;
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{PathTokenizer, TextAnalyzer, Token, TokenizerManager};
+
+    fn token_stream_helper(tokenizer: PathTokenizer, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::from(tokenizer);
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens: Vec<Token> = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
+    #[test]
+    fn test_path_tokenizer() {
+        let tokens = token_stream_helper(PathTokenizer::default(), "/a/b/c");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "/a", 0, 2);
+        assert_token(&tokens[1], 0, "/a/b", 0, 4);
+        assert_token(&tokens[2], 0, "/a/b/c", 0, 6);
+
+        let tokens = token_stream_helper(PathTokenizer::default(), "a/bc");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "a", 0, 1);
+        assert_token(&tokens[1], 0, "a/bc", 0, 4);
+    }
+
+    #[test]
+    fn test_path_tokenizer_suffix() {
+        let tokens = token_stream_helper(PathTokenizer::new('/', true), "/a/b/c");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "a/b/c", 1, 6);
+        assert_token(&tokens[1], 0, "b/c", 3, 6);
+        assert_token(&tokens[2], 0, "c", 5, 6);
+    }
+
+    #[test]
+    fn test_path_tokenizer_custom_delimiter() {
+        let tokens = token_stream_helper(PathTokenizer::new('.', true), "www.example.com");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "www.example.com", 0, 15);
+        assert_token(&tokens[1], 0, "example.com", 4, 15);
+        assert_token(&tokens[2], 0, "com", 12, 15);
+
+        let tokens = token_stream_helper(PathTokenizer::new('→', false), "été→hiver");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "été", 0, 5);
+        assert_token(&tokens[1], 0, "été→hiver", 0, 13);
+    }
+
+    #[test]
+    fn test_path_tokenizer_empty_components() {
+        let tokens = token_stream_helper(PathTokenizer::default(), "//a//b/");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "//a", 0, 3);
+        assert_token(&tokens[1], 0, "//a//b", 0, 6);
+
+        let tokens = token_stream_helper(PathTokenizer::new('/', true), "//a//b/");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "a//b", 2, 6);
+        assert_token(&tokens[1], 0, "b", 5, 6);
+
+        assert!(token_stream_helper(PathTokenizer::default(), "").is_empty());
+        assert!(token_stream_helper(PathTokenizer::default(), "///").is_empty());
+        assert!(token_stream_helper(PathTokenizer::new('/', true), "///").is_empty());
+    }
+
+    #[test]
+    fn test_path_tokenizer_registered() {
+        let mut analyzer = TokenizerManager::default().get("path").unwrap();
+        let mut token_stream = analyzer.token_stream("/a/b");
+        let mut tokens: Vec<String> = vec![];
+        token_stream.process(&mut |token: &Token| tokens.push(token.text.clone()));
+        assert_eq!(tokens, vec!["/a".to_string(), "/a/b".to_string()]);
+    }
+}
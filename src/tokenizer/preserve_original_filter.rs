@@ -0,0 +1,135 @@
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// Token filter recording the text of each token in [`Token::original_text`], so that it
+/// remains accessible after the following filters normalize [`Token::text`], e.g. for
+/// highlighting or exact matching.
+///
+/// It should be placed right after the tokenizer, before the filters modifying the token text.
+/// The offsets of the token can also be used to map it back to the original text.
+///
+/// ```rust
+/// use tantivy::tokenizer::*;
+///
+/// let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+///     .filter(PreserveOriginalFilter)
+///     .filter(LowerCaser)
+///     .filter(Stemmer::new(Language::English))
+///     .build();
+/// let mut stream = analyzer.token_stream("Running");
+/// let token = stream.next().unwrap();
+/// assert_eq!(token.text, "run");
+/// assert_eq!(token.original_text.as_deref(), Some("Running"));
+/// ```
+#[derive(Clone)]
+pub struct PreserveOriginalFilter;
+
+impl TokenFilter for PreserveOriginalFilter {
+    type Tokenizer<T: Tokenizer> = PreserveOriginalFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PreserveOriginalFilterWrapper<T> {
+        PreserveOriginalFilterWrapper(tokenizer)
+    }
+}
+
+#[derive(Clone)]
+pub struct PreserveOriginalFilterWrapper<T>(T);
+
+impl<T: Tokenizer> Tokenizer for PreserveOriginalFilterWrapper<T> {
+    type TokenStream<'a> = PreserveOriginalFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PreserveOriginalFilterStream {
+            tail: self.0.token_stream(text),
+        }
+    }
+}
+
+pub struct PreserveOriginalFilterStream<T> {
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for PreserveOriginalFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        token.original_text = Some(token.text.clone());
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::PreserveOriginalFilter;
+    use crate::tokenizer::{
+        Language, LowerCaser, SimpleTokenizer, Stemmer, SynonymFilter, TextAnalyzer, Token,
+    };
+
+    fn token_stream_helper(text: &str, analyzer: &mut TextAnalyzer) -> Vec<Token> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
+    #[test]
+    fn test_preserve_original_filter() {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(PreserveOriginalFilter)
+            .filter(LowerCaser)
+            .filter(Stemmer::new(Language::English))
+            .build();
+        let text = "The Foxes JUMPED";
+        let tokens = token_stream_helper(text, &mut analyzer);
+        let texts: Vec<(&str, &str)> = tokens
+            .iter()
+            .map(|token| (token.text.as_str(), token.original_text.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![("the", "The"), ("fox", "Foxes"), ("jump", "JUMPED")]
+        );
+        for token in &tokens {
+            assert_eq!(
+                token.original_text.as_deref(),
+                Some(&text[token.offset_from..token.offset_to])
+            );
+        }
+    }
+
+    #[test]
+    fn test_preserve_original_filter_synonyms() {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(PreserveOriginalFilter)
+            .filter(LowerCaser)
+            .filter(SynonymFilter::new(HashMap::from([(
+                "tv".to_string(),
+                vec!["television".to_string()],
+            )])))
+            .build();
+        let tokens = token_stream_helper("TV", &mut analyzer);
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[1].text, "television");
+        assert!(tokens
+            .iter()
+            .all(|token| token.original_text.as_deref() == Some("TV")));
+        // Without the filter, the original text is not recorded.
+        let mut analyzer = TextAnalyzer::from(SimpleTokenizer::default());
+        let tokens = token_stream_helper("TV", &mut analyzer);
+        assert_eq!(tokens[0].original_text, None);
+    }
+}
@@ -45,10 +45,13 @@ use crate::TantivyError;
 /// }
 /// assert!(stream.next().is_none());
 /// ```
-
+///
+/// Alternatively, [`RegexTokenizer::split`] builds a tokenizer splitting the text on the
+/// matches of the regex.
 #[derive(Clone)]
 pub struct RegexTokenizer {
     regex: Regex,
+    split: bool,
     token: Token,
 }
 
@@ -59,9 +62,48 @@ impl RegexTokenizer {
             .map_err(|_| TantivyError::InvalidArgument(regex_pattern.to_owned()))
             .map(|regex| Self {
                 regex,
+                split: false,
                 token: Token::default(),
             })
     }
+
+    /// Creates a new RegexTokenizer splitting the text on the matches of the regex: the text
+    /// between two matches is emitted as a token, e.g. `[\s,;]+` splits the text on
+    /// whitespaces, commas and semicolons.
+    ///
+    /// Empty tokens, e.g. before a match at the beginning of the text, are not emitted, and
+    /// neither are empty matches.
+    ///
+    /// ```rust
+    /// use tantivy::tokenizer::*;
+    ///
+    /// let mut tokenizer = RegexTokenizer::split(r"\s*[,;]\s*").unwrap();
+    /// let mut stream = tokenizer.token_stream("level=warn, user=bob ;code=42");
+    /// {
+    ///     let token = stream.next().unwrap();
+    ///     assert_eq!(token.text, "level=warn");
+    ///     assert_eq!(token.offset_from, 0);
+    ///     assert_eq!(token.offset_to, 10);
+    /// }
+    /// {
+    ///     let token = stream.next().unwrap();
+    ///     assert_eq!(token.text, "user=bob");
+    ///     assert_eq!(token.offset_from, 12);
+    ///     assert_eq!(token.offset_to, 20);
+    /// }
+    /// {
+    ///     let token = stream.next().unwrap();
+    ///     assert_eq!(token.text, "code=42");
+    ///     assert_eq!(token.offset_from, 22);
+    ///     assert_eq!(token.offset_to, 29);
+    /// }
+    /// assert!(stream.next().is_none());
+    /// ```
+    pub fn split(regex_pattern: &str) -> crate::Result<RegexTokenizer> {
+        let mut tokenizer = RegexTokenizer::new(regex_pattern)?;
+        tokenizer.split = true;
+        Ok(tokenizer)
+    }
 }
 
 impl Tokenizer for RegexTokenizer {
@@ -70,6 +112,7 @@ impl Tokenizer for RegexTokenizer {
         self.token.reset();
         RegexTokenStream {
             regex: self.regex.clone(),
+            split: self.split,
             text,
             token: &mut self.token,
             cursor: 0,
@@ -79,29 +122,59 @@ impl Tokenizer for RegexTokenizer {
 
 pub struct RegexTokenStream<'a> {
     regex: Regex,
+    split: bool,
     text: &'a str,
     token: &'a mut Token,
     cursor: usize,
 }
 
+impl RegexTokenStream<'_> {
+    fn emit(&mut self, text: &str, offset_from: usize) {
+        self.token.text.clear();
+        self.token.text.push_str(text);
+        self.token.offset_from = offset_from;
+        self.token.offset_to = offset_from + text.len();
+        self.token.position = self.token.position.wrapping_add(1);
+    }
+
+    fn advance_split(&mut self) -> bool {
+        while !self.text.is_empty() {
+            let (token_len, delimiter_end) = match self
+                .regex
+                .find_iter(self.text)
+                .find(|regex_match| !regex_match.as_str().is_empty())
+            {
+                Some(delimiter) => (delimiter.start(), delimiter.end()),
+                None => (self.text.len(), self.text.len()),
+            };
+            let text = self.text;
+            let offset_from = self.cursor;
+            self.cursor += delimiter_end;
+            self.text = &text[delimiter_end..];
+            if token_len > 0 {
+                self.emit(&text[..token_len], offset_from);
+                return true;
+            }
+        }
+        false
+    }
+}
+
 impl TokenStream for RegexTokenStream<'_> {
     fn advance(&mut self) -> bool {
+        if self.split {
+            return self.advance_split();
+        }
         let Some(regex_match) = self.regex.find(self.text) else {
             return false;
         };
         if regex_match.as_str().is_empty() {
             return false;
         }
-        self.token.text.clear();
-        self.token.text.push_str(regex_match.as_str());
-
-        self.token.offset_from = self.cursor + regex_match.start();
+        let text = self.text;
+        self.emit(regex_match.as_str(), self.cursor + regex_match.start());
         self.cursor += regex_match.end();
-        self.token.offset_to = self.cursor;
-
-        self.token.position = self.token.position.wrapping_add(1);
-
-        self.text = &self.text[regex_match.end()..];
+        self.text = &text[regex_match.end()..];
         true
     }
 
@@ -151,8 +224,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_regex_tokenizer_split() {
+        let tokens = split_token_stream_helper("a, b;;c ,\td", r"[\s,;]+");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "a", 0, 1);
+        assert_token(&tokens[1], 1, "b", 3, 4);
+        assert_token(&tokens[2], 2, "c", 6, 7);
+        assert_token(&tokens[3], 3, "d", 10, 11);
+    }
+
+    #[test]
+    fn test_regex_tokenizer_split_multi_char_delimiter() {
+        let text = "<->GET /index<->200<->  <->12ms<->";
+        let tokens = split_token_stream_helper(text, r"\s*<->\s*");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "GET /index", 3, 13);
+        assert_token(&tokens[1], 1, "200", 16, 19);
+        assert_token(&tokens[2], 2, "12ms", 27, 31);
+        for token in &tokens {
+            assert_eq!(&text[token.offset_from..token.offset_to], token.text);
+        }
+        // Empty matches do not split the text.
+        let tokens = split_token_stream_helper("aé b", r"\s*");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "aé", 0, 3);
+        assert_token(&tokens[1], 1, "b", 4, 5);
+        // Without any match, the whole text is a token.
+        let tokens = split_token_stream_helper("abc", ",");
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "abc", 0, 3);
+        assert!(split_token_stream_helper("", ",").is_empty());
+        assert!(split_token_stream_helper(",,", ",").is_empty());
+        assert!(RegexTokenizer::split(r"\@(").is_err());
+    }
+
+    fn split_token_stream_helper(text: &str, pattern: &str) -> Vec<Token> {
+        collect_tokens(RegexTokenizer::split(pattern).unwrap(), text)
+    }
+
     fn token_stream_helper(text: &str, pattern: &str) -> Vec<Token> {
-        let r = RegexTokenizer::new(pattern).unwrap();
+        collect_tokens(RegexTokenizer::new(pattern).unwrap(), text)
+    }
+
+    fn collect_tokens(r: RegexTokenizer, text: &str) -> Vec<Token> {
         let mut a = TextAnalyzer::from(r);
         let mut token_stream = a.token_stream(text);
         let mut tokens: Vec<Token> = vec![];
@@ -151,6 +151,7 @@ impl<T: TokenStream> SplitCompoundWordsTokenStream<'_, T> {
                 text = head;
                 self.parts.push(Token {
                     text: tail.to_owned(),
+                    original_text: token.original_text.clone(),
                     ..*token
                 });
             }
@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::mem;
+use std::sync::Arc;
 
 use rust_stemmers::Algorithm;
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 
 use super::{Token, TokenFilter, TokenStream, Tokenizer};
@@ -61,16 +63,34 @@ impl Language {
 /// Tokens are expected to be lowercased beforehand.
 #[derive(Clone)]
 pub struct Stemmer {
-    stemmer_algorithm: Algorithm,
+    language: Language,
+    protected_words: Arc<FxHashSet<String>>,
 }
 
 impl Stemmer {
     /// Creates a new `Stemmer` [`TokenFilter`] for a given language algorithm.
     pub fn new(language: Language) -> Stemmer {
         Stemmer {
-            stemmer_algorithm: language.algorithm(),
+            language,
+            protected_words: Arc::default(),
+        }
+    }
+
+    /// Protects a set of words from stemming, e.g. brand names or acronyms.
+    ///
+    /// Tokens matching one of these words are passed through unchanged. As tokens are
+    /// lowercased before reaching the `Stemmer`, words are lowercased as well.
+    pub fn with_protected_words<W: IntoIterator<Item = String>>(self, words: W) -> Stemmer {
+        Stemmer {
+            protected_words: Arc::new(words.into_iter().map(|word| word.to_lowercase()).collect()),
+            ..self
         }
     }
+
+    /// Returns the language of the stemming algorithm.
+    pub fn language(&self) -> Language {
+        self.language
+    }
 }
 
 impl Default for Stemmer {
@@ -85,7 +105,8 @@ impl TokenFilter for Stemmer {
 
     fn transform<T: Tokenizer>(self, tokenizer: T) -> StemmerFilter<T> {
         StemmerFilter {
-            stemmer_algorithm: self.stemmer_algorithm,
+            stemmer_algorithm: self.language.algorithm(),
+            protected_words: self.protected_words,
             inner: tokenizer,
         }
     }
@@ -94,6 +115,7 @@ impl TokenFilter for Stemmer {
 #[derive(Clone)]
 pub struct StemmerFilter<T> {
     stemmer_algorithm: Algorithm,
+    protected_words: Arc<FxHashSet<String>>,
     inner: T,
 }
 
@@ -105,6 +127,7 @@ impl<T: Tokenizer> Tokenizer for StemmerFilter<T> {
         StemmerTokenStream {
             tail: self.inner.token_stream(text),
             stemmer,
+            protected_words: self.protected_words.clone(),
             buffer: String::new(),
         }
     }
@@ -113,15 +136,31 @@ impl<T: Tokenizer> Tokenizer for StemmerFilter<T> {
 pub struct StemmerTokenStream<T> {
     tail: T,
     stemmer: rust_stemmers::Stemmer,
+    protected_words: Arc<FxHashSet<String>>,
     buffer: String,
 }
 
+impl<T> StemmerTokenStream<T> {
+    /// Stems the tokens of `tail` for the given language.
+    pub(crate) fn new(tail: T, language: Language) -> StemmerTokenStream<T> {
+        StemmerTokenStream {
+            tail,
+            stemmer: rust_stemmers::Stemmer::create(language.algorithm()),
+            protected_words: Arc::default(),
+            buffer: String::new(),
+        }
+    }
+}
+
 impl<T: TokenStream> TokenStream for StemmerTokenStream<T> {
     fn advance(&mut self) -> bool {
         if !self.tail.advance() {
             return false;
         }
         let token = self.tail.token_mut();
+        if self.protected_words.contains(&token.text) {
+            return true;
+        }
         let stemmed_str = self.stemmer.stem(&token.text);
         match stemmed_str {
             Cow::Owned(stemmed_str) => token.text = stemmed_str,
@@ -142,3 +181,42 @@ impl<T: TokenStream> TokenStream for StemmerTokenStream<T> {
         self.tail.token_mut()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Language, Stemmer};
+    use crate::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer, Token};
+
+    fn token_texts(stemmer: Stemmer, text: &str) -> Vec<String> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .filter(stemmer)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens: Vec<String> = vec![];
+        token_stream.process(&mut |token: &Token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_stemmer_protected_words() {
+        let text = "Windows running on laptops";
+        assert_eq!(
+            token_texts(Stemmer::new(Language::English), text),
+            vec!["window", "run", "on", "laptop"]
+        );
+        let stemmer = Stemmer::new(Language::English)
+            .with_protected_words(["Windows".to_string(), "running".to_string()]);
+        assert_eq!(
+            token_texts(stemmer, text),
+            vec!["windows", "running", "on", "laptop"]
+        );
+    }
+
+    #[test]
+    fn test_stemmer_language() {
+        assert_eq!(Stemmer::default().language(), Language::English);
+        let stemmer = Stemmer::new(Language::French).with_protected_words(Vec::new());
+        assert_eq!(stemmer.language(), Language::French);
+    }
+}
@@ -69,6 +69,12 @@ impl StopWordFilter {
             words: Arc::new(words.into_iter().collect()),
         }
     }
+
+    /// Returns the words removed by the filter.
+    #[cfg(feature = "stopwords")]
+    pub(crate) fn words(&self) -> impl Iterator<Item = &String> {
+        self.words.iter()
+    }
 }
 
 impl TokenFilter for StopWordFilter {
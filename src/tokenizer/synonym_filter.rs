@@ -0,0 +1,261 @@
+//! # Example
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use tantivy::tokenizer::*;
+//!
+//! let synonyms = HashMap::from([("tv".to_string(), vec!["television".to_string()])]);
+//! let mut tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+//!   .filter(LowerCaser)
+//!   .filter(SynonymFilter::new(synonyms))
+//!   .build();
+//!
+//! let mut stream = tokenizer.token_stream("Smart TV");
+//! assert_eq!(stream.next().unwrap().text, "smart");
+//! assert_eq!(stream.next().unwrap().text, "tv");
+//! // `television` is emitted at the same position as `tv`.
+//! let synonym = stream.next().unwrap();
+//! assert_eq!(synonym.text, "television");
+//! assert_eq!(synonym.position, 1);
+//! assert!(stream.next().is_none());
+//! ```
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// `TokenFilter` that injects the synonyms of a token into the token stream.
+///
+/// When the text of a token is a key of the synonym map, the token is emitted first, followed
+/// by each of its synonyms. Synonyms share the position and the offsets of the original token,
+/// so that phrase queries and highlighting are not affected by the expansion.
+///
+/// The map is one-way: `tv => television` expands `tv`, but not `television`. Use
+/// [`SynonymFilter::add_group`] to declare words that are all synonyms of each other.
+///
+/// A multi-word synonym, e.g. `tv => television set`, is emitted as a single token whose text
+/// contains the whitespace, at the position of the original token. It is never split, as the
+/// following tokens would otherwise be shifted, and phrase queries could not match them anymore.
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl SynonymFilter {
+    /// Creates a `SynonymFilter` given a map from words to their synonyms.
+    pub fn new(synonyms: HashMap<String, Vec<String>>) -> SynonymFilter {
+        SynonymFilter {
+            synonyms: Arc::new(synonyms),
+        }
+    }
+
+    /// Declares a group of words that are synonyms of each other.
+    ///
+    /// Each word of the group is expanded into all of the other words of the group, in
+    /// addition to the synonyms it may already have.
+    pub fn add_group<W: IntoIterator<Item = String>>(mut self, group: W) -> SynonymFilter {
+        let group: Vec<String> = group.into_iter().collect();
+        let synonyms = Arc::make_mut(&mut self.synonyms);
+        for word in &group {
+            let word_synonyms = synonyms.entry(word.clone()).or_default();
+            for synonym in &group {
+                if synonym != word && !word_synonyms.contains(synonym) {
+                    word_synonyms.push(synonym.clone());
+                }
+            }
+        }
+        self
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    type Tokenizer<T: Tokenizer> = SynonymFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SynonymFilterWrapper<T> {
+        SynonymFilterWrapper {
+            synonyms: self.synonyms,
+            inner: tokenizer,
+            pending: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SynonymFilterWrapper<T> {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+    inner: T,
+    pending: Vec<Token>,
+}
+
+impl<T: Tokenizer> Tokenizer for SynonymFilterWrapper<T> {
+    type TokenStream<'a> = SynonymFilterStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        self.pending.clear();
+        SynonymFilterStream {
+            synonyms: &self.synonyms,
+            tail: self.inner.token_stream(text),
+            pending: &mut self.pending,
+        }
+    }
+}
+
+pub struct SynonymFilterStream<'a, T> {
+    synonyms: &'a HashMap<String, Vec<String>>,
+    tail: T,
+    // Tokens left to emit for the current token of `tail`, in reverse order.
+    pending: &'a mut Vec<Token>,
+}
+
+impl<T: TokenStream> SynonymFilterStream<'_, T> {
+    fn expand(&mut self) {
+        let token = self.tail.token();
+        let Some(synonyms) = self.synonyms.get(&token.text) else {
+            return;
+        };
+        for synonym in synonyms.iter().rev() {
+            self.pending.push(Token {
+                text: synonym.clone(),
+                original_text: token.original_text.clone(),
+                ..*token
+            });
+        }
+        // The original token is emitted first.
+        self.pending.push(token.clone());
+    }
+}
+
+impl<T: TokenStream> TokenStream for SynonymFilterStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.pending.pop();
+        if !self.pending.is_empty() {
+            return true;
+        }
+        if !self.tail.advance() {
+            return false;
+        }
+        self.expand();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.pending.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.pending
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::collector::Count;
+    use crate::query::{PhraseQuery, TermQuery};
+    use crate::schema::{IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+    use crate::tokenizer::tests::assert_token;
+    use crate::tokenizer::{
+        LowerCaser, SimpleTokenizer, SynonymFilter, TextAnalyzer, Token, WhitespaceTokenizer,
+    };
+    use crate::{Index, IndexWriter, Term};
+
+    fn token_stream_helper(synonym_filter: SynonymFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(synonym_filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens: Vec<Token> = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+
+    fn synonyms(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(word, synonyms)| {
+                let synonyms = synonyms.iter().map(|synonym| synonym.to_string()).collect();
+                (word.to_string(), synonyms)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_synonym_filter_same_position() {
+        let synonym_filter = SynonymFilter::new(synonyms(&[("tv", &["television", "telly"])]));
+        let tokens = token_stream_helper(synonym_filter, "my tv broke");
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "my", 0, 2);
+        assert_token(&tokens[1], 1, "tv", 3, 5);
+        assert_token(&tokens[2], 1, "television", 3, 5);
+        assert_token(&tokens[3], 1, "telly", 3, 5);
+        assert_token(&tokens[4], 2, "broke", 6, 11);
+    }
+
+    #[test]
+    fn test_synonym_filter_one_way_and_two_way() {
+        let synonym_filter = SynonymFilter::new(synonyms(&[("tv", &["television"])]));
+        let tokens = token_stream_helper(synonym_filter.clone(), "television");
+        assert_eq!(tokens.len(), 1);
+
+        let synonym_filter = synonym_filter.add_group(["car".to_string(), "auto".to_string()]);
+        let texts: Vec<String> = token_stream_helper(synonym_filter, "car auto tv")
+            .into_iter()
+            .map(|token| token.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["car", "auto", "auto", "car", "tv", "television"]
+        );
+    }
+
+    #[test]
+    fn test_synonym_filter_downstream_filters() {
+        let synonym_filter = SynonymFilter::new(synonyms(&[("tv", &["TeleVision"])]));
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(synonym_filter)
+            .filter(LowerCaser)
+            .build();
+        let mut token_stream = analyzer.token_stream("tv");
+        assert_eq!(token_stream.next().unwrap().text, "tv");
+        assert_eq!(token_stream.next().unwrap().text, "television");
+        assert!(token_stream.next().is_none());
+    }
+
+    #[test]
+    fn test_synonym_filter_phrase_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("synonyms")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let text = schema_builder.add_text_field("text", text_options);
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register(
+            "synonyms",
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(SynonymFilter::new(synonyms(&[("tv", &["television set"])])))
+                .build(),
+        );
+        let mut index_writer: IndexWriter = index.writer_for_tests()?;
+        index_writer.add_document(doc!(text => "new tv stand"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let term = |word: &str| Term::from_field_text(text, word);
+        let phrase_query = PhraseQuery::new(vec![term("tv"), term("stand")]);
+        assert_eq!(searcher.search(&phrase_query, &Count)?, 1);
+        let phrase_query = PhraseQuery::new(vec![term("new"), term("television set")]);
+        assert_eq!(searcher.search(&phrase_query, &Count)?, 1);
+        let phrase_query = PhraseQuery::new(vec![term("television set"), term("stand")]);
+        assert_eq!(searcher.search(&phrase_query, &Count)?, 1);
+        let term_query = TermQuery::new(term("television set"), IndexRecordOption::Basic);
+        assert_eq!(searcher.search(&term_query, &Count)?, 1);
+        Ok(())
+    }
+}
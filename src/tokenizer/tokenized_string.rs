@@ -15,6 +15,42 @@ pub struct PreTokenizedString {
     pub tokens: Vec<Token>,
 }
 
+impl PreTokenizedString {
+    /// Checks that the tokens are well-formed:
+    /// - their offsets are a valid range of the text, on `char` boundaries,
+    /// - their positions are non-decreasing, and their position lengths are at least 1.
+    ///
+    /// [`IndexWriter::add_document`](crate::IndexWriter::add_document) rejects documents with
+    /// values failing this check.
+    pub fn validate(&self) -> crate::Result<()> {
+        let mut previous_position = 0;
+        for (token_ord, token) in self.tokens.iter().enumerate() {
+            let invalid_token = |reason: &str| {
+                Err(crate::TantivyError::InvalidArgument(format!(
+                    "Invalid pre-tokenized token #{token_ord} {:?}: {reason}",
+                    token.text
+                )))
+            };
+            if token.offset_from > token.offset_to || token.offset_to > self.text.len() {
+                return invalid_token("its offsets are out of the bounds of the text");
+            }
+            if !self.text.is_char_boundary(token.offset_from)
+                || !self.text.is_char_boundary(token.offset_to)
+            {
+                return invalid_token("its offsets are not on char boundaries");
+            }
+            if token.position < previous_position {
+                return invalid_token("its position is lower than the previous token's");
+            }
+            if token.position_length == 0 {
+                return invalid_token("its position length is 0");
+            }
+            previous_position = token.position;
+        }
+        Ok(())
+    }
+}
+
 impl Ord for PreTokenizedString {
     fn cmp(&self, other: &Self) -> Ordering {
         self.text.cmp(&other.text)
@@ -107,6 +143,7 @@ mod tests {
                     position: 0,
                     text: String::from("A"),
                     position_length: 1,
+                    original_text: None,
                 },
                 Token {
                     offset_from: 2,
@@ -114,6 +151,7 @@ mod tests {
                     position: 1,
                     text: String::from("a"),
                     position_length: 1,
+                    original_text: None,
                 },
             ],
         };
@@ -126,4 +164,36 @@ mod tests {
         }
         assert!(!token_stream.advance());
     }
+
+    #[test]
+    fn test_pre_tokenized_string_validate() {
+        let token = |offset_from: usize, offset_to: usize, position: usize| Token {
+            offset_from,
+            offset_to,
+            position,
+            text: String::from("a"),
+            position_length: 1,
+            original_text: None,
+        };
+        let pre_tokenized = |tokens: Vec<Token>| PreTokenizedString {
+            text: String::from("a é a"),
+            tokens,
+        };
+        assert!(pre_tokenized(Vec::new()).validate().is_ok());
+        // Tokens may share a position, e.g. synonyms.
+        assert!(
+            pre_tokenized(vec![token(0, 1, 0), token(0, 1, 0), token(5, 6, 2)])
+                .validate()
+                .is_ok()
+        );
+        assert!(pre_tokenized(vec![token(0, 7, 0)]).validate().is_err());
+        assert!(pre_tokenized(vec![token(1, 0, 0)]).validate().is_err());
+        assert!(pre_tokenized(vec![token(2, 3, 0)]).validate().is_err());
+        assert!(pre_tokenized(vec![token(0, 1, 1), token(5, 6, 0)])
+            .validate()
+            .is_err());
+        let mut zero_length_token = token(0, 1, 0);
+        zero_length_token.position_length = 0;
+        assert!(pre_tokenized(vec![zero_length_token]).validate().is_err());
+    }
 }
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tokenizer::{
+    AlphaNumOnlyFilter, AsciiFoldingFilter, EdgeNgramFilter, FacetTokenizer, Language,
+    LengthFilter, LowerCaser, NgramTokenizer, PathTokenizer, PreserveOriginalFilter, RawTokenizer,
+    RegexTokenizer, RemoveLongFilter, SimpleTokenizer, SplitCompoundWords, Stemmer, StopWordFilter,
+    SynonymFilter, TextAnalyzer, TextAnalyzerBuilder, WhitespaceTokenizer,
+};
+use crate::TantivyError;
+
+/// Serializable description of an analysis chain: a base tokenizer, followed by an ordered
+/// list of token filters.
+///
+/// This makes it possible to define the tokenizers of an index in a configuration file,
+/// and to register them with [`TokenizerManager::register_from_config`](
+/// crate::tokenizer::TokenizerManager::register_from_config).
+///
+/// Tokenizers and filters are identified by their `type`, their parameters being given
+/// alongside. Unknown types and unknown parameters are rejected on deserialization, while
+/// invalid parameter values are rejected when building the [`TextAnalyzer`].
+///
+/// ```rust
+/// use tantivy::tokenizer::{TokenizerConfig, TokenizerManager};
+///
+/// let config: TokenizerConfig = serde_json::from_str(r#"{
+///     "tokenizer": {"type": "simple"},
+///     "filters": [
+///         {"type": "remove_long", "limit": 40},
+///         {"type": "lower_caser"},
+///         {"type": "stemmer", "language": "English"}
+///     ]
+/// }"#).unwrap();
+/// let tokenizer_manager = TokenizerManager::default();
+/// tokenizer_manager.register_from_config("my_en_stem", &config).unwrap();
+///
+/// let mut analyzer = tokenizer_manager.get("my_en_stem").unwrap();
+/// let mut stream = analyzer.token_stream("Running dogs");
+/// assert_eq!(stream.next().unwrap().text, "run");
+/// assert_eq!(stream.next().unwrap().text, "dog");
+/// assert!(stream.next().is_none());
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TokenizerConfig {
+    /// The tokenizer splitting the text into tokens.
+    pub tokenizer: BaseTokenizerConfig,
+    /// The filters applied to the tokens, in order.
+    #[serde(default)]
+    pub filters: Vec<TokenFilterConfig>,
+}
+
+/// Configuration of the tokenizer at the base of a [`TokenizerConfig`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum BaseTokenizerConfig {
+    /// [`SimpleTokenizer`]
+    Simple,
+    /// [`WhitespaceTokenizer`]
+    Whitespace,
+    /// [`RawTokenizer`]
+    Raw,
+    /// [`FacetTokenizer`]
+    Facet,
+    /// [`NgramTokenizer`]
+    Ngram {
+        /// Minimum number of characters of the n-grams.
+        min_gram: usize,
+        /// Maximum number of characters of the n-grams.
+        max_gram: usize,
+        /// Only emits the n-grams starting at the beginning of the text.
+        #[serde(default)]
+        prefix_only: bool,
+    },
+    /// [`RegexTokenizer`]
+    Regex {
+        /// Pattern of the tokens, or of the delimiters if `split` is set.
+        pattern: String,
+        /// Splits the text on the matches of the pattern, see [`RegexTokenizer::split`].
+        #[serde(default)]
+        split: bool,
+    },
+    /// [`PathTokenizer`]
+    Path {
+        /// Separator of the components of the path. Defaults to `/`.
+        #[serde(default = "default_path_delimiter")]
+        delimiter: char,
+        /// Emits the suffixes of the path rather than its prefixes.
+        #[serde(default)]
+        suffix: bool,
+    },
+}
+
+fn default_path_delimiter() -> char {
+    '/'
+}
+
+/// Configuration of a token filter within a [`TokenizerConfig`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum TokenFilterConfig {
+    /// [`LowerCaser`]
+    LowerCaser,
+    /// [`AsciiFoldingFilter`]
+    AsciiFolding,
+    /// [`AlphaNumOnlyFilter`]
+    AlphaNumOnly,
+    /// [`PreserveOriginalFilter`]
+    PreserveOriginal,
+    /// [`RemoveLongFilter`]
+    RemoveLong {
+        /// Tokens of `limit` bytes or more are removed.
+        limit: usize,
+    },
+    /// [`LengthFilter`]
+    Length {
+        /// Minimum number of characters of the tokens.
+        min: usize,
+        /// Maximum number of characters of the tokens, if any.
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    /// [`Stemmer`]
+    Stemmer {
+        /// Language of the stemming algorithm.
+        language: Language,
+    },
+    /// [`StopWordFilter`]
+    StopWords {
+        /// Language of the built-in list of stop words to remove, if any.
+        #[serde(default)]
+        language: Option<Language>,
+        /// Additional stop words to remove.
+        #[serde(default)]
+        words: Vec<String>,
+    },
+    /// [`EdgeNgramFilter`]
+    EdgeNgram {
+        /// Minimum number of characters of the n-grams.
+        min_gram: usize,
+        /// Maximum number of characters of the n-grams.
+        max_gram: usize,
+    },
+    /// [`SynonymFilter`]
+    Synonyms {
+        /// Map from words to their synonyms.
+        synonyms: HashMap<String, Vec<String>>,
+    },
+    /// [`SplitCompoundWords`]
+    SplitCompoundWords {
+        /// Words the compound words are made of.
+        dictionary: Vec<String>,
+    },
+}
+
+impl TokenizerConfig {
+    /// Builds the [`TextAnalyzer`] described by the configuration.
+    ///
+    /// Returns `TantivyError::InvalidArgument` if a parameter is invalid.
+    pub fn build(&self) -> crate::Result<TextAnalyzer> {
+        let mut builder = self.tokenizer.builder()?;
+        for filter in &self.filters {
+            builder = filter.append_to(builder)?;
+        }
+        Ok(builder.build())
+    }
+}
+
+impl BaseTokenizerConfig {
+    fn builder(&self) -> crate::Result<TextAnalyzerBuilder> {
+        let builder = match self {
+            BaseTokenizerConfig::Simple => {
+                TextAnalyzer::builder(SimpleTokenizer::default()).dynamic()
+            }
+            BaseTokenizerConfig::Whitespace => {
+                TextAnalyzer::builder(WhitespaceTokenizer::default()).dynamic()
+            }
+            BaseTokenizerConfig::Raw => TextAnalyzer::builder(RawTokenizer::default()).dynamic(),
+            BaseTokenizerConfig::Facet => {
+                TextAnalyzer::builder(FacetTokenizer::default()).dynamic()
+            }
+            BaseTokenizerConfig::Ngram {
+                min_gram,
+                max_gram,
+                prefix_only,
+            } => TextAnalyzer::builder(NgramTokenizer::new(*min_gram, *max_gram, *prefix_only)?)
+                .dynamic(),
+            BaseTokenizerConfig::Regex { pattern, split } => {
+                let tokenizer = if *split {
+                    RegexTokenizer::split(pattern)?
+                } else {
+                    RegexTokenizer::new(pattern)?
+                };
+                TextAnalyzer::builder(tokenizer).dynamic()
+            }
+            BaseTokenizerConfig::Path { delimiter, suffix } => {
+                TextAnalyzer::builder(PathTokenizer::new(*delimiter, *suffix)).dynamic()
+            }
+        };
+        Ok(builder)
+    }
+}
+
+impl TokenFilterConfig {
+    fn append_to(&self, builder: TextAnalyzerBuilder) -> crate::Result<TextAnalyzerBuilder> {
+        let builder = match self {
+            TokenFilterConfig::LowerCaser => builder.filter_dynamic(LowerCaser),
+            TokenFilterConfig::AsciiFolding => builder.filter_dynamic(AsciiFoldingFilter),
+            TokenFilterConfig::AlphaNumOnly => builder.filter_dynamic(AlphaNumOnlyFilter),
+            TokenFilterConfig::PreserveOriginal => builder.filter_dynamic(PreserveOriginalFilter),
+            TokenFilterConfig::RemoveLong { limit } => {
+                builder.filter_dynamic(RemoveLongFilter::limit(*limit))
+            }
+            TokenFilterConfig::Length { min, max } => {
+                if max.is_some_and(|max| max < *min) {
+                    return Err(TantivyError::InvalidArgument(
+                        "The max length must not be lower than the min length".to_string(),
+                    ));
+                }
+                builder.filter_dynamic(LengthFilter::new(*min, *max))
+            }
+            TokenFilterConfig::Stemmer { language } => {
+                builder.filter_dynamic(Stemmer::new(*language))
+            }
+            TokenFilterConfig::StopWords { language, words } => {
+                builder.filter_dynamic(stop_word_filter(*language, words)?)
+            }
+            TokenFilterConfig::EdgeNgram { min_gram, max_gram } => {
+                builder.filter_dynamic(EdgeNgramFilter::new(*min_gram, *max_gram)?)
+            }
+            TokenFilterConfig::Synonyms { synonyms } => {
+                builder.filter_dynamic(SynonymFilter::new(synonyms.clone()))
+            }
+            TokenFilterConfig::SplitCompoundWords { dictionary } => {
+                builder.filter_dynamic(SplitCompoundWords::from_dictionary(dictionary)?)
+            }
+        };
+        Ok(builder)
+    }
+}
+
+fn stop_word_filter(language: Option<Language>, words: &[String]) -> crate::Result<StopWordFilter> {
+    let mut stop_words: Vec<String> = words.to_vec();
+    if let Some(language) = language {
+        stop_words.extend(language_stop_words(language)?);
+    }
+    Ok(StopWordFilter::remove(stop_words))
+}
+
+#[cfg(feature = "stopwords")]
+fn language_stop_words(language: Language) -> crate::Result<Vec<String>> {
+    StopWordFilter::new(language)
+        .map(|filter| filter.words().cloned().collect())
+        .ok_or_else(|| {
+            TantivyError::InvalidArgument(format!("No stop words are available for {language:?}"))
+        })
+}
+
+#[cfg(not(feature = "stopwords"))]
+fn language_stop_words(language: Language) -> crate::Result<Vec<String>> {
+    Err(TantivyError::InvalidArgument(format!(
+        "No stop words are available for {language:?}: the `stopwords` feature is disabled"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BaseTokenizerConfig, TokenFilterConfig, TokenizerConfig};
+    use crate::tokenizer::{Language, Token, TokenizerManager};
+
+    fn token_texts(tokenizer_manager: &TokenizerManager, name: &str, text: &str) -> Vec<String> {
+        let mut analyzer = tokenizer_manager.get(name).unwrap();
+        let mut tokens = Vec::new();
+        analyzer
+            .token_stream(text)
+            .process(&mut |token: &Token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_tokenizer_config_deserialize_and_register() {
+        let config: TokenizerConfig = serde_json::from_str(
+            r#"{
+                "tokenizer": {"type": "whitespace"},
+                "filters": [
+                    {"type": "lower_caser"},
+                    {"type": "ascii_folding"},
+                    {"type": "stop_words", "language": "English", "words": ["cafe"]},
+                    {"type": "synonyms", "synonyms": {"tv": ["television"]}},
+                    {"type": "length", "min": 2}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.tokenizer, BaseTokenizerConfig::Whitespace);
+        assert_eq!(
+            config.filters[2],
+            TokenFilterConfig::StopWords {
+                language: Some(Language::English),
+                words: vec!["cafe".to_string()],
+            }
+        );
+        let tokenizer_manager = TokenizerManager::new();
+        tokenizer_manager
+            .register_from_config("custom", &config)
+            .unwrap();
+        assert_eq!(
+            token_texts(&tokenizer_manager, "custom", "The Café TV a Éclair"),
+            vec!["tv", "television", "eclair"]
+        );
+
+        let serialized = serde_json::to_string(&config).unwrap();
+        let deserialized: TokenizerConfig = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, config);
+    }
+
+    #[test]
+    fn test_tokenizer_config_base_tokenizers() {
+        let tokenizer_manager = TokenizerManager::new();
+        let register = |name: &str, config: &str| {
+            let config: TokenizerConfig = serde_json::from_str(config).unwrap();
+            tokenizer_manager
+                .register_from_config(name, &config)
+                .unwrap();
+        };
+        register(
+            "ngram",
+            r#"{"tokenizer": {"type": "ngram", "min_gram": 2, "max_gram": 3, "prefix_only": true}}"#,
+        );
+        register(
+            "regex",
+            r#"{"tokenizer": {"type": "regex", "pattern": "[0-9]+"}}"#,
+        );
+        register(
+            "regex_split",
+            r#"{"tokenizer": {"type": "regex", "pattern": "[0-9]+", "split": true}}"#,
+        );
+        register(
+            "domain",
+            r#"{"tokenizer": {"type": "path", "delimiter": ".", "suffix": true}}"#,
+        );
+        assert_eq!(
+            token_texts(&tokenizer_manager, "ngram", "hello"),
+            vec!["he", "hel"]
+        );
+        assert_eq!(
+            token_texts(&tokenizer_manager, "regex", "a1 b22"),
+            vec!["1", "22"]
+        );
+        assert_eq!(
+            token_texts(&tokenizer_manager, "regex_split", "a1 b22"),
+            vec!["a", " b"]
+        );
+        assert_eq!(
+            token_texts(&tokenizer_manager, "domain", "www.example.com"),
+            vec!["www.example.com", "example.com", "com"]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_config_errors() {
+        let unknown_tokenizer =
+            serde_json::from_str::<TokenizerConfig>(r#"{"tokenizer": {"type": "unknown"}}"#)
+                .unwrap_err();
+        assert!(unknown_tokenizer
+            .to_string()
+            .contains("unknown variant `unknown`"));
+        let unknown_filter = serde_json::from_str::<TokenizerConfig>(
+            r#"{"tokenizer": {"type": "simple"}, "filters": [{"type": "upper_caser"}]}"#,
+        )
+        .unwrap_err();
+        assert!(unknown_filter
+            .to_string()
+            .contains("unknown variant `upper_caser`"));
+        let unknown_param = serde_json::from_str::<TokenizerConfig>(
+            r#"{"tokenizer": {"type": "simple"}, "filters": [{"type": "remove_long", "limt": 4}]}"#,
+        )
+        .unwrap_err();
+        assert!(unknown_param.to_string().contains("unknown field `limt`"));
+
+        let tokenizer_manager = TokenizerManager::new();
+        for config in [
+            r#"{"tokenizer": {"type": "ngram", "min_gram": 3, "max_gram": 2}}"#,
+            r#"{"tokenizer": {"type": "regex", "pattern": "(a"}}"#,
+            r#"{"tokenizer": {"type": "simple"}, "filters": [{"type": "length", "min": 3, "max": 2}]}"#,
+            r#"{"tokenizer": {"type": "simple"}, "filters": [{"type": "stop_words", "language": "Tamil"}]}"#,
+        ] {
+            let config: TokenizerConfig = serde_json::from_str(config).unwrap();
+            assert!(matches!(
+                tokenizer_manager.register_from_config("invalid", &config),
+                Err(crate::TantivyError::InvalidArgument(_))
+            ));
+        }
+        assert!(tokenizer_manager.get("invalid").is_none());
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::{Arc, RwLock};
 use crate::tokenizer::stemmer::Language;
 use crate::tokenizer::tokenizer::TextAnalyzer;
 use crate::tokenizer::{
-    LowerCaser, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer, WhitespaceTokenizer,
+    LowerCaser, PathTokenizer, RawTokenizer, RemoveLongFilter, SimpleTokenizer, Stemmer,
+    TokenizerConfig, WhitespaceTokenizer,
 };
 
 /// The tokenizer manager serves as a store for
@@ -18,6 +19,7 @@ use crate::tokenizer::{
 /// - `en_stem` : Like `default`, but also applies stemming on the resulting tokens. Stemming can
 ///   improve the recall of your search engine.
 /// - `whitespace` : Splits the text on whitespaces.
+/// - `path` : Emits all of the prefixes of a `/` separated path, e.g. `/a`, `/a/b` for `/a/b`.
 #[derive(Clone)]
 pub struct TokenizerManager {
     tokenizers: Arc<RwLock<HashMap<String, TextAnalyzer>>>,
@@ -41,6 +43,20 @@ impl TokenizerManager {
             .insert(tokenizer_name.to_string(), boxed_tokenizer);
     }
 
+    /// Builds the analysis chain described by `config`, and registers it with a given name.
+    ///
+    /// Returns `TantivyError::InvalidArgument` if a parameter of the configuration is invalid,
+    /// in which case no tokenizer is registered.
+    pub fn register_from_config(
+        &self,
+        tokenizer_name: &str,
+        config: &TokenizerConfig,
+    ) -> crate::Result<()> {
+        let tokenizer = config.build()?;
+        self.register(tokenizer_name, tokenizer);
+        Ok(())
+    }
+
     /// Accessing a tokenizer given its name.
     pub fn get(&self, tokenizer_name: &str) -> Option<TextAnalyzer> {
         self.tokenizers
@@ -73,6 +89,7 @@ impl Default for TokenizerManager {
                 .build(),
         );
         manager.register("whitespace", WhitespaceTokenizer::default());
+        manager.register("path", PathTokenizer::default());
         manager
     }
 }
@@ -26,6 +26,11 @@ pub struct Token {
     pub text: String,
     /// Is the length expressed in term of number of original tokens.
     pub position_length: usize,
+    /// Text content of the token before it was modified by token filters, if it was recorded
+    /// by a filter such as `PreserveOriginalFilter`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_text: Option<String>,
 }
 
 impl Default for Token {
@@ -36,6 +41,7 @@ impl Default for Token {
             position: usize::MAX,
             text: String::new(),
             position_length: 1,
+            original_text: None,
         }
     }
 }
@@ -48,6 +54,7 @@ impl Token {
         self.position = usize::MAX;
         self.text.clear();
         self.position_length = 1;
+        self.original_text = None;
     }
 }
 
@@ -169,6 +176,7 @@ mod test {
             offset_to: 3,
             text: "abc".to_string(),
             position_length: 1,
+            original_text: None,
         };
         let t2 = t1.clone();
 